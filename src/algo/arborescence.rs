@@ -0,0 +1,167 @@
+//! Minimum spanning arborescence of a directed Graph.
+
+use std::collections::{Deque, RingBuf, TreeMap};
+
+use graph::{Graph, Vertex};
+use weight::WeightProvider;
+
+/**
+* Find a minimum spanning arborescence of a directed Graph with the
+* algorithm of Chu-Liu/Edmonds.
+*
+* An arborescence rooted at a Vertex is a tree where every other Vertex is
+* reached from the root by exactly one path following the direction of the
+* Edges. The minimum one spans the Vertices reachable from the root with
+* the lowest sum of the weights of its Edges. The Edges without weight are
+* ignored, the self loops are never used.
+*
+* # Arguments
+* * graph - The graph to span
+* * weights - The weight of each Edge
+* * root - The key of the root of the arborescence
+*
+* # Return
+* Some(arborescence) with the Vertices reachable from the root and the
+* Edges of the arborescence, None if the root does not exist.
+*/
+pub fn minimum_spanning_arborescence<K: Ord + Eq + Clone,
+                                     L: Eq + Clone,
+                                     V: PartialEq + Clone,
+                                     W: WeightProvider<K, f64>>
+                                     (graph: &Graph<K, L, V>,
+                                     weights: &W,
+                                     root: &K)
+                                     -> Option<Graph<K, L, V>> {
+    let vertices: TreeMap<&K, &Vertex<K, L, V>> = graph.vertices_ref_iter().map(|v| (v.get_key(), v)).collect();
+    if !vertices.contains_key(&root) {
+        return None
+    }
+
+    // index the Vertices reachable from the root by the weighted Edges
+    let mut keys: Vec<&K> = vec!(root);
+    let mut index: TreeMap<&K, uint> = TreeMap::new();
+    index.insert(root, 0);
+    let mut edges: Vec<(uint, uint, f64)> = Vec::new();
+    let mut queue = RingBuf::new();
+    queue.push_back(root);
+    while !queue.is_empty() {
+        let key = queue.pop_front().unwrap();
+        let from = *index.find(&key).unwrap();
+        let vertex = *vertices.find(&key).unwrap();
+        for (to_key, _) in vertex.edges_iter() {
+            let weight = match weights.weight(key, to_key) {
+                Some(weight) => weight,
+                None         => continue
+            };
+            if !vertices.contains_key(&to_key) {
+                continue
+            }
+            if !index.contains_key(&to_key) {
+                index.insert(to_key, keys.len());
+                keys.push(to_key);
+                queue.push_back(to_key);
+            }
+            edges.push((from, *index.find(&to_key).unwrap(), weight));
+        }
+    }
+
+    let mut arborescence = Graph::new();
+    for &key in keys.iter() {
+        let label = vertices.find(&key).unwrap().get_label().map(|l| l.clone());
+        arborescence.add_vertex_opt_l(key.clone(), label);
+    }
+    // every Vertex is reachable so an arborescence always exists
+    for &i in contract(keys.len(), 0, &edges).unwrap().iter() {
+        let &(from, to, _) = edges.get(i);
+        let (from_key, to_key) = (*keys.get(from), *keys.get(to));
+        let value = vertices.find(&from_key).unwrap().get_edge_value(to_key).map(|v| v.clone());
+        arborescence.add_edge_opt_v(from_key.clone(), to_key.clone(), value);
+    }
+    Some(arborescence)
+}
+
+// the positions of the Edges of a minimum arborescence, None if a Vertex
+// can't be reached. Each Vertex takes its cheapest incoming Edge, each
+// cycle of these Edges is contracted in a single Vertex where the weight
+// of an Edge entering the cycle is reduced by the weight of the cycle's
+// Edge it replaces
+fn contract(n: uint, root: uint, edges: &Vec<(uint, uint, f64)>) -> Option<Vec<uint>> {
+    let mut best: Vec<Option<uint>> = Vec::from_elem(n, None);
+    for (i, &(from, to, weight)) in edges.iter().enumerate() {
+        if from == to || to == root {
+            continue
+        }
+        match *best.get(to) {
+            Some(b) if edges.get(b).val2() <= weight => {},
+            _                                        => *best.get_mut(to) = Some(i)
+        }
+    }
+    if range(0, n).any(|v| v != root && best.get(v).is_none()) {
+        return None
+    }
+    let parent = |v: uint| edges.get(best.get(v).unwrap()).val0();
+
+    // number the cycles, then the other Vertices
+    let mut component: Vec<Option<uint>> = Vec::from_elem(n, None);
+    let mut walked: Vec<Option<uint>> = Vec::from_elem(n, None);
+    let mut count = 0u;
+    for start in range(0, n) {
+        let mut v = start;
+        while v != root && walked.get(v).is_none() {
+            *walked.get_mut(v) = Some(start);
+            v = parent(v);
+        }
+        if v != root && *walked.get(v) == Some(start) {
+            let mut u = v;
+            loop {
+                *component.get_mut(u) = Some(count);
+                u = parent(u);
+                if u == v {
+                    break
+                }
+            }
+            count += 1;
+        }
+    }
+    if count == 0 {
+        return Some(range(0, n).filter(|&v| v != root).map(|v| best.get(v).unwrap()).collect())
+    }
+    let cycles = count;
+    for v in range(0, n) {
+        if component.get(v).is_none() {
+            *component.get_mut(v) = Some(count);
+            count += 1;
+        }
+    }
+    let in_cycle = |v: uint| component.get(v).unwrap() < cycles;
+
+    let mut contracted = Vec::new();
+    let mut origin = Vec::new();
+    for (i, &(from, to, weight)) in edges.iter().enumerate() {
+        let (c_from, c_to) = (component.get(from).unwrap(), component.get(to).unwrap());
+        if c_from == c_to {
+            continue
+        }
+        let reduced = if in_cycle(to) { weight - edges.get(best.get(to).unwrap()).val2() } else { weight };
+        contracted.push((c_from, c_to, reduced));
+        origin.push(i);
+    }
+
+    let chosen = match contract(count, component.get(root).unwrap(), &contracted) {
+        Some(chosen) => chosen,
+        None         => return None
+    };
+    let mut entered = Vec::from_elem(n, false);
+    let mut arborescence = Vec::with_capacity(n);
+    for &c in chosen.iter() {
+        let i = *origin.get(c);
+        *entered.get_mut(edges.get(i).val1()) = true;
+        arborescence.push(i);
+    }
+    for v in range(0, n) {
+        if in_cycle(v) && !*entered.get(v) {
+            arborescence.push(best.get(v).unwrap());
+        }
+    }
+    Some(arborescence)
+}
@@ -0,0 +1,169 @@
+//! Weighted bipartite assignment.
+
+use std::f64;
+
+use graph::Graph;
+use indexed::{IndexedGraph, contains_sorted};
+use weight::WeightProvider;
+
+/**
+* Assignment of the Vertices of a side of a bipartite Graph to the
+* Vertices of the other side.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+*/
+#[deriving(Clone, PartialEq, Show)]
+pub struct Assignment<K> {
+    /// The (left_key, right_key) of each assigned pair
+    pub pairs:         Vec<(K, K)>,
+    /// The sum of the weights of the Edges of the pairs
+    pub cost:          f64
+}
+
+/**
+* Find an assignment of minimum or maximum total weight with the
+* Hungarian algorithm of Kuhn and Munkres, in O(n^2 m).
+*
+* Every Vertex of the smaller side is assigned to a distinct Vertex of the
+* other side through an Edge, in either direction, between them. The
+* Vertices of both sides must be distinct.
+*
+* # Arguments
+* * graph - The graph of the Edges
+* * weights - The cost of each Edge
+* * left - The keys of the Vertices of the first side
+* * right - The keys of the Vertices of the second side
+* * maximize - Search the maximum total weight instead of the minimum
+*
+* # Return
+* Some(assignment) with the pairs sorted by left key, None if a Vertex does
+* not exist or if the smaller side can't be fully assigned.
+*/
+pub fn hungarian<K: Ord + Eq + Clone,
+                 L: Eq + Clone,
+                 V: PartialEq + Clone,
+                 W: WeightProvider<K, f64>>
+                 (graph: &Graph<K, L, V>,
+                 weights: &W,
+                 left: &[K],
+                 right: &[K],
+                 maximize: bool)
+                 -> Option<Assignment<K>> {
+    let indexed = IndexedGraph::from_graph(graph);
+    for key in left.iter().chain(right.iter()) {
+        if !indexed.index.contains_key(key) {
+            return None
+        }
+    }
+    let weight = |a: &K, b: &K| -> Option<f64> {
+        let (from, to) = (*indexed.index.find(a).unwrap(), *indexed.index.find(b).unwrap());
+        if contains_sorted(indexed.out_edges.get(from).as_slice(), to) {
+            weights.weight(a, b)
+        } else if contains_sorted(indexed.out_edges.get(to).as_slice(), from) {
+            weights.weight(b, a)
+        } else {
+            None
+        }
+    };
+
+    // the rows are the smaller side
+    let transposed = left.len() > right.len();
+    let (rows, columns) = if transposed { (right, left) } else { (left, right) };
+    let mut costs: Vec<Vec<Option<f64>>> = Vec::with_capacity(rows.len());
+    for row in rows.iter() {
+        let mut row_costs = Vec::with_capacity(columns.len());
+        for column in columns.iter() {
+            let w = if transposed { weight(column, row) } else { weight(row, column) };
+            row_costs.push(w.map(|w| if maximize { -w } else { w }));
+        }
+        costs.push(row_costs);
+    }
+    // the missing Edges cost more than any assignment of real Edges
+    let missing = costs.iter().flat_map(|row| row.iter())
+                              .fold(1.0, |sum, c| sum + c.map_or(0.0, |c| c.abs()));
+    let matrix: Vec<Vec<f64>> = costs.iter().map(|row| {
+        row.iter().map(|c| c.unwrap_or(missing)).collect()
+    }).collect();
+
+    let assigned = solve(&matrix, columns.len());
+    let mut assignment = Assignment { pairs: Vec::with_capacity(rows.len()), cost: 0.0 };
+    for (i, &j) in assigned.iter().enumerate() {
+        let cost = match *costs.get(i).get(j) {
+            Some(cost) => cost,
+            None       => return None
+        };
+        assignment.cost += if maximize { -cost } else { cost };
+        let (row, column) = (rows[i].clone(), columns[j].clone());
+        assignment.pairs.push(if transposed { (column, row) } else { (row, column) });
+    }
+    assignment.pairs.sort();
+    Some(assignment)
+}
+
+// the column assigned to each row of a cost matrix with at most as many
+// rows as columns, by shortest augmenting paths with potentials. The rows
+// and the columns are numbered from 1, 0 is a virtual column
+pub fn solve(costs: &Vec<Vec<f64>>, m: uint) -> Vec<uint> {
+    let n = costs.len();
+    let mut u = Vec::from_elem(n + 1, 0.0f64);
+    let mut v = Vec::from_elem(m + 1, 0.0f64);
+    // the row assigned to each column
+    let mut p = Vec::from_elem(m + 1, 0u);
+    let mut way = Vec::from_elem(m + 1, 0u);
+
+    for i in range(1, n + 1) {
+        *p.get_mut(0) = i;
+        let mut j0 = 0u;
+        let mut min_v = Vec::from_elem(m + 1, f64::INFINITY);
+        let mut used = Vec::from_elem(m + 1, false);
+        loop {
+            *used.get_mut(j0) = true;
+            let i0 = *p.get(j0);
+            let mut delta = f64::INFINITY;
+            let mut j1 = 0u;
+            for j in range(1, m + 1) {
+                if *used.get(j) {
+                    continue
+                }
+                let current = *costs.get(i0 - 1).get(j - 1) - *u.get(i0) - *v.get(j);
+                if current < *min_v.get(j) {
+                    *min_v.get_mut(j) = current;
+                    *way.get_mut(j) = j0;
+                }
+                if *min_v.get(j) < delta {
+                    delta = *min_v.get(j);
+                    j1 = j;
+                }
+            }
+            for j in range(0, m + 1) {
+                if *used.get(j) {
+                    *u.get_mut(*p.get(j)) += delta;
+                    *v.get_mut(j) -= delta;
+                } else {
+                    *min_v.get_mut(j) -= delta;
+                }
+            }
+            j0 = j1;
+            if *p.get(j0) == 0 {
+                break
+            }
+        }
+        loop {
+            let j1 = *way.get(j0);
+            *p.get_mut(j0) = *p.get(j1);
+            j0 = j1;
+            if j0 == 0 {
+                break
+            }
+        }
+    }
+
+    let mut assigned = Vec::from_elem(n, 0u);
+    for j in range(1, m + 1) {
+        if *p.get(j) != 0 {
+            *assigned.get_mut(*p.get(j) - 1) = j - 1;
+        }
+    }
+    assigned
+}
@@ -0,0 +1,203 @@
+//! Bipartite Graphs and their maximum matchings.
+
+use std::collections::{Deque, RingBuf};
+
+use graph::Graph;
+use indexed::IndexedGraph;
+
+/**
+* Check if a Graph is bipartite by coloring its Vertices with two colors,
+* ignoring the direction of the Edges.
+*
+* The first Vertex of each connected component is put on the first side.
+*
+* # Arguments
+* * graph - The graph to split
+*
+* # Return
+* Some((first, second)) with the sorted keys of the Vertices of each side
+* if no Edge joins two Vertices of the same side, None otherwise, also
+* when the Graph has a self loop.
+*/
+pub fn is_bipartite<K: Ord + Eq + Clone,
+                    L: Eq + Clone,
+                    V: PartialEq + Clone>
+                    (graph: &Graph<K, L, V>)
+                    -> Option<(Vec<K>, Vec<K>)> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let adjacency = indexed.undirected_edges();
+    let side = match two_coloring(&adjacency) {
+        Some(side) => side,
+        None       => return None
+    };
+    let mut first = Vec::new();
+    let mut second = Vec::new();
+    for (key, &s) in indexed.keys.move_iter().zip(side.iter()) {
+        if s { second.push(key) } else { first.push(key) }
+    }
+    Some((first, second))
+}
+
+/**
+* Find a maximum matching of a bipartite Graph with the algorithm of
+* Hopcroft and Karp, in O(m sqrt(n)).
+*
+* A matching is a set of Edges without common Vertex, the direction of the
+* Edges is ignored. The sides are the ones of is_bipartite.
+*
+* # Arguments
+* * graph - The graph to match
+*
+* # Return
+* Some(pairs) with the (first_key, second_key) of the matched Edges sorted
+* by first key, the first key on the first side, None if the Graph is not
+* bipartite.
+*/
+pub fn maximum_bipartite_matching<K: Ord + Eq + Clone,
+                                  L: Eq + Clone,
+                                  V: PartialEq + Clone>
+                                  (graph: &Graph<K, L, V>)
+                                  -> Option<Vec<(K, K)>> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let adjacency = indexed.undirected_edges();
+    let side = match two_coloring(&adjacency) {
+        Some(side) => side,
+        None       => return None
+    };
+    let n = indexed.len();
+    let left: Vec<uint> = range(0, n).filter(|&v| !*side.get(v)).collect();
+
+    let mut mate: Vec<Option<uint>> = Vec::from_elem(n, None);
+    loop {
+        // breadth first layers from the free Vertices of the first side
+        let mut layer: Vec<Option<uint>> = Vec::from_elem(n, None);
+        let mut queue = RingBuf::new();
+        for &u in left.iter() {
+            if mate.get(u).is_none() {
+                *layer.get_mut(u) = Some(0u);
+                queue.push_back(u);
+            }
+        }
+        // the layer of the Vertices next to the first free Vertex of the
+        // second side, the layers stop there
+        let mut limit = None;
+        while !queue.is_empty() {
+            let u = queue.pop_front().unwrap();
+            let current = layer.get(u).unwrap();
+            if limit.map_or(false, |l| current > l) {
+                break
+            }
+            for &v in adjacency.get(u).iter() {
+                match *mate.get(v) {
+                    None                                                    => {
+                        if limit.is_none() {
+                            limit = Some(current);
+                        }
+                    },
+                    Some(w) if layer.get(w).is_none() && limit.is_none()    => {
+                        *layer.get_mut(w) = Some(current + 1);
+                        queue.push_back(w);
+                    },
+                    _                                                       => {}
+                }
+            }
+        }
+        let limit = match limit {
+            Some(limit) => limit,
+            None        => break
+        };
+        for l in layer.mut_iter() {
+            if l.map_or(false, |d| d > limit) {
+                *l = None;
+            }
+        }
+
+        // a maximal set of disjoint shortest augmenting paths
+        let mut position = Vec::from_elem(n, 0u);
+        for &u in left.iter() {
+            if mate.get(u).is_none() {
+                augment(&adjacency, &mut mate, &mut layer, &mut position, limit, u);
+            }
+        }
+    }
+
+    let mut pairs = Vec::new();
+    for &u in left.iter() {
+        match *mate.get(u) {
+            Some(v) => pairs.push((indexed.keys.get(u).clone(), indexed.keys.get(v).clone())),
+            None    => {}
+        }
+    }
+    Some(pairs)
+}
+
+// the side of each Vertex, false for the first one, None if an Edge joins
+// two Vertices of the same side
+fn two_coloring(adjacency: &Vec<Vec<uint>>) -> Option<Vec<bool>> {
+    let n = adjacency.len();
+    let mut side: Vec<Option<bool>> = Vec::from_elem(n, None);
+    let mut queue = RingBuf::new();
+    for root in range(0, n) {
+        if side.get(root).is_some() {
+            continue
+        }
+        *side.get_mut(root) = Some(false);
+        queue.push_back(root);
+        while !queue.is_empty() {
+            let v = queue.pop_front().unwrap();
+            let s = side.get(v).unwrap();
+            for &u in adjacency.get(v).iter() {
+                match *side.get(u) {
+                    Some(t) if t == s => return None,
+                    Some(_)           => {},
+                    None              => {
+                        *side.get_mut(u) = Some(!s);
+                        queue.push_back(u);
+                    }
+                }
+            }
+        }
+    }
+    Some(side.move_iter().map(|s| s.unwrap()).collect())
+}
+
+// search an augmenting path from a free Vertex along the layers, with a
+// loop rather than a recursion as the paths can be as long as the Graph,
+// and flip the matching along it; the path ends at a free Vertex next to
+// the last layer so it is a shortest one
+fn augment(adjacency: &Vec<Vec<uint>>,
+           mate: &mut Vec<Option<uint>>,
+           layer: &mut Vec<Option<uint>>,
+           position: &mut Vec<uint>,
+           limit: uint,
+           root: uint)
+           -> bool {
+    // the Vertices of the first side on the path
+    let mut path = vec!(root);
+    loop {
+        let u = *path.last().unwrap();
+        if *position.get(u) == adjacency.get(u).len() {
+            // dead end, no other path goes through u in this phase
+            *layer.get_mut(u) = None;
+            path.pop();
+            match path.last() {
+                Some(&p) => *position.get_mut(p) += 1,
+                None     => return false
+            }
+            continue
+        }
+        let v = *adjacency.get(u).get(*position.get(u));
+        match *mate.get(v) {
+            None if *layer.get(u) == Some(limit)                       => {
+                for &w in path.iter() {
+                    let x = *adjacency.get(w).get(*position.get(w));
+                    *mate.get_mut(w) = Some(x);
+                    *mate.get_mut(x) = Some(w);
+                }
+                return true
+            },
+            Some(w) if *layer.get(w) == layer.get(u).map(|l| l + 1) => path.push(w),
+            _                                                          => *position.get_mut(u) += 1
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! Maximal cliques.
+
+use graph::Graph;
+use indexed::{IndexedGraph, contains_sorted};
+
+/**
+* Enumerate the maximal cliques of a Graph with the algorithm of Bron and
+* Kerbosch, with the pivot of Tomita et al.
+*
+* A clique is a set of Vertices all adjacent to each other, ignoring the
+* direction of the Edges and the self loops, it is maximal when no other
+* Vertex is adjacent to all of them. The cliques are searched as they are
+* iterated, so only the current branch of the search is kept in memory.
+*
+* # Arguments
+* * graph - The graph to search
+*
+* # Return
+* An iterator over the sorted keys of the Vertices of each maximal clique,
+* an isolated Vertex is a clique of one Vertex.
+*/
+pub fn maximal_cliques<K: Ord + Eq + Clone,
+                       L: Eq + Clone,
+                       V: PartialEq + Clone>
+                       (graph: &Graph<K, L, V>) -> MaximalCliques<K> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let adjacency: Vec<Vec<uint>> = indexed.undirected_edges().move_iter().enumerate().map(|(v, edges)| {
+        edges.move_iter().filter(|&u| u != v).collect()
+    }).collect();
+    let mut cliques = MaximalCliques {
+        keys:       indexed.keys,
+        adjacency:  adjacency,
+        stack:      Vec::new()
+    };
+    let all: Vec<uint> = range(0, cliques.keys.len()).collect();
+    let root = cliques.frame(Vec::new(), all, Vec::new());
+    cliques.stack.push(root);
+    cliques
+}
+
+/// Iterator over the maximal cliques of a Graph.
+pub struct MaximalCliques<K> {
+    keys:              Vec<K>,
+    adjacency:         Vec<Vec<uint>>,
+    stack:             Vec<Frame>
+}
+
+// a branch of the search: the clique, the Vertices that can extend it and
+// the ones already tried, all sorted, and the candidates left to try
+struct Frame {
+    clique:            Vec<uint>,
+    extensions:        Vec<uint>,
+    excluded:          Vec<uint>,
+    candidates:        Vec<uint>,
+    position:          uint
+}
+
+impl<K> MaximalCliques<K> {
+    // the candidates are the extensions not adjacent to the pivot, the
+    // Vertex of extensions or excluded with the most neighbors in extensions
+    fn frame(&self, clique: Vec<uint>, extensions: Vec<uint>, excluded: Vec<uint>) -> Frame {
+        let mut pivot = None;
+        let mut best = 0u;
+        for &u in extensions.iter().chain(excluded.iter()) {
+            let count = extensions.iter().filter(|&&w| {
+                contains_sorted(self.adjacency.get(u).as_slice(), w)
+            }).count();
+            if pivot.is_none() || count > best {
+                pivot = Some(u);
+                best = count;
+            }
+        }
+        let candidates = match pivot {
+            Some(u) => extensions.iter().filter(|&&w| {
+                !contains_sorted(self.adjacency.get(u).as_slice(), w)
+            }).map(|&w| w).collect(),
+            None    => Vec::new()
+        };
+        Frame {
+            clique:     clique,
+            extensions: extensions,
+            excluded:   excluded,
+            candidates: candidates,
+            position:   0
+        }
+    }
+}
+
+impl Frame {
+    // the (clique, extensions, excluded) of the branch of the next
+    // candidate, None when they are all tried
+    fn next_branch(&mut self, adjacency: &Vec<Vec<uint>>) -> Option<(Vec<uint>, Vec<uint>, Vec<uint>)> {
+        if self.position == self.candidates.len() {
+            return None
+        }
+        let v = *self.candidates.get(self.position);
+        self.position += 1;
+        let neighbors = adjacency.get(v).as_slice();
+        let mut clique = self.clique.clone();
+        clique.push(v);
+        let extensions = intersection(self.extensions.as_slice(), neighbors);
+        let excluded = intersection(self.excluded.as_slice(), neighbors);
+        // the following branches don't extend with v
+        self.extensions.retain(|&u| u != v);
+        self.excluded.push(v);
+        self.excluded.sort();
+        Some((clique, extensions, excluded))
+    }
+}
+
+impl<K: Clone> Iterator<Vec<K>> for MaximalCliques<K> {
+    fn next(&mut self) -> Option<Vec<K>> {
+        loop {
+            let branch = match self.stack.mut_last() {
+                Some(frame) => frame.next_branch(&self.adjacency),
+                None        => return None
+            };
+            match branch {
+                Some((mut clique, extensions, excluded)) => {
+                    if extensions.is_empty() && excluded.is_empty() {
+                        // the indices are in the order of the keys
+                        clique.sort();
+                        return Some(clique.iter().map(|&v| self.keys.get(v).clone()).collect())
+                    }
+                    // with no extension but some excluded Vertex the
+                    // clique is not maximal
+                    if !extensions.is_empty() {
+                        let frame = self.frame(clique, extensions, excluded);
+                        self.stack.push(frame);
+                    }
+                },
+                None                                     => { self.stack.pop(); }
+            }
+        }
+    }
+}
+
+// the common elements of two sorted slices
+fn intersection(a: &[uint], b: &[uint]) -> Vec<uint> {
+    let mut common = Vec::new();
+    let mut i = 0;
+    let mut j = 0;
+    while i < a.len() && j < b.len() {
+        if a[i] < b[j] {
+            i += 1;
+        } else if a[i] > b[j] {
+            j += 1;
+        } else {
+            common.push(a[i]);
+            i += 1;
+            j += 1;
+        }
+    }
+    common
+}
@@ -0,0 +1,207 @@
+//! Transitive closure, transitive reduction and reachability queries.
+
+use std::collections::TreeMap;
+
+use graph::{Graph, StructureViolation};
+use indexed::IndexedGraph;
+use algo::condensation::components;
+
+/**
+* Index answering if a Vertex reaches another one without searching the
+* Graph.
+*
+* The strongly connected components of the Graph are computed once and
+* each component keeps the set of components it reaches as a bit set, so
+* a query is a lookup of the two keys and a bit test. The index takes the
+* square of the number of components over 8 bytes, and does not follow
+* the changes of the Graph.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+*/
+#[deriving(Clone, PartialEq, Show)]
+pub struct ReachabilityIndex<K> {
+    component:         TreeMap<K, uint>,
+    cyclic:            Vec<bool>,
+    reachable:         Vec<Vec<u64>>
+}
+
+impl<K: Ord + Eq + Clone> ReachabilityIndex<K> {
+
+    /**
+    * Build the index of a Graph.
+    *
+    * # Arguments
+    * * graph - The graph to index
+    *
+    * # Return
+    * A new ReachabilityIndex of the current Edges of the Graph.
+    */
+    pub fn new<L: Eq + Clone,
+               V: PartialEq + Clone>
+               (graph: &Graph<K, L, V>) -> ReachabilityIndex<K> {
+        let indexed = IndexedGraph::from_graph(graph);
+        let (count, component) = components(&indexed);
+        let words = (count + 63) / 64;
+
+        let mut cyclic = Vec::from_elem(count, false);
+        let mut successors: Vec<Vec<uint>> = Vec::from_elem(count, Vec::new());
+        for (v, edges) in indexed.out_edges.iter().enumerate() {
+            let c = *component.get(v);
+            for &u in edges.iter() {
+                let d = *component.get(u);
+                if d == c {
+                    // a self loop or an Edge inside a cycle
+                    *cyclic.get_mut(c) = true;
+                } else {
+                    successors.get_mut(c).push(d);
+                }
+            }
+        }
+
+        // the components are in topological order, the successors of a
+        // component are done before it
+        let mut reachable: Vec<Vec<u64>> = Vec::from_elem(count, Vec::from_elem(words, 0u64));
+        for c in range(0, count).rev() {
+            let mut bits = Vec::from_elem(words, 0u64);
+            for &d in successors.get(c).iter() {
+                *bits.get_mut(d / 64) |= 1 << (d % 64);
+                for (word, &other) in bits.mut_iter().zip(reachable.get(d).iter()) {
+                    *word |= other;
+                }
+            }
+            *reachable.get_mut(c) = bits;
+        }
+
+        ReachabilityIndex {
+            component:  indexed.keys.move_iter().zip(component.move_iter()).collect(),
+            cyclic:     cyclic,
+            reachable:  reachable
+        }
+    }
+
+    /**
+    * Check if there is a path following the Edges between two Vertices.
+    *
+    * # Arguments
+    * * from_key - The key of the first Vertex
+    * * to_key - The key of the last Vertex
+    *
+    * # Return
+    * true if the last Vertex is reachable from the first one, a Vertex
+    * always reaches itself, false if a Vertex is not in the index.
+    */
+    pub fn reaches(&self, from_key: &K, to_key: &K) -> bool {
+        match (self.component.find(from_key), self.component.find(to_key)) {
+            (Some(&from), Some(&to)) => {
+                // two distinct Vertices of a component are on a cycle
+                from == to || self.reaches_component(from, to)
+            },
+            _                        => false
+        }
+    }
+
+    /**
+    * Check if a Vertex is on a cycle, it then reaches itself through at
+    * least one Edge.
+    *
+    * # Arguments
+    * * key - The key of the Vertex
+    *
+    * # Return
+    * true if the Vertex is on a cycle or has a self loop, false otherwise.
+    */
+    pub fn on_cycle(&self, key: &K) -> bool {
+        match self.component.find(key) {
+            Some(&c) => *self.cyclic.get(c),
+            None     => false
+        }
+    }
+
+    // the component to is reachable through at least one Edge from the
+    // component from
+    fn reaches_component(&self, from: uint, to: uint) -> bool {
+        *self.reachable.get(from).get(to / 64) & (1 << (to % 64)) != 0
+    }
+}
+
+/**
+* Compute the transitive closure of a Graph.
+*
+* The closure has an Edge from a Vertex to each Vertex reachable from it
+* through at least one Edge, so a Vertex on a cycle has a self loop. The
+* labels and the values of the Edges of the Graph are kept, the added
+* Edges have no value.
+*
+* # Arguments
+* * graph - The graph to close
+*
+* # Return
+* A new graph with the Vertices of the Graph and the Edges of its closure.
+*/
+pub fn transitive_closure<K: Ord + Eq + Clone,
+                          L: Eq + Clone,
+                          V: PartialEq + Clone>
+                          (graph: &Graph<K, L, V>) -> Graph<K, L, V> {
+    let index = ReachabilityIndex::new(graph);
+    let mut members: Vec<Vec<&K>> = Vec::from_elem(index.cyclic.len(), Vec::new());
+    for (key, &c) in index.component.iter() {
+        members.get_mut(c).push(key);
+    }
+
+    let mut closure = graph.clone();
+    for (from_key, &from) in index.component.iter() {
+        for (to, keys) in members.iter().enumerate() {
+            let reached = if to == from {
+                *index.cyclic.get(from)
+            } else {
+                index.reaches_component(from, to)
+            };
+            if reached {
+                for &to_key in keys.iter() {
+                    closure.add_edge(from_key.clone(), to_key.clone());
+                }
+            }
+        }
+    }
+    closure
+}
+
+/**
+* Compute the transitive reduction of a directed acyclic Graph.
+*
+* The reduction removes every Edge from a to b when b is also reachable
+* from a through another path, it has the fewest Edges with the same
+* reachability as the Graph. The labels and the values of the kept Edges
+* are kept.
+*
+* # Arguments
+* * graph - The graph to reduce
+*
+* # Return
+* Ok(reduction) with a new graph, Err(Cycle) with the Vertices of a cycle if
+* the Graph is not acyclic.
+*/
+pub fn transitive_reduction<K: Ord + Eq + Clone,
+                            L: Eq + Clone,
+                            V: PartialEq + Clone>
+                            (graph: &Graph<K, L, V>)
+                            -> Result<Graph<K, L, V>, StructureViolation<K>> {
+    try!(graph.is_dag());
+    let index = ReachabilityIndex::new(graph);
+    let mut redundant = Vec::new();
+    for vertex in graph.vertices_ref_iter() {
+        for (to_key, _) in vertex.edges_iter() {
+            // another successor leads to to_key
+            if graph.neighbors(vertex.get_key()).any(|other| other != to_key && index.reaches(other, to_key)) {
+                redundant.push((vertex.get_key().clone(), to_key.clone()));
+            }
+        }
+    }
+
+    let mut reduction = graph.clone();
+    for (from_key, to_key) in redundant.move_iter() {
+        reduction.remove_edge(from_key, to_key);
+    }
+    Ok(reduction)
+}
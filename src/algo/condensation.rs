@@ -0,0 +1,151 @@
+//! Strongly connected components and the condensation of a Graph.
+
+use graph::Graph;
+use indexed::IndexedGraph;
+use view::GraphView;
+
+/**
+* Find the strongly connected components of a Graph with the algorithm of
+* Kosaraju.
+*
+* # Arguments
+* * graph - The graph to split
+*
+* # Return
+* The sorted keys of the Vertices of each component, in a topological order
+* of the components: no Edge goes from a component to a previous one.
+*/
+pub fn strongly_connected_components<K: Ord + Eq + Clone,
+                                     L: Eq + Clone,
+                                     V: PartialEq + Clone>
+                                     (graph: &Graph<K, L, V>)
+                                     -> Vec<Vec<K>> {
+    members(&IndexedGraph::from_graph(graph))
+}
+
+/**
+* Find the strongly connected components of a view on a Graph with the
+* algorithm of Kosaraju.
+*
+* The view is indexed first, the components follow its Edges.
+*
+* # Arguments
+* * view - The view to split
+*
+* # Return
+* The keys of the Vertices of each component, in the order of the view's
+* vertices, in a topological order of the components.
+*/
+pub fn strongly_connected_components_view<'a,
+                                          K: Ord + Eq + Clone,
+                                          V,
+                                          G: GraphView<'a, K, V>>
+                                          (view: &G)
+                                          -> Vec<Vec<K>> {
+    members(&IndexedGraph::from_view(view))
+}
+
+// the keys of the members of each component
+fn members<K: Ord + Eq + Clone>(indexed: &IndexedGraph<K>) -> Vec<Vec<K>> {
+    let (count, component) = components(indexed);
+    let mut members = Vec::from_elem(count, Vec::new());
+    for (v, &c) in component.iter().enumerate() {
+        members.get_mut(c).push(indexed.keys.get(v).clone());
+    }
+    members
+}
+
+/**
+* Collapse each strongly connected component of a Graph into a Vertex.
+*
+* The condensation is a directed acyclic Graph, its Vertices are numbered
+* in the order of strongly_connected_components and labeled with the keys
+* of their members. There is an Edge between two components when there is
+* an Edge between their members, with the value of the first of these
+* Edges in key order.
+*
+* # Arguments
+* * graph - The graph to condense
+*
+* # Return
+* The condensation of the Graph.
+*/
+pub fn condensation<K: Ord + Eq + Clone,
+                    L: Eq + Clone,
+                    V: PartialEq + Clone>
+                    (graph: &Graph<K, L, V>)
+                    -> Graph<uint, Vec<K>, V> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let (count, component) = components(&indexed);
+    let mut members = Vec::from_elem(count, Vec::new());
+    for (v, &c) in component.iter().enumerate() {
+        members.get_mut(c).push(indexed.keys.get(v).clone());
+    }
+
+    let mut dag = Graph::new();
+    for (c, keys) in members.move_iter().enumerate() {
+        dag.add_vertex_l(c, keys);
+    }
+    for (from_key, to_key, value) in graph.edges_iter() {
+        let from = *component.get(*indexed.index.find(from_key).unwrap());
+        let to = *component.get(*indexed.index.find(to_key).unwrap());
+        if from != to {
+            dag.add_edge_opt_v(from, to, value.map(|v| v.clone()));
+        }
+    }
+    dag
+}
+
+// the number of components and the component of each Vertex, numbered in
+// topological order
+pub fn components<K: Ord + Eq + Clone>(indexed: &IndexedGraph<K>) -> (uint, Vec<uint>) {
+    let n = indexed.len();
+
+    // the Vertices by increasing finish time of a depth first search
+    let mut finished = Vec::with_capacity(n);
+    let mut visited = Vec::from_elem(n, false);
+    for root in range(0, n) {
+        if *visited.get(root) {
+            continue
+        }
+        *visited.get_mut(root) = true;
+        let mut stack = vec!((root, 0u));
+        while !stack.is_empty() {
+            let (v, position) = *stack.last().unwrap();
+            if position == indexed.out_edges.get(v).len() {
+                finished.push(v);
+                stack.pop();
+                continue
+            }
+            *stack.mut_last().unwrap().mut1() += 1;
+            let u = *indexed.out_edges.get(v).get(position);
+            if !*visited.get(u) {
+                *visited.get_mut(u) = true;
+                stack.push((u, 0));
+            }
+        }
+    }
+
+    // the Vertices reaching each root in the reversed Graph, the last
+    // finished first
+    let mut component: Vec<Option<uint>> = Vec::from_elem(n, None);
+    let mut count = 0u;
+    for &root in finished.iter().rev() {
+        if component.get(root).is_some() {
+            continue
+        }
+        *component.get_mut(root) = Some(count);
+        let mut stack = vec!(root);
+        while !stack.is_empty() {
+            let v = stack.pop().unwrap();
+            for &u in indexed.in_edges.get(v).iter() {
+                if component.get(u).is_none() {
+                    *component.get_mut(u) = Some(count);
+                    stack.push(u);
+                }
+            }
+        }
+        count += 1;
+    }
+    (count, component.move_iter().map(|c| c.unwrap()).collect())
+}
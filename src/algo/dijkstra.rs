@@ -0,0 +1,163 @@
+//! Shortest paths weighted by the values of the Edges.
+
+use std::collections::TreeSet;
+
+use graph::Graph;
+use heap::MinHeap;
+use property_map::PropertyMap;
+use weight::Weight;
+
+/**
+* Shortest paths from a source Vertex, computed by dijkstra.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+* * V - The Edge's Value type
+*/
+#[deriving(Clone, PartialEq, Show)]
+pub struct ShortestPaths<K, V> {
+    /// The distance from the source of each reached Vertex
+    pub distances:     PropertyMap<K, V>,
+    /// The Vertex before each reached Vertex on its shortest path, the
+    /// source has none
+    pub predecessors:  PropertyMap<K, K>
+}
+
+impl<K: Ord + Eq + Clone, V> ShortestPaths<K, V> {
+
+    /**
+    * Get the shortest path to a Vertex.
+    *
+    * # Arguments
+    * * target - The key of the last Vertex of the path
+    *
+    * # Return
+    * Some(keys) of the Vertices of the path from the source to target, None
+    * if target was not reached.
+    */
+    pub fn path_to(&self, target: &K) -> Option<Vec<K>> {
+        if !self.distances.contains_key(target) {
+            return None
+        }
+        let mut path = vec!(target.clone());
+        loop {
+            let predecessor = self.predecessors.find(path.last().unwrap()).map(|k| k.clone());
+            match predecessor {
+                Some(key) => path.push(key),
+                None      => break
+            }
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/**
+* Compute the shortest paths from a Vertex with the algorithm of Dijkstra.
+*
+* The paths follow the direction of the Edges and are weighted by the values
+* of the Edges, the Edges without value are ignored and the values must not
+* be negative.
+*
+* # Arguments
+* * graph - The graph to search
+* * source - The key of the first Vertex of the paths
+*
+* # Return
+* The distances and the predecessors of the Vertices reachable from source,
+* empty if source does not exist.
+*/
+pub fn dijkstra<K: Ord + Eq + Clone,
+                L: Eq + Clone,
+                V: PartialEq + Clone + Weight>
+                (graph: &Graph<K, L, V>,
+                source: K)
+                -> ShortestPaths<K, V> {
+    search(graph, source, None)
+}
+
+/**
+* Find a shortest path between two Vertices with the algorithm of Dijkstra.
+*
+* The search follows the direction of the Edges and stops when target is
+* reached, the Edges without value are ignored and the values must not be
+* negative.
+*
+* # Arguments
+* * graph - The graph to search
+* * source - The key of the first Vertex of the path
+* * target - The key of the last Vertex of the path
+*
+* # Return
+* Some((distance, keys)) of the Vertices of the path from source to target,
+* None if there is no path.
+*/
+pub fn shortest_path<K: Ord + Eq + Clone,
+                     L: Eq + Clone,
+                     V: PartialEq + Clone + Weight>
+                     (graph: &Graph<K, L, V>,
+                     source: K,
+                     target: K)
+                     -> Option<(V, Vec<K>)> {
+    let paths = search(graph, source, Some(&target));
+    match paths.distances.find(&target) {
+        Some(distance) => Some((distance.clone(), paths.path_to(&target).unwrap())),
+        None           => None
+    }
+}
+
+// the shortest paths from source, until target is settled if given
+fn search<K: Ord + Eq + Clone,
+          L: Eq + Clone,
+          V: PartialEq + Clone + Weight>
+          (graph: &Graph<K, L, V>,
+          source: K,
+          target: Option<&K>)
+          -> ShortestPaths<K, V> {
+    let mut paths = ShortestPaths {
+        distances:      PropertyMap::new(),
+        predecessors:   PropertyMap::new()
+    };
+    if !graph.vertex_exist(&source) {
+        return paths
+    }
+
+    // the reached Vertices by distance, a Vertex is pushed again when its
+    // distance decreases and its older entries are skipped
+    let mut queue = MinHeap::new();
+    let mut settled = TreeSet::new();
+    paths.distances.insert(source.clone(), Weight::zero());
+    queue.push(Weight::zero(), source);
+    loop {
+        let (distance, key): (V, K) = match queue.pop() {
+            Some(top) => top,
+            None      => break
+        };
+        if !settled.insert(key.clone()) {
+            continue
+        }
+        if target == Some(&key) {
+            break
+        }
+        for (to_key, value) in graph.get_vertex(key.clone()).unwrap().edges_iter() {
+            let value = match value {
+                Some(value) => value,
+                None        => continue
+            };
+            if settled.contains(to_key) {
+                continue
+            }
+            let candidate = distance.add_weight(value);
+            let shorter = match paths.distances.find(to_key) {
+                Some(d) => candidate < *d,
+                None    => true
+            };
+            if shorter {
+                paths.distances.insert(to_key.clone(), candidate.clone());
+                paths.predecessors.insert(to_key.clone(), key.clone());
+                queue.push(candidate, to_key.clone());
+            }
+        }
+    }
+    paths
+}
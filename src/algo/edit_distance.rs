@@ -0,0 +1,359 @@
+//! Approximate graph edit distance.
+
+use std::cmp::{Less, Equal, Greater};
+use std::collections::TreeMap;
+
+use graph::Graph;
+use indexed::IndexedGraph;
+use algo::assignment::solve;
+
+/**
+* Costs of the edit operations turning a Graph into another one.
+*
+* By default inserting or deleting a Vertex or an Edge costs 1 and
+* substituting costs 0 between equal labels or values, 1 otherwise. The
+* costs must not be negative.
+*
+* # Types parameters
+* * L - The Vertex's Label type
+* * V - The Edge's Value type
+*/
+pub trait EditCosts<L: PartialEq, V: PartialEq> {
+
+    /**
+    * Cost of replacing the label of a Vertex.
+    */
+    fn vertex_substitution(&self, from: Option<&L>, to: Option<&L>) -> f64 {
+        if from == to { 0.0 } else { 1.0 }
+    }
+
+    /**
+    * Cost of deleting a Vertex.
+    */
+    fn vertex_deletion(&self, _: Option<&L>) -> f64 {
+        1.0
+    }
+
+    /**
+    * Cost of inserting a Vertex.
+    */
+    fn vertex_insertion(&self, _: Option<&L>) -> f64 {
+        1.0
+    }
+
+    /**
+    * Cost of replacing the value of an Edge.
+    */
+    fn edge_substitution(&self, from: Option<&V>, to: Option<&V>) -> f64 {
+        if from == to { 0.0 } else { 1.0 }
+    }
+
+    /**
+    * Cost of deleting an Edge.
+    */
+    fn edge_deletion(&self, _: Option<&V>) -> f64 {
+        1.0
+    }
+
+    /**
+    * Cost of inserting an Edge.
+    */
+    fn edge_insertion(&self, _: Option<&V>) -> f64 {
+        1.0
+    }
+}
+
+/// The default costs of EditCosts.
+pub struct UniformCosts;
+
+impl<L: PartialEq, V: PartialEq> EditCosts<L, V> for UniformCosts {}
+
+/**
+* Bounds of the edit distance between two Graphs.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+*/
+#[deriving(Clone, PartialEq, Show)]
+pub struct EditDistance<K> {
+    /// The cost of the best edit path found, an upper bound of the distance
+    pub distance:      f64,
+    /// A lower bound of the distance
+    pub lower_bound:   f64,
+    /// The Vertices matched by the edit path, (Some(key), None) for a
+    /// deletion and (None, Some(key)) for an insertion
+    pub mapping:       Vec<(Option<K>, Option<K>)>
+}
+
+/**
+* Approximate the edit distance between two Graphs, the least total cost
+* of the Vertex and Edge insertions, deletions and substitutions turning
+* the first Graph into the second one.
+*
+* An assignment between the Vertices, whose costs include the difference
+* of their degrees, gives a lower bound and a first edit path. A beam
+* search over the mappings of the Vertices of the first Graph then keeps
+* the beam_width cheapest partial edit paths at each step, the best of both
+* edit paths is returned. The exact distance is NP-hard, this is meant for
+* small Graphs.
+*
+* # Arguments
+* * from - The graph to edit
+* * to - The graph to reach
+* * costs - The costs of the edit operations
+* * beam_width - The number of partial edit paths kept, 0 to skip the search
+*
+* # Return
+* The bounds of the distance and the best edit path found.
+*/
+pub fn graph_edit_distance<K: Ord + Eq + Clone,
+                           L: Eq + Clone,
+                           V: PartialEq + Clone,
+                           C: EditCosts<L, V>>
+                           (from: &Graph<K, L, V>,
+                           to: &Graph<K, L, V>,
+                           costs: &C,
+                           beam_width: uint)
+                           -> EditDistance<K> {
+    let problem = Problem {
+        from:   Side::new(from),
+        to:     Side::new(to),
+        costs:  costs
+    };
+    let (n1, n2) = (problem.from.len(), problem.to.len());
+
+    // assignment of the Vertices or of their deletion or insertion, the
+    // Edges around a Vertex are counted for half of their cost
+    let edge_cost = problem.min_edge_cost();
+    let mut matrix = Vec::from_elem(n1 + n2, Vec::from_elem(n1 + n2, 0.0f64));
+    let mut forbidden = 1.0;
+    for i in range(0, n1) {
+        let label = *problem.from.labels.get(i);
+        for j in range(0, n2) {
+            let out_diff = problem.from.out_degree(i) as f64 - problem.to.out_degree(j) as f64;
+            let in_diff = problem.from.in_degree(i) as f64 - problem.to.in_degree(j) as f64;
+            *matrix.get_mut(i).get_mut(j) = costs.vertex_substitution(label, *problem.to.labels.get(j)) +
+                                            (out_diff.abs() + in_diff.abs()) * edge_cost / 2.0;
+        }
+        *matrix.get_mut(i).get_mut(n2 + i) = costs.vertex_deletion(label) +
+                                             problem.from.degree(i) as f64 * edge_cost / 2.0;
+    }
+    for j in range(0, n2) {
+        *matrix.get_mut(n1 + j).get_mut(j) = costs.vertex_insertion(*problem.to.labels.get(j)) +
+                                             problem.to.degree(j) as f64 * edge_cost / 2.0;
+    }
+    for row in matrix.iter() {
+        forbidden += row.iter().fold(0.0, |sum, &c| sum + c);
+    }
+    for i in range(0, n1) {
+        for j in range(0, n1) {
+            if i != j {
+                *matrix.get_mut(i).get_mut(n2 + j) = forbidden;
+            }
+        }
+    }
+    for j in range(0, n2) {
+        for i in range(0, n2) {
+            if i != j {
+                *matrix.get_mut(n1 + j).get_mut(i) = forbidden;
+            }
+        }
+    }
+    let assigned = solve(&matrix, n1 + n2);
+    let lower_bound = assigned.iter().enumerate().fold(0.0, |sum, (i, &j)| sum + *matrix.get(i).get(j));
+    let mut best: Vec<Option<uint>> = range(0, n1).map(|i| {
+        let j = *assigned.get(i);
+        if j < n2 { Some(j) } else { None }
+    }).collect();
+    let mut distance = problem.cost(&best);
+
+    // beam search on the mappings of the Vertices of the first Graph in
+    // their order, with the cost of the edit operations already decided
+    if beam_width > 0 {
+        let mut beam: Vec<(f64, Vec<Option<uint>>, Vec<bool>)> = vec!((0.0, Vec::new(), Vec::from_elem(n2, false)));
+        for v in range(0, n1) {
+            let mut next = Vec::new();
+            for &(cost, ref mapping, ref used) in beam.iter() {
+                for target in range(0, n2).filter(|&j| !*used.get(j)).map(|j| Some(j)).chain(Some(None).move_iter()) {
+                    let mut mapping = mapping.clone();
+                    mapping.push(target);
+                    let mut used = used.clone();
+                    match target {
+                        Some(j) => *used.get_mut(j) = true,
+                        None    => {}
+                    }
+                    next.push((cost + problem.step_cost(&mapping, v), mapping, used));
+                }
+            }
+            next.sort_by(|&(a, _, _), &(b, _, _)| {
+                if a < b { Less } else if a > b { Greater } else { Equal }
+            });
+            next.truncate(beam_width);
+            beam = next;
+        }
+        for &(_, ref mapping, _) in beam.iter() {
+            let cost = problem.cost(mapping);
+            if cost < distance {
+                distance = cost;
+                best = mapping.clone();
+            }
+        }
+    }
+
+    let mut mapping = Vec::with_capacity(n1 + n2);
+    let mut matched = Vec::from_elem(n2, false);
+    for (i, target) in best.iter().enumerate() {
+        let key = problem.from.indexed.keys.get(i).clone();
+        match *target {
+            Some(j) => {
+                *matched.get_mut(j) = true;
+                mapping.push((Some(key), Some(problem.to.indexed.keys.get(j).clone())));
+            },
+            None    => mapping.push((Some(key), None))
+        }
+    }
+    for j in range(0, n2) {
+        if !*matched.get(j) {
+            mapping.push((None, Some(problem.to.indexed.keys.get(j).clone())));
+        }
+    }
+    EditDistance {
+        distance:       distance,
+        lower_bound:    if lower_bound < distance { lower_bound } else { distance },
+        mapping:        mapping
+    }
+}
+
+// the indexed Vertices of a Graph with their labels and the values of
+// their Edges
+struct Side<'a, K, L, V> {
+    indexed:           IndexedGraph<K>,
+    labels:            Vec<Option<&'a L>>,
+    edges:             TreeMap<(uint, uint), Option<&'a V>>
+}
+
+impl<'a,
+     K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
+     Side<'a, K, L, V> {
+    fn new(graph: &'a Graph<K, L, V>) -> Side<'a, K, L, V> {
+        let indexed = IndexedGraph::from_graph(graph);
+        let mut edges = TreeMap::new();
+        for (from, vertex) in graph.vertices_ref_iter().enumerate() {
+            for (to_key, value) in vertex.edges_iter() {
+                match indexed.index.find(to_key) {
+                    Some(&to) => { edges.insert((from, to), value); },
+                    None      => {}
+                }
+            }
+        }
+        Side {
+            labels:     graph.vertices_ref_iter().map(|v| v.get_label()).collect(),
+            indexed:    indexed,
+            edges:      edges
+        }
+    }
+
+    fn len(&self) -> uint {
+        self.indexed.len()
+    }
+
+    fn out_degree(&self, v: uint) -> uint {
+        self.indexed.out_edges.get(v).len()
+    }
+
+    fn in_degree(&self, v: uint) -> uint {
+        self.indexed.in_edges.get(v).len()
+    }
+
+    fn degree(&self, v: uint) -> uint {
+        self.out_degree(v) + self.in_degree(v)
+    }
+}
+
+struct Problem<'a, 'c, K, L, V, C> {
+    from:              Side<'a, K, L, V>,
+    to:                Side<'a, K, L, V>,
+    costs:             &'c C
+}
+
+impl<'a, 'c,
+     K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone,
+     C: EditCosts<L, V>>
+     Problem<'a, 'c, K, L, V, C> {
+
+    // the cheapest insertion or deletion of an Edge
+    fn min_edge_cost(&self) -> f64 {
+        let mut least: Option<f64> = None;
+        for (_, value) in self.from.edges.iter() {
+            let cost = self.costs.edge_deletion(*value);
+            least = Some(least.map_or(cost, |l| l.min(cost)));
+        }
+        for (_, value) in self.to.edges.iter() {
+            let cost = self.costs.edge_insertion(*value);
+            least = Some(least.map_or(cost, |l| l.min(cost)));
+        }
+        least.unwrap_or(0.0)
+    }
+
+    // the cost of mapping the Vertex v, the last one of mapping, and of
+    // the Edges between v and the Vertices mapped before
+    fn step_cost(&self, mapping: &Vec<Option<uint>>, v: uint) -> f64 {
+        let label = *self.from.labels.get(v);
+        let mut cost = match *mapping.get(v) {
+            Some(j) => self.costs.vertex_substitution(label, *self.to.labels.get(j)),
+            None    => self.costs.vertex_deletion(label)
+        };
+        for u in range(0, v + 1) {
+            cost += self.edge_cost(mapping, u, v);
+            if u != v {
+                cost += self.edge_cost(mapping, v, u);
+            }
+        }
+        cost
+    }
+
+    // the cost of the Edge a -> b of the first Graph and of the Edge
+    // between their images in the second one
+    fn edge_cost(&self, mapping: &Vec<Option<uint>>, a: uint, b: uint) -> f64 {
+        let image = match (*mapping.get(a), *mapping.get(b)) {
+            (Some(x), Some(y)) => self.to.edges.find(&(x, y)),
+            _                  => None
+        };
+        match (self.from.edges.find(&(a, b)), image) {
+            (Some(from), Some(to)) => self.costs.edge_substitution(*from, *to),
+            (Some(from), None)     => self.costs.edge_deletion(*from),
+            (None, Some(to))       => self.costs.edge_insertion(*to),
+            (None, None)           => 0.0
+        }
+    }
+
+    // the cost of the edit path of a complete mapping
+    fn cost(&self, mapping: &Vec<Option<uint>>) -> f64 {
+        let mut cost = 0.0;
+        for v in range(0, mapping.len()) {
+            cost += self.step_cost(mapping, v);
+        }
+        let mut image = Vec::from_elem(self.to.len(), false);
+        for target in mapping.iter() {
+            match *target {
+                Some(j) => *image.get_mut(j) = true,
+                None    => {}
+            }
+        }
+        for j in range(0, self.to.len()) {
+            if !*image.get(j) {
+                cost += self.costs.vertex_insertion(*self.to.labels.get(j));
+            }
+        }
+        for (&(x, y), value) in self.to.edges.iter() {
+            if !*image.get(x) || !*image.get(y) {
+                cost += self.costs.edge_insertion(*value);
+            }
+        }
+        cost
+    }
+}
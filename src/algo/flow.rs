@@ -0,0 +1,377 @@
+//! Maximum flows.
+
+use std::collections::{Deque, RingBuf, TreeMap, TreeSet};
+use std::f64;
+
+use graph::Graph;
+use indexed::IndexedGraph;
+use flow_network::FlowNetwork;
+use property_map::{PropertyMap, EdgePropertyMap};
+use weight::WeightProvider;
+
+/**
+* Maximum flow between two Vertices.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+*/
+#[deriving(Clone, PartialEq, Show)]
+pub struct Flow<K> {
+    /// The total flow leaving the source
+    pub value:         f64,
+    /// The flow through each Edge, the Edges without flow are absent
+    pub edges:         EdgePropertyMap<K, f64>,
+    /// The capacity left from a Vertex to another one in the residual
+    /// network, the capacity not used by the flow of the Edge between them
+    /// plus the flow of the opposite Edge, the pairs without residual
+    /// capacity are absent
+    pub residual:      EdgePropertyMap<K, f64>
+}
+
+impl<K: Ord + Eq + Clone> Flow<K> {
+
+    /**
+    * Get the residual capacity from a Vertex to another one.
+    *
+    * # Arguments
+    * * from_key - The key of the first Vertex
+    * * to_key - The key of the second Vertex
+    *
+    * # Return
+    * The capacity that could still be pushed from the first Vertex to the
+    * second one, 0 if none.
+    */
+    pub fn residual_capacity(&self, from_key: &K, to_key: &K) -> f64 {
+        self.residual.find(&(from_key.clone(), to_key.clone())).map_or(0.0, |&c| c)
+    }
+
+    /**
+    * Get the Vertices reachable from a Vertex in the residual network.
+    *
+    * From the source of a maximum flow they are the side of the source of
+    * a minimum cut, the Edges leaving them are saturated.
+    *
+    * # Arguments
+    * * key - The key of the first Vertex
+    *
+    * # Return
+    * The sorted keys of the reachable Vertices, the Vertex itself included.
+    */
+    pub fn residual_reachable(&self, key: &K) -> Vec<K> {
+        let mut adjacency: TreeMap<&K, Vec<&K>> = TreeMap::new();
+        for (&(ref from_key, ref to_key), &capacity) in self.residual.iter() {
+            if capacity > 0.0 {
+                match adjacency.find_mut(&from_key) {
+                    Some(edges) => { edges.push(to_key); continue },
+                    None        => {}
+                }
+                adjacency.insert(from_key, vec!(to_key));
+            }
+        }
+
+        let mut reached: TreeSet<&K> = TreeSet::new();
+        let mut queue = RingBuf::new();
+        reached.insert(key);
+        queue.push_back(key);
+        while !queue.is_empty() {
+            let v = queue.pop_front().unwrap();
+            match adjacency.find(&v) {
+                Some(edges) => {
+                    for &u in edges.iter() {
+                        if reached.insert(u) {
+                            queue.push_back(u);
+                        }
+                    }
+                },
+                None        => {}
+            }
+        }
+        reached.iter().map(|k| (*k).clone()).collect()
+    }
+}
+
+/**
+* Compute a maximum flow between two Vertices with the algorithm of Dinic.
+*
+* The Edges without capacity and the self loops are ignored and the
+* capacities must not be negative, the value is infinite if a path is not
+* bounded. The residual network of the flow gives a minimum cut, see
+* residual_reachable, or min_cut to get it directly.
+*
+* # Arguments
+* * graph - The graph carrying the flow
+* * capacities - The capacity of each Edge
+* * source - The key of the Vertex the flow leaves
+* * sink - The key of the Vertex the flow enters
+*
+* # Return
+* Some(flow) with the maximum flow, None if the source or the sink does not
+* exist or if they are the same Vertex.
+*/
+pub fn max_flow<K: Ord + Eq + Clone,
+                L: Eq + Clone,
+                V: PartialEq + Clone,
+                W: WeightProvider<K, f64>>
+                (graph: &Graph<K, L, V>,
+                capacities: &W,
+                source: &K,
+                sink: &K)
+                -> Option<Flow<K>> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let (s, t) = match (indexed.index.find(source), indexed.index.find(sink)) {
+        (Some(&s), Some(&t)) if s != t => (s, t),
+        _                              => return None
+    };
+    let mut network = FlowNetwork::new(indexed.len());
+    let mut edges = Vec::new();
+    for (from, to_vertices) in indexed.out_edges.iter().enumerate() {
+        for &to in to_vertices.iter() {
+            if from == to {
+                continue
+            }
+            let (from_key, to_key) = (indexed.keys.get(from), indexed.keys.get(to));
+            match capacities.weight(from_key, to_key) {
+                Some(capacity) => {
+                    let id = network.add_edge(from, to, capacity);
+                    edges.push((from_key, to_key, id));
+                },
+                None           => {}
+            }
+        }
+    }
+
+    let value = network.max_flow(s, t);
+    Some(to_flow(&network, value, edges.as_slice()))
+}
+
+/**
+* Compute a maximum flow in a Graph whose Vertices have a capacity too.
+*
+* Each Vertex v is split in an entry and an exit joined by an Edge of the
+* capacity of v, the Edges entering v enter the entry and the Edges leaving
+* v leave the exit, then the flow is computed with the algorithm of Dinic
+* and mapped back on the Edges of the Graph. With a capacity of 1 on every
+* Vertex and Edge the value is the number of Vertex disjoint paths.
+*
+* The Vertices without capacity and the source and the sink are not
+* bounded, the Edges without capacity and the self loops are ignored. The
+* capacities must not be negative, the value is infinite if a path is not
+* bounded. The residual network of the flow only has the Edges, not the
+* capacities of the Vertices.
+*
+* # Arguments
+* * graph - The graph carrying the flow
+* * edge_capacities - The capacity of each Edge
+* * vertex_capacities - The capacity of the Vertices going through
+* * source - The key of the Vertex the flow leaves
+* * sink - The key of the Vertex the flow enters
+*
+* # Return
+* Some(flow) with the maximum flow, None if the source or the sink does not
+* exist or if they are the same Vertex.
+*/
+pub fn vertex_capacitated_max_flow<K: Ord + Eq + Clone,
+                                   L: Eq + Clone,
+                                   V: PartialEq + Clone,
+                                   W: WeightProvider<K, f64>>
+                                   (graph: &Graph<K, L, V>,
+                                   edge_capacities: &W,
+                                   vertex_capacities: &PropertyMap<K, f64>,
+                                   source: &K,
+                                   sink: &K)
+                                   -> Option<Flow<K>> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let (s, t) = match (indexed.index.find(source), indexed.index.find(sink)) {
+        (Some(&s), Some(&t)) if s != t => (s, t),
+        _                              => return None
+    };
+
+    // the entry of v is 2v and its exit 2v + 1
+    let mut network = FlowNetwork::new(2 * indexed.len());
+    for (v, key) in indexed.keys.iter().enumerate() {
+        let capacity = if v == s || v == t {
+            f64::INFINITY
+        } else {
+            vertex_capacities.find(key).map_or(f64::INFINITY, |&c| c)
+        };
+        network.add_edge(2 * v, 2 * v + 1, capacity);
+    }
+    let mut edges = Vec::new();
+    for (from, to_vertices) in indexed.out_edges.iter().enumerate() {
+        for &to in to_vertices.iter() {
+            if from == to {
+                continue
+            }
+            let (from_key, to_key) = (indexed.keys.get(from), indexed.keys.get(to));
+            match edge_capacities.weight(from_key, to_key) {
+                Some(capacity) => {
+                    let id = network.add_edge(2 * from + 1, 2 * to, capacity);
+                    edges.push((from_key, to_key, id));
+                },
+                None           => {}
+            }
+        }
+    }
+
+    let value = network.max_flow(2 * s + 1, 2 * t);
+    Some(to_flow(&network, value, edges.as_slice()))
+}
+
+/**
+* Minimum cut between two Vertices.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+*/
+#[deriving(Clone, PartialEq, Show)]
+pub struct MinCut<K> {
+    /// The sum of the capacities of the cut Edges
+    pub value:         f64,
+    /// The sorted keys of the Vertices on the side of the source
+    pub source_side:   Vec<K>,
+    /// The Edges going from the side of the source to the other side
+    pub edges:         Vec<(K, K)>
+}
+
+/**
+* Compute a minimum cut separating two Vertices, the Edges of least total
+* capacity whose removal leaves no path from the source to the sink.
+*
+* The cut is found from a maximum flow computed with the algorithm of
+* Dinic, its source side is the set of Vertices still reachable from the
+* source in the residual network. The Edges without capacity and the self
+* loops are ignored and the capacities must not be negative.
+*
+* # Arguments
+* * graph - The graph to cut
+* * capacities - The capacity of each Edge
+* * source - The key of the Vertex on the first side
+* * sink - The key of the Vertex on the other side
+*
+* # Return
+* Some(cut) with a minimum cut, None if the source or the sink does not
+* exist or if they are the same Vertex.
+*/
+pub fn min_cut<K: Ord + Eq + Clone,
+               L: Eq + Clone,
+               V: PartialEq + Clone,
+               W: WeightProvider<K, f64>>
+               (graph: &Graph<K, L, V>,
+               capacities: &W,
+               source: &K,
+               sink: &K)
+               -> Option<MinCut<K>> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let (s, t) = match (indexed.index.find(source), indexed.index.find(sink)) {
+        (Some(&s), Some(&t)) if s != t => (s, t),
+        _                              => return None
+    };
+    let mut network = FlowNetwork::new(indexed.len());
+    let mut edges = Vec::new();
+    for (from, to_vertices) in indexed.out_edges.iter().enumerate() {
+        for &to in to_vertices.iter() {
+            if from == to {
+                continue
+            }
+            match capacities.weight(indexed.keys.get(from), indexed.keys.get(to)) {
+                Some(capacity) => {
+                    network.add_edge(from, to, capacity);
+                    edges.push((from, to, capacity));
+                },
+                None           => {}
+            }
+        }
+    }
+
+    let value = network.max_flow(s, t);
+    let side = network.levels(s);
+    let mut source_side: Vec<K> = range(0, indexed.len()).filter(|&v| side.get(v).is_some())
+                                                          .map(|v| indexed.keys.get(v).clone())
+                                                          .collect();
+    source_side.sort();
+    let mut cut_edges = Vec::new();
+    for &(from, to, capacity) in edges.iter() {
+        if capacity > 0.0 && side.get(from).is_some() && side.get(to).is_none() {
+            cut_edges.push((indexed.keys.get(from).clone(), indexed.keys.get(to).clone()));
+        }
+    }
+    Some(MinCut { value: value, source_side: source_side, edges: cut_edges })
+}
+
+// the flow and the residual network of the (from_key, to_key, id) of the
+// Edges of a Graph
+fn to_flow<K: Ord + Eq + Clone>(network: &FlowNetwork,
+                                value: f64,
+                                edges: &[(&K, &K, uint)])
+                                -> Flow<K> {
+    let mut flow = Flow {
+        value:      value,
+        edges:      EdgePropertyMap::new(),
+        residual:   EdgePropertyMap::new()
+    };
+    for &(from_key, to_key, id) in edges.iter() {
+        let f = network.flow(id);
+        if f > 0.0 {
+            flow.edges.insert((from_key.clone(), to_key.clone()), f);
+        }
+        for &(a, b, capacity) in [(from_key, to_key, network.residual(id)),
+                                  (to_key, from_key, f)].iter() {
+            if capacity > 0.0 {
+                let pair = (a.clone(), b.clone());
+                let total = flow.residual.find(&pair).map_or(0.0, |&c| c) + capacity;
+                flow.residual.insert(pair, total);
+            }
+        }
+    }
+    flow
+}
+
+#[cfg(test)]
+mod test {
+    use graph::Graph;
+    use weight::UnitWeights;
+    use super::max_flow;
+
+    // a max flow with unit capacities on a large Graph, the augmenting
+    // paths are as long as the Graph
+    fn check(edges: Vec<(uint, uint, Option<()>)>, source: uint, sink: uint, value: f64) {
+        let graph: Graph<uint, (), ()> = Graph::from_edges(edges);
+        let flow = max_flow(&graph, &UnitWeights, &source, &sink).unwrap();
+        assert_eq!(flow.value, value);
+        let mut leaving = 0.0;
+        for (&(from_key, _), &f) in flow.edges.iter() {
+            if from_key == source {
+                leaving += f;
+            }
+        }
+        assert_eq!(leaving, value);
+    }
+
+    #[test]
+    fn long_path() {
+        let n = 100000u;
+        let edges = range(0, n - 1).map(|v| (v, v + 1, None)).collect();
+        check(edges, 0, n - 1, 1.0);
+    }
+
+    #[test]
+    fn wide_layers() {
+        // 1000 layers of 100 Vertices, each one linked to two Vertices of
+        // the next layer
+        let (layers, width) = (1000u, 100u);
+        let source = layers * width;
+        let sink = source + 1;
+        let mut edges = Vec::new();
+        for i in range(0, width) {
+            edges.push((source, i, None));
+            edges.push(((layers - 1) * width + i, sink, None));
+        }
+        for l in range(0, layers - 1) {
+            for i in range(0, width) {
+                edges.push((l * width + i, (l + 1) * width + i, None));
+                edges.push((l * width + i, (l + 1) * width + (i + 1) % width, None));
+            }
+        }
+        check(edges, source, sink, width as f64);
+    }
+}
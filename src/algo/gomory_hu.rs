@@ -0,0 +1,165 @@
+//! Gomory-Hu tree of the minimum cuts of an undirected Graph.
+
+use std::collections::TreeMap;
+
+use graph::Graph;
+use indexed::IndexedGraph;
+use weight::WeightProvider;
+use flow_network::FlowNetwork;
+
+/**
+* Tree on the Vertices of a Graph where the minimum cut between two
+* Vertices is the lightest Edge of the path between them.
+*
+* Removing an Edge of the tree splits the Vertices in the two sides of a
+* minimum cut between its ends, of the weight of the Edge.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+*/
+#[deriving(Clone, PartialEq, Show)]
+pub struct GomoryHuTree<K> {
+    keys:              Vec<K>,
+    index:             TreeMap<K, uint>,
+    // the parent of each Vertex and the weight of the Edge to it, the
+    // first Vertex is the root
+    parent:            Vec<uint>,
+    weight:            Vec<f64>
+}
+
+impl<K: Ord + Eq + Clone> GomoryHuTree<K> {
+
+    /**
+    * Get the Edges of the tree.
+    *
+    * # Return
+    * The (key, parent_key, weight) of each Edge.
+    */
+    pub fn edges(&self) -> Vec<(K, K, f64)> {
+        range(1, self.keys.len()).map(|v| {
+            (self.keys.get(v).clone(), self.keys.get(*self.parent.get(v)).clone(), *self.weight.get(v))
+        }).collect()
+    }
+
+    /**
+    * Get the value of a minimum cut between two Vertices.
+    *
+    * # Arguments
+    * * from_key - The key of the first Vertex
+    * * to_key - The key of the second Vertex
+    *
+    * # Return
+    * Some(value) with the weight of the lightest Edge between them, None if
+    * a Vertex does not exist or if they are the same Vertex.
+    */
+    pub fn min_cut_between(&self, from_key: &K, to_key: &K) -> Option<f64> {
+        let (mut a, mut b) = match (self.index.find(from_key), self.index.find(to_key)) {
+            (Some(&a), Some(&b)) if a != b => (a, b),
+            _                              => return None
+        };
+        let (mut depth_a, mut depth_b) = (self.depth(a), self.depth(b));
+        let mut lightest: Option<f64> = None;
+        while a != b {
+            let v = if depth_a >= depth_b {
+                depth_a -= 1;
+                let v = a;
+                a = *self.parent.get(a);
+                v
+            } else {
+                depth_b -= 1;
+                let v = b;
+                b = *self.parent.get(b);
+                v
+            };
+            let w = *self.weight.get(v);
+            lightest = match lightest {
+                Some(l) if l <= w => Some(l),
+                _                 => Some(w)
+            };
+        }
+        lightest
+    }
+
+    fn depth(&self, v: uint) -> uint {
+        let mut depth = 0;
+        let mut v = v;
+        while v != 0 {
+            v = *self.parent.get(v);
+            depth += 1;
+        }
+        depth
+    }
+}
+
+/**
+* Build the Gomory-Hu tree of an undirected Graph with the algorithm of
+* Gusfield, from n - 1 maximum flows on the Graph itself.
+*
+* In a directed Graph each Edge is taken as an undirected Edge of its
+* capacity, two opposite Edges are two parallel undirected Edges. The
+* Edges without capacity and the self loops are ignored and the capacities
+* must not be negative.
+*
+* # Arguments
+* * graph - The graph to cut
+* * capacities - The capacity of each Edge
+*
+* # Return
+* The Gomory-Hu tree of the Graph.
+*/
+pub fn gomory_hu_tree<K: Ord + Eq + Clone,
+                      L: Eq + Clone,
+                      V: PartialEq + Clone,
+                      W: WeightProvider<K, f64>>
+                      (graph: &Graph<K, L, V>,
+                      capacities: &W)
+                      -> GomoryHuTree<K> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let n = indexed.len();
+    let mut edges = Vec::new();
+    for (from, to_vertices) in indexed.out_edges.iter().enumerate() {
+        for &to in to_vertices.iter() {
+            // an undirected Graph stores each Edge in both directions
+            if from == to || (!graph.is_directed() && from > to) {
+                continue
+            }
+            match capacities.weight(indexed.keys.get(from), indexed.keys.get(to)) {
+                Some(capacity) => edges.push((from, to, capacity)),
+                None           => {}
+            }
+        }
+    }
+
+    let mut parent = Vec::from_elem(n, 0u);
+    let mut weight = Vec::from_elem(n, 0.0f64);
+    for s in range(1, n) {
+        let t = *parent.get(s);
+        let mut network = FlowNetwork::new(n);
+        for &(from, to, capacity) in edges.iter() {
+            network.add_edge(from, to, capacity);
+            network.add_edge(to, from, capacity);
+        }
+        let value = network.max_flow(s, t);
+        let side = network.levels(s);
+        for v in range(0, n) {
+            if v != s && side.get(v).is_some() && *parent.get(v) == t {
+                *parent.get_mut(v) = s;
+            }
+        }
+        *weight.get_mut(s) = value;
+        let grand_parent = *parent.get(t);
+        if side.get(grand_parent).is_some() {
+            *parent.get_mut(s) = grand_parent;
+            *parent.get_mut(t) = s;
+            *weight.get_mut(s) = *weight.get(t);
+            *weight.get_mut(t) = value;
+        }
+    }
+
+    GomoryHuTree {
+        keys:       indexed.keys.clone(),
+        index:      indexed.index.clone(),
+        parent:     parent,
+        weight:     weight
+    }
+}
@@ -0,0 +1,390 @@
+//! Graph and subgraph isomorphisms.
+
+use std::collections::TreeMap;
+
+use graph::Graph;
+
+/**
+* Check if two Graphs are isomorphic, ignoring the labels and the values.
+*
+* # Arguments
+* * a - The first graph
+* * b - The second graph
+*
+* # Return
+* true if the Vertices of a can be mapped one to one to the Vertices of b
+* such that there is an Edge between two Vertices of a exactly when there
+* is one between their images in b, false otherwise.
+*/
+pub fn is_isomorphic<K: Ord + Eq + Clone,
+                     L: Eq + Clone,
+                     V: PartialEq + Clone>
+                     (a: &Graph<K, L, V>,
+                     b: &Graph<K, L, V>)
+                     -> bool {
+    isomorphisms(a, b, |_, _| true, |_, _| true).next().is_some()
+}
+
+/**
+* Enumerate the isomorphisms between two Graphs with the algorithm VF2 of
+* Cordella et al.
+*
+* # Arguments
+* * a - The first graph
+* * b - The second graph
+* * vertex_match - Check if a Vertex of a can be mapped to a Vertex of b
+* from their labels
+* * edge_match - Check if an Edge of a can be mapped to an Edge of b from
+* their values
+*
+* # Return
+* An iterator over the isomorphisms, each one the (a_key, b_key) of every
+* Vertex of a sorted by a_key.
+*/
+pub fn isomorphisms<K: Ord + Eq + Clone,
+                    L: Eq + Clone,
+                    V: PartialEq + Clone>
+                    (a: &Graph<K, L, V>,
+                    b: &Graph<K, L, V>,
+                    vertex_match: |Option<&L>, Option<&L>| -> bool,
+                    edge_match: |Option<&V>, Option<&V>| -> bool)
+                    -> Isomorphisms<K> {
+    let mut search = Isomorphisms::new(a, b, vertex_match, edge_match, true);
+    if a.len() != b.len() || a.edge_count() != b.edge_count() {
+        search.stack.clear();
+        search.trivial = false;
+    }
+    search
+}
+
+/**
+* Enumerate the mappings of a pattern Graph onto the induced subgraphs of a
+* target Graph with the algorithm VF2 of Cordella et al.
+*
+* Each Vertex of the pattern is mapped to a distinct Vertex of the target
+* such that there is an Edge between two Vertices of the pattern exactly
+* when there is one between their images. The mappings are searched as
+* they are iterated, a Vertex of the pattern having an already mapped
+* neighbor only tries the neighbors of its image.
+*
+* ```ignore
+* let same_label = |a: Option<&String>, b: Option<&String>| a == b;
+* for mapping in subgraph_isomorphisms(&pattern, &target, same_label, |_, _| true) {
+*     println!("{}", mapping);
+* }
+* ```
+*
+* # Arguments
+* * pattern - The graph to search
+* * target - The graph to search in
+* * vertex_match - Check if a Vertex of the pattern can be mapped to a
+* Vertex of the target from their labels
+* * edge_match - Check if an Edge of the pattern can be mapped to an Edge
+* of the target from their values
+*
+* # Return
+* An iterator over the mappings, each one the (pattern_key, target_key) of
+* every Vertex of the pattern sorted by pattern_key.
+*/
+pub fn subgraph_isomorphisms<K: Ord + Eq + Clone,
+                             L: Eq + Clone,
+                             V: PartialEq + Clone>
+                             (pattern: &Graph<K, L, V>,
+                             target: &Graph<K, L, V>,
+                             vertex_match: |Option<&L>, Option<&L>| -> bool,
+                             edge_match: |Option<&V>, Option<&V>| -> bool)
+                             -> Isomorphisms<K> {
+    Isomorphisms::new(pattern, target, vertex_match, edge_match, false)
+}
+
+/// Iterator over the mappings of a pattern Graph onto a target Graph.
+pub struct Isomorphisms<K> {
+    pattern_keys:      Vec<K>,
+    target_keys:       Vec<K>,
+    // the (neighbor, class of the value) of the Edges leaving and entering
+    // each Vertex, sorted by neighbor
+    pattern_out:       Vec<Vec<(uint, uint)>>,
+    pattern_in:        Vec<Vec<(uint, uint)>>,
+    target_out:        Vec<Vec<(uint, uint)>>,
+    target_in:         Vec<Vec<(uint, uint)>>,
+    // compatibility of the Vertices and of the classes of the values
+    vertices_match:    Vec<Vec<bool>>,
+    values_match:      Vec<Vec<bool>>,
+    // the same degrees are required to map the whole target
+    exact:             bool,
+    // the Vertices of the pattern in the order they are mapped
+    order:             Vec<uint>,
+    // the image of each Vertex of the pattern and the antecedent of each
+    // Vertex of the target
+    core_pattern:      Vec<Option<uint>>,
+    core_target:       Vec<Option<uint>>,
+    // the candidates of each mapped depth and the position of the current
+    // one
+    stack:             Vec<(Vec<uint>, uint)>,
+    // the empty pattern has one mapping, not given yet
+    trivial:           bool
+}
+
+impl<K: Ord + Eq + Clone> Isomorphisms<K> {
+    fn new<L: Eq + Clone,
+           V: PartialEq + Clone>
+           (pattern: &Graph<K, L, V>,
+           target: &Graph<K, L, V>,
+           vertex_match: |Option<&L>, Option<&L>| -> bool,
+           edge_match: |Option<&V>, Option<&V>| -> bool,
+           exact: bool)
+           -> Isomorphisms<K> {
+        let pattern_values = value_classes(pattern);
+        let target_values = value_classes(target);
+        let (pattern_keys, pattern_out, pattern_in) = adjacency(pattern, &pattern_values);
+        let (target_keys, target_out, target_in) = adjacency(target, &target_values);
+
+        let vertices_match = pattern.vertices_iter().map(|(_, a)| {
+            target.vertices_iter().map(|(_, b)| vertex_match(a, b)).collect()
+        }).collect();
+        let values_match = pattern_values.iter().map(|a| {
+            target_values.iter().map(|b| edge_match(a.as_ref(), b.as_ref())).collect()
+        }).collect();
+
+        let np = pattern_keys.len();
+        let nt = target_keys.len();
+        let mut search = Isomorphisms {
+            pattern_keys:   pattern_keys,
+            target_keys:    target_keys,
+            pattern_out:    pattern_out,
+            pattern_in:     pattern_in,
+            target_out:     target_out,
+            target_in:      target_in,
+            vertices_match: vertices_match,
+            values_match:   values_match,
+            exact:          exact,
+            order:          Vec::new(),
+            core_pattern:   Vec::from_elem(np, None),
+            core_target:    Vec::from_elem(nt, None),
+            stack:          Vec::new(),
+            trivial:        np == 0
+        };
+        search.order = search.matching_order();
+        if np > 0 && np <= nt {
+            let candidates = search.candidates(0);
+            search.stack.push((candidates, 0));
+        }
+        search
+    }
+
+    // the Vertices of the pattern, each one with the most neighbors among
+    // the previous ones then the highest degree, so the mapped Vertices
+    // stay connected and the search is pruned early
+    fn matching_order(&self) -> Vec<uint> {
+        let np = self.pattern_keys.len();
+        let mut ordered = Vec::from_elem(np, false);
+        let mut links = Vec::from_elem(np, 0u);
+        let mut order = Vec::with_capacity(np);
+        for _ in range(0, np) {
+            let mut best: Option<uint> = None;
+            for v in range(0, np) {
+                if *ordered.get(v) {
+                    continue
+                }
+                best = match best {
+                    Some(b) if (*links.get(b), self.pattern_degree(b)) >=
+                               (*links.get(v), self.pattern_degree(v)) => Some(b),
+                    _                                                  => Some(v)
+                };
+            }
+            let v = best.unwrap();
+            *ordered.get_mut(v) = true;
+            order.push(v);
+            for &(u, _) in self.pattern_out.get(v).iter().chain(self.pattern_in.get(v).iter()) {
+                *links.get_mut(u) += 1;
+            }
+        }
+        order
+    }
+
+    fn pattern_degree(&self, v: uint) -> uint {
+        self.pattern_out.get(v).len() + self.pattern_in.get(v).len()
+    }
+
+    // the Vertices of the target the Vertex of the pattern at depth may be
+    // mapped to: the neighbors of the image of a mapped neighbor, or every
+    // unmapped Vertex
+    fn candidates(&self, depth: uint) -> Vec<uint> {
+        if depth == self.order.len() {
+            return Vec::new()
+        }
+        let p = *self.order.get(depth);
+        for &(q, _) in self.pattern_in.get(p).iter() {
+            match *self.core_pattern.get(q) {
+                Some(s) => return self.target_out.get(s).iter().map(|&(t, _)| t).collect(),
+                None    => {}
+            }
+        }
+        for &(q, _) in self.pattern_out.get(p).iter() {
+            match *self.core_pattern.get(q) {
+                Some(s) => return self.target_in.get(s).iter().map(|&(t, _)| t).collect(),
+                None    => {}
+            }
+        }
+        range(0, self.target_keys.len()).collect()
+    }
+
+    // the Vertex p of the pattern can be mapped to the Vertex t of the
+    // target given the current mapping
+    fn feasible(&self, p: uint, t: uint) -> bool {
+        if self.core_target.get(t).is_some() || !*self.vertices_match.get(p).get(t) {
+            return false
+        }
+        let (po, pi) = (self.pattern_out.get(p), self.pattern_in.get(p));
+        let (to, ti) = (self.target_out.get(t), self.target_in.get(t));
+        if self.exact {
+            if po.len() != to.len() || pi.len() != ti.len() {
+                return false
+            }
+        } else if po.len() > to.len() || pi.len() > ti.len() {
+            return false
+        }
+        // the self loops and the Edges with the mapped Vertices must match
+        // in both directions
+        if !self.same_edge(find_edge(po.as_slice(), p), find_edge(to.as_slice(), t)) {
+            return false
+        }
+        for (q, image) in self.core_pattern.iter().enumerate() {
+            let s = match *image {
+                Some(s) => s,
+                None    => continue
+            };
+            if !self.same_edge(find_edge(po.as_slice(), q), find_edge(to.as_slice(), s)) ||
+               !self.same_edge(find_edge(pi.as_slice(), q), find_edge(ti.as_slice(), s)) {
+                return false
+            }
+        }
+        true
+    }
+
+    fn same_edge(&self, pattern: Option<uint>, target: Option<uint>) -> bool {
+        match (pattern, target) {
+            (Some(a), Some(b)) => *self.values_match.get(a).get(b),
+            (None, None)       => true,
+            _                  => false
+        }
+    }
+}
+
+impl<K: Ord + Eq + Clone> Iterator<Vec<(K, K)>> for Isomorphisms<K> {
+    fn next(&mut self) -> Option<Vec<(K, K)>> {
+        if self.trivial {
+            self.trivial = false;
+            return Some(Vec::new())
+        }
+        loop {
+            let depth = match self.stack.len() {
+                0 => return None,
+                d => d - 1
+            };
+            let p = *self.order.get(depth);
+            // undo the mapping of the current candidate of this depth
+            match *self.core_pattern.get(p) {
+                Some(t) => {
+                    *self.core_pattern.get_mut(p) = None;
+                    *self.core_target.get_mut(t) = None;
+                },
+                None    => {}
+            }
+
+            let next = {
+                let frame = self.stack.get_mut(depth);
+                let position = *frame.ref1();
+                if position < frame.ref0().len() {
+                    *frame.mut1() += 1;
+                    Some(*frame.ref0().get(position))
+                } else {
+                    None
+                }
+            };
+            let t = match next {
+                Some(t) => t,
+                None    => {
+                    self.stack.pop();
+                    continue
+                }
+            };
+            if !self.feasible(p, t) {
+                continue
+            }
+            *self.core_pattern.get_mut(p) = Some(t);
+            *self.core_target.get_mut(t) = Some(p);
+
+            if depth + 1 == self.order.len() {
+                return Some(self.pattern_keys.iter().zip(self.core_pattern.iter()).map(|(key, t)| {
+                    (key.clone(), self.target_keys.get(t.unwrap()).clone())
+                }).collect())
+            }
+            let candidates = self.candidates(depth + 1);
+            self.stack.push((candidates, 0));
+        }
+    }
+}
+
+// the distinct values of the Edges of a Graph, in the order they are met
+fn value_classes<K: Ord + Eq + Clone,
+                 L: Eq + Clone,
+                 V: PartialEq + Clone>
+                 (graph: &Graph<K, L, V>) -> Vec<Option<V>> {
+    let mut values: Vec<Option<V>> = Vec::new();
+    for vertex in graph.vertices_ref_iter() {
+        for (_, value) in vertex.edges_iter() {
+            if !values.iter().any(|v| v.as_ref() == value) {
+                values.push(value.map(|v| v.clone()));
+            }
+        }
+    }
+    values
+}
+
+// the keys and the (neighbor, class) of the Edges leaving and entering each
+// Vertex of a Graph
+fn adjacency<K: Ord + Eq + Clone,
+             L: Eq + Clone,
+             V: PartialEq + Clone>
+             (graph: &Graph<K, L, V>,
+             classes: &Vec<Option<V>>)
+             -> (Vec<K>, Vec<Vec<(uint, uint)>>, Vec<Vec<(uint, uint)>>) {
+    let keys: Vec<K> = graph.vertices_iter().map(|(key, _)| key.clone()).collect();
+    let index: TreeMap<&K, uint> = keys.iter().enumerate().map(|(i, key)| (key, i)).collect();
+    let mut out_edges = Vec::from_elem(keys.len(), Vec::new());
+    let mut in_edges = Vec::from_elem(keys.len(), Vec::new());
+    for (from, vertex) in graph.vertices_ref_iter().enumerate() {
+        for (to_key, value) in vertex.edges_iter() {
+            let to = match index.find(&to_key) {
+                Some(&to) => to,
+                None      => continue
+            };
+            let class = classes.iter().position(|c| c.as_ref() == value).unwrap();
+            out_edges.get_mut(from).push((to, class));
+            in_edges.get_mut(to).push((from, class));
+        }
+    }
+    for edges in out_edges.mut_iter().chain(in_edges.mut_iter()) {
+        edges.sort();
+    }
+    (keys, out_edges, in_edges)
+}
+
+// the class of the Edge to a Vertex in a sorted adjacency list
+fn find_edge(edges: &[(uint, uint)], v: uint) -> Option<uint> {
+    let mut low = 0;
+    let mut high = edges.len();
+    while low < high {
+        let mid = low + (high - low) / 2;
+        let (u, class) = edges[mid];
+        if u < v {
+            low = mid + 1;
+        } else if u > v {
+            high = mid;
+        } else {
+            return Some(class)
+        }
+    }
+    None
+}
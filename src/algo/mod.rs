@@ -0,0 +1,34 @@
+//! Algorithms computing new Graphs or structures from a Graph.
+
+pub use self::spanner::greedy_spanner;
+pub use self::arborescence::minimum_spanning_arborescence;
+pub use self::condensation::{strongly_connected_components, strongly_connected_components_view,
+                             condensation};
+pub use self::flow::{Flow, MinCut, max_flow, vertex_capacitated_max_flow, min_cut};
+pub use self::gomory_hu::{GomoryHuTree, gomory_hu_tree};
+pub use self::assignment::{Assignment, hungarian};
+pub use self::edit_distance::{EditCosts, UniformCosts, EditDistance, graph_edit_distance};
+pub use self::dijkstra::{ShortestPaths, dijkstra, shortest_path};
+pub use self::shortest_path::{PathConstraints, constrained_shortest_path, shortest_path_view};
+pub use self::voronoi::{nearest_seeds, weighted_nearest_seeds};
+pub use self::pagerank::pagerank;
+pub use self::bipartite::{is_bipartite, maximum_bipartite_matching};
+pub use self::closure::{ReachabilityIndex, transitive_closure, transitive_reduction};
+pub use self::clique::{MaximalCliques, maximal_cliques};
+pub use self::isomorphism::{Isomorphisms, is_isomorphic, isomorphisms, subgraph_isomorphisms};
+
+mod spanner;
+mod arborescence;
+mod condensation;
+mod flow;
+mod gomory_hu;
+mod assignment;
+mod edit_distance;
+mod dijkstra;
+mod shortest_path;
+mod voronoi;
+mod pagerank;
+mod bipartite;
+mod closure;
+mod clique;
+mod isomorphism;
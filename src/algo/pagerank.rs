@@ -0,0 +1,81 @@
+//! Ranking of the Vertices by the PageRank random walk.
+
+use graph::Graph;
+use indexed::IndexedGraph;
+use property_map::PropertyMap;
+
+/**
+* Compute the PageRank of each Vertex of a Graph.
+*
+* The random surfer follows an Edge of its Vertex with the probability
+* damping, otherwise or from a Vertex without Edge it teleports. Without
+* teleport vector it teleports to any Vertex, with one it teleports to a
+* Vertex in proportion of its weight in the vector, the personalized
+* PageRank. See parallel::pagerank to spread the work over several tasks.
+*
+* # Arguments
+* * graph - The graph to rank
+* * damping - The probability to follow an Edge instead of teleporting,
+* usually 0.85
+* * iterations - The number of iterations to run
+* * teleport - The weight of the Vertices the surfer teleports to, the
+* missing Vertices have a weight of 0 and the weights are normalized
+*
+* # Return
+* The rank of each Vertex, the ranks sum to 1. With a teleport vector
+* without positive weight the teleports are uniform.
+*/
+pub fn pagerank<K: Ord + Eq + Clone,
+                L: Eq + Clone,
+                V: PartialEq + Clone>
+                (graph: &Graph<K, L, V>,
+                damping: f64,
+                iterations: uint,
+                teleport: Option<&PropertyMap<K, f64>>)
+                -> PropertyMap<K, f64> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let n = indexed.len();
+    if n == 0 {
+        return PropertyMap::new()
+    }
+
+    // the probability to teleport to each Vertex
+    let mut jump: Vec<f64> = match teleport {
+        Some(weights) => indexed.keys.iter().map(|key| {
+            match weights.find(key) {
+                Some(&w) if w > 0.0 => w,
+                _                   => 0.0
+            }
+        }).collect(),
+        None          => Vec::new()
+    };
+    let total = jump.iter().fold(0.0, |sum, &w| sum + w);
+    if total > 0.0 {
+        for w in jump.mut_iter() {
+            *w /= total;
+        }
+    } else {
+        jump = Vec::from_elem(n, 1.0 / n as f64);
+    }
+
+    let mut scores = jump.clone();
+    for _ in range(0, iterations) {
+        // the surfers on the dangling Vertices always teleport
+        let mut teleported = 1.0 - damping;
+        for (v, score) in scores.iter().enumerate() {
+            if indexed.out_edges.get(v).is_empty() {
+                teleported += damping * *score;
+            }
+        }
+        let mut next: Vec<f64> = jump.iter().map(|&j| teleported * j).collect();
+        for (v, score) in scores.iter().enumerate() {
+            let edges = indexed.out_edges.get(v);
+            let share = damping * *score / edges.len() as f64;
+            for &u in edges.iter() {
+                *next.get_mut(u) += share;
+            }
+        }
+        scores = next;
+    }
+    indexed.keys.move_iter().zip(scores.move_iter()).collect()
+}
@@ -0,0 +1,279 @@
+//! Shortest paths on weighted Graphs.
+
+use std::collections::TreeSet;
+
+use graph::Graph;
+use indexed::IndexedGraph;
+use view::GraphView;
+use weight::WeightProvider;
+use heap::MinHeap;
+
+/**
+* Constraints on the paths searched by constrained_shortest_path.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+*/
+#[deriving(Clone, PartialEq, Show)]
+pub struct PathConstraints<K> {
+    forbidden_vertices:    TreeSet<K>,
+    forbidden_edges:       TreeSet<(K, K)>,
+    via:                   Vec<K>
+}
+
+impl<K: Ord + Eq + Clone> PathConstraints<K> {
+
+    /**
+    * Create new PathConstraints allowing every path.
+    */
+    pub fn new() -> PathConstraints<K> {
+        PathConstraints {
+            forbidden_vertices: TreeSet::new(),
+            forbidden_edges:    TreeSet::new(),
+            via:                Vec::new()
+        }
+    }
+
+    /**
+    * Forbid the paths going through a Vertex.
+    *
+    * Forbidding the source or the target leaves no path.
+    */
+    pub fn forbid_vertex(mut self, key: K) -> PathConstraints<K> {
+        self.forbidden_vertices.insert(key);
+        self
+    }
+
+    /**
+    * Forbid the paths using an Edge.
+    *
+    * On an undirected Graph the Edge is forbidden in both directions.
+    */
+    pub fn forbid_edge(mut self, from_key: K, to_key: K) -> PathConstraints<K> {
+        self.forbidden_edges.insert((from_key, to_key));
+        self
+    }
+
+    /**
+    * Require the paths to go through a Vertex, after the Vertices already
+    * required.
+    */
+    pub fn via(mut self, key: K) -> PathConstraints<K> {
+        self.via.push(key);
+        self
+    }
+}
+
+/**
+* Find a shortest path between two Vertices respecting constraints, with
+* the algorithm of Dijkstra.
+*
+* The path follows the direction of the Edges, avoids the forbidden
+* Vertices and Edges and goes through the required Vertices in their
+* order. It is made of a shortest path between each two consecutive
+* required Vertices, so it may go several times through a Vertex. The
+* Edges without weight are ignored and the weights must not be negative.
+*
+* ```ignore
+* let constraints = PathConstraints::new().forbid_vertex("b".to_string())
+*                                         .via("d".to_string());
+* let path = constrained_shortest_path(&graph, &weights, &a, &e, &constraints);
+* ```
+*
+* # Arguments
+* * graph - The graph to search
+* * weights - The weight of each Edge
+* * source - The key of the first Vertex
+* * target - The key of the last Vertex
+* * constraints - The constraints of the path
+*
+* # Return
+* Some((length, path)) with the length of the path and the keys of its
+* Vertices from the source to the target, None if there is no such path.
+*/
+pub fn constrained_shortest_path<K: Ord + Eq + Clone,
+                                 L: Eq + Clone,
+                                 V: PartialEq + Clone,
+                                 W: WeightProvider<K, f64>>
+                                 (graph: &Graph<K, L, V>,
+                                 weights: &W,
+                                 source: &K,
+                                 target: &K,
+                                 constraints: &PathConstraints<K>)
+                                 -> Option<(f64, Vec<K>)> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let directed = graph.is_directed();
+    let adjacency = weighted_edges(&indexed, weights, |from_key, to_key| {
+        !constraints.forbidden_vertices.contains(to_key) &&
+        !constraints.forbidden_edges.contains(&(from_key.clone(), to_key.clone())) &&
+        (directed ||
+         !constraints.forbidden_edges.contains(&(to_key.clone(), from_key.clone())))
+    });
+
+    let mut stops = vec!(source);
+    stops.extend(constraints.via.iter());
+    stops.push(target);
+    let mut length = 0.0;
+    let mut path = vec!(source.clone());
+    for pair in stops.as_slice().windows(2) {
+        let (from, to) = match (indexed.index.find(pair[0]), indexed.index.find(pair[1])) {
+            (Some(&from), Some(&to)) => (from, to),
+            _                        => return None
+        };
+        if constraints.forbidden_vertices.contains(pair[0]) {
+            return None
+        }
+        let search = dijkstra(&adjacency, &[(from, 0.0)], Some(to));
+        match *search.distances.get(to) {
+            Some(distance) => length += distance,
+            None           => return None
+        }
+        let mut leg = Vec::new();
+        let mut v = to;
+        while v != from {
+            leg.push(indexed.keys.get(v).clone());
+            v = search.predecessors.get(v).unwrap();
+        }
+        path.extend(leg.move_iter().rev());
+    }
+    Some((length, path))
+}
+
+/**
+* Find a shortest path between two Vertices of a view on a Graph, with the
+* algorithm of Dijkstra.
+*
+* The view is indexed first and the path follows the Edges of the view, the
+* weights are asked in the direction of the view. The Edges without weight
+* are ignored and the weights must not be negative.
+*
+* # Arguments
+* * view - The view to search
+* * weights - The weight of each Edge of the view
+* * source - The key of the first Vertex
+* * target - The key of the last Vertex
+*
+* # Return
+* Some((length, path)) with the length of the path and the keys of its
+* Vertices from the source to the target, None if there is no such path.
+*/
+pub fn shortest_path_view<'a,
+                          K: Ord + Eq + Clone,
+                          V,
+                          G: GraphView<'a, K, V>,
+                          W: WeightProvider<K, f64>>
+                          (view: &G,
+                          weights: &W,
+                          source: &K,
+                          target: &K)
+                          -> Option<(f64, Vec<K>)> {
+    let indexed = IndexedGraph::from_view(view);
+    let (from, to) = match (indexed.index.find(source), indexed.index.find(target)) {
+        (Some(&from), Some(&to)) => (from, to),
+        _                        => return None
+    };
+    let adjacency = weighted_edges(&indexed, weights, |_, _| true);
+    let search = dijkstra(&adjacency, &[(from, 0.0)], Some(to));
+    let length = match *search.distances.get(to) {
+        Some(distance) => distance,
+        None           => return None
+    };
+    let mut path = vec!(indexed.keys.get(to).clone());
+    let mut v = to;
+    while v != from {
+        v = search.predecessors.get(v).unwrap();
+        path.push(indexed.keys.get(v).clone());
+    }
+    path.reverse();
+    Some((length, path))
+}
+
+// the weighted Edges leaving each Vertex, keeping the Edges accepted by
+// the filter
+pub fn weighted_edges<K: Ord + Eq + Clone,
+                      W: WeightProvider<K, f64>>
+                      (indexed: &IndexedGraph<K>,
+                      weights: &W,
+                      filter: |&K, &K| -> bool)
+                      -> Vec<Vec<(uint, f64)>> {
+    let mut adjacency = Vec::with_capacity(indexed.len());
+    for (from, to_vertices) in indexed.out_edges.iter().enumerate() {
+        let from_key = indexed.keys.get(from);
+        let mut edges = Vec::with_capacity(to_vertices.len());
+        for &to in to_vertices.iter() {
+            let to_key = indexed.keys.get(to);
+            if !filter(from_key, to_key) {
+                continue
+            }
+            match weights.weight(from_key, to_key) {
+                Some(weight) => edges.push((to, weight)),
+                None         => {}
+            }
+        }
+        adjacency.push(edges);
+    }
+    adjacency
+}
+
+// distances from the closest source, predecessors on the shortest paths
+// and closest source of each Vertex
+pub struct Search {
+    pub distances:     Vec<Option<f64>>,
+    pub predecessors:  Vec<Option<uint>>,
+    pub origins:       Vec<Option<uint>>
+}
+
+// Dijkstra from several sources with their initial distances, stopping
+// when the target is reached
+pub fn dijkstra(adjacency: &Vec<Vec<(uint, f64)>>,
+                sources: &[(uint, f64)],
+                target: Option<uint>)
+                -> Search {
+    let n = adjacency.len();
+    let mut search = Search {
+        distances:      Vec::from_elem(n, None),
+        predecessors:   Vec::from_elem(n, None),
+        origins:        Vec::from_elem(n, None)
+    };
+    let mut done = Vec::from_elem(n, false);
+    let mut heap = MinHeap::new();
+    for &(source, distance) in sources.iter() {
+        let better = match *search.distances.get(source) {
+            Some(d) => distance < d,
+            None    => true
+        };
+        if better {
+            *search.distances.get_mut(source) = Some(distance);
+            *search.origins.get_mut(source) = Some(source);
+            heap.push(distance, source);
+        }
+    }
+
+    loop {
+        let (distance, v) = match heap.pop() {
+            Some(top) => top,
+            None      => break
+        };
+        if *done.get(v) {
+            continue
+        }
+        *done.get_mut(v) = true;
+        if target == Some(v) {
+            break
+        }
+        for &(u, weight) in adjacency.get(v).iter() {
+            let next = distance + weight;
+            let better = match *search.distances.get(u) {
+                Some(d) => next < d,
+                None    => true
+            };
+            if better && !*done.get(u) {
+                *search.distances.get_mut(u) = Some(next);
+                *search.predecessors.get_mut(u) = Some(v);
+                *search.origins.get_mut(u) = *search.origins.get(v);
+                heap.push(next, u);
+            }
+        }
+    }
+    search
+}
@@ -0,0 +1,123 @@
+//! Sparsification of a Graph preserving its distances.
+
+use std::cmp::{Less, Equal, Greater};
+use std::collections::{TreeMap, TreeSet};
+
+use graph::Graph;
+use indexed::IndexedGraph;
+use weight::WeightProvider;
+
+/**
+* Build a t-spanner of a Graph with the greedy algorithm.
+*
+* The Edges are taken by increasing weight and an Edge is kept only when
+* the distance between its Vertices in the Edges already kept is greater
+* than stretch times its weight. The distance between two Vertices in the
+* spanner is then at most stretch times their distance in the Graph, and a
+* (2k - 1)-spanner has O(n^(1 + 1/k)) Edges.
+*
+* The direction of the Edges is ignored for the distances, the kept Edges
+* keep their direction and their value. The Edges without weight are
+* always kept and the weights must not be negative.
+*
+* # Arguments
+* * graph - The graph to sparsify
+* * weights - The weight of each Edge
+* * stretch - The maximum factor between the distances, at least 1
+*
+* # Return
+* A new Graph with every Vertex and the kept Edges.
+*/
+pub fn greedy_spanner<K: Ord + Eq + Clone,
+                      L: Eq + Clone,
+                      V: PartialEq + Clone,
+                      W: WeightProvider<K, f64>>
+                      (graph: &Graph<K, L, V>,
+                      weights: &W,
+                      stretch: f64)
+                      -> Graph<K, L, V> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let mut spanner = Graph::new();
+    for vertex in graph.vertices_ref_iter() {
+        spanner.add_vertex_opt_l(vertex.get_key().clone(), vertex.get_label().map(|l| l.clone()));
+    }
+
+    let mut edges: Vec<(uint, uint, f64, Option<&V>)> = Vec::new();
+    for (from, vertex) in graph.vertices_ref_iter().enumerate() {
+        for (to_key, value) in vertex.edges_iter() {
+            let to = match indexed.index.find(to_key) {
+                Some(&to) => to,
+                None      => continue
+            };
+            match weights.weight(vertex.get_key(), to_key) {
+                Some(weight) => edges.push((from, to, weight, value)),
+                None         => {
+                    spanner.add_edge_opt_v(vertex.get_key().clone(),
+                                           to_key.clone(),
+                                           value.map(|v| v.clone()));
+                }
+            }
+        }
+    }
+    edges.sort_by(|&(_, _, a, _), &(_, _, b, _)| {
+        if a < b { Less } else if a > b { Greater } else { Equal }
+    });
+
+    let mut adjacency: Vec<Vec<(uint, f64)>> = Vec::from_elem(indexed.len(), Vec::new());
+    for &(from, to, weight, value) in edges.iter() {
+        if from == to || within(&adjacency, from, to, stretch * weight) {
+            continue
+        }
+        adjacency.get_mut(from).push((to, weight));
+        adjacency.get_mut(to).push((from, weight));
+        spanner.add_edge_opt_v(indexed.keys.get(from).clone(),
+                               indexed.keys.get(to).clone(),
+                               value.map(|v| v.clone()));
+    }
+    spanner
+}
+
+// Dijkstra cut at bound, true if target is at most at bound from source.
+// The explored ball is small in a sparse spanner, so the closest Vertex is
+// found by scanning the frontier
+fn within(adjacency: &Vec<Vec<(uint, f64)>>,
+          source: uint,
+          target: uint,
+          bound: f64)
+          -> bool {
+    let mut distances: TreeMap<uint, f64> = TreeMap::new();
+    let mut done = TreeSet::new();
+    let mut frontier = vec!((source, 0.0f64));
+    distances.insert(source, 0.0);
+
+    while !frontier.is_empty() {
+        let mut closest = 0;
+        for i in range(1, frontier.len()) {
+            if frontier.get(i).val1() < frontier.get(closest).val1() {
+                closest = i;
+            }
+        }
+        let (v, distance) = frontier.swap_remove(closest).unwrap();
+        if v == target {
+            return true
+        }
+        if !done.insert(v) {
+            continue
+        }
+        for &(u, weight) in adjacency.get(v).iter() {
+            let next = distance + weight;
+            if next > bound || done.contains(&u) {
+                continue
+            }
+            let better = match distances.find(&u) {
+                Some(&d) => next < d,
+                None     => true
+            };
+            if better {
+                distances.insert(u, next);
+                frontier.push((u, next));
+            }
+        }
+    }
+    false
+}
@@ -0,0 +1,109 @@
+//! Partition of a Graph by the nearest of several seed Vertices.
+
+use std::collections::{Deque, RingBuf};
+
+use graph::Graph;
+use indexed::IndexedGraph;
+use property_map::PropertyMap;
+use weight::WeightProvider;
+use algo::shortest_path::{dijkstra, weighted_edges};
+
+/**
+* Find the nearest seed of each Vertex by a breadth first search started
+* from all the seeds at once.
+*
+* The paths follow the direction of the Edges and are counted in Edges,
+* the Vertices at the same distance of several seeds get any of them.
+* The Vertices sharing a seed form a discrete Voronoi cell of the Graph.
+*
+* # Arguments
+* * graph - The graph to search
+* * seeds - The keys of the seeds, the unknown ones are ignored
+*
+* # Return
+* The (seed, distance) of each Vertex reachable from a seed.
+*/
+pub fn nearest_seeds<K: Ord + Eq + Clone,
+                     L: Eq + Clone,
+                     V: PartialEq + Clone>
+                     (graph: &Graph<K, L, V>,
+                     seeds: &[K])
+                     -> PropertyMap<K, (K, uint)> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let mut nearest: Vec<Option<(uint, uint)>> = Vec::from_elem(indexed.len(), None);
+    let mut queue = RingBuf::new();
+    for seed in seeds.iter() {
+        match indexed.index.find(seed) {
+            Some(&s) if nearest.get(s).is_none() => {
+                *nearest.get_mut(s) = Some((s, 0));
+                queue.push_back(s);
+            },
+            _                                    => {}
+        }
+    }
+    while !queue.is_empty() {
+        let v = queue.pop_front().unwrap();
+        let (seed, distance) = nearest.get(v).unwrap();
+        for &u in indexed.out_edges.get(v).iter() {
+            if nearest.get(u).is_none() {
+                *nearest.get_mut(u) = Some((seed, distance + 1));
+                queue.push_back(u);
+            }
+        }
+    }
+
+    let mut labels = PropertyMap::new();
+    for (v, entry) in nearest.iter().enumerate() {
+        match *entry {
+            Some((seed, distance)) => {
+                labels.insert(indexed.keys.get(v).clone(), (indexed.keys.get(seed).clone(), distance));
+            },
+            None                   => {}
+        }
+    }
+    labels
+}
+
+/**
+* Find the nearest seed of each Vertex by the algorithm of Dijkstra started
+* from all the seeds at once.
+*
+* The paths follow the direction of the Edges, the Edges without weight are
+* ignored and the weights must not be negative. The Vertices at the same
+* distance of several seeds get any of them.
+*
+* # Arguments
+* * graph - The graph to search
+* * weights - The weight of each Edge
+* * seeds - The keys of the seeds, the unknown ones are ignored
+*
+* # Return
+* The (seed, distance) of each Vertex reachable from a seed.
+*/
+pub fn weighted_nearest_seeds<K: Ord + Eq + Clone,
+                              L: Eq + Clone,
+                              V: PartialEq + Clone,
+                              W: WeightProvider<K, f64>>
+                              (graph: &Graph<K, L, V>,
+                              weights: &W,
+                              seeds: &[K])
+                              -> PropertyMap<K, (K, f64)> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let adjacency = weighted_edges(&indexed, weights, |_, _| true);
+    let sources: Vec<(uint, f64)> = seeds.iter()
+                                         .filter_map(|seed| indexed.index.find(seed))
+                                         .map(|&s| (s, 0.0))
+                                         .collect();
+    let search = dijkstra(&adjacency, sources.as_slice(), None);
+
+    let mut labels = PropertyMap::new();
+    for v in range(0, indexed.len()) {
+        match (*search.origins.get(v), *search.distances.get(v)) {
+            (Some(seed), Some(distance)) => {
+                labels.insert(indexed.keys.get(v).clone(), (indexed.keys.get(seed).clone(), distance));
+            },
+            _                            => {}
+        }
+    }
+    labels
+}
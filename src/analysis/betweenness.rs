@@ -0,0 +1,375 @@
+//! Betweenness centrality of the Vertices and the Edges.
+
+use std::collections::{Deque, RingBuf};
+use std::rand::Rng;
+
+use graph::Graph;
+use indexed::IndexedGraph;
+use property_map::{PropertyMap, EdgePropertyMap};
+use weight::WeightProvider;
+use heap::MinHeap;
+
+/**
+* Estimation of the betweenness of the Edges of a Graph.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+*/
+#[deriving(Clone, PartialEq, Show)]
+pub struct BetweennessEstimate<K> {
+    /// The estimated betweenness of each Edge, on the scale of
+    /// edge_betweenness
+    pub scores:        EdgePropertyMap<K, f64>,
+    /// The standard error of the estimation of each Edge
+    pub std_errors:    EdgePropertyMap<K, f64>,
+    /// The number of pivots used
+    pub pivots:        uint
+}
+
+/**
+* Compute the betweenness of each Edge of a Graph with the algorithm of
+* Brandes.
+*
+* The betweenness of an Edge is the sum over the ordered pairs of Vertices
+* (s, t) of the fraction of the shortest paths from s to t using the Edge.
+* The paths follow the direction of the Edges and are counted in Edges.
+* The Edges of highest betweenness are the bridges between the dense parts
+* of the Graph, the ones removed by the Girvan-Newman community detection.
+*
+* # Arguments
+* * graph - The graph to measure
+*
+* # Return
+* The betweenness of each Edge, 0 for the self loops and the Edges on no
+* shortest path.
+*/
+pub fn edge_betweenness<K: Ord + Eq + Clone,
+                        L: Eq + Clone,
+                        V: PartialEq + Clone>
+                        (graph: &Graph<K, L, V>) -> EdgePropertyMap<K, f64> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let mut scores = zero_scores(&indexed);
+    for source in range(0, indexed.len()) {
+        for &(v, i, share) in dependencies(&indexed, source).iter() {
+            *scores.get_mut(v).get_mut(i) += share;
+        }
+    }
+    edge_map(&indexed, &scores)
+}
+
+/**
+* Estimate the betweenness of each Edge of a Graph from the shortest paths
+* leaving a sample of pivot Vertices, as proposed by Brandes and Pich.
+*
+* The contributions of the pivots are scaled by the number of Vertices
+* over the number of pivots, so the estimates are unbiased and directly
+* comparable with edge_betweenness. The standard error of each estimate
+* shrinks with the square root of the number of pivots.
+*
+* # Arguments
+* * graph - The graph to measure
+* * pivots - The number of pivots to sample
+* * rng - The random number generator used to choose the pivots
+*
+* # Return
+* The estimation, exact with a standard error of 0 if pivots is at least
+* the number of Vertices.
+*/
+pub fn approximate_edge_betweenness<K: Ord + Eq + Clone,
+                                    L: Eq + Clone,
+                                    V: PartialEq + Clone,
+                                    R: Rng>
+                                    (graph: &Graph<K, L, V>,
+                                    pivots: uint,
+                                    rng: &mut R)
+                                    -> BetweennessEstimate<K> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let n = indexed.len();
+    let mut sources: Vec<uint> = range(0, n).collect();
+    if pivots < n {
+        rng.shuffle(sources.as_mut_slice());
+        sources.truncate(pivots);
+    }
+    let k = sources.len();
+
+    // sums of the contributions scaled to the whole Graph and of their
+    // squares
+    let mut sums = zero_scores(&indexed);
+    let mut sums_sq = zero_scores(&indexed);
+    for &source in sources.iter() {
+        for &(v, i, share) in dependencies(&indexed, source).iter() {
+            let scaled = share * n as f64;
+            *sums.get_mut(v).get_mut(i) += scaled;
+            *sums_sq.get_mut(v).get_mut(i) += scaled * scaled;
+        }
+    }
+
+    let mut scores = zero_scores(&indexed);
+    let mut errors = zero_scores(&indexed);
+    if k > 0 {
+        // sampling without replacement from the n Vertices
+        let correction = if k < n && n > 1 { (n - k) as f64 / (n - 1) as f64 } else { 0.0 };
+        for v in range(0, n) {
+            for i in range(0, scores.get(v).len()) {
+                let mean = *sums.get(v).get(i) / k as f64;
+                *scores.get_mut(v).get_mut(i) = mean;
+                if k > 1 {
+                    let variance = (*sums_sq.get(v).get(i) - k as f64 * mean * mean) / (k - 1) as f64;
+                    *errors.get_mut(v).get_mut(i) = (variance.max(0.0) * correction / k as f64).sqrt();
+                }
+            }
+        }
+    }
+    BetweennessEstimate {
+        scores:     edge_map(&indexed, &scores),
+        std_errors: edge_map(&indexed, &errors),
+        pivots:     k
+    }
+}
+
+/**
+* Compute the betweenness of each Vertex of a Graph with the algorithm of
+* Brandes.
+*
+* The betweenness of a Vertex is the sum over the ordered pairs of other
+* Vertices (s, t) of the fraction of the shortest paths from s to t going
+* through the Vertex. The paths follow the direction of the Edges and are
+* counted in Edges.
+*
+* # Arguments
+* * graph - The graph to measure
+*
+* # Return
+* The betweenness of each Vertex, 0 for the Vertices on no shortest path.
+*/
+pub fn betweenness_centrality<K: Ord + Eq + Clone,
+                              L: Eq + Clone,
+                              V: PartialEq + Clone>
+                              (graph: &Graph<K, L, V>) -> PropertyMap<K, f64> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let mut scores = Vec::from_elem(indexed.len(), 0.0f64);
+    for source in range(0, indexed.len()) {
+        add_vertex_dependencies(&mut scores, source, dependencies(&indexed, source).as_slice());
+    }
+    indexed.keys.move_iter().zip(scores.move_iter()).collect()
+}
+
+/**
+* Compute the betweenness of each Vertex of a Graph with the algorithm of
+* Brandes, the paths being weighted.
+*
+* The shortest paths are searched with the algorithm of Dijkstra, the
+* Edges without weight are ignored and the weights must be positive. Two
+* paths are both shortest only if their lengths are exactly equal.
+*
+* # Arguments
+* * graph - The graph to measure
+* * weights - The weight of each Edge
+*
+* # Return
+* The betweenness of each Vertex, 0 for the Vertices on no shortest path.
+*/
+pub fn weighted_betweenness_centrality<K: Ord + Eq + Clone,
+                                       L: Eq + Clone,
+                                       V: PartialEq + Clone,
+                                       W: WeightProvider<K, f64>>
+                                       (graph: &Graph<K, L, V>,
+                                       weights: &W)
+                                       -> PropertyMap<K, f64> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let adjacency = weighted_adjacency(&indexed, weights);
+    let mut scores = Vec::from_elem(indexed.len(), 0.0f64);
+    for source in range(0, indexed.len()) {
+        let shares = weighted_dependencies(&adjacency, source);
+        add_vertex_dependencies(&mut scores, source, shares.as_slice());
+    }
+    indexed.keys.move_iter().zip(scores.move_iter()).collect()
+}
+
+/**
+* Estimate the betweenness of each Vertex of a Graph from the shortest
+* paths leaving a sample of pivot Vertices.
+*
+* The contributions of the pivots are scaled by the number of Vertices
+* over the number of pivots, so the estimates are unbiased and directly
+* comparable with betweenness_centrality.
+*
+* # Arguments
+* * graph - The graph to measure
+* * pivots - The number of pivots to sample
+* * rng - The random number generator used to choose the pivots
+*
+* # Return
+* The estimated betweenness of each Vertex, exact if pivots is at least
+* the number of Vertices.
+*/
+pub fn approximate_betweenness_centrality<K: Ord + Eq + Clone,
+                                          L: Eq + Clone,
+                                          V: PartialEq + Clone,
+                                          R: Rng>
+                                          (graph: &Graph<K, L, V>,
+                                          pivots: uint,
+                                          rng: &mut R)
+                                          -> PropertyMap<K, f64> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let n = indexed.len();
+    let mut sources: Vec<uint> = range(0, n).collect();
+    if pivots < n {
+        rng.shuffle(sources.as_mut_slice());
+        sources.truncate(pivots);
+    }
+
+    let mut scores = Vec::from_elem(n, 0.0f64);
+    for &source in sources.iter() {
+        add_vertex_dependencies(&mut scores, source, dependencies(&indexed, source).as_slice());
+    }
+    if !sources.is_empty() {
+        let scale = n as f64 / sources.len() as f64;
+        for score in scores.mut_iter() {
+            *score *= scale;
+        }
+    }
+    indexed.keys.move_iter().zip(scores.move_iter()).collect()
+}
+
+// the dependency of a Vertex is the sum of the shares of the Edges leaving
+// it, the source is not between itself and the other Vertices
+fn add_vertex_dependencies(scores: &mut Vec<f64>,
+                           source: uint,
+                           shares: &[(uint, uint, f64)]) {
+    for &(v, _, share) in shares.iter() {
+        if v != source {
+            *scores.get_mut(v) += share;
+        }
+    }
+}
+
+// the (v, i, dependency) of the i-th Edge leaving v on the shortest paths
+// from a source, each Edge appears at most once
+fn dependencies<K: Ord + Eq + Clone>
+               (indexed: &IndexedGraph<K>,
+               source: uint)
+               -> Vec<(uint, uint, f64)> {
+    let n = indexed.len();
+    let mut distance: Vec<Option<uint>> = Vec::from_elem(n, None);
+    let mut paths = Vec::from_elem(n, 0.0f64);
+    // the predecessors of each Vertex and the position of the Edge
+    let mut predecessors: Vec<Vec<(uint, uint)>> = Vec::from_elem(n, Vec::new());
+    let mut order = Vec::with_capacity(n);
+    let mut queue = RingBuf::new();
+    *distance.get_mut(source) = Some(0u);
+    *paths.get_mut(source) = 1.0;
+    queue.push_back(source);
+    while !queue.is_empty() {
+        let v = queue.pop_front().unwrap();
+        order.push(v);
+        let next = distance.get(v).unwrap() + 1;
+        for (i, &w) in indexed.out_edges.get(v).iter().enumerate() {
+            if distance.get(w).is_none() {
+                *distance.get_mut(w) = Some(next);
+                queue.push_back(w);
+            }
+            if *distance.get(w) == Some(next) {
+                *paths.get_mut(w) += *paths.get(v);
+                predecessors.get_mut(w).push((v, i));
+            }
+        }
+    }
+
+    let mut dependency = Vec::from_elem(n, 0.0f64);
+    let mut shares = Vec::new();
+    for &w in order.iter().rev() {
+        for &(v, i) in predecessors.get(w).iter() {
+            let share = *paths.get(v) / *paths.get(w) * (1.0 + *dependency.get(w));
+            shares.push((v, i, share));
+            *dependency.get_mut(v) += share;
+        }
+    }
+    shares
+}
+
+// same as dependencies on the shortest paths weighted by the adjacency,
+// found in order of distance by the algorithm of Dijkstra
+fn weighted_dependencies(adjacency: &Vec<Vec<(uint, f64)>>,
+                         source: uint)
+                         -> Vec<(uint, uint, f64)> {
+    let n = adjacency.len();
+    let mut distance: Vec<Option<f64>> = Vec::from_elem(n, None);
+    let mut paths = Vec::from_elem(n, 0.0f64);
+    let mut predecessors: Vec<Vec<(uint, uint)>> = Vec::from_elem(n, Vec::new());
+    let mut done = Vec::from_elem(n, false);
+    let mut order = Vec::with_capacity(n);
+    let mut heap = MinHeap::new();
+    *distance.get_mut(source) = Some(0.0);
+    *paths.get_mut(source) = 1.0;
+    heap.push(0.0, source);
+    loop {
+        let (d, v) = match heap.pop() {
+            Some(top) => top,
+            None      => break
+        };
+        if *done.get(v) {
+            continue
+        }
+        *done.get_mut(v) = true;
+        order.push(v);
+        for (i, &(w, weight)) in adjacency.get(v).iter().enumerate() {
+            let next = d + weight;
+            match *distance.get(w) {
+                Some(current) if next > current => continue,
+                Some(current) if next == current => {},
+                _                                => {
+                    *distance.get_mut(w) = Some(next);
+                    *paths.get_mut(w) = 0.0;
+                    predecessors.get_mut(w).clear();
+                    heap.push(next, w);
+                }
+            }
+            *paths.get_mut(w) += *paths.get(v);
+            predecessors.get_mut(w).push((v, i));
+        }
+    }
+
+    let mut dependency = Vec::from_elem(n, 0.0f64);
+    let mut shares = Vec::new();
+    for &w in order.iter().rev() {
+        for &(v, i) in predecessors.get(w).iter() {
+            let share = *paths.get(v) / *paths.get(w) * (1.0 + *dependency.get(w));
+            shares.push((v, i, share));
+            *dependency.get_mut(v) += share;
+        }
+    }
+    shares
+}
+
+// the weighted Edges leaving each Vertex, the Edges without weight and the
+// self loops are dropped
+fn weighted_adjacency<K: Ord + Eq + Clone,
+                      W: WeightProvider<K, f64>>
+                      (indexed: &IndexedGraph<K>,
+                      weights: &W)
+                      -> Vec<Vec<(uint, f64)>> {
+    indexed.out_edges.iter().enumerate().map(|(from, to_vertices)| {
+        to_vertices.iter().filter(|&&to| to != from).filter_map(|&to| {
+            weights.weight(indexed.keys.get(from), indexed.keys.get(to)).map(|w| (to, w))
+        }).collect()
+    }).collect()
+}
+
+// a score for each Edge, in the order of the out_edges of each Vertex
+fn zero_scores<K: Ord + Eq + Clone>(indexed: &IndexedGraph<K>) -> Vec<Vec<f64>> {
+    indexed.out_edges.iter().map(|edges| Vec::from_elem(edges.len(), 0.0)).collect()
+}
+
+fn edge_map<K: Ord + Eq + Clone>
+           (indexed: &IndexedGraph<K>,
+           scores: &Vec<Vec<f64>>)
+           -> EdgePropertyMap<K, f64> {
+    let mut map = EdgePropertyMap::new();
+    for (v, edges) in indexed.out_edges.iter().enumerate() {
+        for (i, &w) in edges.iter().enumerate() {
+            map.insert((indexed.keys.get(v).clone(), indexed.keys.get(w).clone()), *scores.get(v).get(i));
+        }
+    }
+    map
+}
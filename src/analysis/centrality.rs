@@ -0,0 +1,170 @@
+//! Centrality of the Vertices from their degree, their distances and the
+//! centrality of their neighbors.
+
+use graph::Graph;
+use indexed::IndexedGraph;
+use property_map::PropertyMap;
+
+/**
+* Compute the harmonic centrality of each Vertex of a Graph.
+*
+* The harmonic centrality of a Vertex is the sum of the inverses of its
+* distances to the other Vertices, an unreachable Vertex adds 0 so unlike
+* the closeness it stays meaningful in a disconnected Graph. The paths
+* follow the direction of the Edges and are counted in Edges.
+*
+* # Arguments
+* * graph - The graph to measure
+*
+* # Return
+* The harmonic centrality of each Vertex, between 0 and the number of
+* Vertices minus one.
+*/
+pub fn harmonic_centrality<K: Ord + Eq + Clone,
+                           L: Eq + Clone,
+                           V: PartialEq + Clone>
+                           (graph: &Graph<K, L, V>) -> PropertyMap<K, f64> {
+    let indexed = IndexedGraph::from_graph(graph);
+    range(0, indexed.len()).map(|v| {
+        let sum = indexed.distances(v, &indexed.out_edges).iter().fold(0.0, |sum, d| {
+            match *d {
+                Some(d) if d > 0 => sum + 1.0 / d as f64,
+                _                => sum
+            }
+        });
+        (indexed.keys.get(v).clone(), sum)
+    }).collect()
+}
+
+/**
+* Compute the degree centrality of each Vertex of a Graph.
+*
+* The degree of a Vertex is the number of Edges starting from it plus the
+* number of Edges ending to it, in an undirected Graph the number of Edges
+* touching it. It is normalized by the number of other Vertices.
+*
+* # Arguments
+* * graph - The graph to measure
+*
+* # Return
+* The degree centrality of each Vertex, between 0 and 1 in a Graph without
+* self loop and 2 in a directed one, 0 in a Graph of a single Vertex.
+*/
+pub fn degree_centrality<K: Ord + Eq + Clone,
+                         L: Eq + Clone,
+                         V: PartialEq + Clone>
+                         (graph: &Graph<K, L, V>) -> PropertyMap<K, f64> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let others = if indexed.len() > 1 { (indexed.len() - 1) as f64 } else { 0.0 };
+    range(0, indexed.len()).map(|v| {
+        let mut degree = indexed.out_edges.get(v).len();
+        if graph.is_directed() {
+            degree += indexed.in_edges.get(v).len();
+        }
+        let centrality = if others > 0.0 { degree as f64 / others } else { 0.0 };
+        (indexed.keys.get(v).clone(), centrality)
+    }).collect()
+}
+
+/**
+* Compute the closeness centrality of each Vertex of a Graph.
+*
+* The closeness of a Vertex is the number of Vertices it reaches over the
+* sum of its distances to them. In a disconnected Graph it is scaled by the
+* fraction of the other Vertices it reaches, as proposed by Wasserman and
+* Faust, so a Vertex reaching few close Vertices is not central. The paths
+* follow the direction of the Edges and are counted in Edges.
+*
+* # Arguments
+* * graph - The graph to measure
+*
+* # Return
+* The closeness centrality of each Vertex, between 0 and 1, 0 for the
+* Vertices reaching no other Vertex.
+*/
+pub fn closeness_centrality<K: Ord + Eq + Clone,
+                            L: Eq + Clone,
+                            V: PartialEq + Clone>
+                            (graph: &Graph<K, L, V>) -> PropertyMap<K, f64> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let n = indexed.len();
+    range(0, n).map(|v| {
+        let mut reached = 0u;
+        let mut total = 0u;
+        for d in indexed.distances(v, &indexed.out_edges).iter() {
+            match *d {
+                Some(d) if d > 0 => {
+                    reached += 1;
+                    total += d;
+                },
+                _                => {}
+            }
+        }
+        let closeness = if total > 0 {
+            reached as f64 / total as f64 * reached as f64 / (n - 1) as f64
+        } else {
+            0.0
+        };
+        (indexed.keys.get(v).clone(), closeness)
+    }).collect()
+}
+
+/**
+* Compute the eigenvector centrality of each Vertex of a Graph by power
+* iteration.
+*
+* A Vertex is central when the Vertices with an Edge to it are central,
+* its centrality is the component of the principal eigenvector of the
+* transposed adjacency matrix. Each iteration adds the previous scores to
+* the new ones, which does not change the eigenvector but makes the
+* iteration converge on a bipartite Graph.
+*
+* # Arguments
+* * graph - The graph to measure
+* * iterations - The maximum number of iterations
+* * tolerance - The iteration stops when the sum of the changes of the
+* scores is below it
+*
+* # Return
+* The eigenvector centrality of each Vertex, the scores have a euclidean
+* norm of 1, or are all 0 in a Graph without Edge.
+*/
+pub fn eigenvector_centrality<K: Ord + Eq + Clone,
+                              L: Eq + Clone,
+                              V: PartialEq + Clone>
+                              (graph: &Graph<K, L, V>,
+                              iterations: uint,
+                              tolerance: f64)
+                              -> PropertyMap<K, f64> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let n = indexed.len();
+    if n == 0 {
+        return PropertyMap::new()
+    }
+
+    let mut scores = Vec::from_elem(n, 1.0 / (n as f64).sqrt());
+    for _ in range(0, iterations) {
+        let mut next = scores.clone();
+        for (v, sources) in indexed.in_edges.iter().enumerate() {
+            for &u in sources.iter() {
+                *next.get_mut(v) += *scores.get(u);
+            }
+        }
+        let norm = next.iter().fold(0.0, |sum, &x| sum + x * x).sqrt();
+        if norm == 0.0 {
+            break
+        }
+        for x in next.mut_iter() {
+            *x /= norm;
+        }
+        let change = scores.iter().zip(next.iter()).fold(0.0, |sum, (&a, &b)| sum + (a - b).abs());
+        scores = next;
+        if change < tolerance {
+            break
+        }
+    }
+    if indexed.out_edges.iter().all(|edges| edges.is_empty()) {
+        scores = Vec::from_elem(n, 0.0);
+    }
+    indexed.keys.move_iter().zip(scores.move_iter()).collect()
+}
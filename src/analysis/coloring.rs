@@ -0,0 +1,197 @@
+//! Vertex coloring and bounds of the chromatic number.
+
+use std::cmp::min;
+
+use graph::Graph;
+use property_map::PropertyMap;
+use indexed::{IndexedGraph, contains_sorted};
+
+/**
+* Color the Vertices of a Graph with the DSATUR heuristic (Brelaz).
+*
+* The Vertex with the most distinct colors among its neighbors is colored
+* first with the smallest available color. The direction of the Edges and
+* the self loops are ignored.
+*
+* # Arguments
+* * graph - The graph to color
+*
+* # Return
+* The color of each Vertex, numbered from 0, two adjacent Vertices never
+* have the same color.
+*/
+pub fn dsatur_coloring<K: Ord + Eq + Clone,
+                       L: Eq + Clone,
+                       V: PartialEq + Clone>
+                       (graph: &Graph<K, L, V>) -> PropertyMap<K, uint> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let neighbors = simple_neighbors(&indexed);
+    let colors = dsatur(&neighbors);
+    indexed.keys.move_iter().zip(colors.move_iter()).collect()
+}
+
+/**
+* Compute bounds of the chromatic number of a Graph.
+*
+* The lower bound is the size of the largest clique found greedily, the
+* upper bound is the number of colors used by DSATUR. The direction of the
+* Edges and the self loops are ignored.
+*
+* # Arguments
+* * graph - The graph to measure
+*
+* # Return
+* A tuple (lower, upper), the chromatic number is between them.
+*/
+pub fn chromatic_bounds<K: Ord + Eq + Clone,
+                        L: Eq + Clone,
+                        V: PartialEq + Clone>
+                        (graph: &Graph<K, L, V>) -> (uint, uint) {
+    let indexed = IndexedGraph::from_graph(graph);
+    let neighbors = simple_neighbors(&indexed);
+    (greedy_clique(&neighbors), color_count(&dsatur(&neighbors)))
+}
+
+/**
+* Compute the exact chromatic number of a small Graph by branch and bound.
+*
+* The search is exponential in the worst case, it starts from the bounds of
+* chromatic_bounds and stops as soon as they meet.
+*
+* # Arguments
+* * graph - The graph to measure
+* * max_vertices - The largest number of Vertices to try to solve
+*
+* # Return
+* Some(chromatic number), None if the Graph has more than max_vertices
+* Vertices.
+*/
+pub fn chromatic_number<K: Ord + Eq + Clone,
+                        L: Eq + Clone,
+                        V: PartialEq + Clone>
+                        (graph: &Graph<K, L, V>,
+                        max_vertices: uint)
+                        -> Option<uint> {
+    let indexed = IndexedGraph::from_graph(graph);
+    if indexed.len() > max_vertices {
+        return None
+    }
+    let neighbors = simple_neighbors(&indexed);
+    let lower = greedy_clique(&neighbors);
+    let mut best = color_count(&dsatur(&neighbors));
+    let mut colors = Vec::from_elem(indexed.len(), None);
+    branch(&neighbors, &mut colors, 0, 0, lower, &mut best);
+    Some(best)
+}
+
+fn simple_neighbors<K>(indexed: &IndexedGraph<K>) -> Vec<Vec<uint>> {
+    indexed.undirected_edges().move_iter().enumerate().map(|(v, mut ns)| {
+        ns.retain(|&u| u != v);
+        ns
+    }).collect()
+}
+
+fn color_count(colors: &Vec<uint>) -> uint {
+    colors.iter().max().map(|c| *c + 1).unwrap_or(0)
+}
+
+// the number of distinct colors among the neighbors of v
+fn saturation(neighbors: &Vec<Vec<uint>>, colors: &Vec<Option<uint>>, v: uint) -> uint {
+    let mut seen: Vec<uint> = neighbors.get(v).iter().filter_map(|&u| *colors.get(u)).collect();
+    seen.sort();
+    seen.dedup();
+    seen.len()
+}
+
+// the uncolored Vertex with the highest saturation, then the highest degree
+fn most_saturated(neighbors: &Vec<Vec<uint>>, colors: &Vec<Option<uint>>) -> Option<uint> {
+    let mut best = None;
+    let mut best_score = (0u, 0u);
+    for v in range(0, colors.len()).filter(|&v| colors.get(v).is_none()) {
+        let score = (saturation(neighbors, colors, v), neighbors.get(v).len());
+        if best.is_none() || score > best_score {
+            best = Some(v);
+            best_score = score;
+        }
+    }
+    best
+}
+
+fn available(neighbors: &Vec<Vec<uint>>,
+             colors: &Vec<Option<uint>>,
+             v: uint,
+             color: uint)
+             -> bool {
+    neighbors.get(v).iter().all(|&u| *colors.get(u) != Some(color))
+}
+
+fn dsatur(neighbors: &Vec<Vec<uint>>) -> Vec<uint> {
+    let mut colors = Vec::from_elem(neighbors.len(), None);
+    loop {
+        let v = match most_saturated(neighbors, &colors) {
+            Some(v) => v,
+            None    => break
+        };
+        let mut color = 0;
+        while !available(neighbors, &colors, v, color) {
+            color += 1;
+        }
+        *colors.get_mut(v) = Some(color);
+    }
+    colors.move_iter().map(|c| c.unwrap()).collect()
+}
+
+// grow a clique from each Vertex, adding the neighbor of highest degree
+// adjacent to the whole clique
+fn greedy_clique(neighbors: &Vec<Vec<uint>>) -> uint {
+    let mut largest = 0;
+    for v in range(0, neighbors.len()) {
+        let mut candidates = neighbors.get(v).clone();
+        let mut size = 1;
+        loop {
+            let next = candidates.iter().map(|u| *u).max_by(|&u| neighbors.get(u).len());
+            match next {
+                Some(u) => {
+                    size += 1;
+                    candidates.retain(|&w| contains_sorted(neighbors.get(u).as_slice(), w));
+                },
+                None    => break
+            }
+        }
+        if size > largest {
+            largest = size;
+        }
+    }
+    largest
+}
+
+fn branch(neighbors: &Vec<Vec<uint>>,
+          colors: &mut Vec<Option<uint>>,
+          colored: uint,
+          used: uint,
+          lower: uint,
+          best: &mut uint) {
+    if *best <= lower {
+        return
+    }
+    if colored == colors.len() {
+        if used < *best {
+            *best = used;
+        }
+        return
+    }
+    let v = most_saturated(neighbors, colors).unwrap();
+    // a new color is only worth trying if it stays below the best coloring
+    for color in range(0, min(used + 1, *best - 1)) {
+        if *best <= lower || color + 1 >= *best {
+            return
+        }
+        if !available(neighbors, colors, v, color) {
+            continue
+        }
+        *colors.get_mut(v) = Some(color);
+        let used = if color == used { used + 1 } else { used };
+        branch(neighbors, colors, colored + 1, used, lower, best);
+        *colors.get_mut(v) = None;
+    }
+}
@@ -0,0 +1,213 @@
+//! Vertex and edge connectivity.
+
+use std::cmp::min;
+
+use graph::Graph;
+use indexed::{IndexedGraph, contains_sorted};
+use flow_network::FlowNetwork;
+use property_map::PropertyMap;
+use weight::UnitWeights;
+use algo::{vertex_capacitated_max_flow, min_cut};
+
+/**
+* Compute the number of internally Vertex disjoint paths from a Vertex to
+* another one.
+*
+* By the theorem of Menger this is the number of Vertices to remove to cut
+* every path between them when they are not adjacent. The paths follow the
+* direction of the Edges, an Edge between them counts as one path.
+*
+* # Arguments
+* * graph - The graph to measure
+* * from_key - The key of the first Vertex
+* * to_key - The key of the last Vertex
+*
+* # Return
+* Some(connectivity), None if a Vertex does not exist or if they are the
+* same Vertex.
+*/
+pub fn local_vertex_connectivity<K: Ord + Eq + Clone,
+                                 L: Eq + Clone,
+                                 V: PartialEq + Clone>
+                                 (graph: &Graph<K, L, V>,
+                                 from_key: &K,
+                                 to_key: &K)
+                                 -> Option<uint> {
+    let capacities = PropertyMap::from_graph(graph, 1.0f64);
+    vertex_capacitated_max_flow(graph, &UnitWeights, &capacities, from_key, to_key).map(|flow| {
+        flow.value as uint
+    })
+}
+
+/**
+* Compute the vertex connectivity of a Graph, the least number of Vertices
+* to remove so that some Vertex can't reach another one.
+*
+* The paths follow the direction of the Edges, a Graph where every Vertex
+* has an Edge to every other one has a connectivity of its number of
+* Vertices minus one. Uses the algorithm of Even: the connectivity is the
+* least local connectivity between the first k + 1 Vertices and the others
+* they are not adjacent to, k being the least found so far.
+*
+* # Arguments
+* * graph - The graph to measure
+*
+* # Return
+* The vertex connectivity, 0 if the Graph is not strongly connected or has
+* less than two Vertices.
+*/
+pub fn vertex_connectivity<K: Ord + Eq + Clone,
+                           L: Eq + Clone,
+                           V: PartialEq + Clone>
+                           (graph: &Graph<K, L, V>) -> uint {
+    let indexed = IndexedGraph::from_graph(graph);
+    let n = indexed.len();
+    if n < 2 || !graph.is_strongly_connected() {
+        return 0
+    }
+    let adjacent = |from: uint, to: uint| contains_sorted(indexed.out_edges.get(from).as_slice(), to);
+
+    // the network of vertex_capacitated_max_flow with unit capacities, built
+    // once: the entry of v is 2v and its exit 2v + 1, the flow leaves the
+    // exit of the source and enters the entry of the sink
+    let mut network = FlowNetwork::new(2 * n);
+    for v in range(0, n) {
+        network.add_edge(2 * v, 2 * v + 1, 1.0);
+    }
+    for (from, to_vertices) in indexed.out_edges.iter().enumerate() {
+        for &to in to_vertices.iter() {
+            if from != to {
+                network.add_edge(2 * from + 1, 2 * to, 1.0);
+            }
+        }
+    }
+    let local = |from: uint, to: uint| {
+        network.reset();
+        network.max_flow(2 * from + 1, 2 * to) as uint
+    };
+
+    let mut connectivity = n - 1;
+    let mut i = 0;
+    while i <= connectivity && i < n {
+        for j in range(0, n) {
+            if j == i {
+                continue
+            }
+            if !adjacent(i, j) {
+                connectivity = min(connectivity, local(i, j));
+            }
+            if !adjacent(j, i) {
+                connectivity = min(connectivity, local(j, i));
+            }
+        }
+        i += 1;
+    }
+    connectivity
+}
+
+/**
+* Compute the number of Edge disjoint paths from a Vertex to another one
+* and a smallest set of Edges cutting every path between them.
+*
+* The paths follow the direction of the Edges, the self loops are
+* ignored.
+*
+* # Arguments
+* * graph - The graph to measure
+* * from_key - The key of the first Vertex
+* * to_key - The key of the last Vertex
+*
+* # Return
+* Some((connectivity, cut)), None if a Vertex does not exist or if they are
+* the same Vertex.
+*/
+pub fn local_edge_connectivity<K: Ord + Eq + Clone,
+                               L: Eq + Clone,
+                               V: PartialEq + Clone>
+                               (graph: &Graph<K, L, V>,
+                               from_key: &K,
+                               to_key: &K)
+                               -> Option<(uint, Vec<(K, K)>)> {
+    min_cut(graph, &UnitWeights, from_key, to_key).map(|cut| (cut.value as uint, cut.edges))
+}
+
+/**
+* Compute the edge connectivity of a Graph, the least number of Edges to
+* remove so that some Vertex can't reach another one.
+*
+* The paths follow the direction of the Edges. Any Vertex v is on one side
+* of a minimum cut, so the connectivity is the least local connectivity
+* from v to every other Vertex and from every other Vertex to v.
+*
+* # Arguments
+* * graph - The graph to measure
+*
+* # Return
+* The edge connectivity and the Edges of a minimum cut, (0, empty) if the
+* Graph is not strongly connected or has less than two Vertices.
+*/
+pub fn edge_connectivity<K: Ord + Eq + Clone,
+                         L: Eq + Clone,
+                         V: PartialEq + Clone>
+                         (graph: &Graph<K, L, V>) -> (uint, Vec<(K, K)>) {
+    let indexed = IndexedGraph::from_graph(graph);
+    if indexed.len() < 2 || !graph.is_strongly_connected() {
+        return (0, Vec::new())
+    }
+    let first = indexed.keys.get(0);
+    let mut best: Option<(uint, Vec<(K, K)>)> = None;
+    for key in indexed.keys.iter().skip(1) {
+        for &(from_key, to_key) in [(first, key), (key, first)].iter() {
+            let (connectivity, cut) = local_edge_connectivity(graph, from_key, to_key).unwrap();
+            let better = match best {
+                Some((least, _)) => connectivity < least,
+                None             => true
+            };
+            if better {
+                best = Some((connectivity, cut));
+            }
+        }
+    }
+    best.unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use std::cmp::min;
+
+    use graph::Graph;
+    use super::{vertex_connectivity, local_vertex_connectivity};
+
+    // the least local connectivity between the Vertices not adjacent
+    fn least_local(graph: &Graph<uint, (), ()>, n: uint) -> uint {
+        let mut least = n - 1;
+        for from in range(0, n) {
+            for to in range(0, n) {
+                if from != to && !graph.edge_exist(from, to) {
+                    least = min(least, local_vertex_connectivity(graph, &from, &to).unwrap());
+                }
+            }
+        }
+        least
+    }
+
+    #[test]
+    fn matches_local_connectivity() {
+        // a cycle of 8 Vertices with chords to the Vertex 2 steps ahead
+        let n = 8u;
+        let mut edges = Vec::new();
+        for v in range(0, n) {
+            edges.push((v, (v + 1) % n, None));
+            edges.push((v, (v + 2) % n, None));
+        }
+        let graph: Graph<uint, (), ()> = Graph::from_edges(edges);
+        assert_eq!(vertex_connectivity(&graph), 2);
+        assert_eq!(vertex_connectivity(&graph), least_local(&graph, n));
+
+        // the same cycle without chords
+        let edges = range(0, n).map(|v| (v, (v + 1) % n, None)).collect();
+        let graph: Graph<uint, (), ()> = Graph::from_edges(edges);
+        assert_eq!(vertex_connectivity(&graph), 1);
+        assert_eq!(vertex_connectivity(&graph), least_local(&graph, n));
+    }
+}
@@ -0,0 +1,384 @@
+//! Distribution of the distances between the Vertices of a Graph.
+
+use std::rand::Rng;
+
+use graph::Graph;
+use indexed::IndexedGraph;
+use instrument::{Probe, NoProbe};
+
+/**
+* Distribution of the lengths of the shortest paths between the ordered
+* pairs of distinct Vertices of a Graph.
+*
+* The counts are exact or estimated depending on the function used to
+* compute the distribution.
+*/
+#[deriving(Clone, PartialEq, Show)]
+pub struct DistanceDistribution {
+    /// counts[d] is the number of pairs at distance d, counts[0] is always 0
+    pub counts:        Vec<f64>,
+    /// The number of pairs without path between them
+    pub unreachable:   f64,
+    /// The number of Vertices used as the source of the paths
+    pub sources:       uint,
+    // sums of the average distance from each source and of its square
+    source_sum:        f64,
+    source_sum_sq:     f64,
+    exact:             bool
+}
+
+impl DistanceDistribution {
+
+    /**
+    * Create a DistanceDistribution from estimated counts.
+    *
+    * # Arguments
+    * * counts - The number of pairs at each distance, counts[0] is ignored
+    * * unreachable - The number of pairs without path between them
+    *
+    * # Return
+    * A new estimated DistanceDistribution.
+    */
+    pub fn from_counts(counts: Vec<f64>, unreachable: f64) -> DistanceDistribution {
+        let mut counts = counts;
+        if counts.is_empty() {
+            counts.push(0.0);
+        }
+        *counts.get_mut(0) = 0.0;
+        DistanceDistribution {
+            counts:         counts,
+            unreachable:    unreachable,
+            sources:        0,
+            source_sum:     0.0,
+            source_sum_sq:  0.0,
+            exact:          false
+        }
+    }
+
+    /**
+    * Is the distribution computed from every Vertex or estimated.
+    */
+    pub fn is_exact(&self) -> bool {
+        self.exact
+    }
+
+    /**
+    * Get the number of pairs connected by a path.
+    */
+    pub fn reachable_pairs(&self) -> f64 {
+        self.counts.iter().fold(0.0, |sum, c| sum + *c)
+    }
+
+    /**
+    * Get the longest of the shortest paths.
+    *
+    * When the distribution is estimated this is a lower bound of the
+    * diameter.
+    */
+    pub fn diameter(&self) -> uint {
+        match self.counts.iter().rposition(|c| *c > 0.0) {
+            Some(d) => d,
+            None    => 0
+        }
+    }
+
+    /**
+    * Get the average length of the shortest paths.
+    *
+    * # Return
+    * The average over the reachable pairs, 0 if there is none.
+    */
+    pub fn average(&self) -> f64 {
+        let pairs = self.reachable_pairs();
+        if pairs == 0.0 {
+            return 0.0
+        }
+        let total = self.counts.iter().enumerate().fold(0.0, |sum, (d, c)| {
+            sum + d as f64 * *c
+        });
+        total / pairs
+    }
+
+    /**
+    * Get the standard error of the average length estimated by sampling.
+    *
+    * The average is estimated from the average of each source, use it to
+    * choose the number of samples for a wanted precision, a 95% confidence
+    * interval is average +/- 1.96 * error.
+    *
+    * # Return
+    * The standard error, 0 if the distribution is exact or if there is
+    * less than two sources.
+    */
+    pub fn average_std_error(&self) -> f64 {
+        if self.exact || self.sources < 2 {
+            return 0.0
+        }
+        let k = self.sources as f64;
+        let mean = self.source_sum / k;
+        let variance = (self.source_sum_sq - k * mean * mean) / (k - 1.0);
+        (variance.max(0.0) / k).sqrt()
+    }
+
+    /**
+    * Get the effective diameter, the distance within which a fraction of
+    * the reachable pairs are, interpolated between integer distances.
+    *
+    * # Arguments
+    * * fraction - The fraction of pairs to include, usually 0.9
+    *
+    * # Return
+    * The effective diameter.
+    */
+    pub fn effective_diameter(&self, fraction: f64) -> f64 {
+        let target = fraction * self.reachable_pairs();
+        let mut cumulated = 0.0;
+        for (d, c) in self.counts.iter().enumerate() {
+            if *c > 0.0 && cumulated + *c >= target {
+                return (d - 1) as f64 + (target - cumulated) / *c
+            }
+            cumulated += *c;
+        }
+        self.diameter() as f64
+    }
+}
+
+/**
+* Compute the exact distance distribution of a Graph by a breadth first
+* search from every Vertex.
+*
+* This takes O(n * m) time, use sampled_distance_distribution on large
+* Graphs.
+*
+* # Arguments
+* * graph - The graph to measure
+*
+* # Return
+* The exact distance distribution.
+*/
+pub fn path_length_distribution<K: Ord + Eq + Clone,
+                                L: Eq + Clone,
+                                V: PartialEq + Clone>
+                                (graph: &Graph<K, L, V>) -> DistanceDistribution {
+    path_length_distribution_probed(graph, &mut NoProbe)
+}
+
+/**
+* Same as path_length_distribution, notifying a Probe of the operations of
+* the breadth first searches.
+*
+* # Arguments
+* * graph - The graph to measure
+* * probe - The probe to notify
+*
+* # Return
+* The exact distance distribution.
+*/
+pub fn path_length_distribution_probed<K: Ord + Eq + Clone,
+                                       L: Eq + Clone,
+                                       V: PartialEq + Clone,
+                                       P: Probe>
+                                       (graph: &Graph<K, L, V>,
+                                       probe: &mut P)
+                                       -> DistanceDistribution {
+    let indexed = IndexedGraph::from_graph(graph);
+    let sources = range(0, indexed.len()).collect();
+    distribution_from(&indexed, sources, probe)
+}
+
+/**
+* Compute the average length of the shortest paths of a Graph.
+*
+* The pairs without path between them are ignored.
+*
+* # Arguments
+* * graph - The graph to measure
+*
+* # Return
+* The exact average, 0 if no pair is connected.
+*/
+pub fn average_shortest_path_length<K: Ord + Eq + Clone,
+                                    L: Eq + Clone,
+                                    V: PartialEq + Clone>
+                                    (graph: &Graph<K, L, V>) -> f64 {
+    path_length_distribution(graph).average()
+}
+
+/**
+* Estimate the average length of the shortest paths of a Graph from a
+* sample of source Vertices.
+*
+* # Arguments
+* * graph - The graph to measure
+* * samples - The number of source Vertices to sample
+* * rng - The random number generator used to choose the sources
+*
+* # Return
+* The estimated average and its standard error.
+*/
+pub fn sampled_average_shortest_path_length<K: Ord + Eq + Clone,
+                                            L: Eq + Clone,
+                                            V: PartialEq + Clone,
+                                            R: Rng>
+                                            (graph: &Graph<K, L, V>,
+                                            samples: uint,
+                                            rng: &mut R)
+                                            -> (f64, f64) {
+    let distribution = sampled_distance_distribution(graph, samples, rng);
+    (distribution.average(), distribution.average_std_error())
+}
+
+/**
+* Estimate the distance distribution of a Graph by breadth first searches
+* from a sample of Vertices.
+*
+* The counts are scaled to the whole Graph, the precision of the estimation
+* grows with the number of samples, see average_std_error.
+*
+* # Arguments
+* * graph - The graph to measure
+* * samples - The number of source Vertices to sample
+* * rng - The random number generator used to choose the sources
+*
+* # Return
+* The estimated distance distribution, exact if samples is greater than
+* the number of Vertices.
+*/
+pub fn sampled_distance_distribution<K: Ord + Eq + Clone,
+                                     L: Eq + Clone,
+                                     V: PartialEq + Clone,
+                                     R: Rng>
+                                     (graph: &Graph<K, L, V>,
+                                     samples: uint,
+                                     rng: &mut R)
+                                     -> DistanceDistribution {
+    let indexed = IndexedGraph::from_graph(graph);
+    let mut sources: Vec<uint> = range(0, indexed.len()).collect();
+    if samples < sources.len() {
+        rng.shuffle(sources.as_mut_slice());
+        sources.truncate(samples);
+    }
+    distribution_from(&indexed, sources, &mut NoProbe)
+}
+
+/**
+* Estimate the diameter of a Graph by double sweeps from a sample of
+* Vertices.
+*
+* Each sweep runs a breadth first search from a sampled Vertex, then
+* another one from the farthest Vertex found, which is usually close to a
+* diametral pair.
+*
+* # Arguments
+* * graph - The graph to measure
+* * samples - The number of sweeps to run
+* * rng - The random number generator used to choose the sources
+*
+* # Return
+* A lower bound of the diameter, the longest shortest path found.
+*/
+pub fn approximate_diameter<K: Ord + Eq + Clone,
+                            L: Eq + Clone,
+                            V: PartialEq + Clone,
+                            R: Rng>
+                            (graph: &Graph<K, L, V>,
+                            samples: uint,
+                            rng: &mut R)
+                            -> uint {
+    let indexed = IndexedGraph::from_graph(graph);
+    if indexed.len() == 0 {
+        return 0
+    }
+    let mut diameter = 0;
+    for _ in range(0, samples) {
+        let source = rng.gen_range(0, indexed.len());
+        let (far, _) = farthest(&indexed, source);
+        let (_, distance) = farthest(&indexed, far);
+        if distance > diameter {
+            diameter = distance;
+        }
+    }
+    diameter
+}
+
+/**
+* Estimate the effective diameter of a Graph by sampling.
+*
+* # Arguments
+* * graph - The graph to measure
+* * fraction - The fraction of pairs to include, usually 0.9
+* * samples - The number of source Vertices to sample
+* * rng - The random number generator used to choose the sources
+*
+* # Return
+* The estimated effective diameter.
+*/
+pub fn approximate_effective_diameter<K: Ord + Eq + Clone,
+                                      L: Eq + Clone,
+                                      V: PartialEq + Clone,
+                                      R: Rng>
+                                      (graph: &Graph<K, L, V>,
+                                      fraction: f64,
+                                      samples: uint,
+                                      rng: &mut R)
+                                      -> f64 {
+    sampled_distance_distribution(graph, samples, rng).effective_diameter(fraction)
+}
+
+// accumulate the distances from each source, scaled to the whole Graph
+fn distribution_from<K, P: Probe>(indexed: &IndexedGraph<K>,
+                                  sources: Vec<uint>,
+                                  probe: &mut P)
+                                  -> DistanceDistribution {
+    let n = indexed.len();
+    let mut counts: Vec<f64> = vec!(0.0);
+    let mut unreachable = 0.0;
+    let mut source_sum = 0.0;
+    let mut source_sum_sq = 0.0;
+    for &source in sources.iter() {
+        let mut total = 0u;
+        let mut reached = 0u;
+        let distances = indexed.distances_probed(source, &indexed.out_edges, probe);
+        for (v, distance) in distances.iter().enumerate() {
+            match *distance {
+                Some(d) if v != source => {
+                    while counts.len() <= d {
+                        counts.push(0.0);
+                    }
+                    *counts.get_mut(d) += 1.0;
+                    total += d;
+                    reached += 1;
+                },
+                Some(_)                => {},
+                None                   => unreachable += 1.0
+            }
+        }
+        if reached > 0 {
+            let average = total as f64 / reached as f64;
+            source_sum += average;
+            source_sum_sq += average * average;
+        }
+    }
+
+    let scale = if sources.is_empty() { 0.0 } else { n as f64 / sources.len() as f64 };
+    DistanceDistribution {
+        counts:         counts.move_iter().map(|c| c * scale).collect(),
+        unreachable:    unreachable * scale,
+        sources:        sources.len(),
+        source_sum:     source_sum,
+        source_sum_sq:  source_sum_sq,
+        exact:          sources.len() == n
+    }
+}
+
+// the farthest Vertex reachable from source and its distance
+fn farthest<K>(indexed: &IndexedGraph<K>, source: uint) -> (uint, uint) {
+    let mut best = (source, 0u);
+    for (v, distance) in indexed.distances(source, &indexed.out_edges).iter().enumerate() {
+        match *distance {
+            Some(d) if d > best.val1() => best = (v, d),
+            _                          => {}
+        }
+    }
+    best
+}
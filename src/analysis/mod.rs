@@ -0,0 +1,36 @@
+//! Statistics and measures on Graphs.
+
+pub use self::distance::{DistanceDistribution,
+                         path_length_distribution,
+                         path_length_distribution_probed,
+                         average_shortest_path_length,
+                         sampled_average_shortest_path_length,
+                         sampled_distance_distribution,
+                         approximate_diameter,
+                         approximate_effective_diameter};
+pub use self::neighborhood::{NeighborhoodFunction, neighborhood_function};
+pub use self::summary::{GraphSummary, summary};
+pub use self::reciprocity::reciprocity;
+pub use self::rich_club::{rich_club_coefficient, normalized_rich_club_coefficient};
+pub use self::modularity::{modularity, undirected_modularity};
+pub use self::coloring::{dsatur_coloring, chromatic_bounds, chromatic_number};
+pub use self::motifs::{TRIAD_TYPES, TriadCensus, MotifCensus, triad_census, motif_census};
+pub use self::betweenness::{BetweennessEstimate, edge_betweenness, approximate_edge_betweenness,
+                             betweenness_centrality, weighted_betweenness_centrality,
+                             approximate_betweenness_centrality};
+pub use self::centrality::{harmonic_centrality, degree_centrality, closeness_centrality,
+                           eigenvector_centrality};
+pub use self::connectivity::{vertex_connectivity, local_vertex_connectivity,
+                              edge_connectivity, local_edge_connectivity};
+
+mod distance;
+mod neighborhood;
+mod summary;
+mod reciprocity;
+mod rich_club;
+mod modularity;
+mod motifs;
+mod coloring;
+mod betweenness;
+mod centrality;
+mod connectivity;
@@ -0,0 +1,131 @@
+//! Modularity of a partition of the Vertices.
+
+use std::collections::HashMap;
+
+use graph::Graph;
+use property_map::PropertyMap;
+use indexed::IndexedGraph;
+
+/**
+* Compute the directed modularity of a partition of the Vertices of a Graph.
+*
+* This is the fraction of the Edges inside the communities minus its
+* expected value when the Edges are placed at random with the same in and
+* out degrees (Leicht and Newman).
+*
+* # Arguments
+* * graph - The graph to measure
+* * partition - The community of each Vertex, the Vertices missing from it
+*   are alone in their community
+*
+* # Return
+* The modularity between -1 and 1, 0 if the Graph has no Edge.
+*/
+pub fn modularity<K: Ord + Eq + Clone,
+                  L: Eq + Clone,
+                  V: PartialEq + Clone>
+                  (graph: &Graph<K, L, V>,
+                  partition: &PropertyMap<K, uint>)
+                  -> f64 {
+    let indexed = IndexedGraph::from_graph(graph);
+    let communities = communities_of(&indexed, partition);
+    let c = communities.iter().max().map(|c| *c + 1).unwrap_or(0);
+
+    let mut inside = Vec::from_elem(c, 0.0f64);
+    let mut out_degrees = Vec::from_elem(c, 0.0f64);
+    let mut in_degrees = Vec::from_elem(c, 0.0f64);
+    let mut m = 0.0;
+    for (v, successors) in indexed.out_edges.iter().enumerate() {
+        let cv = *communities.get(v);
+        for &u in successors.iter() {
+            let cu = *communities.get(u);
+            m += 1.0;
+            *out_degrees.get_mut(cv) += 1.0;
+            *in_degrees.get_mut(cu) += 1.0;
+            if cu == cv {
+                *inside.get_mut(cv) += 1.0;
+            }
+        }
+    }
+    if m == 0.0 {
+        return 0.0
+    }
+    range(0, c).fold(0.0, |q, i| {
+        q + *inside.get(i) / m - *out_degrees.get(i) * *in_degrees.get(i) / (m * m)
+    })
+}
+
+/**
+* Compute the undirected modularity of a partition of the Vertices of a
+* Graph.
+*
+* The direction of the Edges is ignored, two opposite Edges count as one
+* and the self loops are ignored.
+*
+* # Arguments
+* * graph - The graph to measure
+* * partition - The community of each Vertex, the Vertices missing from it
+*   are alone in their community
+*
+* # Return
+* The modularity between -1/2 and 1, 0 if the Graph has no Edge.
+*/
+pub fn undirected_modularity<K: Ord + Eq + Clone,
+                             L: Eq + Clone,
+                             V: PartialEq + Clone>
+                             (graph: &Graph<K, L, V>,
+                             partition: &PropertyMap<K, uint>)
+                             -> f64 {
+    let indexed = IndexedGraph::from_graph(graph);
+    let communities = communities_of(&indexed, partition);
+    let c = communities.iter().max().map(|c| *c + 1).unwrap_or(0);
+
+    let mut inside = Vec::from_elem(c, 0.0f64);
+    let mut degrees = Vec::from_elem(c, 0.0f64);
+    let mut m = 0.0;
+    for (v, neighbors) in indexed.undirected_edges().iter().enumerate() {
+        let cv = *communities.get(v);
+        for &u in neighbors.iter().filter(|&&u| u > v) {
+            let cu = *communities.get(u);
+            m += 1.0;
+            *degrees.get_mut(cv) += 1.0;
+            *degrees.get_mut(cu) += 1.0;
+            if cu == cv {
+                *inside.get_mut(cv) += 1.0;
+            }
+        }
+    }
+    if m == 0.0 {
+        return 0.0
+    }
+    range(0, c).fold(0.0, |q, i| {
+        let share = *degrees.get(i) / (2.0 * m);
+        q + *inside.get(i) / m - share * share
+    })
+}
+
+// the dense community number of each Vertex
+fn communities_of<K: Ord>(indexed: &IndexedGraph<K>,
+                          partition: &PropertyMap<K, uint>)
+                          -> Vec<uint> {
+    let mut ids: HashMap<uint, uint> = HashMap::new();
+    let mut communities = Vec::with_capacity(indexed.len());
+    let mut next = 0u;
+    for key in indexed.keys.iter() {
+        let id = match partition.find(key) {
+            Some(community) => {
+                let id = *ids.find_or_insert(*community, next);
+                if id == next {
+                    next += 1;
+                }
+                id
+            },
+            None            => {
+                next += 1;
+                next - 1
+            }
+        };
+        communities.push(id);
+    }
+    communities
+}
@@ -0,0 +1,223 @@
+//! Census of the small subgraphs of a Graph.
+
+use std::fmt;
+
+use graph::Graph;
+use indexed::{IndexedGraph, contains_sorted};
+
+/// The names of the 16 types of directed triads, in the order of the census.
+pub static TRIAD_TYPES: [&'static str, ..16] = ["003", "012", "102", "021D",
+                                                 "021U", "021C", "111D", "111U",
+                                                 "030T", "030C", "201", "120D",
+                                                 "120U", "120C", "210", "300"];
+
+// type of a triad from the code of its six possible Edges
+static TRICODES: [uint, ..64] = [0, 1, 1, 2, 1, 3, 5, 7, 1, 5, 4, 6, 2, 7, 6, 10,
+                                 1, 5, 3, 7, 4, 8, 8, 12, 5, 9, 8, 13, 6, 13, 11, 14,
+                                 1, 4, 5, 6, 5, 8, 9, 13, 3, 8, 8, 11, 7, 12, 13, 14,
+                                 2, 6, 7, 10, 6, 11, 13, 14, 7, 13, 12, 14, 10, 14, 14, 15];
+
+/**
+* Number of triads of each type of a directed Graph.
+*/
+#[deriving(Clone, PartialEq)]
+pub struct TriadCensus {
+    /// counts[i] is the number of triads of type TRIAD_TYPES[i]
+    pub counts:        [uint, ..16]
+}
+
+impl TriadCensus {
+
+    /**
+    * Get the number of triads of a type.
+    *
+    * # Arguments
+    * * name - The name of the type, as in TRIAD_TYPES
+    *
+    * # Return
+    * Some(count) if the type exist, None otherwise.
+    */
+    pub fn get(&self, name: &str) -> Option<uint> {
+        TRIAD_TYPES.iter().position(|t| *t == name).map(|i| self.counts[i])
+    }
+}
+
+impl fmt::Show for TriadCensus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (name, count) in TRIAD_TYPES.iter().zip(self.counts.iter()) {
+            try!(writeln!(f, "{:>4s}: {}", *name, *count));
+        }
+        Ok(())
+    }
+}
+
+/**
+* Number of connected induced subgraphs with 3 and 4 Vertices of each shape,
+* the direction of the Edges being ignored.
+*/
+#[deriving(Clone, PartialEq, Show)]
+pub struct MotifCensus {
+    pub paths3:        uint,
+    pub triangles:     uint,
+    pub paths4:        uint,
+    pub stars4:        uint,
+    pub cycles4:       uint,
+    /// triangles with a pendant Edge
+    pub paws4:         uint,
+    /// 4-cycles with one chord
+    pub diamonds4:     uint,
+    pub cliques4:      uint
+}
+
+/**
+* Compute the triad census of a Graph (Batagelj and Mrvar algorithm).
+*
+* Every unordered triple of Vertices is counted in one of the 16 types,
+* self loops are ignored.
+*
+* # Arguments
+* * graph - The graph to measure
+*
+* # Return
+* The count of each type of triad.
+*/
+pub fn triad_census<K: Ord + Eq + Clone,
+                    L: Eq + Clone,
+                    V: PartialEq + Clone>
+                    (graph: &Graph<K, L, V>) -> TriadCensus {
+    let indexed = IndexedGraph::from_graph(graph);
+    let n = indexed.len();
+    let neighbors = simple_neighbors(&indexed);
+    let linked = |a: uint, b: uint| contains_sorted(indexed.out_edges.get(a).as_slice(), b);
+    let mut counts = [0u, ..16];
+
+    for v in range(0, n) {
+        for &u in neighbors.get(v).iter().filter(|&&u| u > v) {
+            let mut union: Vec<uint> = neighbors.get(v).iter()
+                                                .chain(neighbors.get(u).iter())
+                                                .map(|w| *w)
+                                                .filter(|&w| w != u && w != v)
+                                                .collect();
+            union.sort();
+            union.dedup();
+            for &w in union.iter() {
+                let adjacent_v = contains_sorted(neighbors.get(v).as_slice(), w);
+                if u < w || (v < w && w < u && !adjacent_v) {
+                    let code = linked(v, u) as uint + 2 * linked(u, v) as uint +
+                               4 * linked(v, w) as uint + 8 * linked(w, v) as uint +
+                               16 * linked(u, w) as uint + 32 * linked(w, u) as uint;
+                    counts[TRICODES[code]] += 1;
+                }
+            }
+            // the triads with a single dyad
+            let dyads = n - union.len() - 2;
+            if linked(u, v) && linked(v, u) {
+                counts[2] += dyads;
+            } else {
+                counts[1] += dyads;
+            }
+        }
+    }
+    let triads = if n < 3 { 0 } else { n * (n - 1) * (n - 2) / 6 };
+    let connected = counts.iter().fold(0, |sum, c| sum + *c);
+    counts[0] = triads - connected;
+    TriadCensus { counts: counts }
+}
+
+/**
+* Count the connected induced subgraphs with 3 and 4 Vertices of a Graph by
+* their shape (ESU enumeration).
+*
+* The direction of the Edges and the self loops are ignored.
+*
+* # Arguments
+* * graph - The graph to measure
+*
+* # Return
+* The count of each shape.
+*/
+pub fn motif_census<K: Ord + Eq + Clone,
+                    L: Eq + Clone,
+                    V: PartialEq + Clone>
+                    (graph: &Graph<K, L, V>) -> MotifCensus {
+    let indexed = IndexedGraph::from_graph(graph);
+    let neighbors = simple_neighbors(&indexed);
+    let mut census = MotifCensus {
+        paths3:     0,
+        triangles:  0,
+        paths4:     0,
+        stars4:     0,
+        cycles4:    0,
+        paws4:      0,
+        diamonds4:  0,
+        cliques4:   0
+    };
+    for v in range(0, indexed.len()) {
+        let extension: Vec<uint> = neighbors.get(v).iter().map(|u| *u).filter(|&u| u > v).collect();
+        extend(&neighbors, vec!(v), extension, v, &mut census);
+    }
+    census
+}
+
+fn simple_neighbors<K>(indexed: &IndexedGraph<K>) -> Vec<Vec<uint>> {
+    indexed.undirected_edges().move_iter().enumerate().map(|(v, mut ns)| {
+        ns.retain(|&u| u != v);
+        ns
+    }).collect()
+}
+
+// grow the subgraph with the Vertices of the extension greater than root,
+// each connected subgraph is found exactly once
+fn extend(neighbors: &Vec<Vec<uint>>,
+          subgraph: Vec<uint>,
+          extension: Vec<uint>,
+          root: uint,
+          census: &mut MotifCensus) {
+    if subgraph.len() >= 3 {
+        classify(neighbors, &subgraph, census);
+        if subgraph.len() == 4 {
+            return
+        }
+    }
+    let mut extension = extension;
+    while !extension.is_empty() {
+        let w = extension.pop().unwrap();
+        let mut next = extension.clone();
+        for &u in neighbors.get(w).iter() {
+            let exclusive = u > root && !subgraph.contains(&u) && !next.contains(&u) &&
+                            subgraph.iter().all(|&s| !contains_sorted(neighbors.get(s).as_slice(), u));
+            if exclusive {
+                next.push(u);
+            }
+        }
+        let mut grown = subgraph.clone();
+        grown.push(w);
+        extend(neighbors, grown, next, root, census);
+    }
+}
+
+fn classify(neighbors: &Vec<Vec<uint>>, subgraph: &Vec<uint>, census: &mut MotifCensus) {
+    let mut edges = 0u;
+    let mut max_degree = 0u;
+    for &a in subgraph.iter() {
+        let degree = subgraph.iter().filter(|&&b| {
+            contains_sorted(neighbors.get(a).as_slice(), b)
+        }).count();
+        edges += degree;
+        if degree > max_degree {
+            max_degree = degree;
+        }
+    }
+    edges /= 2;
+    match (subgraph.len(), edges, max_degree) {
+        (3, 2, _) => census.paths3 += 1,
+        (3, 3, _) => census.triangles += 1,
+        (4, 3, 3) => census.stars4 += 1,
+        (4, 3, _) => census.paths4 += 1,
+        (4, 4, 3) => census.paws4 += 1,
+        (4, 4, _) => census.cycles4 += 1,
+        (4, 5, _) => census.diamonds4 += 1,
+        (4, 6, _) => census.cliques4 += 1,
+        _         => {}
+    }
+}
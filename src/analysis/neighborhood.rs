@@ -0,0 +1,191 @@
+//! Approximate neighborhood function (HyperANF).
+
+use std::hash::sip::hash_with_keys;
+
+use graph::Graph;
+use property_map::PropertyMap;
+use indexed::IndexedGraph;
+use super::distance::DistanceDistribution;
+
+/**
+* Estimated number of Vertices reachable within r hops.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+*/
+#[deriving(Clone, Show)]
+pub struct NeighborhoodFunction<K> {
+    /// pairs[r] is the number of pairs (u, v) with v reachable from u in r
+    /// hops or less, u itself included
+    pub pairs:         Vec<f64>,
+    /// For each Vertex the number of Vertices reachable in r hops or less
+    pub vertices:      PropertyMap<K, Vec<f64>>,
+    len:               uint
+}
+
+impl<K> NeighborhoodFunction<K> {
+
+    /**
+    * Convert the neighborhood function to a distance distribution.
+    *
+    * The pairs still growing at the last hop are counted as unreachable.
+    */
+    pub fn distance_distribution(&self) -> DistanceDistribution {
+        let mut counts = vec!(0.0);
+        for r in range(1, self.pairs.len()) {
+            let delta = *self.pairs.get(r) - *self.pairs.get(r - 1);
+            counts.push(delta.max(0.0));
+        }
+        let n = self.len as f64;
+        let reached = match self.pairs.last() {
+            Some(p) => *p,
+            None    => n
+        };
+        DistanceDistribution::from_counts(counts, (n * n - reached).max(0.0))
+    }
+
+    /**
+    * Get the estimated effective diameter.
+    *
+    * # Arguments
+    * * fraction - The fraction of pairs to include, usually 0.9
+    */
+    pub fn effective_diameter(&self, fraction: f64) -> f64 {
+        self.distance_distribution().effective_diameter(fraction)
+    }
+}
+
+/**
+* Estimate the neighborhood function of a Graph with HyperLogLog counters.
+*
+* Each Vertex has a counter of the set of Vertices it reaches, at each hop
+* the counter of a Vertex is united with the counters of its successors.
+* The memory is 2 ^ precision bytes per Vertex and the relative error of
+* each estimate is about 1.04 / sqrt(2 ^ precision).
+*
+* # Arguments
+* * graph - The graph to measure
+* * max_hops - The maximum number of hops, the computation stops earlier
+*   when no counter changes anymore
+* * precision - The log2 of the number of registers per counter, between 4
+*   and 16
+*
+* # Return
+* The estimated neighborhood function of the Graph and of each Vertex.
+*/
+pub fn neighborhood_function<K: Ord + Eq + Clone,
+                             L: Eq + Clone,
+                             V: PartialEq + Clone>
+                             (graph: &Graph<K, L, V>,
+                             max_hops: uint,
+                             precision: uint)
+                             -> NeighborhoodFunction<K> {
+    let precision = if precision < 4 { 4 } else if precision > 16 { 16 } else { precision };
+    let indexed = IndexedGraph::from_graph(graph);
+    let n = indexed.len();
+
+    let mut counters: Vec<HyperLogLog> = range(0, n).map(|v| {
+        let mut counter = HyperLogLog::new(precision);
+        counter.add(v);
+        counter
+    }).collect();
+    let mut estimates: Vec<Vec<f64>> = counters.iter().map(|c| vec!(c.estimate())).collect();
+
+    for _ in range(0, max_hops) {
+        let mut changed = false;
+        let mut next = counters.clone();
+        for v in range(0, n) {
+            for &u in indexed.out_edges.get(v).iter() {
+                changed = next.get_mut(v).union(counters.get(u)) || changed;
+            }
+        }
+        if !changed {
+            break
+        }
+        counters = next;
+        for (v, counter) in counters.iter().enumerate() {
+            estimates.get_mut(v).push(counter.estimate());
+        }
+    }
+
+    let hops = estimates.iter().map(|e| e.len()).max().unwrap_or(0);
+    let mut pairs = Vec::from_elem(hops, 0.0f64);
+    for estimate in estimates.iter() {
+        for r in range(0, hops) {
+            *pairs.get_mut(r) += *estimate.get(r);
+        }
+    }
+    let vertices = indexed.keys.move_iter().zip(estimates.move_iter()).collect();
+    NeighborhoodFunction {
+        pairs:      pairs,
+        vertices:   vertices,
+        len:        n
+    }
+}
+
+#[deriving(Clone)]
+struct HyperLogLog {
+    precision:         uint,
+    registers:         Vec<u8>
+}
+
+impl HyperLogLog {
+    fn new(precision: uint) -> HyperLogLog {
+        HyperLogLog {
+            precision:  precision,
+            registers:  Vec::from_elem(1 << precision, 0u8)
+        }
+    }
+
+    fn add(&mut self, element: uint) {
+        let hash = hash_with_keys(0, 0, &element);
+        let register = (hash >> (64 - self.precision)) as uint;
+        // position of the first bit set among the remaining ones
+        let mut rest = hash << self.precision;
+        let mut rank = 1u8;
+        while rank as uint <= 64 - self.precision && rest & (1u64 << 63) == 0 {
+            rest = rest << 1;
+            rank += 1;
+        }
+        if rank > *self.registers.get(register) {
+            *self.registers.get_mut(register) = rank;
+        }
+    }
+
+    // returns true if the counter changed
+    fn union(&mut self, other: &HyperLogLog) -> bool {
+        let mut changed = false;
+        for (mine, theirs) in self.registers.mut_iter().zip(other.registers.iter()) {
+            if *theirs > *mine {
+                *mine = *theirs;
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    fn estimate(&self) -> f64 {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _  => 0.7213 / (1.0 + 1.079 / m)
+        };
+        let mut sum = 0.0;
+        let mut zeros = 0u;
+        for r in self.registers.iter() {
+            sum += 1.0 / (1u64 << *r as uint) as f64;
+            if *r == 0 {
+                zeros += 1;
+            }
+        }
+        let estimate = alpha * m * m / sum;
+        if estimate <= 2.5 * m && zeros > 0 {
+            // linear counting for the small cardinalities
+            m * (m / zeros as f64).ln()
+        } else {
+            estimate
+        }
+    }
+}
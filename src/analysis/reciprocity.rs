@@ -0,0 +1,38 @@
+//! Reciprocity of directed Graphs.
+
+use graph::Graph;
+use indexed::{IndexedGraph, contains_sorted};
+
+/**
+* Compute the fraction of the Edges of a Graph whose reverse Edge also
+* exist.
+*
+* Self loops are ignored.
+*
+* # Arguments
+* * graph - The graph to measure
+*
+* # Return
+* The reciprocity between 0 and 1, 0 if the Graph has no Edge.
+*/
+pub fn reciprocity<K: Ord + Eq + Clone,
+                   L: Eq + Clone,
+                   V: PartialEq + Clone>
+                   (graph: &Graph<K, L, V>) -> f64 {
+    let indexed = IndexedGraph::from_graph(graph);
+    let mut edges = 0u;
+    let mut reciprocated = 0u;
+    for (v, successors) in indexed.out_edges.iter().enumerate() {
+        for &u in successors.iter().filter(|&&u| u != v) {
+            edges += 1;
+            if contains_sorted(indexed.out_edges.get(u).as_slice(), v) {
+                reciprocated += 1;
+            }
+        }
+    }
+    if edges == 0 {
+        0.0
+    } else {
+        reciprocated as f64 / edges as f64
+    }
+}
@@ -0,0 +1,146 @@
+//! Rich-club coefficient.
+
+use std::cmp::min;
+use std::collections::TreeSet;
+use std::f64;
+use std::rand::Rng;
+
+use graph::Graph;
+use indexed::IndexedGraph;
+
+/**
+* Compute the rich-club coefficient of a Graph.
+*
+* The coefficient for a degree k is the density of the subgraph induced by
+* the Vertices of degree greater than k. The direction of the Edges and the
+* self loops are ignored, the degree of a Vertex is its number of distinct
+* neighbors.
+*
+* # Arguments
+* * graph - The graph to measure
+*
+* # Return
+* The coefficient for each degree k, from 0 to the last degree having at
+* least two Vertices above it.
+*/
+pub fn rich_club_coefficient<K: Ord + Eq + Clone,
+                             L: Eq + Clone,
+                             V: PartialEq + Clone>
+                             (graph: &Graph<K, L, V>) -> Vec<f64> {
+    let indexed = IndexedGraph::from_graph(graph);
+    coefficients(&simple_edges(&indexed), indexed.len())
+}
+
+/**
+* Compute the rich-club coefficient of a Graph normalized by the one of a
+* random Graph with the same degrees.
+*
+* The random Graph is obtained by swapping the ends of random pairs of
+* Edges, which keeps the degree of every Vertex. A value greater than 1
+* means that the high degree Vertices are more interconnected than by
+* chance.
+*
+* # Arguments
+* * graph - The graph to measure
+* * swaps - The number of swaps to try per Edge, 10 is usually enough
+* * rng - The random number generator used for the swaps
+*
+* # Return
+* The normalized coefficient for each degree k, NaN when the random Graph
+* has no Edge between the Vertices of degree greater than k.
+*/
+pub fn normalized_rich_club_coefficient<K: Ord + Eq + Clone,
+                                        L: Eq + Clone,
+                                        V: PartialEq + Clone,
+                                        R: Rng>
+                                        (graph: &Graph<K, L, V>,
+                                        swaps: uint,
+                                        rng: &mut R)
+                                        -> Vec<f64> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let edges = simple_edges(&indexed);
+    let observed = coefficients(&edges, indexed.len());
+    let random = coefficients(&randomize(edges, swaps, rng), indexed.len());
+    observed.iter().zip(random.iter()).map(|(o, r)| {
+        if *r == 0.0 { f64::NAN } else { *o / *r }
+    }).collect()
+}
+
+// the undirected Edges (u, v) with u < v
+fn simple_edges<K>(indexed: &IndexedGraph<K>) -> Vec<(uint, uint)> {
+    let mut edges = Vec::new();
+    for (v, neighbors) in indexed.undirected_edges().iter().enumerate() {
+        for &u in neighbors.iter().filter(|&&u| u > v) {
+            edges.push((v, u));
+        }
+    }
+    edges
+}
+
+fn coefficients(edges: &Vec<(uint, uint)>, n: uint) -> Vec<f64> {
+    let mut degrees = Vec::from_elem(n, 0u);
+    for &(u, v) in edges.iter() {
+        *degrees.get_mut(u) += 1;
+        *degrees.get_mut(v) += 1;
+    }
+    let max_degree = degrees.iter().max().map(|d| *d).unwrap_or(0);
+
+    // an Edge is in every club below the smallest degree of its ends
+    let mut vertices_by_degree = Vec::from_elem(max_degree + 1, 0u);
+    for &d in degrees.iter() {
+        *vertices_by_degree.get_mut(d) += 1;
+    }
+    let mut edges_by_degree = Vec::from_elem(max_degree + 1, 0u);
+    for &(u, v) in edges.iter() {
+        let d = min(*degrees.get(u), *degrees.get(v));
+        *edges_by_degree.get_mut(d) += 1;
+    }
+
+    let mut club_vertices = 0u;
+    let mut club_edges = 0u;
+    let mut k = max_degree;
+    let mut coefficients = Vec::new();
+    while k > 0 {
+        club_vertices += *vertices_by_degree.get(k);
+        club_edges += *edges_by_degree.get(k);
+        k -= 1;
+        // club_* now count the Vertices and Edges of degree greater than k
+        if club_vertices >= 2 {
+            let pairs = club_vertices * (club_vertices - 1) / 2;
+            coefficients.push(club_edges as f64 / pairs as f64);
+        }
+    }
+    coefficients.reverse();
+    coefficients
+}
+
+fn randomize<R: Rng>(edges: Vec<(uint, uint)>, swaps: uint, rng: &mut R) -> Vec<(uint, uint)> {
+    let mut edges = edges;
+    if edges.len() < 2 {
+        return edges
+    }
+    let mut present: TreeSet<(uint, uint)> = edges.iter().map(|e| *e).collect();
+    for _ in range(0, swaps * edges.len()) {
+        let i = rng.gen_range(0, edges.len());
+        let j = rng.gen_range(0, edges.len());
+        let (a, b) = *edges.get(i);
+        let (c, d) = *edges.get(j);
+        // (a, b), (c, d) -> (a, d), (c, b) unless it creates a loop or a
+        // parallel Edge
+        if a == d || c == b {
+            continue
+        }
+        let first = if a < d { (a, d) } else { (d, a) };
+        let second = if c < b { (c, b) } else { (b, c) };
+        if first == second || present.contains(&first) || present.contains(&second) {
+            continue
+        }
+        present.remove(&(a, b));
+        present.remove(&(c, d));
+        present.insert(first);
+        present.insert(second);
+        *edges.get_mut(i) = first;
+        *edges.get_mut(j) = second;
+    }
+    edges
+}
@@ -0,0 +1,129 @@
+//! One screen summary of a Graph.
+
+use std::fmt;
+
+use graph::Graph;
+use indexed::{IndexedGraph, intersection_len};
+use union_find::UnionFind;
+
+/**
+* Summary statistics of a Graph.
+*
+* The degree of a Vertex counts its incoming and outgoing Edges, or its
+* Edges in an undirected Graph, where each Edge is counted once. A self
+* loop adds 2 to the degree of its Vertex, as in Graph::vertices_by_degree.
+* The components and the clustering coefficient ignore the direction of
+* the Edges.
+*/
+#[deriving(Clone, PartialEq)]
+pub struct GraphSummary {
+    pub vertices:      uint,
+    pub edges:         uint,
+    pub directed:      bool,
+    /// edges / the number of possible Edges
+    pub density:       f64,
+    /// The number of weakly connected components
+    pub components:    uint,
+    /// The number of Vertices of the largest component
+    pub largest_component: uint,
+    pub min_degree:    uint,
+    pub max_degree:    uint,
+    pub mean_degree:   f64,
+    /// The average of the local clustering coefficients
+    pub clustering:    f64
+}
+
+impl fmt::Show for GraphSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        try!(writeln!(f, "{} graph", if self.directed { "directed" } else { "undirected" }));
+        try!(writeln!(f, "  vertices:           {}", self.vertices));
+        try!(writeln!(f, "  edges:              {}", self.edges));
+        try!(writeln!(f, "  density:            {:.6f}", self.density));
+        try!(writeln!(f, "  components:         {} (largest: {} vertices)",
+                      self.components, self.largest_component));
+        try!(writeln!(f, "  degree:             min {}, max {}, mean {:.3f}",
+                      self.min_degree, self.max_degree, self.mean_degree));
+        write!(f, "  clustering:         {:.6f}", self.clustering)
+    }
+}
+
+/**
+* Compute the summary statistics of a Graph.
+*
+* # Arguments
+* * graph - The graph to summarize
+*
+* # Return
+* The summary of the Graph, use its Show implementation for a readable
+* report.
+*/
+pub fn summary<K: Ord + Eq + Clone,
+               L: Eq + Clone,
+               V: PartialEq + Clone>
+               (graph: &Graph<K, L, V>) -> GraphSummary {
+    let indexed = IndexedGraph::from_graph(graph);
+    let n = indexed.len();
+    let directed = graph.is_directed();
+    // an undirected Graph stores each Edge in both directions
+    let edges = if directed {
+        indexed.out_edges.iter().fold(0, |sum, e| sum + e.len())
+    } else {
+        indexed.out_edges.iter().enumerate().fold(0, |sum, (v, e)| {
+            sum + e.iter().filter(|&&u| u >= v).count()
+        })
+    };
+    let degrees = indexed.degrees(directed);
+
+    let mut sets = UnionFind::new(n);
+    for (v, successors) in indexed.out_edges.iter().enumerate() {
+        for &u in successors.iter() {
+            sets.union(v, u);
+        }
+    }
+    let mut sizes = Vec::from_elem(n, 0u);
+    for v in range(0, n) {
+        *sizes.get_mut(sets.find(v)) += 1;
+    }
+
+    let possible = n as f64 * (n as f64 - 1.0) / if directed { 1.0 } else { 2.0 };
+    GraphSummary {
+        vertices:           n,
+        edges:              edges,
+        directed:           directed,
+        density:            if possible > 0.0 { edges as f64 / possible } else { 0.0 },
+        components:         sets.sets(),
+        largest_component:  sizes.iter().max().map(|s| *s).unwrap_or(0),
+        min_degree:         degrees.iter().min().map(|d| *d).unwrap_or(0),
+        max_degree:         degrees.iter().max().map(|d| *d).unwrap_or(0),
+        mean_degree:        if n > 0 { 2.0 * edges as f64 / n as f64 } else { 0.0 },
+        clustering:         average_clustering(&indexed)
+    }
+}
+
+// average local clustering coefficient of the undirected simple Graph,
+// Vertices with less than two neighbors count as 0
+fn average_clustering<K>(indexed: &IndexedGraph<K>) -> f64 {
+    let n = indexed.len();
+    if n == 0 {
+        return 0.0
+    }
+    let neighbors: Vec<Vec<uint>> = indexed.undirected_edges().move_iter().enumerate().map(|(v, mut ns)| {
+        ns.retain(|&u| u != v);
+        ns
+    }).collect();
+
+    let mut total = 0.0;
+    for ns in neighbors.iter() {
+        let k = ns.len();
+        if k < 2 {
+            continue
+        }
+        let mut links = 0u;
+        for &u in ns.iter() {
+            links += intersection_len(ns.as_slice(), neighbors.get(u).as_slice());
+        }
+        // each link between two neighbors is seen from both of them
+        total += (links as f64 / 2.0) / (k * (k - 1) / 2) as f64;
+    }
+    total / n as f64
+}
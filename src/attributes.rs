@@ -0,0 +1,380 @@
+//! Named and typed attributes of the Vertices and the Edges.
+
+use std::collections::TreeMap;
+use std::collections::treemap::Entries;
+
+/**
+* Type of an attribute, as declared in an AttributeSchema.
+*/
+#[deriving(Clone, PartialEq, Eq, Show, Encodable, Decodable)]
+pub enum AttributeType {
+    BoolType,
+    IntType,
+    FloatType,
+    StringType
+}
+
+/**
+* Value of an attribute.
+*/
+#[deriving(Clone, PartialEq, Show, Encodable, Decodable)]
+pub enum AttributeValue {
+    AttrBool(bool),
+    AttrInt(i64),
+    AttrFloat(f64),
+    AttrString(String)
+}
+
+impl AttributeValue {
+
+    /**
+    * Get the type of the value.
+    */
+    pub fn get_type(&self) -> AttributeType {
+        match *self {
+            AttrBool(_)   => BoolType,
+            AttrInt(_)    => IntType,
+            AttrFloat(_)  => FloatType,
+            AttrString(_) => StringType
+        }
+    }
+}
+
+/**
+* Declaration of an attribute.
+*/
+#[deriving(Clone, PartialEq, Show, Encodable, Decodable)]
+pub struct AttributeDef {
+    pub attr_type:     AttributeType,
+    /// The value of the elements without their own value
+    pub default:       Option<AttributeValue>
+}
+
+/**
+* Names, types and default values of the attributes of the Vertices and of
+* the Edges.
+*
+* The exporters use it to declare the attributes before the elements.
+*/
+#[deriving(Clone, PartialEq, Show, Encodable, Decodable)]
+pub struct AttributeSchema {
+    vertex_attributes: TreeMap<String, AttributeDef>,
+    edge_attributes:   TreeMap<String, AttributeDef>
+}
+
+impl AttributeSchema {
+
+    /**
+    * Create a new empty AttributeSchema.
+    *
+    * # Return
+    * A new AttributeSchema without any attribute.
+    */
+    pub fn new() -> AttributeSchema {
+        AttributeSchema {
+            vertex_attributes:  TreeMap::new(),
+            edge_attributes:    TreeMap::new()
+        }
+    }
+
+    /**
+    * Declare an attribute of the Vertices.
+    *
+    * # Arguments
+    * * name - The name of the attribute
+    * * attr_type - The type of the attribute
+    * * default - The optional default value, of type attr_type
+    *
+    * # Return
+    * true if the attribute is declared, false if the name is already used
+    * or if the default value has another type.
+    */
+    pub fn declare_vertex_attribute(&mut self,
+                                    name: &str,
+                                    attr_type: AttributeType,
+                                    default: Option<AttributeValue>)
+                                    -> bool {
+        declare(&mut self.vertex_attributes, name, attr_type, default)
+    }
+
+    /**
+    * Declare an attribute of the Edges.
+    *
+    * # Arguments
+    * * name - The name of the attribute
+    * * attr_type - The type of the attribute
+    * * default - The optional default value, of type attr_type
+    *
+    * # Return
+    * true if the attribute is declared, false if the name is already used
+    * or if the default value has another type.
+    */
+    pub fn declare_edge_attribute(&mut self,
+                                  name: &str,
+                                  attr_type: AttributeType,
+                                  default: Option<AttributeValue>)
+                                  -> bool {
+        declare(&mut self.edge_attributes, name, attr_type, default)
+    }
+
+    /**
+    * Get the declaration of an attribute of the Vertices.
+    */
+    pub fn vertex_attribute<'r>(&'r self, name: &str) -> Option<&'r AttributeDef> {
+        self.vertex_attributes.find(&name.to_string())
+    }
+
+    /**
+    * Get the declaration of an attribute of the Edges.
+    */
+    pub fn edge_attribute<'r>(&'r self, name: &str) -> Option<&'r AttributeDef> {
+        self.edge_attributes.find(&name.to_string())
+    }
+
+    /**
+    * Get an iterator over the attributes of the Vertices, sorted by name.
+    */
+    pub fn vertex_attributes<'r>(&'r self) -> Entries<'r, String, AttributeDef> {
+        self.vertex_attributes.iter()
+    }
+
+    /**
+    * Get an iterator over the attributes of the Edges, sorted by name.
+    */
+    pub fn edge_attributes<'r>(&'r self) -> Entries<'r, String, AttributeDef> {
+        self.edge_attributes.iter()
+    }
+}
+
+/**
+* Attributes of the Vertices and the Edges of a Graph, checked against an
+* AttributeSchema.
+*
+* Like a PropertyMap it is not owned by the Graph, register it as an
+* observer to drop the attributes of the removed elements.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+*/
+#[deriving(Clone, PartialEq, Show)]
+pub struct Attributes<K> {
+    schema:            AttributeSchema,
+    vertices:          TreeMap<K, TreeMap<String, AttributeValue>>,
+    edges:             TreeMap<(K, K), TreeMap<String, AttributeValue>>
+}
+
+impl<K: Ord + Clone> Attributes<K> {
+
+    /**
+    * Create a new Attributes without any value.
+    *
+    * # Arguments
+    * * schema - The declaration of the attributes
+    *
+    * # Return
+    * A new Attributes.
+    */
+    pub fn new(schema: AttributeSchema) -> Attributes<K> {
+        Attributes {
+            schema:     schema,
+            vertices:   TreeMap::new(),
+            edges:      TreeMap::new()
+        }
+    }
+
+    /**
+    * Get the declaration of the attributes.
+    */
+    pub fn schema<'r>(&'r self) -> &'r AttributeSchema {
+        &self.schema
+    }
+
+    /**
+    * Set an attribute of a Vertex.
+    *
+    * # Arguments
+    * * key - The key of the Vertex
+    * * name - The name of the attribute
+    * * value - The value of the attribute
+    *
+    * # Return
+    * true if the value is set, false if the attribute is not declared or
+    * has another type.
+    */
+    pub fn set_vertex_attribute(&mut self,
+                                key: K,
+                                name: &str,
+                                value: AttributeValue)
+                                -> bool {
+        if !accepts(self.schema.vertex_attribute(name), &value) {
+            return false
+        }
+        if !self.vertices.contains_key(&key) {
+            self.vertices.insert(key.clone(), TreeMap::new());
+        }
+        self.vertices.find_mut(&key).unwrap().insert(name.to_string(), value);
+        true
+    }
+
+    /**
+    * Get an attribute of a Vertex.
+    *
+    * # Return
+    * Some(value) with the value of the Vertex or the default value, None
+    * if there is none.
+    */
+    pub fn get_vertex_attribute<'r>(&'r self,
+                                    key: &K,
+                                    name: &str)
+                                    -> Option<&'r AttributeValue> {
+        let own = self.vertices.find(key).and_then(|values| values.find(&name.to_string()));
+        own.or_else(|| self.schema.vertex_attribute(name).and_then(|def| def.default.as_ref()))
+    }
+
+    /**
+    * Remove an attribute of a Vertex, its default value is used after.
+    *
+    * # Return
+    * Some(value) with the removed value, None if the Vertex had none.
+    */
+    pub fn remove_vertex_attribute(&mut self,
+                                   key: &K,
+                                   name: &str)
+                                   -> Option<AttributeValue> {
+        self.vertices.find_mut(key).and_then(|values| values.pop(&name.to_string()))
+    }
+
+    /**
+    * Get the own attributes of a Vertex, without the default values.
+    */
+    pub fn vertex_attributes<'r>(&'r self,
+                                 key: &K)
+                                 -> Option<&'r TreeMap<String, AttributeValue>> {
+        self.vertices.find(key)
+    }
+
+    /**
+    * Set an attribute of an Edge.
+    *
+    * # Arguments
+    * * from_key - The key of the first Vertex of the Edge
+    * * to_key - The key of the second Vertex of the Edge
+    * * name - The name of the attribute
+    * * value - The value of the attribute
+    *
+    * # Return
+    * true if the value is set, false if the attribute is not declared or
+    * has another type.
+    */
+    pub fn set_edge_attribute(&mut self,
+                              from_key: K,
+                              to_key: K,
+                              name: &str,
+                              value: AttributeValue)
+                              -> bool {
+        if !accepts(self.schema.edge_attribute(name), &value) {
+            return false
+        }
+        let edge = (from_key, to_key);
+        if !self.edges.contains_key(&edge) {
+            self.edges.insert(edge.clone(), TreeMap::new());
+        }
+        self.edges.find_mut(&edge).unwrap().insert(name.to_string(), value);
+        true
+    }
+
+    /**
+    * Get an attribute of an Edge.
+    *
+    * # Return
+    * Some(value) with the value of the Edge or the default value, None if
+    * there is none.
+    */
+    pub fn get_edge_attribute<'r>(&'r self,
+                                  from_key: &K,
+                                  to_key: &K,
+                                  name: &str)
+                                  -> Option<&'r AttributeValue> {
+        let edge = (from_key.clone(), to_key.clone());
+        let own = self.edges.find(&edge).and_then(|values| values.find(&name.to_string()));
+        own.or_else(|| self.schema.edge_attribute(name).and_then(|def| def.default.as_ref()))
+    }
+
+    /**
+    * Remove an attribute of an Edge, its default value is used after.
+    *
+    * # Return
+    * Some(value) with the removed value, None if the Edge had none.
+    */
+    pub fn remove_edge_attribute(&mut self,
+                                 from_key: &K,
+                                 to_key: &K,
+                                 name: &str)
+                                 -> Option<AttributeValue> {
+        let edge = (from_key.clone(), to_key.clone());
+        self.edges.find_mut(&edge).and_then(|values| values.pop(&name.to_string()))
+    }
+
+    /**
+    * Get the own attributes of an Edge, without the default values.
+    */
+    pub fn edge_attributes<'r>(&'r self,
+                               from_key: &K,
+                               to_key: &K)
+                               -> Option<&'r TreeMap<String, AttributeValue>> {
+        self.edges.find(&(from_key.clone(), to_key.clone()))
+    }
+
+    /**
+    * Drop every attribute of a Vertex and of its Edges.
+    */
+    pub fn remove_vertex(&mut self, key: &K) {
+        self.vertices.pop(key);
+        let edges: Vec<(K, K)> = self.edges.iter().filter(|&(&(ref from, ref to), _)| {
+            from == key || to == key
+        }).map(|(edge, _)| edge.clone()).collect();
+        for edge in edges.iter() {
+            self.edges.pop(edge);
+        }
+    }
+
+    /**
+    * Drop every attribute of an Edge.
+    */
+    pub fn remove_edge(&mut self, from_key: &K, to_key: &K) {
+        self.edges.pop(&(from_key.clone(), to_key.clone()));
+    }
+
+    /**
+    * Drop every attribute, the schema is kept.
+    */
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+        self.edges.clear();
+    }
+}
+
+fn declare(attributes: &mut TreeMap<String, AttributeDef>,
+           name: &str,
+           attr_type: AttributeType,
+           default: Option<AttributeValue>)
+           -> bool {
+    let default_ok = match default {
+        Some(ref value) => value.get_type() == attr_type,
+        None            => true
+    };
+    if !default_ok || attributes.contains_key(&name.to_string()) {
+        return false
+    }
+    attributes.insert(name.to_string(), AttributeDef {
+        attr_type:  attr_type,
+        default:    default
+    })
+}
+
+fn accepts(def: Option<&AttributeDef>, value: &AttributeValue) -> bool {
+    match def {
+        Some(def) => def.attr_type == value.get_type(),
+        None      => false
+    }
+}
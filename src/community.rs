@@ -0,0 +1,248 @@
+//! Detection of the communities of a Graph.
+
+use std::collections::HashMap;
+use std::rand::Rng;
+
+use graph::Graph;
+use indexed::IndexedGraph;
+use property_map::PropertyMap;
+use analysis::undirected_modularity;
+
+/**
+* Partition of the Vertices of a Graph in communities.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+*/
+#[deriving(Clone, PartialEq, Show)]
+pub struct Communities<K> {
+    /// The community of each Vertex, numbered from 0 in the order of the
+    /// first Vertex of each community
+    pub partition:     PropertyMap<K, uint>,
+    /// The number of communities
+    pub count:         uint,
+    /// The undirected modularity of the partition
+    pub modularity:    f64
+}
+
+/**
+* Detect the communities of a Graph by label propagation, as proposed by
+* Raghavan, Albert and Kumara.
+*
+* Every Vertex starts with its own label, then in a random order each
+* Vertex takes the label the most frequent among its neighbors, until every
+* Vertex has one of the most frequent labels of its neighbors. The
+* direction of the Edges is ignored. It runs in almost linear time but the
+* result depends on the random order.
+*
+* # Arguments
+* * graph - The graph to split
+* * max_iterations - The maximum number of passes over the Vertices
+* * rng - The random number generator used for the order and the ties
+*
+* # Return
+* The communities, the Vertices sharing a label.
+*/
+pub fn label_propagation<K: Ord + Eq + Clone,
+                         L: Eq + Clone,
+                         V: PartialEq + Clone,
+                         R: Rng>
+                         (graph: &Graph<K, L, V>,
+                         max_iterations: uint,
+                         rng: &mut R)
+                         -> Communities<K> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let n = indexed.len();
+    let adjacency: Vec<Vec<uint>> = indexed.undirected_edges().move_iter().enumerate().map(|(v, edges)| {
+        edges.move_iter().filter(|&u| u != v).collect()
+    }).collect();
+
+    let mut labels: Vec<uint> = range(0, n).collect();
+    let mut order: Vec<uint> = range(0, n).collect();
+    for _ in range(0, max_iterations) {
+        rng.shuffle(order.as_mut_slice());
+        for &v in order.iter() {
+            let best = most_frequent(adjacency.get(v).as_slice(), &labels);
+            if !best.is_empty() && !best.contains(labels.get(v)) {
+                *labels.get_mut(v) = best[rng.gen_range(0, best.len())];
+            }
+        }
+        let stable = range(0, n).all(|v| {
+            let best = most_frequent(adjacency.get(v).as_slice(), &labels);
+            best.is_empty() || best.contains(labels.get(v))
+        });
+        if stable {
+            break
+        }
+    }
+    communities(graph, &indexed, labels.as_slice())
+}
+
+/**
+* Detect the communities of a Graph with the Louvain method of Blondel et
+* al.
+*
+* Each Vertex is moved to the community of a neighbor while it increases
+* the modularity, then each community is collapsed into a Vertex and the
+* moves start again on the collapsed Graph, until no move increases the
+* modularity. The direction of the Edges is ignored, two opposite Edges
+* count as one and the self loops are ignored, as in undirected_modularity.
+*
+* # Arguments
+* * graph - The graph to split
+*
+* # Return
+* The communities of the last level.
+*/
+pub fn louvain<K: Ord + Eq + Clone,
+               L: Eq + Clone,
+               V: PartialEq + Clone>
+               (graph: &Graph<K, L, V>) -> Communities<K> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let n = indexed.len();
+
+    // the weighted Edges between the nodes of the current level and the
+    // weight of the Edges inside each node
+    let mut adjacency: Vec<Vec<(uint, f64)>> = indexed.undirected_edges().move_iter().enumerate().map(|(v, edges)| {
+        edges.move_iter().filter(|&u| u != v).map(|u| (u, 1.0)).collect()
+    }).collect();
+    let mut inside: Vec<f64> = Vec::from_elem(n, 0.0);
+    // the node of each Vertex in the current level
+    let mut membership: Vec<uint> = range(0, n).collect();
+
+    loop {
+        let nodes = adjacency.len();
+        let degrees: Vec<f64> = range(0, nodes).map(|i| {
+            adjacency.get(i).iter().fold(2.0 * *inside.get(i), |sum, &(_, w)| sum + w)
+        }).collect();
+        let m2 = degrees.iter().fold(0.0, |sum, &k| sum + k);
+        if m2 == 0.0 {
+            break
+        }
+
+        let mut community: Vec<uint> = range(0, nodes).collect();
+        let mut totals = degrees.clone();
+        let mut moved = false;
+        loop {
+            let mut improved = false;
+            for i in range(0, nodes) {
+                let current = *community.get(i);
+                let k = *degrees.get(i);
+                *totals.get_mut(current) -= k;
+
+                // the weight of the Edges from i to each neighbor community
+                let mut links: HashMap<uint, f64> = HashMap::new();
+                for &(j, w) in adjacency.get(i).iter() {
+                    let c = *community.get(j);
+                    let total = links.find(&c).map_or(0.0, |&l| l) + w;
+                    links.insert(c, total);
+                }
+                let best = {
+                    let gain = |c: uint| -> f64 {
+                        links.find(&c).map_or(0.0, |&l| l) - *totals.get(c) * k / m2
+                    };
+                    let mut best = current;
+                    let mut best_gain = gain(current);
+                    let mut candidates: Vec<uint> = links.keys().map(|&c| c).collect();
+                    candidates.sort();
+                    for &c in candidates.iter() {
+                        let g = gain(c);
+                        if g > best_gain {
+                            best = c;
+                            best_gain = g;
+                        }
+                    }
+                    best
+                };
+
+                *totals.get_mut(best) += k;
+                if best != current {
+                    *community.get_mut(i) = best;
+                    improved = true;
+                    moved = true;
+                }
+            }
+            if !improved {
+                break
+            }
+        }
+        if !moved {
+            break
+        }
+
+        // collapse each community into a node
+        let mut ids: Vec<Option<uint>> = Vec::from_elem(nodes, None);
+        let mut count = 0u;
+        for i in range(0, nodes) {
+            let c = *community.get(i);
+            if ids.get(c).is_none() {
+                *ids.get_mut(c) = Some(count);
+                count += 1;
+            }
+        }
+        let node_of: Vec<uint> = community.iter().map(|&c| ids.get(c).unwrap()).collect();
+        let mut next_inside = Vec::from_elem(count, 0.0f64);
+        let mut weights: Vec<HashMap<uint, f64>> = Vec::from_elem(count, HashMap::new());
+        for i in range(0, nodes) {
+            let a = *node_of.get(i);
+            *next_inside.get_mut(a) += *inside.get(i);
+            for &(j, w) in adjacency.get(i).iter() {
+                let b = *node_of.get(j);
+                if a == b {
+                    // each Edge is seen from both ends
+                    *next_inside.get_mut(a) += w / 2.0;
+                } else {
+                    let total = weights.get(a).find(&b).map_or(0.0, |&t| t) + w;
+                    weights.get_mut(a).insert(b, total);
+                }
+            }
+        }
+        adjacency = weights.move_iter().map(|edges| {
+            let mut edges: Vec<(uint, f64)> = edges.move_iter().collect();
+            edges.sort_by(|&(a, _), &(b, _)| a.cmp(&b));
+            edges
+        }).collect();
+        inside = next_inside;
+        for node in membership.mut_iter() {
+            *node = *node_of.get(*node);
+        }
+    }
+    communities(graph, &indexed, membership.as_slice())
+}
+
+// the labels the most frequent among the neighbors, empty without neighbor
+fn most_frequent(neighbors: &[uint], labels: &Vec<uint>) -> Vec<uint> {
+    let mut counts: HashMap<uint, uint> = HashMap::new();
+    for &u in neighbors.iter() {
+        let label = *labels.get(u);
+        let count = counts.find(&label).map_or(0, |&c| c) + 1;
+        counts.insert(label, count);
+    }
+    let max = counts.values().max().map_or(0, |&c| c);
+    let mut best: Vec<uint> = counts.iter().filter(|&(_, &c)| c == max).map(|(&l, _)| l).collect();
+    best.sort();
+    best
+}
+
+// number the labels of the Vertices in the order of their first Vertex
+fn communities<K: Ord + Eq + Clone,
+               L: Eq + Clone,
+               V: PartialEq + Clone>
+               (graph: &Graph<K, L, V>,
+               indexed: &IndexedGraph<K>,
+               labels: &[uint])
+               -> Communities<K> {
+    let mut ids: HashMap<uint, uint> = HashMap::new();
+    let mut partition = PropertyMap::new();
+    for (key, label) in indexed.keys.iter().zip(labels.iter()) {
+        let next = ids.len();
+        let id = *ids.find_or_insert(*label, next);
+        partition.insert(key.clone(), id);
+    }
+    let modularity = undirected_modularity(graph, &partition);
+    Communities {
+        partition:  partition,
+        count:      ids.len(),
+        modularity: modularity
+    }
+}
@@ -0,0 +1,330 @@
+//! Immutable Graph in compressed sparse row form.
+
+use std::collections::{Deque, RingBuf};
+
+use graph::{Graph, GraphConfig};
+use heap::MinHeap;
+use property_map::PropertyMap;
+use weight::Weight;
+use algo::ShortestPaths;
+
+/**
+* Frozen copy of a Graph in compressed sparse row form, created by
+* Graph::freeze.
+*
+* The Vertices are numbered in ascending key order and the Edges of all the
+* Vertices are stored in one vector, the Edges of the Vertex i being
+* between offsets[i] and offsets[i + 1]. The searches walk contiguous
+* slices of numbers instead of following a map of keys, and clone a key
+* only for the result. A CsrGraph can't be changed, thaw gives back a
+* Graph.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+* * L - The Vertex's Label type
+* * V - The Edge's Value type
+*/
+#[deriving(Clone, PartialEq)]
+pub struct CsrGraph<K, L, V> {
+    keys:              Vec<K>,
+    labels:            Vec<Option<L>>,
+    offsets:           Vec<uint>,
+    targets:           Vec<uint>,
+    values:            Vec<Option<V>>,
+    config:            GraphConfig
+}
+
+impl<K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
+     Graph<K, L, V> {
+
+    /**
+    * Freeze a copy of the Graph for the searches.
+    *
+    * # Return
+    * A new CsrGraph with the Vertices and the Edges of the Graph.
+    */
+    pub fn freeze(&self) -> CsrGraph<K, L, V> {
+        let mut keys = Vec::new();
+        let mut labels = Vec::new();
+        for (key, label) in self.vertices_iter() {
+            keys.push(key.clone());
+            labels.push(label.map(|l| l.clone()));
+        }
+        let mut offsets = vec!(0u);
+        let mut targets = Vec::new();
+        let mut values = Vec::new();
+        for vertex in self.vertices_ref_iter() {
+            // the Edges are sorted by key, so are their numbers
+            for (to_key, value) in vertex.edges_iter() {
+                targets.push(position(keys.as_slice(), to_key).unwrap());
+                values.push(value.map(|v| v.clone()));
+            }
+            offsets.push(targets.len());
+        }
+        CsrGraph {
+            keys:       keys,
+            labels:     labels,
+            offsets:    offsets,
+            targets:    targets,
+            values:     values,
+            config:     self.config()
+        }
+    }
+}
+
+impl<K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
+     CsrGraph<K, L, V> {
+
+    /**
+    * Create a Graph with the Vertices and the Edges of the CsrGraph.
+    *
+    * # Return
+    * A new graph with the config of the frozen Graph.
+    */
+    pub fn thaw(&self) -> Graph<K, L, V> {
+        let mut graph = Graph::new_with_config(self.config.clone());
+        for (key, label) in self.keys.iter().zip(self.labels.iter()) {
+            graph.add_vertex_opt_l(key.clone(), label.clone());
+        }
+        for v in range(0, self.len()) {
+            let (start, end) = (*self.offsets.get(v), *self.offsets.get(v + 1));
+            for e in range(start, end) {
+                graph.add_edge_opt_v(self.keys.get(v).clone(),
+                                     self.keys.get(*self.targets.get(e)).clone(),
+                                     self.values.get(e).clone());
+            }
+        }
+        graph
+    }
+
+    /**
+    * Get the number of Vertices.
+    */
+    pub fn len(&self) -> uint {
+        self.keys.len()
+    }
+
+    /**
+    * Get the number of stored Edges, an undirected Graph stores each Edge
+    * in both directions.
+    */
+    pub fn edge_count(&self) -> uint {
+        self.targets.len()
+    }
+
+    /**
+    * Is the frozen Graph directed or not.
+    */
+    pub fn is_directed(&self) -> bool {
+        self.config.is_directed()
+    }
+
+    /**
+    * Get the number of a Vertex, in logarithmic time.
+    *
+    * # Return
+    * Some(number) if the Vertex exist, None otherwise.
+    */
+    pub fn index_of(&self, key: &K) -> Option<uint> {
+        position(self.keys.as_slice(), key)
+    }
+
+    /**
+    * Get the key of a Vertex from its number.
+    */
+    pub fn key<'r>(&'r self, v: uint) -> &'r K {
+        self.keys.get(v)
+    }
+
+    /**
+    * Get the label of a Vertex from its number.
+    */
+    pub fn label<'r>(&'r self, v: uint) -> Option<&'r L> {
+        self.labels.get(v).as_ref()
+    }
+
+    /**
+    * Get the numbers of the second Vertices of the Edges starting from a
+    * Vertex, in ascending order.
+    */
+    pub fn neighbors<'r>(&'r self, v: uint) -> &'r [uint] {
+        self.targets.slice(*self.offsets.get(v), *self.offsets.get(v + 1))
+    }
+
+    /**
+    * Get the values of the Edges starting from a Vertex, in the order of
+    * neighbors.
+    */
+    pub fn edge_values<'r>(&'r self, v: uint) -> &'r [Option<V>] {
+        self.values.slice(*self.offsets.get(v), *self.offsets.get(v + 1))
+    }
+
+    /**
+    * Search the Graph breadth first, the successors of a Vertex are
+    * discovered in the order of their keys.
+    *
+    * # Arguments
+    * * start - The key of the first Vertex
+    *
+    * # Return
+    * The keys of the Vertices reachable from start in the order they are
+    * visited, empty if start does not exist.
+    */
+    pub fn bfs(&self, start: &K) -> Vec<K> {
+        let mut order = Vec::new();
+        let start = match self.index_of(start) {
+            Some(start) => start,
+            None        => return order
+        };
+        let mut discovered = Vec::from_elem(self.len(), false);
+        let mut queue = RingBuf::new();
+        *discovered.get_mut(start) = true;
+        queue.push_back(start);
+        while !queue.is_empty() {
+            let v = queue.pop_front().unwrap();
+            order.push(self.keys.get(v).clone());
+            for &u in self.neighbors(v).iter() {
+                if !*discovered.get(u) {
+                    *discovered.get_mut(u) = true;
+                    queue.push_back(u);
+                }
+            }
+        }
+        order
+    }
+
+    /**
+    * Search the Graph depth first, the successors of a Vertex are explored
+    * in the order of their keys.
+    *
+    * # Arguments
+    * * start - The key of the first Vertex
+    *
+    * # Return
+    * The keys of the Vertices reachable from start in preorder, empty if
+    * start does not exist.
+    */
+    pub fn dfs(&self, start: &K) -> Vec<K> {
+        let mut order = Vec::new();
+        let mut visited = Vec::from_elem(self.len(), false);
+        let mut stack: Vec<uint> = self.index_of(start).move_iter().collect();
+        while !stack.is_empty() {
+            let v = stack.pop().unwrap();
+            if *visited.get(v) {
+                continue
+            }
+            *visited.get_mut(v) = true;
+            order.push(self.keys.get(v).clone());
+            // the first successor is explored first
+            for &u in self.neighbors(v).iter().rev() {
+                if !*visited.get(u) {
+                    stack.push(u);
+                }
+            }
+        }
+        order
+    }
+}
+
+impl<K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone + Weight>
+     CsrGraph<K, L, V> {
+
+    /**
+    * Compute the shortest paths from a Vertex with the algorithm of
+    * Dijkstra, as algo::dijkstra.
+    *
+    * The paths are weighted by the values of the Edges, the Edges without
+    * value are ignored and the values must not be negative.
+    *
+    * # Arguments
+    * * source - The key of the first Vertex of the paths
+    *
+    * # Return
+    * The distances and the predecessors of the Vertices reachable from
+    * source, empty if source does not exist.
+    */
+    pub fn dijkstra(&self, source: &K) -> ShortestPaths<K, V> {
+        let mut paths = ShortestPaths {
+            distances:      PropertyMap::new(),
+            predecessors:   PropertyMap::new()
+        };
+        let source = match self.index_of(source) {
+            Some(source) => source,
+            None         => return paths
+        };
+
+        let n = self.len();
+        let mut distance: Vec<Option<V>> = Vec::from_elem(n, None);
+        let mut predecessor: Vec<Option<uint>> = Vec::from_elem(n, None);
+        let mut settled = Vec::from_elem(n, false);
+        let mut queue = MinHeap::new();
+        *distance.get_mut(source) = Some(Weight::zero());
+        queue.push(Weight::zero(), source);
+        loop {
+            let (d, v): (V, uint) = match queue.pop() {
+                Some(top) => top,
+                None      => break
+            };
+            if *settled.get(v) {
+                continue
+            }
+            *settled.get_mut(v) = true;
+            for (&u, value) in self.neighbors(v).iter().zip(self.edge_values(v).iter()) {
+                let value = match *value {
+                    Some(ref value) => value,
+                    None            => continue
+                };
+                if *settled.get(u) {
+                    continue
+                }
+                let candidate = d.add_weight(value);
+                let shorter = match *distance.get(u) {
+                    Some(ref old) => candidate < *old,
+                    None          => true
+                };
+                if shorter {
+                    *distance.get_mut(u) = Some(candidate.clone());
+                    *predecessor.get_mut(u) = Some(v);
+                    queue.push(candidate, u);
+                }
+            }
+        }
+
+        for (v, d) in distance.move_iter().enumerate() {
+            match d {
+                Some(d) => { paths.distances.insert(self.keys.get(v).clone(), d); },
+                None    => {}
+            }
+        }
+        for (v, p) in predecessor.iter().enumerate() {
+            match *p {
+                Some(p) => { paths.predecessors.insert(self.keys.get(v).clone(), self.keys.get(p).clone()); },
+                None    => {}
+            }
+        }
+        paths
+    }
+}
+
+// the position of a key in the sorted keys
+fn position<K: Ord>(keys: &[K], key: &K) -> Option<uint> {
+    let mut low = 0;
+    let mut high = keys.len();
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if keys[mid] < *key {
+            low = mid + 1;
+        } else if keys[mid] > *key {
+            high = mid;
+        } else {
+            return Some(mid)
+        }
+    }
+    None
+}
@@ -0,0 +1,348 @@
+//! Differences between two Graphs.
+
+use std::cmp::{Less, Equal, Greater};
+use std::collections::{TreeMap, TreeSet};
+
+use graph::{Graph, Vertex};
+
+/**
+* Changes transforming a Graph into another one.
+*
+* The removed Vertices and Edges keep their label and value so the changes
+* can be checked and reverted.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+* * L - The Vertex's Label type
+* * V - The Edge's Value type
+*/
+#[deriving(Clone, PartialEq, Show, Encodable, Decodable)]
+pub struct GraphDiff<K, L, V> {
+    /// The (key, label) of the new Vertices
+    pub added_vertices:    Vec<(K, Option<L>)>,
+    /// The (key, label) of the removed Vertices, their Edges are in
+    /// removed_edges
+    pub removed_vertices:  Vec<(K, Option<L>)>,
+    /// The (key, old label, new label) of the Vertices in both Graphs
+    pub changed_labels:    Vec<(K, Option<L>, Option<L>)>,
+    /// The (from_key, to_key, value) of the new Edges
+    pub added_edges:       Vec<(K, K, Option<V>)>,
+    /// The (from_key, to_key, value) of the removed Edges
+    pub removed_edges:     Vec<(K, K, Option<V>)>,
+    /// The (from_key, to_key, old value, new value) of the Edges in both
+    /// Graphs
+    pub changed_values:    Vec<(K, K, Option<V>, Option<V>)>
+}
+
+impl<K, L, V> GraphDiff<K, L, V> {
+
+    /**
+    * Create a new empty GraphDiff.
+    *
+    * # Return
+    * A GraphDiff without any change.
+    */
+    pub fn new() -> GraphDiff<K, L, V> {
+        GraphDiff {
+            added_vertices:     Vec::new(),
+            removed_vertices:   Vec::new(),
+            changed_labels:     Vec::new(),
+            added_edges:        Vec::new(),
+            removed_edges:      Vec::new(),
+            changed_values:     Vec::new()
+        }
+    }
+
+    /**
+    * Check if the two Graphs are the same.
+    */
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /**
+    * Get the number of changes.
+    */
+    pub fn len(&self) -> uint {
+        self.added_vertices.len() + self.removed_vertices.len() +
+            self.changed_labels.len() + self.added_edges.len() +
+            self.removed_edges.len() + self.changed_values.len()
+    }
+}
+
+/**
+* Difference between a Graph and the state expected by a GraphDiff applied
+* to it.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+* * L - The Vertex's Label type
+* * V - The Edge's Value type
+*/
+#[deriving(Clone, PartialEq, Show)]
+pub enum DiffConflict<K, L, V> {
+    /// A Vertex to add already exist
+    VertexExists(K),
+    /// A Vertex to remove, to relabel or to link does not exist
+    MissingVertex(K),
+    /// The label of a Vertex is not the expected one (key, expected, found)
+    LabelMismatch(K, Option<L>, Option<L>),
+    /// An Edge to add already exist
+    EdgeExists(K, K),
+    /// An Edge to remove or to change does not exist
+    MissingEdge(K, K),
+    /// The value of an Edge is not the expected one (from_key, to_key,
+    /// expected, found)
+    ValueMismatch(K, K, Option<V>, Option<V>),
+    /// An Edge of a Vertex to remove is not removed by the diff
+    UnexpectedEdge(K, K)
+}
+
+impl<K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
+     Graph<K, L, V> {
+
+    /**
+    * Compute the changes transforming the Graph into another one.
+    *
+    * # Arguments
+    * * other - The graph to reach
+    *
+    * # Return
+    * The changes, sorted by key.
+    */
+    pub fn diff(&self, other: &Graph<K, L, V>) -> GraphDiff<K, L, V> {
+        let old = index_vertices(self);
+        let new = index_vertices(other);
+        let mut diff = GraphDiff::new();
+
+        for (key, vertex) in old.iter() {
+            match new.find(key) {
+                Some(other_vertex) => {
+                    let label = vertex.get_label();
+                    let other_label = other_vertex.get_label();
+                    if label != other_label {
+                        diff.changed_labels.push(((*key).clone(),
+                                                  label.map(|l| l.clone()),
+                                                  other_label.map(|l| l.clone())));
+                    }
+                    diff_edges(*vertex, *other_vertex, &mut diff);
+                },
+                None               => {
+                    diff.removed_vertices.push(((*key).clone(),
+                                                vertex.get_label().map(|l| l.clone())));
+                    for (to_key, value) in vertex.edges_iter() {
+                        diff.removed_edges.push(((*key).clone(),
+                                                 to_key.clone(),
+                                                 value.map(|v| v.clone())));
+                    }
+                }
+            }
+        }
+        for (key, vertex) in new.iter() {
+            if !old.contains_key(key) {
+                diff.added_vertices.push(((*key).clone(),
+                                          vertex.get_label().map(|l| l.clone())));
+                for (to_key, value) in vertex.edges_iter() {
+                    diff.added_edges.push(((*key).clone(),
+                                           to_key.clone(),
+                                           value.map(|v| v.clone())));
+                }
+            }
+        }
+        diff
+    }
+
+    /**
+    * Apply the changes of a GraphDiff to the Graph.
+    *
+    * Every change is checked against the current state of the Graph
+    * before any mutation, so the Graph is either fully patched or left
+    * untouched.
+    *
+    * # Arguments
+    * * diff - The changes to apply, usually computed by diff on a Graph
+    *   equal to this one
+    *
+    * # Return
+    * Ok if the changes are applied, Err with every conflict otherwise.
+    */
+    pub fn apply(&mut self,
+                 diff: &GraphDiff<K, L, V>)
+                 -> Result<(), Vec<DiffConflict<K, L, V>>> {
+        let conflicts = self.check_diff(diff);
+        if !conflicts.is_empty() {
+            return Err(conflicts)
+        }
+        for &(ref from_key, ref to_key, _) in diff.removed_edges.iter() {
+            self.remove_edge(from_key.clone(), to_key.clone());
+        }
+        for &(ref from_key, ref to_key, _, ref value) in diff.changed_values.iter() {
+            self.set_edge_value_opt(from_key.clone(), to_key.clone(), value.clone());
+        }
+        for &(ref key, _, ref label) in diff.changed_labels.iter() {
+            self.set_vertex_label_opt(key.clone(), label.clone());
+        }
+        for &(ref key, _) in diff.removed_vertices.iter() {
+            self.remove_vertex(key.clone());
+        }
+        for &(ref key, ref label) in diff.added_vertices.iter() {
+            self.add_vertex_opt_l(key.clone(), label.clone());
+        }
+        for &(ref from_key, ref to_key, ref value) in diff.added_edges.iter() {
+            self.add_edge_opt_v(from_key.clone(), to_key.clone(), value.clone());
+        }
+        Ok(())
+    }
+
+    fn check_diff(&self, diff: &GraphDiff<K, L, V>) -> Vec<DiffConflict<K, L, V>> {
+        let vertices = index_vertices(self);
+        let mut conflicts = Vec::new();
+
+        let mut removed_edges: TreeSet<(&K, &K)> = TreeSet::new();
+        for &(ref from_key, ref to_key, ref expected) in diff.removed_edges.iter() {
+            match edge_value(&vertices, from_key, to_key) {
+                Some(ref value) if value == expected => {
+                    removed_edges.insert((from_key, to_key));
+                },
+                Some(value)                          => {
+                    conflicts.push(ValueMismatch(from_key.clone(), to_key.clone(),
+                                                 expected.clone(), value));
+                },
+                None                                 => {
+                    conflicts.push(MissingEdge(from_key.clone(), to_key.clone()));
+                }
+            }
+        }
+        for &(ref from_key, ref to_key, ref expected, _) in diff.changed_values.iter() {
+            match edge_value(&vertices, from_key, to_key) {
+                Some(ref value) if value == expected => {},
+                Some(value)                          => {
+                    conflicts.push(ValueMismatch(from_key.clone(), to_key.clone(),
+                                                 expected.clone(), value));
+                },
+                None                                 => {
+                    conflicts.push(MissingEdge(from_key.clone(), to_key.clone()));
+                }
+            }
+        }
+
+        let removed_vertices: TreeSet<&K> = diff.removed_vertices.iter()
+                                                                 .map(|&(ref key, _)| key)
+                                                                 .collect();
+        let added_vertices: TreeSet<&K> = diff.added_vertices.iter()
+                                                             .map(|&(ref key, _)| key)
+                                                             .collect();
+        let removed = diff.removed_vertices.iter().map(|&(ref key, ref label)| (key, label));
+        let relabeled = diff.changed_labels.iter().map(|&(ref key, ref label, _)| (key, label));
+        for (key, expected) in removed.chain(relabeled) {
+            match vertices.find(&key) {
+                Some(vertex) => {
+                    let label = vertex.get_label().map(|l| l.clone());
+                    if label != *expected {
+                        conflicts.push(LabelMismatch(key.clone(), expected.clone(), label));
+                    }
+                },
+                None         => conflicts.push(MissingVertex(key.clone()))
+            }
+        }
+        // the Edges of the removed Vertices must be removed with them
+        for vertex in self.vertices_ref_iter() {
+            let from_key = vertex.get_key();
+            for (to_key, _) in vertex.edges_iter() {
+                let incident = removed_vertices.contains(&from_key) ||
+                               removed_vertices.contains(&to_key);
+                if incident && !removed_edges.contains(&(from_key, to_key)) {
+                    conflicts.push(UnexpectedEdge(from_key.clone(), to_key.clone()));
+                }
+            }
+        }
+
+        for &(ref key, _) in diff.added_vertices.iter() {
+            if vertices.contains_key(&key) && !removed_vertices.contains(&key) {
+                conflicts.push(VertexExists(key.clone()));
+            }
+        }
+        for &(ref from_key, ref to_key, _) in diff.added_edges.iter() {
+            let exists_after = |key: &K| {
+                added_vertices.contains(&key) ||
+                    (vertices.contains_key(&key) && !removed_vertices.contains(&key))
+            };
+            if !exists_after(from_key) {
+                conflicts.push(MissingVertex(from_key.clone()));
+            } else if !exists_after(to_key) {
+                conflicts.push(MissingVertex(to_key.clone()));
+            } else if edge_value(&vertices, from_key, to_key).is_some() &&
+                      !removed_edges.contains(&(from_key, to_key)) {
+                conflicts.push(EdgeExists(from_key.clone(), to_key.clone()));
+            }
+        }
+        conflicts
+    }
+}
+
+fn index_vertices<'a,
+                  K: Ord + Eq + Clone,
+                  L: Eq + Clone,
+                  V: PartialEq + Clone>
+                  (graph: &'a Graph<K, L, V>)
+                  -> TreeMap<&'a K, &'a Vertex<K, L, V>> {
+    graph.vertices_ref_iter().map(|vertex| (vertex.get_key(), vertex)).collect()
+}
+
+// Some(value) of an Edge if it exist, None otherwise
+fn edge_value<K: Ord + Eq + Clone,
+              L: Eq + Clone,
+              V: PartialEq + Clone>
+              (vertices: &TreeMap<&K, &Vertex<K, L, V>>,
+              from_key: &K,
+              to_key: &K)
+              -> Option<Option<V>> {
+    match vertices.find(&from_key) {
+        Some(vertex) if vertex.edge_exist(to_key) => {
+            Some(vertex.get_edge_value(to_key).map(|v| v.clone()))
+        },
+        _                                         => None
+    }
+}
+
+// merge the Edges of a Vertex in both Graphs, they are sorted by key
+fn diff_edges<K: Ord + Eq + Clone,
+              L: Eq + Clone,
+              V: PartialEq + Clone>
+              (old: &Vertex<K, L, V>,
+              new: &Vertex<K, L, V>,
+              diff: &mut GraphDiff<K, L, V>) {
+    let key = old.get_key();
+    let mut old_edges = old.edges_iter().peekable();
+    let mut new_edges = new.edges_iter().peekable();
+    loop {
+        let order = match (old_edges.peek(), new_edges.peek()) {
+            (Some(&(a, _)), Some(&(b, _))) => a.cmp(b),
+            (Some(_), None)                => Less,
+            (None, Some(_))                => Greater,
+            (None, None)                   => break
+        };
+        match order {
+            Less    => {
+                let (to_key, value) = old_edges.next().unwrap();
+                diff.removed_edges.push((key.clone(), to_key.clone(), value.map(|v| v.clone())));
+            },
+            Greater => {
+                let (to_key, value) = new_edges.next().unwrap();
+                diff.added_edges.push((key.clone(), to_key.clone(), value.map(|v| v.clone())));
+            },
+            Equal   => {
+                let (to_key, value) = old_edges.next().unwrap();
+                let (_, other_value) = new_edges.next().unwrap();
+                if value != other_value {
+                    diff.changed_values.push((key.clone(),
+                                              to_key.clone(),
+                                              value.map(|v| v.clone()),
+                                              other_value.map(|v| v.clone())));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,384 @@
+//! Structures kept up to date while a Graph grows.
+//!
+//! These are companions of a Graph: feed them the same insertions as the
+//! Graph and they answer their queries without recomputing from scratch.
+
+use std::collections::{TreeMap, TreeSet};
+use std::mem::replace;
+
+use graph::Graph;
+use union_find::UnionFind;
+
+/**
+* Strongly connected components of a growing directed Graph.
+*
+* The components are kept as a condensation DAG. Inserting an Edge walks
+* every component reachable from the component of its target, which can
+* be the whole DAG, to check if the Edge closes a cycle; when it does, the
+* components on the cycle are merged.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+*/
+pub struct DynamicScc<K> {
+    keys:              Vec<K>,
+    index:             TreeMap<K, uint>,
+    sets:              UnionFind,
+    out_edges:         Vec<TreeSet<uint>>,
+    in_edges:          Vec<TreeSet<uint>>,
+    members:           Vec<Vec<uint>>,
+    self_loops:        Vec<bool>,
+    cyclic:            bool
+}
+
+impl<K: Ord + Eq + Clone> DynamicScc<K> {
+
+    /**
+    * Create a new DynamicScc without Vertices.
+    *
+    * # Return
+    * A new empty DynamicScc.
+    */
+    pub fn new() -> DynamicScc<K> {
+        DynamicScc {
+            keys:       Vec::new(),
+            index:      TreeMap::new(),
+            sets:       UnionFind::new(0),
+            out_edges:  Vec::new(),
+            in_edges:   Vec::new(),
+            members:    Vec::new(),
+            self_loops: Vec::new(),
+            cyclic:     false
+        }
+    }
+
+    /**
+    * Create a new DynamicScc from the Vertices and the Edges of a Graph.
+    *
+    * # Arguments
+    * * graph - The graph to start from
+    *
+    * # Return
+    * A new DynamicScc in sync with the Graph.
+    */
+    pub fn from_graph<L: Eq + Clone,
+                      V: PartialEq + Clone>
+                      (graph: &Graph<K, L, V>) -> DynamicScc<K> {
+        let mut scc = DynamicScc::new();
+        for vertex in graph.vertices_ref_iter() {
+            scc.add_vertex(vertex.get_key().clone());
+        }
+        for vertex in graph.vertices_ref_iter() {
+            for (to_key, _) in vertex.edges_iter() {
+                scc.add_edge(vertex.get_key().clone(), to_key.clone());
+            }
+        }
+        scc
+    }
+
+    /**
+    * Add a Vertex in its own component.
+    *
+    * # Arguments
+    * * key - The key of the new Vertex
+    *
+    * # Return
+    * true if the Vertex is added, false if it already exist.
+    */
+    pub fn add_vertex(&mut self, key: K) -> bool {
+        if self.index.contains_key(&key) {
+            return false
+        }
+        let v = self.sets.add();
+        self.index.insert(key.clone(), v);
+        self.keys.push(key);
+        self.out_edges.push(TreeSet::new());
+        self.in_edges.push(TreeSet::new());
+        self.members.push(vec!(v));
+        self.self_loops.push(false);
+        true
+    }
+
+    /**
+    * Add an Edge, the missing Vertices are added.
+    *
+    * # Arguments
+    * * from_key - The key of the first Vertex of the Edge
+    * * to_key - The key of the second Vertex of the Edge
+    *
+    * # Return
+    * true if the Edge closes a new cycle, false otherwise.
+    */
+    pub fn add_edge(&mut self, from_key: K, to_key: K) -> bool {
+        self.add_vertex(from_key.clone());
+        self.add_vertex(to_key.clone());
+        let from = *self.index.find(&from_key).unwrap();
+        let to = *self.index.find(&to_key).unwrap();
+
+        if from == to {
+            let new_loop = !*self.self_loops.get(from);
+            *self.self_loops.get_mut(from) = true;
+            self.cyclic = self.cyclic || new_loop;
+            return new_loop && self.members.get(self.sets.find(from)).len() == 1
+        }
+        let from = self.sets.find(from);
+        let to = self.sets.find(to);
+        if from == to {
+            return false
+        }
+
+        // the Edge closes a cycle if `to` already reaches `from`
+        let forward = self.reach(to, true, None);
+        if !forward.contains(&from) {
+            self.out_edges.get_mut(from).insert(to);
+            self.in_edges.get_mut(to).insert(from);
+            return false
+        }
+        let cycle = self.reach(from, false, Some(&forward));
+        self.merge(cycle);
+        self.cyclic = true;
+        true
+    }
+
+    /**
+    * Check if two Vertices are in the same component.
+    *
+    * # Return
+    * true if both Vertices exist and reach each other, false otherwise.
+    */
+    pub fn same_component(&self, a: &K, b: &K) -> bool {
+        match (self.index.find(a), self.index.find(b)) {
+            (Some(&a), Some(&b)) => self.sets.find_imm(a) == self.sets.find_imm(b),
+            _                    => false
+        }
+    }
+
+    /**
+    * Check if a Vertex belongs to a cycle.
+    *
+    * # Return
+    * true if the Vertex is in a non-trivial component or has a self loop.
+    */
+    pub fn in_cycle(&self, key: &K) -> bool {
+        match self.index.find(key) {
+            Some(&v) => {
+                *self.self_loops.get(v) ||
+                self.members.get(self.sets.find_imm(v)).len() > 1
+            },
+            None     => false
+        }
+    }
+
+    /**
+    * Check if the Graph contains a cycle.
+    */
+    pub fn has_cycle(&self) -> bool {
+        self.cyclic
+    }
+
+    /**
+    * Get the keys of the component of a Vertex.
+    *
+    * # Return
+    * Some(keys) if the Vertex exist, None otherwise.
+    */
+    pub fn component(&self, key: &K) -> Option<Vec<K>> {
+        self.index.find(key).map(|&v| {
+            self.members.get(self.sets.find_imm(v)).iter().map(|&m| {
+                self.keys.get(m).clone()
+            }).collect()
+        })
+    }
+
+    /**
+    * Get every component.
+    *
+    * # Return
+    * The vector of the keys of each component.
+    */
+    pub fn components(&self) -> Vec<Vec<K>> {
+        let mut components = Vec::new();
+        for (v, members) in self.members.iter().enumerate() {
+            if !members.is_empty() && self.sets.find_imm(v) == v {
+                components.push(members.iter().map(|&m| self.keys.get(m).clone()).collect());
+            }
+        }
+        components
+    }
+
+    /**
+    * Get the number of components.
+    */
+    pub fn len(&self) -> uint {
+        self.sets.sets()
+    }
+
+    // components reachable from start in the condensation, restricted to
+    // the given set if any
+    fn reach(&self,
+             start: uint,
+             forward: bool,
+             within: Option<&TreeSet<uint>>)
+             -> TreeSet<uint> {
+        let mut visited = TreeSet::new();
+        let mut stack = vec!(start);
+        visited.insert(start);
+        while !stack.is_empty() {
+            let c = stack.pop().unwrap();
+            let next = if forward { self.out_edges.get(c) } else { self.in_edges.get(c) };
+            for &n in next.iter() {
+                let allowed = match within {
+                    Some(set) => set.contains(&n),
+                    None      => true
+                };
+                if allowed && visited.insert(n) {
+                    stack.push(n);
+                }
+            }
+        }
+        visited
+    }
+
+    // merge components into a single one and update the condensation
+    fn merge(&mut self, components: TreeSet<uint>) {
+        let first = *components.iter().next().unwrap();
+        for &c in components.iter() {
+            self.sets.union(first, c);
+        }
+        let root = self.sets.find(first);
+
+        let mut out_edges = TreeSet::new();
+        let mut in_edges = TreeSet::new();
+        let mut members = Vec::new();
+        for &c in components.iter() {
+            let c_out = replace(self.out_edges.get_mut(c), TreeSet::new());
+            for n in c_out.move_iter().filter(|n| !components.contains(n)) {
+                self.in_edges.get_mut(n).remove(&c);
+                self.in_edges.get_mut(n).insert(root);
+                out_edges.insert(n);
+            }
+            let c_in = replace(self.in_edges.get_mut(c), TreeSet::new());
+            for n in c_in.move_iter().filter(|n| !components.contains(n)) {
+                self.out_edges.get_mut(n).remove(&c);
+                self.out_edges.get_mut(n).insert(root);
+                in_edges.insert(n);
+            }
+            members.push_all_move(replace(self.members.get_mut(c), Vec::new()));
+        }
+        *self.out_edges.get_mut(root) = out_edges;
+        *self.in_edges.get_mut(root) = in_edges;
+        *self.members.get_mut(root) = members;
+    }
+}
+
+/**
+* Connected components of a growing Graph, the direction of the Edges is
+* ignored.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+*/
+#[deriving(Clone)]
+pub struct DynamicConnectivity<K> {
+    index:             TreeMap<K, uint>,
+    sets:              UnionFind
+}
+
+impl<K: Ord + Eq + Clone> DynamicConnectivity<K> {
+
+    /**
+    * Create a new DynamicConnectivity without Vertices.
+    *
+    * # Return
+    * A new empty DynamicConnectivity.
+    */
+    pub fn new() -> DynamicConnectivity<K> {
+        DynamicConnectivity {
+            index:  TreeMap::new(),
+            sets:   UnionFind::new(0)
+        }
+    }
+
+    /**
+    * Create a new DynamicConnectivity from the Vertices and the Edges of a
+    * Graph.
+    *
+    * # Arguments
+    * * graph - The graph to start from
+    *
+    * # Return
+    * A new DynamicConnectivity in sync with the Graph.
+    */
+    pub fn from_graph<L: Eq + Clone,
+                      V: PartialEq + Clone>
+                      (graph: &Graph<K, L, V>) -> DynamicConnectivity<K> {
+        let mut connectivity = DynamicConnectivity::new();
+        for vertex in graph.vertices_ref_iter() {
+            connectivity.add_vertex(vertex.get_key().clone());
+        }
+        for vertex in graph.vertices_ref_iter() {
+            for (to_key, _) in vertex.edges_iter() {
+                connectivity.add_edge(vertex.get_key().clone(), to_key.clone());
+            }
+        }
+        connectivity
+    }
+
+    /**
+    * Add a Vertex in its own component.
+    *
+    * # Arguments
+    * * key - The key of the new Vertex
+    *
+    * # Return
+    * true if the Vertex is added, false if it already exist.
+    */
+    pub fn add_vertex(&mut self, key: K) -> bool {
+        if self.index.contains_key(&key) {
+            false
+        } else {
+            let v = self.sets.add();
+            self.index.insert(key, v);
+            true
+        }
+    }
+
+    /**
+    * Add an Edge, the missing Vertices are added.
+    *
+    * # Arguments
+    * * from_key - The key of the first Vertex of the Edge
+    * * to_key - The key of the second Vertex of the Edge
+    *
+    * # Return
+    * true if the Edge merges two components, false otherwise.
+    */
+    pub fn add_edge(&mut self, from_key: K, to_key: K) -> bool {
+        self.add_vertex(from_key.clone());
+        self.add_vertex(to_key.clone());
+        let from = *self.index.find(&from_key).unwrap();
+        let to = *self.index.find(&to_key).unwrap();
+        self.sets.union(from, to)
+    }
+
+    /**
+    * Check if two Vertices are connected.
+    *
+    * # Return
+    * true if both Vertices exist and are in the same component, false
+    * otherwise.
+    */
+    pub fn connected(&self, a: &K, b: &K) -> bool {
+        match (self.index.find(a), self.index.find(b)) {
+            (Some(&a), Some(&b)) => self.sets.find_imm(a) == self.sets.find_imm(b),
+            _                    => false
+        }
+    }
+
+    /**
+    * Get the number of components.
+    */
+    pub fn len(&self) -> uint {
+        self.sets.sets()
+    }
+}
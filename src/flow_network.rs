@@ -0,0 +1,134 @@
+//! Residual network of the maximum flow algorithms, on Vertices numbered
+//! from 0.
+
+use std::collections::{Deque, RingBuf};
+use std::f64;
+
+// Residual network, the Edge i and its reverse i ^ 1 are stored together
+pub struct FlowNetwork {
+    adjacency:         Vec<Vec<uint>>,
+    to:                Vec<uint>,
+    capacity:          Vec<f64>,
+    residual:          Vec<f64>
+}
+
+impl FlowNetwork {
+    pub fn new(n: uint) -> FlowNetwork {
+        FlowNetwork {
+            adjacency:  Vec::from_elem(n, Vec::new()),
+            to:         Vec::new(),
+            capacity:   Vec::new(),
+            residual:   Vec::new()
+        }
+    }
+
+    pub fn add_edge(&mut self, from: uint, to: uint, capacity: f64) -> uint {
+        let id = self.to.len();
+        self.to.push(to);
+        self.capacity.push(capacity);
+        self.residual.push(capacity);
+        self.to.push(from);
+        self.capacity.push(0.0);
+        self.residual.push(0.0);
+        self.adjacency.get_mut(from).push(id);
+        self.adjacency.get_mut(to).push(id + 1);
+        id
+    }
+
+    pub fn flow(&self, id: uint) -> f64 {
+        *self.residual.get(id ^ 1)
+    }
+
+    pub fn residual(&self, id: uint) -> f64 {
+        *self.residual.get(id)
+    }
+
+    // remove the flow, to compute another one on the same network
+    pub fn reset(&mut self) {
+        self.residual = self.capacity.clone();
+    }
+
+    // Dinic: blocking flows along the breadth first levels
+    pub fn max_flow(&mut self, source: uint, sink: uint) -> f64 {
+        let n = self.adjacency.len();
+        let mut value = 0.0;
+        loop {
+            let level = self.levels(source);
+            if level.get(sink).is_none() {
+                return value
+            }
+            let mut next = Vec::from_elem(n, 0u);
+            loop {
+                let pushed = self.augment(source, sink, &level, &mut next);
+                if !(pushed > 0.0) {
+                    break
+                }
+                value += pushed;
+            }
+        }
+    }
+
+    // breadth first levels in the residual network, None if unreachable
+    pub fn levels(&self, source: uint) -> Vec<Option<uint>> {
+        let mut level = Vec::from_elem(self.adjacency.len(), None);
+        let mut queue = RingBuf::new();
+        *level.get_mut(source) = Some(0u);
+        queue.push_back(source);
+        while !queue.is_empty() {
+            let v = queue.pop_front().unwrap();
+            let next = level.get(v).unwrap() + 1;
+            for &id in self.adjacency.get(v).iter() {
+                let to = *self.to.get(id);
+                if *self.residual.get(id) > 0.0 && level.get(to).is_none() {
+                    *level.get_mut(to) = Some(next);
+                    queue.push_back(to);
+                }
+            }
+        }
+        level
+    }
+
+    // push flow along one path of the level graph, with a loop rather than
+    // a recursion as the paths can be as long as the network
+    fn augment(&mut self,
+               source: uint,
+               sink: uint,
+               level: &Vec<Option<uint>>,
+               next: &mut Vec<uint>)
+               -> f64 {
+        // the edges from source to v
+        let mut path: Vec<uint> = Vec::new();
+        let mut v = source;
+        loop {
+            if v == sink {
+                let pushed = path.iter().fold(f64::INFINITY, |limit, &id| {
+                    limit.min(*self.residual.get(id))
+                });
+                for &id in path.iter() {
+                    *self.residual.get_mut(id) -= pushed;
+                    *self.residual.get_mut(id ^ 1) += pushed;
+                }
+                return pushed
+            }
+            if *next.get(v) < self.adjacency.get(v).len() {
+                let id = *self.adjacency.get(v).get(*next.get(v));
+                let to = *self.to.get(id);
+                if *self.residual.get(id) > 0.0 && *level.get(to) == level.get(v).map(|l| l + 1) {
+                    path.push(id);
+                    v = to;
+                    continue
+                }
+                *next.get_mut(v) += 1;
+            } else {
+                // dead end, skip the edge leading to it
+                match path.pop() {
+                    Some(id) => {
+                        v = *self.to.get(id ^ 1);
+                        *next.get_mut(v) += 1;
+                    },
+                    None     => return 0.0
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,24 @@
 //! Abstract Graph build on adjacency lists.
 
 use std::iter::Iterator;
+use std::cmp::{Less, Equal, Greater};
+use std::collections::{HashMap, TreeMap, TreeSet};
+use std::collections::treemap::{Entries, MutEntries};
+use std::slice::MutItems;
+use std::hash::Hash;
+use std::vec::MoveItems;
+use serialize::{Encodable, Decodable, Encoder, Decoder};
+
+use indexed::{IndexedGraph, contains_sorted};
+use union_find::UnionFind;
+use observer::{GraphObserver, ObserverId, ObserverList};
+
+/// Version of the encoded form of a Graph, increased when it changes. The
+/// version 1 had no self_loops field.
+static FORMAT_VERSION: uint = 2;
+
+/// Number of Edges stored inline in a Vertex before spilling to the heap.
+static INLINE_EDGES: uint = 4;
 
 /**
 * Representation of a Graph vertex.
@@ -10,24 +28,23 @@ use std::iter::Iterator;
 * * L - The Vertex's Label type
 * * V - The Edge's Value type
 */
-#[deriving(Clone, PartialEq, Encodable, Decodable)]
+#[deriving(Clone, PartialEq)]
 pub struct Vertex<K, L, V> {
     key:                   K,
     label:                 Option<L>,
-    edges:                 Option<Box<Edge<K, V>>>,
-    next:                  Option<Box<Vertex<K, L, V>>>,
+    edges:                 EdgeList<K, V>,
 }
 
 /// Iterator to iterate easily other all the vertex of a Graph.
 // #[deriving(Clone)]
 pub struct VertexIterator<'s, K, L, V> {
-    head: &'s Option<Box<Vertex<K, L, V>>>,
+    vertices: Entries<'s, K, Box<Vertex<K, L, V>>>,
 }
 
 impl<'s,
-     K: ToString + Ord + Eq + Clone,
-     L: ToString + Ord + Eq + Clone,
-     V: ToString + Ord + Eq + Clone>
+     K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
      Iterator<(&'s K, Option<&'s L>)> for VertexIterator<'s, K, L, V> {
 
     /**
@@ -38,16 +55,15 @@ impl<'s,
     */
     #[inline]
     fn next(&mut self) -> Option<(&'s K, Option<&'s L>)> {
-        self.head.as_ref().map( |head| {
-            self.head = &head.next;
-            (&head.key, Some(head.label.get_ref()))
+        self.vertices.next().map( |(key, vertex)| {
+            (key, vertex.label.as_ref())
         })
     }
 }
 
-impl<K: ToString + Ord + Eq + Clone,
-     L: ToString + Ord + Eq + Clone,
-     V: ToString + Ord + Eq + Clone>
+impl<K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
      Vertex<K, L, V> {
 
     /**
@@ -63,8 +79,7 @@ impl<K: ToString + Ord + Eq + Clone,
         Vertex {
             key:    key,
             label:  None,
-            edges:  None,
-            next:   None
+            edges:  EdgeList::new()
         }
     }
 
@@ -84,8 +99,7 @@ impl<K: ToString + Ord + Eq + Clone,
         Vertex {
             key:    key,
             label:  label,
-            edges:  None,
-            next:   None
+            edges:  EdgeList::new()
         }
     }
 
@@ -102,17 +116,15 @@ impl<K: ToString + Ord + Eq + Clone,
     * A new Vertex.
     */
     pub fn new_with_edges(key: K,
-                          mut edges: Vec<Box<Edge<K, V>>>)
+                          edges: Vec<Box<Edge<K, V>>>)
                           -> Vertex<K, L, V> {
-        let tmp_edges: Option<Box<Edge<K, V>>> = edges.shift();
         let mut vertex = Vertex {
             key:    key,
             label:  None,
-            edges:  tmp_edges,
-            next:   None
+            edges:  EdgeList::new()
         };
         for i in edges.move_iter() {
-            VertexUtils::add_edge(&mut vertex.edges, i);
+            vertex.edges.insert_sorted(*i);
         }
         vertex
     }
@@ -133,8 +145,7 @@ impl<K: ToString + Ord + Eq + Clone,
         Vertex {
             key:    key,
             label:  Some(label),
-            edges:  None,
-            next:   None,
+            edges:  EdgeList::new()
         }
     }
 
@@ -151,21 +162,29 @@ impl<K: ToString + Ord + Eq + Clone,
     */
     pub fn new_with_label_edges(key: K,
                                 label: L,
-                                mut edges: Vec<Box<Edge<K, V>>>)
+                                edges: Vec<Box<Edge<K, V>>>)
                                 -> Vertex<K, L, V> {
-        let tmp_edges: Option<Box<Edge<K, V>>> = edges.shift();
         let mut vertex = Vertex {
             key:    key,
             label:  Some(label),
-            edges:  tmp_edges,
-            next:   None
+            edges:  EdgeList::new()
         };
         for i in edges.move_iter() {
-            VertexUtils::add_edge(&mut vertex.edges, i);
+            vertex.edges.insert_sorted(*i);
         }
         vertex
     }
 
+    /**
+    * Get the key of a Vertex.
+    *
+    * # Return
+    * The immuable key of the Vertex.
+    */
+    pub fn get_key<'r>(&'r self) -> &'r K {
+        &self.key
+    }
+
     /**
     * Get the label of a Vertex.
     *
@@ -199,12 +218,33 @@ impl<K: ToString + Ord + Eq + Clone,
     /**
     * Get an Edge iterator.
     *
+    * The Edges are yielded sorted by the key of their second Vertex.
+    *
     * # Return
     * An iterator to iterate over the Edges of the Vertex.
     */
     pub fn edges_iter<'r>(&'r self) -> EdgeIterator<'r, K, V> {
         EdgeIterator {
-            head: &self.edges
+            edges:  &self.edges,
+            pos:    0
+        }
+    }
+
+    /**
+    * Get an Edge iterator giving the values to update them in place.
+    *
+    * The Edges are yielded sorted by the key of their second Vertex. The
+    * observers of the Graph are not notified and the opposite Edges of an
+    * undirected Graph are not updated.
+    *
+    * # Return
+    * An iterator over the (to_key, value) of the Edges of the Vertex.
+    */
+    pub fn edges_iter_mut<'r>(&'r mut self) -> EdgeMutIterator<'r, K, V> {
+        let inline_len = self.edges.inline_len;
+        EdgeMutIterator {
+            inline:     self.edges.inline.mut_slice_to(inline_len).mut_iter(),
+            spilled:    self.edges.spilled.mut_iter()
         }
     }
 
@@ -220,9 +260,24 @@ impl<K: ToString + Ord + Eq + Clone,
     pub fn edge_exist(&self,
                       key: &K)
                       -> bool {
-        match VertexUtils::get_edge_imm(&self.edges, key) {
-            Some(_) => true,
-            None    => false
+        self.edges.find(key).is_some()
+    }
+
+    /**
+    * Get the value of an Edge of the Vertex.
+    *
+    * # Arguments
+    * * key - The key of the second Vertex of the Edge
+    *
+    * # Return
+    * Some(value) if the Edge exist and has a value, None otherwise.
+    */
+    pub fn get_edge_value<'r>(&'r self,
+                              key: &K)
+                              -> Option<&'r V> {
+        match self.edges.find(key) {
+            Some(i) => self.edges.get(i).value.as_ref(),
+            None    => None
         }
     }
 
@@ -240,18 +295,7 @@ impl<K: ToString + Ord + Eq + Clone,
                          to_key: K,
                          value: Option<V>)
                          -> bool {
-        if !self.edge_exist(&to_key) {
-            match value {
-                Some(v) => VertexUtils::add_edge(&mut self.edges,
-                                                 box Edge::new_with_value(to_key,
-                                                                       v)),
-                None    => VertexUtils::add_edge(&mut self.edges,
-                                                 box Edge::new(to_key))
-            }
-            true
-        } else {
-            false
-        }
+        self.edges.insert_sorted(Edge::new_with_opt(to_key, value))
     }
 
     /**
@@ -268,13 +312,7 @@ impl<K: ToString + Ord + Eq + Clone,
                       to_key: K,
                       value: V)
                       -> bool {
-        if !self.edge_exist(&to_key) {
-            VertexUtils::add_edge(&mut self.edges,
-                                  box Edge::new_with_value(to_key, value));
-            true
-        } else {
-            false
-        }
+        self.edges.insert_sorted(Edge::new_with_value(to_key, value))
     }
 
     /**
@@ -289,12 +327,7 @@ impl<K: ToString + Ord + Eq + Clone,
     pub fn add_edge(&mut self,
                     to_key: K)
                     -> bool {
-        if !self.edge_exist(&to_key) {
-            VertexUtils::add_edge(&mut self.edges, box Edge::new(to_key));
-            true
-        } else {
-            false
-        }
+        self.edges.insert_sorted(Edge::new(to_key))
     }
 
     /**
@@ -309,11 +342,12 @@ impl<K: ToString + Ord + Eq + Clone,
     pub fn remove_edge(&mut self,
                        to_key: K)
                        -> bool {
-        if self.edge_exist(&to_key) {
-            VertexUtils::remove_edge(&mut self.edges, to_key);
-            true
-        } else {
-            false
+        match self.edges.find(&to_key) {
+            Some(pos) => {
+                self.edges.remove(pos);
+                true
+            },
+            None      => false
         }
     }
 
@@ -331,11 +365,12 @@ impl<K: ToString + Ord + Eq + Clone,
                               to_key: K,
                               new_value: Option<V>)
                               -> bool {
-        if self.edge_exist(&to_key) {
-            VertexUtils::update_edge_value(&mut self.edges, new_value, to_key);
-            true
-        } else {
-            false
+        match self.edges.find(&to_key) {
+            Some(pos) => {
+                self.edges.get_mut(pos).value = new_value;
+                true
+            },
+            None      => false
         }
     }
 
@@ -353,13 +388,7 @@ impl<K: ToString + Ord + Eq + Clone,
                           to_key: K,
                           new_value: V)
                           -> bool {
-        if self.edge_exist(&to_key) {
-            VertexUtils::update_edge_value(&mut self.edges,
-                                           Some(new_value), to_key);
-            true
-        } else {
-            false
-        }
+        self.set_edge_value_opt(to_key, Some(new_value))
     }
 
     /**
@@ -374,75 +403,229 @@ impl<K: ToString + Ord + Eq + Clone,
     pub fn remove_edge_value(&mut self,
                              to_key: K)
                              -> bool {
-        if self.edge_exist(&to_key) {
-            VertexUtils::update_edge_value(&mut self.edges, None, to_key);
-            true
+        self.set_edge_value_opt(to_key, None)
+    }
+}
+
+impl<E,
+     S: Encoder<E>,
+     K: Encodable<S, E>,
+     L: Encodable<S, E>,
+     V: Encodable<S, E>>
+     Encodable<S, E> for Vertex<K, L, V> {
+    fn encode(&self, s: &mut S) -> Result<(), E> {
+        s.emit_struct("Vertex", 3, |s| {
+            try!(s.emit_struct_field("key", 0, |s| self.key.encode(s)));
+            try!(s.emit_struct_field("label", 1, |s| self.label.encode(s)));
+            s.emit_struct_field("edges", 2, |s| self.edges.encode(s))
+        })
+    }
+}
+
+impl<E,
+     D: Decoder<E>,
+     K: Decodable<D, E>,
+     L: Decodable<D, E>,
+     V: Decodable<D, E>>
+     Decodable<D, E> for Vertex<K, L, V> {
+    fn decode(d: &mut D) -> Result<Vertex<K, L, V>, E> {
+        d.read_struct("Vertex", 3, |d| {
+            Ok(Vertex {
+                key:    try!(d.read_struct_field("key", 0, |d| Decodable::decode(d))),
+                label:  try!(d.read_struct_field("label", 1, |d| Decodable::decode(d))),
+                edges:  try!(d.read_struct_field("edges", 2, |d| Decodable::decode(d)))
+            })
+        })
+    }
+}
+
+/**
+* Storage of the Edges of a Vertex.
+*
+* The first INLINE_EDGES Edges are stored directly inside the Vertex, the
+* following ones spill to a heap allocated vector. Most vertices have a low
+* degree, so this saves an allocation per Edge and keeps them contiguous.
+*
+* The Edges are kept sorted by the key of their second Vertex, so lookups
+* are done by binary search and two lists can be intersected by merging.
+*/
+struct EdgeList<K, V> {
+    inline:            [Option<Edge<K, V>>, ..INLINE_EDGES],
+    inline_len:        uint,
+    spilled:           Vec<Edge<K, V>>
+}
+
+impl<K, V> EdgeList<K, V> {
+    fn new() -> EdgeList<K, V> {
+        EdgeList {
+            inline:     [None, None, None, None],
+            inline_len: 0,
+            spilled:    Vec::new()
+        }
+    }
+
+    fn with_capacity(capacity: uint) -> EdgeList<K, V> {
+        let spilled = if capacity > INLINE_EDGES {
+            capacity - INLINE_EDGES
         } else {
-            false
+            0
+        };
+        EdgeList {
+            inline:     [None, None, None, None],
+            inline_len: 0,
+            spilled:    Vec::with_capacity(spilled)
         }
     }
-}
 
-mod VertexUtils {
-    use super::{Edge};
+    fn len(&self) -> uint {
+        self.inline_len + self.spilled.len()
+    }
 
-    pub fn update_edge_value<K: Eq, V>(edge: &mut Option<Box<Edge<K, V>>>,
-                                       value: Option<V>,
-                                       to_key: K) -> () {
-        match *edge {
-            Some(ref mut e) => {
-                if e.to_key == to_key {
-                    e.value = value;
-                } else {
-                    update_edge_value(&mut e.next, value, to_key)
-                }
-            },
-            None => {}
+    fn get<'r>(&'r self, index: uint) -> &'r Edge<K, V> {
+        if index < self.inline_len {
+            self.inline[index].get_ref()
+        } else {
+            self.spilled.get(index - self.inline_len)
         }
     }
 
-    pub fn remove_edge<K: Eq, V>(edge: &mut Option<Box<Edge<K, V>>>,
-                                 to_key: K) {
-        match *edge {
-            Some(ref mut e) => {
-                if e.next.get_ref().to_key == to_key {
-                    if e.next.get_ref().next.is_some() {
-                        e.next = Some(e.next.take_unwrap().next.take_unwrap());
-                    } else {
-                        e.next = None;
-                    }
+    fn get_mut<'r>(&'r mut self, index: uint) -> &'r mut Edge<K, V> {
+        if index < self.inline_len {
+            self.inline[index].get_mut_ref()
+        } else {
+            self.spilled.get_mut(index - self.inline_len)
+        }
+    }
 
-                } else {
-                    remove_edge(&mut e.next, to_key)
-                }
-            },
-            None => {}
+    fn push(&mut self, edge: Edge<K, V>) {
+        if self.inline_len < INLINE_EDGES {
+            self.inline[self.inline_len] = Some(edge);
+            self.inline_len += 1;
+        } else {
+            self.spilled.push(edge);
         }
     }
 
-    pub fn get_edge_imm<'r, K: Eq, V>(edge: &'r Option<Box<Edge<K, V>>>,
-                                      to_key: &K)
-                                      -> Option<&'r Box<Edge<K, V>>> {
-        match *edge {
-            Some(ref e) => {
-                if e.to_key == *to_key {
-                    edge.as_ref()
-                } else {
-                    get_edge_imm(&e.next, to_key)
-                }
-            },
-            None => None
+    fn insert(&mut self, index: uint, edge: Edge<K, V>) {
+        if index > self.inline_len ||
+           (index == self.inline_len && self.inline_len == INLINE_EDGES) {
+            self.spilled.insert(index - self.inline_len, edge);
+            return
+        }
+        // make room in the inline slots by spilling the last one
+        if self.inline_len == INLINE_EDGES {
+            let last = self.inline[INLINE_EDGES - 1].take_unwrap();
+            self.spilled.insert(0, last);
+            self.inline_len -= 1;
+        }
+        let mut i = self.inline_len;
+        while i > index {
+            self.inline.swap(i - 1, i);
+            i -= 1;
+        }
+        self.inline[index] = Some(edge);
+        self.inline_len += 1;
+    }
+
+    fn remove(&mut self, index: uint) -> Edge<K, V> {
+        if index >= self.inline_len {
+            return self.spilled.remove(index - self.inline_len).unwrap()
+        }
+        let removed = self.inline[index].take_unwrap();
+        for i in range(index, self.inline_len - 1) {
+            self.inline.swap(i, i + 1);
+        }
+        // keep the inline slots full as long as there is spilled Edges
+        if self.spilled.is_empty() {
+            self.inline_len -= 1;
+        } else {
+            self.inline[self.inline_len - 1] = self.spilled.remove(0);
+        }
+        removed
+    }
+}
+
+impl<K: Ord, V> EdgeList<K, V> {
+    /// Ok(position) of the Edge to to_key, or Err(position) to insert it.
+    fn search(&self, to_key: &K) -> Result<uint, uint> {
+        let mut low = 0u;
+        let mut high = self.len();
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match self.get(mid).to_key.cmp(to_key) {
+                Less    => low = mid + 1,
+                Greater => high = mid,
+                Equal   => return Ok(mid)
+            }
+        }
+        Err(low)
+    }
+
+    fn find(&self, to_key: &K) -> Option<uint> {
+        match self.search(to_key) {
+            Ok(pos) => Some(pos),
+            Err(_)  => None
         }
     }
 
-    pub fn add_edge<K: ToString + Ord + Eq + Clone,
-                    V: ToString + Ord + Eq + Clone>
-                    (edge: &mut Option<Box<Edge<K, V>>>,
-                    new_edge: Box<Edge<K, V>>) {
-        match *edge {
-            Some(ref mut e) => add_edge(&mut e.next, new_edge),
-            None => {*edge = Some(new_edge)}
+    fn insert_sorted(&mut self, edge: Edge<K, V>) -> bool {
+        match self.search(&edge.to_key) {
+            Ok(_)    => false,
+            Err(pos) => {
+                self.insert(pos, edge);
+                true
+            }
+        }
+    }
+}
+
+impl<K: Clone, V: Clone> Clone for EdgeList<K, V> {
+    fn clone(&self) -> EdgeList<K, V> {
+        let mut list = EdgeList::new();
+        for i in range(0, self.len()) {
+            list.push(self.get(i).clone());
+        }
+        list
+    }
+}
+
+impl<K: PartialEq, V: PartialEq> PartialEq for EdgeList<K, V> {
+    fn eq(&self, other: &EdgeList<K, V>) -> bool {
+        if self.len() != other.len() {
+            return false
         }
+        range(0, self.len()).all(|i| self.get(i) == other.get(i))
+    }
+}
+
+impl<E,
+     S: Encoder<E>,
+     K: Encodable<S, E>,
+     V: Encodable<S, E>>
+     Encodable<S, E> for EdgeList<K, V> {
+    fn encode(&self, s: &mut S) -> Result<(), E> {
+        s.emit_seq(self.len(), |s| {
+            for i in range(0, self.len()) {
+                try!(s.emit_seq_elt(i, |s| self.get(i).encode(s)));
+            }
+            Ok(())
+        })
+    }
+}
+
+impl<E,
+     D: Decoder<E>,
+     K: Decodable<D, E>,
+     V: Decodable<D, E>>
+     Decodable<D, E> for EdgeList<K, V> {
+    fn decode(d: &mut D) -> Result<EdgeList<K, V>, E> {
+        d.read_seq(|d, len| {
+            let mut list = EdgeList::new();
+            for i in range(0, len) {
+                list.push(try!(d.read_seq_elt(i, |d| Decodable::decode(d))));
+            }
+            Ok(list)
+        })
     }
 }
 
@@ -456,19 +639,19 @@ mod VertexUtils {
 #[deriving(Clone, PartialEq, Encodable, Decodable)]
 pub struct Edge<K, V> {
     value:             Option<V>,
-    to_key:            K,
-    next:              Option<Box<Edge<K, V>>>
+    to_key:            K
 }
 
 /// An Iterator to iterate othe the Edge of a Vertex
 // #[deriving(Clone)]
 pub struct EdgeIterator<'s, K, V> {
-    head: &'s Option<Box<Edge<K, V>>>,
+    edges: &'s EdgeList<K, V>,
+    pos:   uint
 }
 
 impl<'s,
-     K: ToString + Ord + Eq + Clone,
-     V: ToString + Ord + Eq + Clone>
+     K: Ord + Eq + Clone,
+     V: PartialEq + Clone>
      Iterator<(&'s K, Option<&'s V>)> for EdgeIterator<'s, K, V> {
     /**
     * Get the next iterator of the Edge.
@@ -479,15 +662,38 @@ impl<'s,
     */
     #[inline]
     fn next(&mut self) -> Option<(&'s K, Option<&'s V>)> {
-        self.head.as_ref().map( |head| {
-            self.head = &head.next;
-            (&head.to_key, Some(head.value.get_ref()))
-        })
+        if self.pos < self.edges.len() {
+            let edge = self.edges.get(self.pos);
+            self.pos += 1;
+            Some((&edge.to_key, edge.value.as_ref()))
+        } else {
+            None
+        }
+    }
+}
+
+/// An Iterator over the Edges of a Vertex giving mutable values
+pub struct EdgeMutIterator<'s, K, V> {
+    inline:  MutItems<'s, Option<Edge<K, V>>>,
+    spilled: MutItems<'s, Edge<K, V>>
+}
+
+impl<'s, K, V> Iterator<(&'s K, &'s mut Option<V>)> for EdgeMutIterator<'s, K, V> {
+    #[inline]
+    fn next(&mut self) -> Option<(&'s K, &'s mut Option<V>)> {
+        let edge = match self.inline.next() {
+            Some(slot) => slot.get_mut_ref(),
+            None       => match self.spilled.next() {
+                Some(edge) => edge,
+                None       => return None
+            }
+        };
+        Some((&edge.to_key, &mut edge.value))
     }
 }
 
-impl<K: ToString + Ord + Eq + Clone,
-     V: ToString + Ord + Eq + Clone>
+impl<K: Ord + Eq + Clone,
+     V: PartialEq + Clone>
      Edge<K, V> {
 
     /**
@@ -502,8 +708,7 @@ impl<K: ToString + Ord + Eq + Clone,
     pub fn new(to_key: K) -> Edge<K, V> {
         Edge {
             value:  None,
-            to_key: to_key,
-            next:   None
+            to_key: to_key
         }
     }
 
@@ -522,8 +727,7 @@ impl<K: ToString + Ord + Eq + Clone,
                         -> Edge<K, V> {
         Edge {
             value:  value,
-            to_key: to_key,
-            next:   None
+            to_key: to_key
         }
     }
 
@@ -542,30 +746,150 @@ impl<K: ToString + Ord + Eq + Clone,
                            -> Edge<K, V> {
         Edge {
             value:   Some(value),
-            to_key: to_key,
-            next:   None
+            to_key: to_key
+        }
+    }
+}
+
+/**
+* Reason why a Graph does not have a structure, returned by the structural
+* checks of the Graph.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+*/
+#[deriving(Clone, PartialEq, Show)]
+pub enum StructureViolation<K> {
+    /// The Vertices of a directed cycle, each one having an Edge to the next
+    /// and the last one an Edge to the first
+    Cycle(Vec<K>),
+    /// An Edge closing a cycle when the direction of the Edges is ignored
+    ExtraEdge(K, K),
+    /// Two Vertices without path between them
+    Disconnected(K, K),
+    /// The Graph has no Vertex
+    EmptyGraph
+}
+
+/**
+* Broken invariant of the storage of a Graph, returned by validate.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+*/
+#[deriving(Clone, PartialEq, Show)]
+pub enum IntegrityViolation<K> {
+    /// An Edge to a key without Vertex
+    DanglingEdge(K, K),
+    /// An Edge stored several times
+    DuplicateEdge(K, K),
+    /// A Vertex whose Edges are not sorted by key
+    UnsortedEdges(K),
+    /// An Edge of an undirected Graph without its opposite Edge
+    MissingReverseEdge(K, K)
+}
+
+/**
+* Reason why a mutation of a Graph failed, returned by the try_ methods.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+*/
+#[deriving(Clone, PartialEq, Show)]
+pub enum GraphError<K> {
+    /// No Vertex has the key
+    VertexNotFound(K),
+    /// A Vertex already has the key
+    VertexAlreadyExists(K),
+    /// No Edge between the two Vertices
+    EdgeNotFound(K, K),
+    /// An Edge already links the two Vertices
+    EdgeAlreadyExists(K, K),
+    /// The Graph does not allow an Edge from the Vertex to itself
+    SelfLoopForbidden(K)
+}
+
+/**
+* Policies of a Graph, given to Graph::new_with_config.
+*
+* The default config is the one of Graph::new: a directed Graph allowing
+* the self loops.
+*/
+#[deriving(Clone, PartialEq, Show)]
+pub struct GraphConfig {
+    directed:          bool,
+    self_loops:        bool
+}
+
+impl GraphConfig {
+
+    /**
+    * Create a new GraphConfig of a directed Graph allowing the self loops.
+    */
+    pub fn new() -> GraphConfig {
+        GraphConfig {
+            directed:   true,
+            self_loops: true
         }
     }
+
+    /**
+    * Choose if the Graph is directed.
+    */
+    pub fn directed(mut self, directed: bool) -> GraphConfig {
+        self.directed = directed;
+        self
+    }
+
+    /**
+    * Choose if an Edge can go from a Vertex to itself, when it can't
+    * add_edge refuses it and try_add_edge returns SelfLoopForbidden.
+    */
+    pub fn allow_self_loops(mut self, allow: bool) -> GraphConfig {
+        self.self_loops = allow;
+        self
+    }
+
+    /**
+    * Is the Graph directed or not.
+    */
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    /**
+    * Can an Edge go from a Vertex to itself.
+    */
+    pub fn allows_self_loops(&self) -> bool {
+        self.self_loops
+    }
 }
 
 /**
 * Representation of the Graph.
 *
+* The Vertices are stored in a map ordered by key, so finding a Vertex is
+* logarithmic and the Vertices are iterated in ascending key order, not in
+* their insertion order as with the former Vertex list. The Edges of each
+* Vertex are sorted by the key of their second Vertex. A Graph is directed
+* unless created by new_undirected.
+*
 * # Types parameters
 * * K - The Vertex's Key type
 * * L - The Vertex's Label type
 * * V - The Edge's Value type
 */
-#[deriving(Clone, PartialEq, Encodable, Decodable)]
+#[deriving(Clone, PartialEq)]
 pub struct Graph<K, L, V> {
-    vertices:      Option<Box<Vertex<K, L, V>>>,
-    len:           uint,
-    directed:      bool
+    vertices:      TreeMap<K, Box<Vertex<K, L, V>>>,
+    directed:      bool,
+    self_loops:    bool,
+    observers:     ObserverList<K, L, V>
 }
 
-impl<K: ToString + Ord + Eq + Clone,
-     L: ToString + Ord + Eq + Clone,
-     V: ToString + Ord + Eq + Clone>
+impl<K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
      Graph<K, L, V> {
 
     /**
@@ -576,126 +900,345 @@ impl<K: ToString + Ord + Eq + Clone,
     */
     pub fn new() -> Graph<K, L, V> {
         Graph {
-            vertices:   None,
-            len:        0,
-            directed:   true
+            vertices:   TreeMap::new(),
+            directed:   true,
+            self_loops: true,
+            observers:  ObserverList::new()
         }
     }
 
     /**
-    * Create a new Graph with an vector of Vertex.
+    * Create a new empty undirected Graph.
     *
-    * Warning: The validity of the vector of vertex is not certified.
-    *
-    * # Arguments
-    * * vertices - The vector of Vertiex to attach to the Graph
+    * An undirected Graph stores each Edge in both directions: adding,
+    * removing or setting the value of an Edge also does it on the opposite
+    * Edge, so the Edges of a Vertex are all the Edges touching it.
     *
     * # Return
-    * A new graph with initialized with vertices.
+    * A new empty undirected graph.
     */
-    pub fn new_with_vertices(mut vertices: Vec<Box<Vertex<K, L, V>>>) -> Graph<K, L, V> {
-        let tmp_vertice: Option<Box<Vertex<K, L, V>>> = vertices.shift();
-        let mut graph = Graph {
-            vertices:   tmp_vertice,
-            len:        0,
-            directed:   true
-        };
-        for i in vertices.move_iter() {
-            GraphUtils::add_vertex(&mut graph.vertices, i);
+    pub fn new_undirected() -> Graph<K, L, V> {
+        Graph {
+            vertices:   TreeMap::new(),
+            directed:   false,
+            self_loops: true,
+            observers:  ObserverList::new()
         }
-        graph
     }
 
     /**
-    * Is the Graph directed or not.
+    * Create a new empty Graph with the policies of a config.
+    *
+    * # Arguments
+    * * config - The policies of the Graph
     *
     * # Return
-    * true if the graph is directed, false otherwise.
+    * A new empty graph.
     */
-    pub fn is_directed(&self) -> bool {
-        self.directed
+    pub fn new_with_config(config: GraphConfig) -> Graph<K, L, V> {
+        Graph {
+            vertices:   TreeMap::new(),
+            directed:   config.directed,
+            self_loops: config.self_loops,
+            observers:  ObserverList::new()
+        }
     }
 
     /**
-    * Add a Vertex to the Graph with a Key and an optional Label.
+    * Create a new Graph with an vector of Vertex.
     *
-    * Check if the vertex already exist.
+    * Warning: The validity of the vector of vertex is not certified, use
+    * validate to check it. When several Vertices have the same key, only the
+    * first one is kept.
     *
     * # Arguments
-    * * key - The key of the new Vertex
-    * * label - The option Label to attach to the graph
+    * * vertices - The vector of Vertiex to attach to the Graph
     *
     * # Return
-    * true if the Vertex is successfully added, false otherwise.
+    * A new graph with initialized with vertices.
     */
-    pub fn add_vertex_opt_l(&mut self,
-                            key: K,
-                            label: Option<L>)
-                            -> bool {
-        if !self.vertex_exist(&key) {
-            match label {
-                Some(l) => GraphUtils::add_vertex(&mut self.vertices,
-                                                  box Vertex::new_with_label(key,
-                                                                          l)),
-                None    => GraphUtils::add_vertex(&mut self.vertices,
-                                                  box Vertex::new(key))
+    pub fn new_with_vertices(vertices: Vec<Box<Vertex<K, L, V>>>) -> Graph<K, L, V> {
+        let mut graph = Graph::new();
+        for vertex in vertices.move_iter() {
+            if !graph.vertices.contains_key(&vertex.key) {
+                graph.vertices.insert(vertex.key.clone(), vertex);
             }
-            self.len += 1;
-            true
-        } else {
-            false
         }
+        graph
     }
 
     /**
-    * Add a Vertex to the Graph with a Key and a Label.
+    * Create a new Graph from a list of Edges.
     *
-    * Check if the vertex already exist.
+    * The Vertices are created from the keys of the Edges and duplicated Edges
+    * are ignored, only the first one is kept. The Edges are sorted once and
+    * the storage of each Vertex is sized from its degree, this is much faster
+    * than calling add_vertex and add_edge for each element.
     *
     * # Arguments
-    * * key - The key of the new Vertex
-    * * label - The Label to attach to the graph
+    * * edges - The vector of (from_key, to_key, value) of the Edges
     *
     * # Return
-    * true if the Vertex is successfully added, false otherwise.
+    * A new graph containing the Edges and their Vertices.
     */
-    pub fn add_vertex_l(&mut self,
-                            key: K,
-                            label: L)
-                            -> bool {
-        if !self.vertex_exist(&key) {
-            GraphUtils::add_vertex(&mut self.vertices,
-                                   box Vertex::new_with_label(key, label));
-            self.len += 1;
-            true
-        } else {
-            false
+    pub fn from_edges(edges: Vec<(K, K, Option<V>)>) -> Graph<K, L, V> {
+        let mut edges = edges;
+        edges.sort_by(|a, b| {
+            let (ref a_from, ref a_to, _) = *a;
+            let (ref b_from, ref b_to, _) = *b;
+            match a_from.cmp(b_from) {
+                Equal => a_to.cmp(b_to),
+                order => order
+            }
+        });
+
+        // the keys of every Vertex and their degree
+        let mut keys: Vec<K> = Vec::with_capacity(edges.len() * 2);
+        for &(ref from, ref to, _) in edges.iter() {
+            keys.push(from.clone());
+            keys.push(to.clone());
+        }
+        keys.sort();
+        keys.dedup();
+        let mut degrees: Vec<uint> = Vec::from_elem(keys.len(), 0u);
+        {
+            let mut pos = 0u;
+            let mut last: Option<(&K, &K)> = None;
+            for &(ref from, ref to, _) in edges.iter() {
+                if last == Some((from, to)) {
+                    continue
+                }
+                while keys.get(pos) != from {
+                    pos += 1;
+                }
+                *degrees.get_mut(pos) += 1;
+                last = Some((from, to));
+            }
+        }
+
+        let mut vertices: Vec<Vertex<K, L, V>> = Vec::with_capacity(keys.len());
+        let mut edges = edges.move_iter().peekable();
+        for (key, degree) in keys.move_iter().zip(degrees.move_iter()) {
+            let mut vertex = Vertex::new(key);
+            vertex.edges = EdgeList::with_capacity(degree);
+            loop {
+                match edges.peek() {
+                    Some(&(ref from, _, _)) if *from == vertex.key => {},
+                    _                                              => break
+                }
+                let (_, to, value) = edges.next().unwrap();
+                let last = vertex.edges.len();
+                if last == 0 || vertex.edges.get(last - 1).to_key != to {
+                    vertex.edges.push(Edge::new_with_opt(to, value));
+                }
+            }
+            vertices.push(vertex);
         }
+
+        Graph::from_vertex_list(vertices)
     }
 
     /**
-    * Add a Vertex to the Graph with a Key.
+    * Create a new Graph from an adjacency matrix.
+    *
+    * The entry (i, j) of the matrix is the value of the Edge from the i-th
+    * Vertex to the j-th Vertex, or None if there is no such Edge.
+    *
+    * # Arguments
+    * * keys - The keys of the Vertices of each row and column
+    * * matrix - The square matrix of the Edges' values
+    *
+    * # Return
+    * Some(graph), None if the keys are not unique or if the matrix is not a
+    * square of their size.
+    */
+    pub fn from_adjacency_matrix(keys: Vec<K>,
+                                 matrix: Vec<Vec<Option<V>>>)
+                                 -> Option<Graph<K, L, V>> {
+        Graph::from_matrix(keys, matrix.move_iter().map(|row| {
+            row.move_iter().map(|value| value.map(|v| Some(v))).collect()
+        }).collect())
+    }
+
+    /**
+    * Create a new Graph from a boolean adjacency matrix.
+    *
+    * The entry (i, j) of the matrix is true if there is an Edge without
+    * value from the i-th Vertex to the j-th Vertex.
+    *
+    * # Arguments
+    * * keys - The keys of the Vertices of each row and column
+    * * matrix - The square matrix of the Edges
+    *
+    * # Return
+    * Some(graph), None if the keys are not unique or if the matrix is not a
+    * square of their size.
+    */
+    pub fn from_boolean_matrix(keys: Vec<K>,
+                               matrix: Vec<Vec<bool>>)
+                               -> Option<Graph<K, L, V>> {
+        Graph::from_matrix(keys, matrix.move_iter().map(|row| {
+            row.move_iter().map(|edge| if edge { Some(None) } else { None }).collect()
+        }).collect())
+    }
+
+    // build a Graph from a matrix of optional Edges with an optional value
+    fn from_matrix(keys: Vec<K>,
+                   matrix: Vec<Vec<Option<Option<V>>>>)
+                   -> Option<Graph<K, L, V>> {
+        let mut sorted: Vec<&K> = keys.iter().collect();
+        sorted.sort();
+        sorted.dedup();
+        if sorted.len() != keys.len() || matrix.len() != keys.len() ||
+           matrix.iter().any(|row| row.len() != keys.len()) {
+            return None
+        }
+        let vertices = keys.iter().zip(matrix.move_iter()).map(|(key, row)| {
+            let mut vertex = Vertex::new(key.clone());
+            for (to, edge) in keys.iter().zip(row.move_iter()) {
+                match edge {
+                    Some(value) => {
+                        vertex.edges.insert_sorted(Edge::new_with_opt(to.clone(), value));
+                    },
+                    None        => {}
+                }
+            }
+            vertex
+        }).collect();
+        Some(Graph::from_vertex_list(vertices))
+    }
+
+    // store Vertices with unique keys
+    fn from_vertex_list(vertices: Vec<Vertex<K, L, V>>) -> Graph<K, L, V> {
+        let mut graph = Graph::new();
+        for vertex in vertices.move_iter() {
+            graph.vertices.insert(vertex.key.clone(), box vertex);
+        }
+        graph
+    }
+
+    /**
+    * Is the Graph directed or not.
+    *
+    * # Return
+    * true if the graph is directed, false otherwise.
+    */
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    /**
+    * Get the policies of the Graph.
+    *
+    * # Return
+    * The config of the Graph, the one to create an empty copy of it.
+    */
+    pub fn config(&self) -> GraphConfig {
+        GraphConfig {
+            directed:   self.directed,
+            self_loops: self.self_loops
+        }
+    }
+
+    /**
+    * Register an observer notified of every following mutation of the
+    * Graph.
+    *
+    * The observers are not cloned nor encoded with the Graph.
+    *
+    * # Arguments
+    * * observer - The observer to notify
+    *
+    * # Return
+    * The identifier to unregister the observer.
+    */
+    pub fn add_observer(&mut self,
+                        observer: Box<GraphObserver<K, L, V>>)
+                        -> ObserverId {
+        let mut observer = observer;
+        observer.on_registered(self.directed);
+        self.observers.add(observer)
+    }
+
+    /**
+    * Unregister an observer.
+    *
+    * # Arguments
+    * * id - The identifier returned by add_observer
+    *
+    * # Return
+    * Some(observer) if it was registered, None otherwise.
+    */
+    pub fn remove_observer(&mut self,
+                           id: &ObserverId)
+                           -> Option<Box<GraphObserver<K, L, V>>> {
+        self.observers.remove(id)
+    }
+
+    /**
+    * Add a Vertex to the Graph with a Key and an optional Label.
     *
     * Check if the vertex already exist.
     *
     * # Arguments
     * * key - The key of the new Vertex
+    * * label - The option Label to attach to the graph
     *
     * # Return
     * true if the Vertex is successfully added, false otherwise.
     */
-    pub fn add_vertex(&mut self,
-                      key: K)
-                      -> bool {
+    pub fn add_vertex_opt_l(&mut self,
+                            key: K,
+                            label: Option<L>)
+                            -> bool {
         if !self.vertex_exist(&key) {
-            GraphUtils::add_vertex(&mut self.vertices, box Vertex::new(key));
-            self.len += 1;
+            self.vertices.insert(key.clone(), box Vertex::new_with_opt(key.clone(), label));
+            if !self.observers.is_empty() {
+                let label = self.vertices.find(&key).unwrap().get_label();
+                self.observers.notify(|o| o.on_vertex_added(&key, label));
+            }
             true
         } else {
             false
         }
     }
 
+    /**
+    * Add a Vertex to the Graph with a Key and a Label.
+    *
+    * Check if the vertex already exist.
+    *
+    * # Arguments
+    * * key - The key of the new Vertex
+    * * label - The Label to attach to the graph
+    *
+    * # Return
+    * true if the Vertex is successfully added, false otherwise.
+    */
+    pub fn add_vertex_l(&mut self,
+                            key: K,
+                            label: L)
+                            -> bool {
+        self.add_vertex_opt_l(key, Some(label))
+    }
+
+    /**
+    * Add a Vertex to the Graph with a Key.
+    *
+    * Check if the vertex already exist.
+    *
+    * # Arguments
+    * * key - The key of the new Vertex
+    *
+    * # Return
+    * true if the Vertex is successfully added, false otherwise.
+    */
+    pub fn add_vertex(&mut self,
+                      key: K)
+                      -> bool {
+        self.add_vertex_opt_l(key, None)
+    }
+
     /**
     * Get an Option to an immutable reference to a vertex.
     *
@@ -708,7 +1251,7 @@ impl<K: ToString + Ord + Eq + Clone,
     pub fn get_vertex<'r>(&'r self,
                           vertex_key: K)
                           -> Option<&'r Box<Vertex<K, L, V>>> {
-        GraphUtils::get_vertex_imm(&self.vertices, &vertex_key)
+        self.vertices.find(&vertex_key)
     }
 
     /**
@@ -730,108 +1273,1251 @@ impl<K: ToString + Ord + Eq + Clone,
     pub fn get_vertex_mut<'r>(&'r mut self,
                               vertex_key: K)
                               -> Option<&'r mut Box<Vertex<K, L, V>>> {
-        GraphUtils::get_vertex_mut(&mut self.vertices, vertex_key)
+        self.vertices.find_mut(&vertex_key)
     }
 
     /**
     * Iterate over the vertices of the Graph.
     *
+    * The Vertices are yielded in ascending key order, not in the order
+    * they were added.
+    *
     * # Return
     * An immutable iterator to the vertices of the Graph.
     */
     pub fn vertices_iter<'r>(&'r self) -> VertexIterator<'r, K, L, V> {
         VertexIterator {
-            head: &self.vertices
+            vertices: self.vertices.iter()
         }
     }
 
     /**
-    * Set the label of a Vertex with an optional label.
+    * Iterate over the vertices of the Graph giving their labels to update
+    * them in place.
     *
-    * # Arguments
-    * * vertex_key - The key of the vertex to set
-    * * new_label - The optional label to set to the Vertex
+    * The Vertices are yielded in ascending key order, not in the order
+    * they were added. The observers of the Graph are not notified.
     *
     * # Return
-    * true if the label is successfully set, false otherwise.
+    * An iterator over the (key, label) of the Vertices.
     */
-    pub fn set_vertex_label_opt(&mut self,
-                                vertex_key: K,
-                                new_label: Option<L>)
-                                -> bool {
-        if self.vertex_exist(&vertex_key) {
-            GraphUtils::update_vertex_label(&mut self.vertices,
-                                            new_label, vertex_key);
-            true
+    pub fn vertices_iter_mut<'r>(&'r mut self) -> VertexMutIterator<'r, K, L, V> {
+        VertexMutIterator {
+            vertices: self.vertices.mut_iter()
+        }
+    }
+
+    /**
+    * Iterate over the vertices of the Graph sorted by their degree.
+    *
+    * The degree of a Vertex is the number of Edges starting from it plus
+    * the number of Edges ending to it, or the number of its Edges in an
+    * undirected Graph. A self loop adds 2 to the degree of its Vertex in
+    * both cases, as in the summary of analysis. Vertices with the same
+    * degree keep the order of vertices_iter.
+    *
+    * # Return
+    * An iterator over the (key, degree) of the Vertices in ascending degree
+    * order, use rev() to get them in descending order.
+    */
+    pub fn vertices_by_degree<'r>(&'r self) -> MoveItems<(&'r K, uint)> {
+        // the indexed Vertices are numbered in the order of vertices_iter
+        let indexed = IndexedGraph::from_graph(self);
+        let mut degrees: Vec<(&'r K, uint)> = self.vertices_ref_iter().map(|vertex| &vertex.key)
+                                                  .zip(indexed.degrees(self.directed).move_iter())
+                                                  .collect();
+        degrees.sort_by(|&(_, a), &(_, b)| a.cmp(&b));
+        degrees.move_iter()
+    }
+
+    /**
+    * Iterate over the Vertex structures of the Graph.
+    *
+    * The Vertices are yielded in ascending key order, not in the order
+    * they were added.
+    *
+    * # Return
+    * An immutable iterator to the Vertex structures of the Graph.
+    */
+    pub fn vertices_ref_iter<'r>(&'r self) -> VertexRefIterator<'r, K, L, V> {
+        VertexRefIterator {
+            vertices: self.vertices.iter()
+        }
+    }
+
+    /**
+    * Iterate over the Edges of the Graph.
+    *
+    * The Edges are sorted by their first and then their second key. An
+    * undirected Graph gives each Edge once, from its lowest key.
+    *
+    * # Return
+    * An iterator over the (from_key, to_key, value) of the Edges.
+    */
+    pub fn edges_iter<'r>(&'r self) -> GraphEdgeIterator<'r, K, L, V> {
+        GraphEdgeIterator {
+            vertices:   self.vertices.iter(),
+            current:    None,
+            directed:   self.directed
+        }
+    }
+
+    /**
+    * Get the number of Edges of the Graph.
+    *
+    * # Return
+    * The number of Edges, counted once in an undirected Graph.
+    */
+    pub fn edge_count(&self) -> uint {
+        if self.directed {
+            self.vertices_ref_iter().fold(0, |count, vertex| count + vertex.edges.len())
         } else {
-            false
+            self.edges_iter().count()
         }
     }
 
     /**
-    * Set the label of a Vertex.
+    * Iterate over the self loops of the Graph, the Edges from a Vertex to
+    * itself.
+    *
+    * # Return
+    * An iterator over the (key, value) of the self loops, sorted by key.
+    */
+    pub fn self_loops_iter<'r>(&'r self) -> SelfLoopIterator<'r, K, L, V> {
+        SelfLoopIterator {
+            vertices: self.vertices.iter()
+        }
+    }
+
+    /**
+    * Remove every Edge from a Vertex to itself.
+    *
+    * # Return
+    * The number of removed self loops.
+    */
+    pub fn remove_self_loops(&mut self) -> uint {
+        let keys: Vec<K> = self.self_loops_iter().map(|(key, _)| key.clone()).collect();
+        for key in keys.iter() {
+            self.remove_edge(key.clone(), key.clone());
+        }
+        keys.len()
+    }
+
+    /**
+    * Iterate over the neighbors of a Vertex, the second Vertices of the
+    * Edges starting from it.
     *
     * # Arguments
-    * * vertex_key - The key of the vertex to set
-    * * new_label - The new label of the Vertex
+    * * vertex_key - The key of the Vertex
     *
     * # Return
-    * true if the label is successfully set, false otherwise.
+    * An iterator over the keys of the neighbors sorted by key, empty if the
+    * Vertex does not exist.
     */
-    pub fn set_vertex_label(&mut self,
-                            vertex_key: K,
-                            new_label: L)
-                            -> bool {
-        if self.vertex_exist(&vertex_key) {
-            GraphUtils::update_vertex_label(&mut self.vertices,
-                                            Some(new_label),
-                                            vertex_key);
-            true
-        } else {
-            false
+    pub fn neighbors<'r>(&'r self, vertex_key: &K) -> NeighborIterator<'r, K, V> {
+        NeighborIterator {
+            edges: self.vertices.find(vertex_key).map(|vertex| vertex.edges_iter())
         }
     }
 
     /**
-    * Remove the label of a Vertex
+    * Get the number of Edges starting from a Vertex.
     *
     * # Arguments
-    * * vertex_key - The key of the vertex
+    * * vertex_key - The key of the Vertex
     *
     * # Return
-    * true if the label is successfully set, false otherwise
+    * Some(degree) if the Vertex exist, None otherwise.
     */
-    pub fn remove_vertex_label(&mut self,
-                               vertex_key: K)
-                               -> bool {
-        if self.vertex_exist(&vertex_key) {
-            GraphUtils::update_vertex_label(&mut self.vertices,
-                                            None,
-                                            vertex_key);
-            true
-        } else {
-            false
+    pub fn out_degree(&self, vertex_key: &K) -> Option<uint> {
+        self.vertices.find(vertex_key).map(|vertex| vertex.edges.len())
+    }
+
+    /**
+    * Get the number of Edges ending to a Vertex.
+    *
+    * The Edges are stored by their first Vertex, so this searches the Edges
+    * of every Vertex. In an undirected Graph it is the out degree.
+    *
+    * # Arguments
+    * * vertex_key - The key of the Vertex
+    *
+    * # Return
+    * Some(degree) if the Vertex exist, None otherwise.
+    */
+    pub fn in_degree(&self, vertex_key: &K) -> Option<uint> {
+        if !self.vertex_exist(vertex_key) {
+            return None
         }
+        if !self.directed {
+            return self.out_degree(vertex_key)
+        }
+        Some(self.vertices_ref_iter().filter(|vertex| vertex.edge_exist(vertex_key)).count())
     }
 
     /**
-    * Get the label of a Vertex.
+    * Get the dense adjacency matrix of the Graph.
+    *
+    * The entry (i, j) is 1 if there is an Edge from the i-th Vertex to the
+    * j-th Vertex, 0 otherwise.
+    *
+    * # Return
+    * A tuple (keys, matrix) where keys gives the Vertex of each row and
+    * column of the matrix.
+    */
+    pub fn adjacency_matrix(&self) -> (Vec<K>, Vec<Vec<f64>>) {
+        let keys: Vec<K> = self.vertices_ref_iter().map(|v| v.key.clone()).collect();
+        let mut index: TreeMap<&K, uint> = TreeMap::new();
+        for (i, key) in keys.iter().enumerate() {
+            index.insert(key, i);
+        }
+
+        let mut matrix = Vec::from_elem(keys.len(), Vec::from_elem(keys.len(), 0.0f64));
+        for (i, vertex) in self.vertices_ref_iter().enumerate() {
+            for (to_key, _) in vertex.edges_iter() {
+                match index.find(&to_key) {
+                    Some(&j) => *matrix.get_mut(i).get_mut(j) = 1.0,
+                    None     => {}
+                }
+            }
+        }
+        (keys, matrix)
+    }
+
+    /**
+    * Get the adjacency matrix of the Graph with the values of the Edges.
+    *
+    * The entry (i, j) is the value of the Edge from the i-th Vertex to the
+    * j-th Vertex, None if there is no Edge or if it has no value, use
+    * to_boolean_matrix to distinguish them.
+    *
+    * # Return
+    * A tuple (keys, matrix) where keys gives the Vertex of each row and
+    * column of the matrix, the inverse of from_adjacency_matrix.
+    */
+    pub fn to_adjacency_matrix(&self) -> (Vec<K>, Vec<Vec<Option<V>>>) {
+        let keys: Vec<K> = self.vertices_ref_iter().map(|v| v.key.clone()).collect();
+        let mut index: TreeMap<&K, uint> = TreeMap::new();
+        for (i, key) in keys.iter().enumerate() {
+            index.insert(key, i);
+        }
+
+        let mut matrix = Vec::from_elem(keys.len(), Vec::from_elem(keys.len(), None));
+        for (i, vertex) in self.vertices_ref_iter().enumerate() {
+            for (to_key, value) in vertex.edges_iter() {
+                match index.find(&to_key) {
+                    Some(&j) => *matrix.get_mut(i).get_mut(j) = value.map(|v| v.clone()),
+                    None     => {}
+                }
+            }
+        }
+        (keys, matrix)
+    }
+
+    /**
+    * Get the boolean adjacency matrix of the Graph.
+    *
+    * # Return
+    * A tuple (keys, matrix) where keys gives the Vertex of each row and
+    * column of the matrix, the entry (i, j) is true if there is an Edge
+    * from the i-th Vertex to the j-th Vertex.
+    */
+    pub fn to_boolean_matrix(&self) -> (Vec<K>, Vec<Vec<bool>>) {
+        let (keys, adjacency) = self.adjacency_matrix();
+        let matrix = adjacency.move_iter().map(|row| {
+            row.move_iter().map(|a| a != 0.0).collect()
+        }).collect();
+        (keys, matrix)
+    }
+
+    /**
+    * Get the dense Laplacian matrix of the Graph.
+    *
+    * The Laplacian is D - A where A is the adjacency matrix and D the
+    * diagonal matrix of the number of Edges starting from each Vertex.
+    *
+    * # Return
+    * A tuple (keys, matrix) where keys gives the Vertex of each row and
+    * column of the matrix.
+    */
+    pub fn laplacian_matrix(&self) -> (Vec<K>, Vec<Vec<f64>>) {
+        let (keys, adjacency) = self.adjacency_matrix();
+        let matrix = adjacency.move_iter().enumerate().map(|(i, row)| {
+            let degree = row.iter().fold(0.0, |sum, a| sum + *a);
+            row.move_iter().enumerate().map(|(j, a)| {
+                if i == j { degree - a } else { -a }
+            }).collect()
+        }).collect();
+        (keys, matrix)
+    }
+
+    /**
+    * Create a copy of the Graph with every Edge reversed.
+    *
+    * The labels and the values are cloned, use a ReversedView to follow
+    * the Edges backward without copying the Graph. The observers are not
+    * copied.
+    *
+    * # Return
+    * A new graph with an Edge from b to a for each Edge from a to b.
+    */
+    pub fn reverse(&self) -> Graph<K, L, V> {
+        let mut reversed = Graph::new();
+        reversed.directed = self.directed;
+        reversed.self_loops = self.self_loops;
+        for vertex in self.vertices_ref_iter() {
+            reversed.vertices.insert(vertex.key.clone(),
+                                     box Vertex::new_with_opt(vertex.key.clone(), vertex.label.clone()));
+        }
+        for vertex in self.vertices_ref_iter() {
+            for (to_key, value) in vertex.edges_iter() {
+                match reversed.vertices.find_mut(to_key) {
+                    Some(to) => {
+                        to.edges.insert_sorted(Edge::new_with_opt(vertex.key.clone(),
+                                                                  value.map(|v| v.clone())));
+                    },
+                    None     => {}
+                }
+            }
+        }
+        reversed
+    }
+
+    /**
+    * Create the subgraph induced by the Vertices accepted by a predicate.
+    *
+    * The labels and the values are cloned and every Edge between two kept
+    * Vertices is kept.
+    *
+    * # Arguments
+    * * keep - Accept a Vertex from its key and its label
+    *
+    * # Return
+    * A new graph with the accepted Vertices and the Edges between them.
+    */
+    pub fn subgraph(&self, keep: |&K, Option<&L>| -> bool) -> Graph<K, L, V> {
+        let mut kept: Vec<&Vertex<K, L, V>> = Vec::new();
+        for vertex in self.vertices_ref_iter() {
+            if keep(&vertex.key, vertex.label.as_ref()) {
+                kept.push(vertex);
+            }
+        }
+        let keys: TreeSet<&K> = kept.iter().map(|vertex| &vertex.key).collect();
+
+        let mut subgraph = Graph::new();
+        subgraph.directed = self.directed;
+        subgraph.self_loops = self.self_loops;
+        for vertex in kept.iter() {
+            let mut copy = Vertex::new_with_opt(vertex.key.clone(), vertex.label.clone());
+            // the Edges stay sorted
+            for i in range(0, vertex.edges.len()) {
+                let edge = vertex.edges.get(i);
+                if keys.contains(&&edge.to_key) {
+                    copy.edges.push(edge.clone());
+                }
+            }
+            subgraph.vertices.insert(vertex.key.clone(), box copy);
+        }
+        subgraph
+    }
+
+    /**
+    * Create the union of two Graphs, with the Vertices and the Edges of
+    * both.
+    *
+    * The union is directed as self. A label or a value present in only one
+    * Graph is kept, the ones present in both are merged.
+    *
+    * # Arguments
+    * * other - The graph to merge with self
+    * * merge_label - Give the label of a Vertex from its labels in self
+    * and in other
+    * * merge_value - Give the value of an Edge from its values in self and
+    * in other
+    *
+    * # Return
+    * A new graph with the Vertices and the Edges of self and other.
+    */
+    pub fn union(&self,
+                 other: &Graph<K, L, V>,
+                 merge_label: |&L, &L| -> L,
+                 merge_value: |&V, &V| -> V)
+                 -> Graph<K, L, V> {
+        let mut union = self.clone();
+        for vertex in other.vertices_ref_iter() {
+            let label = match (union.get_vertex_label(vertex.key.clone()), vertex.get_label()) {
+                (Some(a), Some(b)) => Some(merge_label(a, b)),
+                (a, b)             => a.or(b).map(|l| l.clone())
+            };
+            if !union.add_vertex_opt_l(vertex.key.clone(), label.clone()) {
+                union.set_vertex_label_opt(vertex.key.clone(), label);
+            }
+        }
+        for vertex in other.vertices_ref_iter() {
+            let from_key = &vertex.key;
+            for (to_key, value) in vertex.edges_iter() {
+                // both undirected, the opposite Edge is the same one
+                if !union.directed && !other.directed && from_key > to_key {
+                    continue
+                }
+                if union.edge_exist(from_key.clone(), to_key.clone()) {
+                    let merged = match (union.get_vertex(from_key.clone()).unwrap().get_edge_value(to_key),
+                                        value) {
+                        (Some(a), Some(b)) => Some(merge_value(a, b)),
+                        (a, b)             => a.or(b).map(|v| v.clone())
+                    };
+                    union.set_edge_value_opt(from_key.clone(), to_key.clone(), merged);
+                } else {
+                    union.add_edge_opt_v(from_key.clone(), to_key.clone(), value.map(|v| v.clone()));
+                }
+            }
+        }
+        union
+    }
+
+    /**
+    * Create the intersection of two Graphs, with the Vertices and the
+    * Edges present in both.
+    *
+    * The intersection is directed as self. A label or a value present in
+    * only one Graph is kept, the ones present in both are merged.
+    *
+    * # Arguments
+    * * other - The graph to intersect with self
+    * * merge_label - Give the label of a Vertex from its labels in self
+    * and in other
+    * * merge_value - Give the value of an Edge from its values in self and
+    * in other
+    *
+    * # Return
+    * A new graph with the Vertices and the Edges of both self and other.
+    */
+    pub fn intersection(&self,
+                        other: &Graph<K, L, V>,
+                        merge_label: |&L, &L| -> L,
+                        merge_value: |&V, &V| -> V)
+                        -> Graph<K, L, V> {
+        let mut intersection = Graph::new();
+        intersection.directed = self.directed;
+        intersection.self_loops = self.self_loops;
+        for vertex in self.vertices_ref_iter() {
+            let label = match other.get_vertex(vertex.key.clone()) {
+                Some(found) => match (vertex.get_label(), found.get_label()) {
+                    (Some(a), Some(b)) => Some(merge_label(a, b)),
+                    (a, b)             => a.or(b).map(|l| l.clone())
+                },
+                None        => continue
+            };
+            intersection.add_vertex_opt_l(vertex.key.clone(), label);
+        }
+        for (from_key, to_key, value) in self.edges_iter() {
+            if !other.edge_exist(from_key.clone(), to_key.clone()) {
+                continue
+            }
+            let merged = match (value, other.get_vertex(from_key.clone()).unwrap().get_edge_value(to_key)) {
+                (Some(a), Some(b)) => Some(merge_value(a, b)),
+                (a, b)             => a.or(b).map(|v| v.clone())
+            };
+            intersection.add_edge_opt_v(from_key.clone(), to_key.clone(), merged);
+        }
+        intersection
+    }
+
+    /**
+    * Create the difference of two Graphs, with the Vertices of self and
+    * its Edges absent from other.
+    *
+    * # Arguments
+    * * other - The graph whose Edges are removed
+    *
+    * # Return
+    * A new graph with the Vertices of self and the Edges of self not in
+    * other.
+    */
+    pub fn difference(&self, other: &Graph<K, L, V>) -> Graph<K, L, V> {
+        let mut difference = self.clone();
+        for (from_key, to_key, _) in self.edges_iter() {
+            if other.edge_exist(from_key.clone(), to_key.clone()) {
+                difference.remove_edge(from_key.clone(), to_key.clone());
+            }
+        }
+        difference
+    }
+
+    /**
+    * Check if every Vertex can be reached from every other one, ignoring
+    * the direction of the Edges.
+    *
+    * This is the same as is_weakly_connected, an empty Graph is connected.
+    *
+    * # Return
+    * true if the Graph is connected, false otherwise.
+    */
+    pub fn is_connected(&self) -> bool {
+        self.is_weakly_connected()
+    }
+
+    /**
+    * Check if the Graph is connected when the direction of the Edges is
+    * ignored.
+    *
+    * # Return
+    * true if the Graph is weakly connected, false otherwise.
+    */
+    pub fn is_weakly_connected(&self) -> bool {
+        let indexed = IndexedGraph::from_graph(self);
+        if indexed.len() == 0 {
+            return true
+        }
+        let undirected = indexed.undirected_edges();
+        indexed.distances(0, &undirected).iter().all(|d| d.is_some())
+    }
+
+    /**
+    * Check if there is a path from every Vertex to every other one.
+    *
+    * Every Vertex must be reachable from the first Vertex and the first
+    * Vertex must be reachable from every Vertex.
+    *
+    * # Return
+    * true if the Graph is strongly connected, false otherwise.
+    */
+    pub fn is_strongly_connected(&self) -> bool {
+        let indexed = IndexedGraph::from_graph(self);
+        if indexed.len() == 0 {
+            return true
+        }
+        indexed.distances(0, &indexed.out_edges).iter().all(|d| d.is_some()) &&
+            indexed.distances(0, &indexed.in_edges).iter().all(|d| d.is_some())
+    }
+
+    /**
+    * Get the Vertices reachable from a Vertex by following the Edges.
+    *
+    * # Arguments
+    * * vertex_key - The key of the first Vertex
+    *
+    * # Return
+    * The sorted keys of the reachable Vertices, the Vertex itself excluded,
+    * empty if the Vertex does not exist.
+    */
+    pub fn descendants(&self, vertex_key: &K) -> Vec<K> {
+        let indexed = IndexedGraph::from_graph(self);
+        indexed.reachable(vertex_key, &indexed.out_edges)
+    }
+
+    /**
+    * Get the Vertices from which a Vertex is reachable by following the
+    * Edges, what depends on the Vertex when the Edges go from the dependent
+    * to its dependencies.
+    *
+    * # Arguments
+    * * vertex_key - The key of the last Vertex
+    *
+    * # Return
+    * The sorted keys of the Vertices reaching the Vertex, the Vertex itself
+    * excluded, empty if the Vertex does not exist.
+    */
+    pub fn ancestors(&self, vertex_key: &K) -> Vec<K> {
+        let indexed = IndexedGraph::from_graph(self);
+        indexed.reachable(vertex_key, &indexed.in_edges)
+    }
+
+    /**
+    * Check if there is a path following the Edges between two Vertices.
+    *
+    * # Arguments
+    * * from_key - The key of the first Vertex
+    * * to_key - The key of the last Vertex
+    *
+    * # Return
+    * true if the last Vertex is reachable from the first one, a Vertex
+    * always reaches itself, false if a Vertex does not exist.
+    */
+    pub fn reachable(&self, from_key: &K, to_key: &K) -> bool {
+        let indexed = IndexedGraph::from_graph(self);
+        match (indexed.index.find(from_key), indexed.index.find(to_key)) {
+            (Some(&from), Some(&to)) => indexed.distances(from, &indexed.out_edges).get(to).is_some(),
+            _                        => false
+        }
+    }
+
+    /**
+    * Check if the Graph is a forest, ignoring the direction of the Edges.
+    *
+    * Two opposite Edges count as one, a self loop is a cycle.
+    *
+    * # Return
+    * Ok if the Graph has no cycle, Err(ExtraEdge) with an Edge closing a
+    * cycle otherwise.
+    */
+    pub fn is_forest(&self) -> Result<(), StructureViolation<K>> {
+        let indexed = IndexedGraph::from_graph(self);
+        self.forest_components(&indexed).map(|_| ())
+    }
+
+    /**
+    * Check if the Graph is a tree, ignoring the direction of the Edges.
+    *
+    * # Return
+    * Ok if the Graph is connected and has no cycle, Err(EmptyGraph) if it
+    * has no Vertex, Err(ExtraEdge) with an Edge closing a cycle or
+    * Err(Disconnected) with two Vertices of different components otherwise.
+    */
+    pub fn is_tree(&self) -> Result<(), StructureViolation<K>> {
+        let indexed = IndexedGraph::from_graph(self);
+        if indexed.len() == 0 {
+            return Err(EmptyGraph)
+        }
+        let sets = try!(self.forest_components(&indexed));
+        match range(1, indexed.len()).find(|&v| sets.find_imm(v) != sets.find_imm(0)) {
+            Some(v) => Err(Disconnected(indexed.keys.get(0).clone(),
+                                        indexed.keys.get(v).clone())),
+            None    => Ok(())
+        }
+    }
+
+    /**
+    * Check if the Graph is a directed acyclic graph.
+    *
+    * # Return
+    * Ok if the Graph has no directed cycle, Err(Cycle) with the Vertices of
+    * a cycle otherwise.
+    */
+    pub fn is_dag(&self) -> Result<(), StructureViolation<K>> {
+        let indexed = IndexedGraph::from_graph(self);
+        // 0: not visited, 1: on the depth first search path, 2: done
+        let mut state = Vec::from_elem(indexed.len(), 0u8);
+        for root in range(0, indexed.len()) {
+            if *state.get(root) != 0 {
+                continue
+            }
+            let mut path = vec!(root);
+            let mut positions = vec!(0u);
+            *state.get_mut(root) = 1;
+            while !path.is_empty() {
+                let top = path.len() - 1;
+                let v = *path.get(top);
+                let position = *positions.get(top);
+                if position == indexed.out_edges.get(v).len() {
+                    *state.get_mut(v) = 2;
+                    path.pop();
+                    positions.pop();
+                    continue
+                }
+                *positions.get_mut(top) += 1;
+                let u = *indexed.out_edges.get(v).get(position);
+                match *state.get(u) {
+                    0 => {
+                        *state.get_mut(u) = 1;
+                        path.push(u);
+                        positions.push(0);
+                    },
+                    1 => {
+                        let start = path.iter().position(|&w| w == u).unwrap();
+                        let cycle = path.iter().skip(start).map(|&w| {
+                            indexed.keys.get(w).clone()
+                        }).collect();
+                        return Err(Cycle(cycle))
+                    },
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /**
+    * Check the invariants of the storage of the Graph.
+    *
+    * The constructors taking Vertices or Edges as they are, like
+    * new_with_vertices, don't check them.
+    *
+    * # Return
+    * Ok if the Graph is consistent, Err(violations) with every broken
+    * invariant otherwise.
+    */
+    pub fn validate(&self) -> Result<(), Vec<IntegrityViolation<K>>> {
+        let mut violations = Vec::new();
+        for vertex in self.vertices_ref_iter() {
+            let mut previous: Option<&K> = None;
+            let mut sorted = true;
+            for (to_key, _) in vertex.edges_iter() {
+                match previous.map(|p| p.cmp(to_key)) {
+                    Some(Equal)   => {
+                        violations.push(DuplicateEdge(vertex.key.clone(), to_key.clone()))
+                    },
+                    Some(Greater) => sorted = false,
+                    _             => {}
+                }
+                previous = Some(to_key);
+                match self.vertices.find(to_key) {
+                    Some(to) => {
+                        if !self.directed && !to.edge_exist(&vertex.key) {
+                            violations.push(MissingReverseEdge(vertex.key.clone(),
+                                                               to_key.clone()));
+                        }
+                    },
+                    None     => {
+                        violations.push(DanglingEdge(vertex.key.clone(), to_key.clone()))
+                    }
+                }
+            }
+            if !sorted {
+                violations.push(UnsortedEdges(vertex.key.clone()));
+            }
+        }
+
+        if violations.is_empty() { Ok(()) } else { Err(violations) }
+    }
+
+    // the components of the undirected Graph, or an Edge closing a cycle
+    fn forest_components(&self,
+                         indexed: &IndexedGraph<K>)
+                         -> Result<UnionFind, StructureViolation<K>> {
+        let mut sets = UnionFind::new(indexed.len());
+        for (v, neighbors) in indexed.undirected_edges().iter().enumerate() {
+            for &u in neighbors.iter().filter(|&&u| u >= v) {
+                if u == v || !sets.union(u, v) {
+                    // report the Edge in its direction in the Graph
+                    let (from, to) = if contains_sorted(indexed.out_edges.get(v).as_slice(), u) {
+                        (v, u)
+                    } else {
+                        (u, v)
+                    };
+                    return Err(ExtraEdge(indexed.keys.get(from).clone(),
+                                         indexed.keys.get(to).clone()))
+                }
+            }
+        }
+        Ok(sets)
+    }
+
+    /**
+    * Set the label of a Vertex with an optional label.
+    *
+    * # Arguments
+    * * vertex_key - The key of the vertex to set
+    * * new_label - The optional label to set to the Vertex
+    *
+    * # Return
+    * true if the label is successfully set, false otherwise.
+    */
+    pub fn set_vertex_label_opt(&mut self,
+                                vertex_key: K,
+                                new_label: Option<L>)
+                                -> bool {
+        if self.vertex_exist(&vertex_key) {
+            self.vertices.find_mut(&vertex_key).unwrap().label = new_label;
+            if !self.observers.is_empty() {
+                let label = self.vertices.find(&vertex_key).unwrap().get_label();
+                self.observers.notify(|o| o.on_vertex_label_changed(&vertex_key, label));
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    /**
+    * Set the label of a Vertex.
+    *
+    * # Arguments
+    * * vertex_key - The key of the vertex to set
+    * * new_label - The new label of the Vertex
+    *
+    * # Return
+    * true if the label is successfully set, false otherwise.
+    */
+    pub fn set_vertex_label(&mut self,
+                            vertex_key: K,
+                            new_label: L)
+                            -> bool {
+        self.set_vertex_label_opt(vertex_key, Some(new_label))
+    }
+
+    /**
+    * Remove the label of a Vertex
+    *
+    * # Arguments
+    * * vertex_key - The key of the vertex
+    *
+    * # Return
+    * true if the label is successfully set, false otherwise
+    */
+    pub fn remove_vertex_label(&mut self,
+                               vertex_key: K)
+                               -> bool {
+        self.set_vertex_label_opt(vertex_key, None)
+    }
+
+    /**
+    * Get the label of a Vertex.
+    *
+    * # Arguments
+    * * vertex_key - The key of the vertex
+    *
+    * # Return
+    * Some(label) if there is a label, None otherwise.
+    */
+    pub fn get_vertex_label<'r>(&'r self,
+                            vertex_key: K)
+                            -> Option<&'r L> {
+        if self.vertex_exist(&vertex_key) {
+            self.vertices.find(&vertex_key).unwrap().get_label()
+        } else {
+            None
+        }
+    }
+
+    /**
+    * Get the value of an Edge.
+    *
+    * # Arguments
+    * * from_key - The key of the first Vertex of the Edge
+    * * to_key - The key of the second Vertex of the Edge
+    *
+    * # Return
+    * Some(value) if the Edge exist and has a value, None otherwise.
+    */
+    pub fn get_edge_value<'r>(&'r self,
+                              from_key: K,
+                              to_key: K)
+                              -> Option<&'r V> {
+        match self.vertices.find(&from_key) {
+            Some(vertex) => vertex.get_edge_value(&to_key),
+            None         => None
+        }
+    }
+
+    /**
+    * Add an Edge to the graph with an optional value attached to.
+    *
+    * # Arguments
+    * * from_key - The Key of the first Vertex of the Edge
+    * * to_key - The Kye of the second Vertex of the Edge
+    * * value - The optional value to attach to the Edge
+    *
+    * # Return
+    * true if the edge is successfully added, false otherwise, also for a
+    * self loop when the Graph does not allow them.
+    */
+    pub fn add_edge_opt_v(&mut self,
+                         from_key: K,
+                         to_key: K,
+                         value: Option<V>)
+                         -> bool {
+        if !self.self_loops && from_key == to_key {
+            return false
+        }
+        if self.vertex_exist(&from_key) &&
+           self.vertex_exist(&to_key) {
+            let added = {
+                if !self.directed && from_key != to_key {
+                    let vertex = self.vertices.find_mut(&to_key).unwrap();
+                    vertex.add_edge_opt_v(from_key.clone(), value.clone());
+                }
+                let vertex = self.vertices.find_mut(&from_key).unwrap();
+                vertex.add_edge_opt_v(to_key.clone(), value)
+            };
+            if added && !self.observers.is_empty() {
+                let value = self.vertices.find(&from_key).unwrap().get_edge_value(&to_key);
+                self.observers.notify(|o| o.on_edge_added(&from_key, &to_key, value));
+            }
+            added
+        } else {
+            false
+        }
+    }
+
+    /**
+    * Add an Edge to the graph with a value attached to.
+    *
+    * # Arguments
+    * * from_key - The Key of the first Vertex of the Edge
+    * * to_key - The Kye of the second Vertex of the Edge
+    * * value - The value to attach to the Edge
+    *
+    * # Return
+    * true if the edge is successfully added, false otherwise.
+    */
+    pub fn add_edge_v(&mut self,
+                      from_key: K,
+                      to_key: K,
+                      value: V)
+                      -> bool {
+        self.add_edge_opt_v(from_key, to_key, Some(value))
+    }
+
+    /**
+    * Add an Edge to the graph.
+    *
+    * The both Vertex should exist.
+    *
+    * # Arguments
+    * * from_key - The Key of the first Vertex of the Edge
+    * * to_key - The Kye of the second Vertex of the Edge
+    *
+    * # Return
+    * true if the edge is successfully added, false otherwise.
+    */
+    pub fn add_edge(&mut self,
+                    from_key: K,
+                    to_key: K)
+                    -> bool {
+        self.add_edge_opt_v(from_key, to_key, None)
+    }
+
+    /**
+    * Set an optional value to an Edge.
+    *
+    * The both Vertex should exist.
+    *
+    * # Arguments
+    * * from_key - The first Key of the Edge
+    * * to_key - The second Key of the Edge
+    * * new_value - The optional value to set to the Edge
+    *
+    * # Return
+    * true if the value is successfully set, false otherwise
+    */
+    pub fn set_edge_value_opt(&mut self,
+                             from_key: K,
+                             to_key: K,
+                             new_value: Option<V>)
+                             -> bool {
+        if self.vertex_exist(&from_key) &&
+           self.vertex_exist(&to_key) {
+            let set = {
+                if !self.directed && from_key != to_key {
+                    let vertex = self.vertices.find_mut(&to_key).unwrap();
+                    vertex.set_edge_value_opt(from_key.clone(), new_value.clone());
+                }
+                let vertex = self.vertices.find_mut(&from_key).unwrap();
+                vertex.set_edge_value_opt(to_key.clone(), new_value)
+            };
+            if set && !self.observers.is_empty() {
+                let value = self.vertices.find(&from_key).unwrap().get_edge_value(&to_key);
+                self.observers.notify(|o| o.on_edge_value_changed(&from_key, &to_key, value));
+            }
+            set
+        } else {
+            false
+        }
+    }
+
+    /**
+    * Set a value of an Edge.
+    *
+    * The both Vertex should exist.
+    *
+    * # Arguments
+    * * from_key - The first Key of the Edge
+    * * to_key - The second Key of the Edge
+    * * new_value - The value to set to the Edge
+    *
+    * # Return
+    * true if the value is successfully set, false otherwise
+    */
+    pub fn set_edge_value(&mut self,
+                          from_key: K,
+                          to_key: K,
+                          new_value: V)
+                          -> bool {
+        self.set_edge_value_opt(from_key, to_key, Some(new_value))
+    }
+
+    /**
+    * Remove a value of an Edge.
+    *
+    * The both Vertex should exist.
+    *
+    * # Arguments
+    * * from_key - The first Key of the Edge
+    * * to_key - The second Key of the Edge
+    *
+    * # Return
+    * true if the value is successfully removed, false otherwise
+    */
+    pub fn remove_edge_value(&mut self,
+                             from_key: K,
+                             to_key: K)
+                             -> bool {
+        self.set_edge_value_opt(from_key, to_key, None)
+    }
+
+    /**
+    * Check if a Vertex exist or not.
+    *
+    * # Arguments
+    * * vertex_key - The key of the Vertex to check
+    *
+    * # Return
+    * true if the Vertex exist, false otherwise.
+    */
+    pub fn vertex_exist(&self,
+                        vertex_key: &K)
+                        -> bool {
+        self.vertices.contains_key(vertex_key)
+    }
+
+    /**
+    * Check if an Edge exist or not.
+    *
+    * # Arguments
+    * * from_key - The key of the first Vertex of the Edge to check
+    * * to_key - The key of the second Vertex of the Edge to check
+    *
+    * # Return
+    * true if the Edge exist, false otherwise.
+    */
+    pub fn edge_exist(&self,
+                      from_key: K,
+                      to_key: K)
+                      -> bool {
+        match self.vertices.find(&from_key) {
+            Some(v) => v.edge_exist(&to_key),
+            None    => false
+        }
+    }
+
+    /**
+    * Check if two Vertex are adjacent.
+    *
+    * The two Vertex should exist in the Graph. In an undirected Graph the
+    * order of the keys does not matter.
+    *
+    * # Arguments
+    * * from_key - The key of the first Vertex of the Edge
+    * * to_key - The key of the second Vertex of the Edge
+    *
+    * # Return
+    * true if the two Vertex are adjacent, false otherwise.
+    */
+    pub fn adjacent(&self,
+                    from_key: K,
+                    to_key: K)
+                    -> bool {
+        if self.vertex_exist(&from_key) &&
+           self.vertex_exist(&to_key) {
+            self.vertices.find(&from_key).unwrap().edge_exist(&to_key) ||
+                (!self.directed && self.vertices.find(&to_key).unwrap().edge_exist(&from_key))
+        } else {
+            false
+        }
+    }
+
+    /**
+    * Remove an Edge.
+    *
+    * The both Vertex should exist.
+    *
+    * # Arguments
+    * * from_key - The first Key of the Edge
+    * * to_key - The second Key of the Edge
+    *
+    * # Return
+    * true if the edge is successfully removed, false otherwise
+    */
+    pub fn remove_edge(&mut self,
+                       from_key: K,
+                       to_key: K)
+                       -> bool {
+        if self.vertex_exist(&from_key) &&
+           self.vertex_exist(&to_key) {
+            let removed = {
+                if !self.directed && from_key != to_key {
+                    let vertex = self.vertices.find_mut(&to_key).unwrap();
+                    vertex.remove_edge(from_key.clone());
+                }
+                let vertex = self.vertices.find_mut(&from_key).unwrap();
+                vertex.remove_edge(to_key.clone())
+            };
+            if removed {
+                self.observers.notify(|o| o.on_edge_removed(&from_key, &to_key));
+            }
+            removed
+        } else {
+            false
+        }
+    }
+
+    /**
+    * Remove a Vertex.
+    *
+    * The Vertex should exist.
+    *
+    * # Arguments
+    * * vertex_key - The first Key of the Edge
+    *
+    * # Return
+    * true if the Vertex is successfully removed, false otherwise
+    */
+    pub fn remove_vertex(&mut self,
+                         vertex_key: K)
+                         -> bool {
+        if self.vertex_exist(&vertex_key) {
+            if !self.observers.is_empty() {
+                // the Edges of the Vertex disappear with it, an undirected
+                // Edge is notified once, from the removed Vertex
+                let mut edges: Vec<(K, K)> = Vec::new();
+                for vertex in self.vertices_ref_iter() {
+                    if vertex.key == vertex_key {
+                        for (to_key, _) in vertex.edges_iter() {
+                            edges.push((vertex.key.clone(), to_key.clone()));
+                        }
+                    } else if self.directed && vertex.edge_exist(&vertex_key) {
+                        edges.push((vertex.key.clone(), vertex_key.clone()));
+                    }
+                }
+                for &(ref from_key, ref to_key) in edges.iter() {
+                    self.observers.notify(|o| o.on_edge_removed(from_key, to_key));
+                }
+            }
+            self.vertices.remove(&vertex_key);
+            for (_, vertex) in self.vertices.mut_iter() {
+                vertex.remove_edge(vertex_key.clone());
+            }
+            self.observers.notify(|o| o.on_vertex_removed(&vertex_key));
+            true
+        } else {
+            false
+        }
+    }
+
+    /**
+    * Remove a Vertex and give back its data.
+    *
+    * The Edges ending to the Vertex are removed as by remove_vertex.
+    *
+    * # Arguments
+    * * vertex_key - The key of the Vertex
+    *
+    * # Return
+    * Some((label, edges)) with the label of the Vertex and the (to_key,
+    * value) of the Edges starting from it, None if the Vertex does not
+    * exist.
+    */
+    pub fn take_vertex(&mut self,
+                       vertex_key: K)
+                       -> Option<(Option<L>, Vec<(K, Option<V>)>)> {
+        let taken = match self.vertices.find_mut(&vertex_key) {
+            Some(vertex) => {
+                let mut edges = Vec::with_capacity(vertex.edges.len());
+                for i in range(0, vertex.edges.len()) {
+                    let edge = vertex.edges.get_mut(i);
+                    edges.push((edge.to_key.clone(), edge.value.take()));
+                }
+                (vertex.label.take(), edges)
+            },
+            None         => return None
+        };
+        self.remove_vertex(vertex_key);
+        Some(taken)
+    }
+
+    /**
+    * Add a Vertex to the Graph with a Key and an optional Label.
+    *
+    * # Arguments
+    * * key - The key of the new Vertex
+    * * label - The option Label to attach to the graph
+    *
+    * # Return
+    * Ok if it succeeds, Err(error) otherwise.
+    */
+    pub fn try_add_vertex_opt_l(&mut self,
+                                key: K,
+                                label: Option<L>)
+                                -> Result<(), GraphError<K>> {
+        if self.vertex_exist(&key) {
+            return Err(VertexAlreadyExists(key))
+        }
+        self.add_vertex_opt_l(key, label);
+        Ok(())
+    }
+
+    /**
+    * Add a Vertex to the Graph with a Key and a Label.
+    *
+    * # Arguments
+    * * key - The key of the new Vertex
+    * * label - The Label to attach to the graph
+    *
+    * # Return
+    * Ok if it succeeds, Err(error) otherwise.
+    */
+    pub fn try_add_vertex_l(&mut self,
+                            key: K,
+                            label: L)
+                            -> Result<(), GraphError<K>> {
+        self.try_add_vertex_opt_l(key, Some(label))
+    }
+
+    /**
+    * Add a Vertex to the Graph with a Key.
+    *
+    * # Arguments
+    * * key - The key of the new Vertex
+    *
+    * # Return
+    * Ok if it succeeds, Err(error) otherwise.
+    */
+    pub fn try_add_vertex(&mut self,
+                          key: K)
+                          -> Result<(), GraphError<K>> {
+        self.try_add_vertex_opt_l(key, None)
+    }
+
+    /**
+    * Set the label of a Vertex with an optional label.
+    *
+    * # Arguments
+    * * vertex_key - The key of the vertex to set
+    * * new_label - The optional label to set to the Vertex
+    *
+    * # Return
+    * Ok if it succeeds, Err(error) otherwise.
+    */
+    pub fn try_set_vertex_label_opt(&mut self,
+                                    vertex_key: K,
+                                    new_label: Option<L>)
+                                    -> Result<(), GraphError<K>> {
+        try!(self.check_vertex(&vertex_key));
+        self.set_vertex_label_opt(vertex_key, new_label);
+        Ok(())
+    }
+
+    /**
+    * Set the label of a Vertex.
+    *
+    * # Arguments
+    * * vertex_key - The key of the vertex to set
+    * * new_label - The new label of the Vertex
+    *
+    * # Return
+    * Ok if it succeeds, Err(error) otherwise.
+    */
+    pub fn try_set_vertex_label(&mut self,
+                                vertex_key: K,
+                                new_label: L)
+                                -> Result<(), GraphError<K>> {
+        self.try_set_vertex_label_opt(vertex_key, Some(new_label))
+    }
+
+    /**
+    * Remove the label of a Vertex.
     *
     * # Arguments
     * * vertex_key - The key of the vertex
     *
     * # Return
-    * Some(label) if there is a label, None otherwise.
+    * Ok if it succeeds, Err(error) otherwise.
     */
-    pub fn get_vertex_label<'r>(&'r self,
-                            vertex_key: K)
-                            -> Option<&'r L> {
-        if self.vertex_exist(&vertex_key) {
-            GraphUtils::get_vertex_imm(&self.vertices,
-                                       &vertex_key).unwrap().get_label()
-        } else {
-            None
-        }
+    pub fn try_remove_vertex_label(&mut self,
+                                   vertex_key: K)
+                                   -> Result<(), GraphError<K>> {
+        self.try_set_vertex_label_opt(vertex_key, None)
     }
 
     /**
@@ -839,25 +2525,27 @@ impl<K: ToString + Ord + Eq + Clone,
     *
     * # Arguments
     * * from_key - The Key of the first Vertex of the Edge
-    * * to_key - The Kye of the second Vertex of the Edge
+    * * to_key - The Key of the second Vertex of the Edge
     * * value - The optional value to attach to the Edge
     *
     * # Return
-    * true if the edge is successfully added, false otherwise.
+    * Ok if it succeeds, Err(error) otherwise.
     */
-    pub fn add_edge_opt_v(&mut self,
-                         from_key: K,
-                         to_key: K,
-                         value: Option<V>)
-                         -> bool {
-        if self.vertex_exist(&from_key) &&
-           self.vertex_exist(&to_key) {
-            GraphUtils::get_vertex_mut(&mut self.vertices,
-                                       from_key).unwrap().add_edge_opt_v(to_key,
-                                                                         value)
-        } else {
-            false
+    pub fn try_add_edge_opt_v(&mut self,
+                              from_key: K,
+                              to_key: K,
+                              value: Option<V>)
+                              -> Result<(), GraphError<K>> {
+        try!(self.check_vertex(&from_key));
+        try!(self.check_vertex(&to_key));
+        if !self.self_loops && from_key == to_key {
+            return Err(SelfLoopForbidden(from_key))
         }
+        if self.adjacent(from_key.clone(), to_key.clone()) {
+            return Err(EdgeAlreadyExists(from_key, to_key))
+        }
+        self.add_edge_opt_v(from_key, to_key, value);
+        Ok(())
     }
 
     /**
@@ -865,355 +2553,609 @@ impl<K: ToString + Ord + Eq + Clone,
     *
     * # Arguments
     * * from_key - The Key of the first Vertex of the Edge
-    * * to_key - The Kye of the second Vertex of the Edge
+    * * to_key - The Key of the second Vertex of the Edge
     * * value - The value to attach to the Edge
     *
     * # Return
-    * true if the edge is successfully added, false otherwise.
+    * Ok if it succeeds, Err(error) otherwise.
     */
-    pub fn add_edge_v(&mut self,
-                      from_key: K,
-                      to_key: K,
-                      value: V)
-                      -> bool {
-        if self.vertex_exist(&from_key) &&
-           self.vertex_exist(&to_key) {
-            GraphUtils::get_vertex_mut(&mut self.vertices,
-                                       from_key).unwrap().add_edge_v(to_key,
-                                                                     value)
-        } else {
-            false
-        }
+    pub fn try_add_edge_v(&mut self,
+                          from_key: K,
+                          to_key: K,
+                          value: V)
+                          -> Result<(), GraphError<K>> {
+        self.try_add_edge_opt_v(from_key, to_key, Some(value))
     }
 
     /**
     * Add an Edge to the graph.
     *
-    * The both Vertex should exist.
-    *
     * # Arguments
     * * from_key - The Key of the first Vertex of the Edge
-    * * to_key - The Kye of the second Vertex of the Edge
+    * * to_key - The Key of the second Vertex of the Edge
     *
     * # Return
-    * true if the edge is successfully added, false otherwise.
+    * Ok if it succeeds, Err(error) otherwise.
     */
-    pub fn add_edge(&mut self,
-                    from_key: K,
-                    to_key: K)
-                    -> bool {
-        if self.vertex_exist(&from_key) &&
-           self.vertex_exist(&to_key) {
-            GraphUtils::get_vertex_mut(&mut self.vertices,
-                                       from_key).unwrap().add_edge(to_key)
-        } else {
-            false
-        }
+    pub fn try_add_edge(&mut self,
+                        from_key: K,
+                        to_key: K)
+                        -> Result<(), GraphError<K>> {
+        self.try_add_edge_opt_v(from_key, to_key, None)
     }
 
     /**
     * Set an optional value to an Edge.
     *
-    * The both Vertex should exist.
-    *
     * # Arguments
     * * from_key - The first Key of the Edge
     * * to_key - The second Key of the Edge
     * * new_value - The optional value to set to the Edge
     *
     * # Return
-    * true if the value is successfully set, false otherwise
+    * Ok if it succeeds, Err(error) otherwise.
     */
-    pub fn set_edge_value_opt(&mut self,
-                             from_key: K,
-                             to_key: K,
-                             new_value: Option<V>)
-                             -> bool {
-        if self.vertex_exist(&from_key) &&
-           self.vertex_exist(&to_key) {
-            GraphUtils::get_vertex_mut(&mut self.vertices,
-                                       from_key).unwrap().set_edge_value_opt(to_key,
-                                                                             new_value)
-        } else {
-            false
-        }
+    pub fn try_set_edge_value_opt(&mut self,
+                                  from_key: K,
+                                  to_key: K,
+                                  new_value: Option<V>)
+                                  -> Result<(), GraphError<K>> {
+        try!(self.check_edge(&from_key, &to_key));
+        self.set_edge_value_opt(from_key, to_key, new_value);
+        Ok(())
     }
 
     /**
     * Set a value of an Edge.
     *
-    * The both Vertex should exist.
-    *
     * # Arguments
     * * from_key - The first Key of the Edge
     * * to_key - The second Key of the Edge
     * * new_value - The value to set to the Edge
     *
     * # Return
-    * true if the value is successfully set, false otherwise
+    * Ok if it succeeds, Err(error) otherwise.
     */
-    pub fn set_edge_value(&mut self,
-                          from_key: K,
-                          to_key: K,
-                          new_value: V)
-                          -> bool {
-        if self.vertex_exist(&from_key) &&
-           self.vertex_exist(&to_key) {
-            GraphUtils::get_vertex_mut(&mut self.vertices,
-                                       from_key).unwrap().set_edge_value(to_key,
-                                                                         new_value)
-        } else {
-            false
-        }
+    pub fn try_set_edge_value(&mut self,
+                              from_key: K,
+                              to_key: K,
+                              new_value: V)
+                              -> Result<(), GraphError<K>> {
+        self.try_set_edge_value_opt(from_key, to_key, Some(new_value))
     }
 
     /**
     * Remove a value of an Edge.
     *
-    * The both Vertex should exist.
-    *
     * # Arguments
     * * from_key - The first Key of the Edge
     * * to_key - The second Key of the Edge
     *
     * # Return
-    * true if the value is successfully removed, false otherwise
+    * Ok if it succeeds, Err(error) otherwise.
     */
-    pub fn remove_edge_value(&mut self,
-                             from_key: K,
-                             to_key: K)
-                             -> bool {
-        if self.vertex_exist(&from_key) &&
-           self.vertex_exist(&to_key) {
-            GraphUtils::get_vertex_mut(&mut self.vertices,
-                                       from_key).unwrap().remove_edge_value(to_key)
-        } else {
-            false
-        }
+    pub fn try_remove_edge_value(&mut self,
+                                 from_key: K,
+                                 to_key: K)
+                                 -> Result<(), GraphError<K>> {
+        self.try_set_edge_value_opt(from_key, to_key, None)
     }
 
     /**
-    * Check if a Vertex exist or not.
+    * Remove an Edge.
     *
     * # Arguments
-    * * vertex_key - The key of the Vertex to check
+    * * from_key - The first Key of the Edge
+    * * to_key - The second Key of the Edge
     *
     * # Return
-    * true if the Vertex exist, false otherwise.
+    * Ok if it succeeds, Err(error) otherwise.
     */
-    pub fn vertex_exist(&self,
-                        vertex_key: &K)
-                        -> bool {
-        match GraphUtils::get_vertex_imm(&self.vertices, vertex_key) {
-            Some(_) => true,
-            None    => false
-        }
+    pub fn try_remove_edge(&mut self,
+                           from_key: K,
+                           to_key: K)
+                           -> Result<(), GraphError<K>> {
+        try!(self.check_edge(&from_key, &to_key));
+        self.remove_edge(from_key, to_key);
+        Ok(())
     }
 
     /**
-    * Check if an Edge exist or not.
+    * Remove a Vertex and its Edges.
     *
     * # Arguments
-    * * from_key - The key of the first Vertex of the Edge to check
-    * * to_key - The key of the second Vertex of the Edge to check
+    * * vertex_key - The key of the Vertex
     *
     * # Return
-    * true if the Edge exist, false otherwise.
+    * Ok if it succeeds, Err(error) otherwise.
     */
-    pub fn edge_exist(&self,
-                      from_key: K,
-                      to_key: K)
-                      -> bool {
-        match GraphUtils::get_vertex_imm(&self.vertices, &from_key) {
-            Some(v) => v.edge_exist(&to_key),
-            None    => false
+    pub fn try_remove_vertex(&mut self,
+                             vertex_key: K)
+                             -> Result<(), GraphError<K>> {
+        try!(self.check_vertex(&vertex_key));
+        self.remove_vertex(vertex_key);
+        Ok(())
+    }
+
+    // Err(VertexNotFound) if the Vertex does not exist
+    fn check_vertex(&self, vertex_key: &K) -> Result<(), GraphError<K>> {
+        if self.vertex_exist(vertex_key) {
+            Ok(())
+        } else {
+            Err(VertexNotFound(vertex_key.clone()))
         }
     }
 
-    /**
-    * Check if two Vertex are adjacent.
-    *
-    * The two Vertex should exist in the Graph.
-    *
-    * # Arguments
-    * * from_key - The key of the first Vertex of the Edge
-    * * to_key - The key of the second Vertex of the Edge
-    *
-    * # Return
-    * true if the two Vertex are adjacent, false otherwise.
-    */
-    pub fn adjacent(&self,
-                    from_key: K,
-                    to_key: K)
-                    -> bool {
-        if self.vertex_exist(&from_key) &&
-           self.vertex_exist(&to_key) {
-            GraphUtils::get_vertex_imm(&self.vertices,
-                                       &from_key).unwrap().edge_exist(&to_key)
+    // Err(VertexNotFound) or Err(EdgeNotFound) if the Edge does not exist
+    fn check_edge(&self, from_key: &K, to_key: &K) -> Result<(), GraphError<K>> {
+        try!(self.check_vertex(from_key));
+        try!(self.check_vertex(to_key));
+        if self.adjacent(from_key.clone(), to_key.clone()) {
+            Ok(())
         } else {
-            false
+            Err(EdgeNotFound(from_key.clone(), to_key.clone()))
+        }
+    }
+}
+
+/// Iterator over the keys and the mutable labels of the Vertices of a Graph.
+pub struct VertexMutIterator<'s, K, L, V> {
+    vertices: MutEntries<'s, K, Box<Vertex<K, L, V>>>
+}
+
+impl<'s, K, L, V> Iterator<(&'s K, &'s mut Option<L>)> for VertexMutIterator<'s, K, L, V> {
+    #[inline]
+    fn next(&mut self) -> Option<(&'s K, &'s mut Option<L>)> {
+        self.vertices.next().map(|(key, vertex)| (key, &mut vertex.label))
+    }
+}
+
+/// Iterator over the Edges of a Graph.
+pub struct GraphEdgeIterator<'s, K, L, V> {
+    vertices: Entries<'s, K, Box<Vertex<K, L, V>>>,
+    current:  Option<(&'s K, EdgeIterator<'s, K, V>)>,
+    directed: bool
+}
+
+impl<'s,
+     K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
+     Iterator<(&'s K, &'s K, Option<&'s V>)> for GraphEdgeIterator<'s, K, L, V> {
+    fn next(&mut self) -> Option<(&'s K, &'s K, Option<&'s V>)> {
+        loop {
+            let edge = match self.current {
+                Some((from_key, ref mut edges)) => {
+                    edges.next().map(|(to_key, value)| (from_key, to_key, value))
+                },
+                None                            => None
+            };
+            match edge {
+                Some((from_key, to_key, value)) => {
+                    if self.directed || from_key <= to_key {
+                        return Some((from_key, to_key, value))
+                    }
+                },
+                None                            => {
+                    match self.vertices.next() {
+                        Some((key, vertex)) => self.current = Some((key, vertex.edges_iter())),
+                        None                => return None
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Iterator over the keys of the neighbors of a Vertex.
+pub struct NeighborIterator<'s, K, V> {
+    edges: Option<EdgeIterator<'s, K, V>>
+}
+
+impl<'s,
+     K: Ord + Eq + Clone,
+     V: PartialEq + Clone>
+     Iterator<&'s K> for NeighborIterator<'s, K, V> {
+    #[inline]
+    fn next(&mut self) -> Option<&'s K> {
+        match self.edges {
+            Some(ref mut edges) => edges.next().map(|(to_key, _)| to_key),
+            None                => None
+        }
+    }
+}
+
+/// Iterator over the Vertex structures of a Graph.
+pub struct VertexRefIterator<'s, K, L, V> {
+    vertices: Entries<'s, K, Box<Vertex<K, L, V>>>,
+}
+
+impl<'s, K, L, V> Iterator<&'s Vertex<K, L, V>> for VertexRefIterator<'s, K, L, V> {
+    #[inline]
+    fn next(&mut self) -> Option<&'s Vertex<K, L, V>> {
+        self.vertices.next().map(|(_, vertex)| &**vertex)
+    }
+}
+
+/// Iterator over the self loops of a Graph.
+pub struct SelfLoopIterator<'s, K, L, V> {
+    vertices: Entries<'s, K, Box<Vertex<K, L, V>>>
+}
+
+impl<'s,
+     K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
+     Iterator<(&'s K, Option<&'s V>)> for SelfLoopIterator<'s, K, L, V> {
+    fn next(&mut self) -> Option<(&'s K, Option<&'s V>)> {
+        for (key, vertex) in self.vertices.by_ref() {
+            match vertex.edges.find(key) {
+                Some(i) => return Some((key, vertex.edges.get(i).value.as_ref())),
+                None    => {}
+            }
         }
+        None
     }
+}
+
+impl<K: Ord + Eq + Clone + Hash,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
+     Graph<K, L, V> {
 
     /**
-    * Remove an Edge.
+    * Create a new Graph from an adjacency map.
     *
-    * The both Vertex should exist.
+    * The Vertices are created from the keys of the map and of the Edges,
+    * duplicated Edges are ignored, only the first one is kept.
     *
     * # Arguments
-    * * from_key - The first Key of the Edge
-    * * to_key - The second Key of the Edge
+    * * map - The (to_key, value) of the Edges starting from each Vertex
     *
     * # Return
-    * true if the edge is successfully removed, false otherwise
+    * A new graph with the Vertices sorted by key.
     */
-    pub fn remove_edge(&mut self,
-                       from_key: K,
-                       to_key: K)
-                       -> bool {
-        if self.vertex_exist(&from_key) &&
-           self.vertex_exist(&to_key) {
-            GraphUtils::get_vertex_mut(&mut self.vertices,
-                                       from_key).unwrap().remove_edge(to_key)
-        } else {
-            false
+    pub fn from_adjacency_map(map: HashMap<K, Vec<(K, Option<V>)>>) -> Graph<K, L, V> {
+        let mut keys: Vec<K> = Vec::with_capacity(map.len());
+        for (from, edges) in map.iter() {
+            keys.push(from.clone());
+            for &(ref to, _) in edges.iter() {
+                keys.push(to.clone());
+            }
         }
+        keys.sort();
+        keys.dedup();
+
+        let mut map = map;
+        let vertices = keys.move_iter().map(|key| {
+            let mut vertex = Vertex::new(key);
+            match map.pop(&vertex.key) {
+                Some(edges) => {
+                    for (to, value) in edges.move_iter() {
+                        vertex.edges.insert_sorted(Edge::new_with_opt(to, value));
+                    }
+                },
+                None        => {}
+            }
+            vertex
+        }).collect();
+        Graph::from_vertex_list(vertices)
     }
 
     /**
-    * Remove a Vertex.
-    *
-    * The Vertex should exist.
-    *
-    * # Arguments
-    * * vertex_key - The first Key of the Edge
+    * Get the adjacency map of the Graph.
     *
     * # Return
-    * true if the Vertex is successfully removed, false otherwise
+    * The (to_key, value) of the Edges starting from each Vertex, sorted by
+    * to_key, every Vertex is in the map, the inverse of from_adjacency_map.
     */
-    pub fn remove_vertex(&mut self,
-                         vertex_key: K)
-                         -> bool {
-        if self.vertex_exist(&vertex_key) {
-            GraphUtils::remove_vertex(&mut self.vertices, vertex_key.clone());
-            GraphUtils::remove_edge_to(&mut self.vertices, vertex_key);
-            true
-        } else {
-            false
+    pub fn to_adjacency_map(&self) -> HashMap<K, Vec<(K, Option<V>)>> {
+        let mut map = HashMap::with_capacity(self.len());
+        for vertex in self.vertices_ref_iter() {
+            let edges = vertex.edges_iter().map(|(to, value)| {
+                (to.clone(), value.map(|v| v.clone()))
+            }).collect();
+            map.insert(vertex.key.clone(), edges);
         }
+        map
     }
 }
 
-impl<K, L, V> Collection for Graph<K, L, V> {
+impl<K: Ord, L, V> Collection for Graph<K, L, V> {
     fn len(&self) -> uint {
-        self.len
+        self.vertices.len()
     }
 
     fn is_empty(&self) -> bool {
-        self.len == 0
+        self.vertices.is_empty()
     }
 }
 
-impl<K: ToString + Ord + Eq + Clone,
-     L: ToString + Ord + Eq + Clone,
-     V: ToString + Ord + Eq + Clone>
+impl<K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
      Mutable for Graph<K, L, V> {
     /// Clear the Graph, removing all Vertices and edges
     fn clear(&mut self) {
-        self.vertices = None;
-        self.len = 0;
+        self.vertices.clear();
+        self.observers.notify(|o| o.on_clear());
     }
 }
 
-mod GraphUtils {
-    use super::{Vertex};
+/*
+* A Graph is encoded as a flat structure independent of its storage:
+*
+* * version - FORMAT_VERSION
+* * directed - Is the Graph directed
+* * vertices - The sequence of (key, label) of the Vertices
+* * edges - The sequence of (from_key, to_key, value) of the Edges
+* * self_loops - Does the Graph allow the self loops
+*
+* The older versions are still decoded.
+*/
+impl<E,
+     S: Encoder<E>,
+     K: Encodable<S, E>,
+     L: Encodable<S, E>,
+     V: Encodable<S, E>>
+     Encodable<S, E> for Graph<K, L, V> {
+    fn encode(&self, s: &mut S) -> Result<(), E> {
+        let vertices: Vec<(&K, &Option<L>)> = self.vertices_ref_iter().map(|vertex| {
+            (&vertex.key, &vertex.label)
+        }).collect();
+        let mut edges: Vec<(&K, &K, &Option<V>)> = Vec::new();
+        for vertex in self.vertices_ref_iter() {
+            for i in range(0, vertex.edges.len()) {
+                let edge = vertex.edges.get(i);
+                edges.push((&vertex.key, &edge.to_key, &edge.value));
+            }
+        }
+        s.emit_struct("Graph", 5, |s| {
+            try!(s.emit_struct_field("version", 0, |s| FORMAT_VERSION.encode(s)));
+            try!(s.emit_struct_field("directed", 1, |s| self.directed.encode(s)));
+            try!(s.emit_struct_field("vertices", 2, |s| vertices.encode(s)));
+            try!(s.emit_struct_field("edges", 3, |s| edges.encode(s)));
+            s.emit_struct_field("self_loops", 4, |s| self.self_loops.encode(s))
+        })
+    }
+}
 
-    pub fn remove_vertex<K: Eq, L, V>(vertex: &mut Option<Box<Vertex<K, L, V>>>,
-                                      key: K) -> () {
-        match *vertex {
-            Some(ref mut v) => {
-                if v.next.get_ref().key == key {
-                    if v.next.get_ref().next.is_some() {
-                        v.next = Some(v.next.take_unwrap().next.take_unwrap());
-                    } else {
-                        v.next = None;
+impl<E,
+     D: Decoder<E>,
+     K: Decodable<D, E> + Ord + Eq + Clone,
+     L: Decodable<D, E> + Eq + Clone,
+     V: Decodable<D, E> + PartialEq + Clone>
+     Decodable<D, E> for Graph<K, L, V> {
+    fn decode(d: &mut D) -> Result<Graph<K, L, V>, E> {
+        d.read_struct("Graph", 5, |d| {
+            let version: uint = try!(d.read_struct_field("version", 0, |d| Decodable::decode(d)));
+            if version == 0 || version > FORMAT_VERSION {
+                return Err(d.error(format!("unsupported Graph format version {}",
+                                           version).as_slice()))
+            }
+            let directed: bool = try!(d.read_struct_field("directed", 1, |d| Decodable::decode(d)));
+            let vertices: Vec<(K, Option<L>)> = try!(d.read_struct_field("vertices", 2, |d| {
+                Decodable::decode(d)
+            }));
+            let edges: Vec<(K, K, Option<V>)> = try!(d.read_struct_field("edges", 3, |d| {
+                Decodable::decode(d)
+            }));
+            let self_loops: bool = if version >= 2 {
+                try!(d.read_struct_field("self_loops", 4, |d| Decodable::decode(d)))
+            } else {
+                true
+            };
+
+            let mut index: TreeMap<K, uint> = TreeMap::new();
+            let mut list = Vec::with_capacity(vertices.len());
+            for (key, label) in vertices.move_iter() {
+                if !index.insert(key.clone(), list.len()) {
+                    return Err(d.error("duplicated Vertex key"))
+                }
+                list.push(Vertex::new_with_opt(key, label));
+            }
+            for (from, to, value) in edges.move_iter() {
+                if !self_loops && from == to {
+                    return Err(d.error("self loop in a Graph forbidding them"))
+                }
+                let i = match index.find(&from) {
+                    Some(&i) => i,
+                    None     => return Err(d.error("Edge from an unknown Vertex"))
+                };
+                if !index.contains_key(&to) {
+                    return Err(d.error("Edge to an unknown Vertex"))
+                }
+                list.get_mut(i).edges.insert_sorted(Edge::new_with_opt(to, value));
+            }
+            // an undirected Graph stores each Edge in both directions
+            if !directed {
+                for vertex in list.iter() {
+                    for (to_key, _) in vertex.edges_iter() {
+                        let j = *index.find(to_key).unwrap();
+                        if !list.get(j).edge_exist(&vertex.key) {
+                            return Err(d.error("Edge without its reverse in an undirected Graph"))
+                        }
                     }
-
-                } else {
-                    remove_vertex(&mut v.next, key)
                 }
-            },
-            None => {}
-        }
+            }
+            let mut graph = Graph::from_vertex_list(list);
+            graph.directed = directed;
+            graph.self_loops = self_loops;
+            Ok(graph)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serialize::Decodable;
+    use serialize::json;
+
+    use super::{Graph, GraphConfig};
+
+    fn decode(text: &str) -> Result<Graph<uint, String, int>, json::DecoderError> {
+        let mut decoder = json::Decoder::new(json::from_str(text).unwrap());
+        Decodable::decode(&mut decoder)
     }
 
-    pub fn remove_edge_to<K: ToString + Ord + Eq + Clone,
-                          L: ToString + Ord + Eq + Clone,
-                          V: ToString + Ord + Eq + Clone>
-                          (vertex: &mut Option<Box<Vertex<K, L, V>>>, key: K) -> () {
-        match *vertex {
-            Some(ref mut v) => {
-                if v.edge_exist(&key) {
-                    v.remove_edge(key.clone());
-                }
-                remove_edge_to(&mut v.next, key);
-            },
-            None => {}
+    fn round_trip(graph: &Graph<uint, String, int>) -> Graph<uint, String, int> {
+        decode(json::Encoder::str_encode(graph).as_slice()).unwrap()
+    }
+
+    #[test]
+    fn round_trip_directed() {
+        let mut graph = Graph::new();
+        for v in range(0u, 4) {
+            graph.add_vertex(v);
         }
+        graph.add_edge(0, 1);
+        graph.add_edge(1, 2);
+        graph.add_edge(2, 0);
+        graph.add_edge(0, 3);
+        let decoded = round_trip(&graph);
+        assert!(decoded == graph);
+        assert!(decoded.is_directed());
+        assert!(!decoded.edge_exist(1, 0));
+        assert!(decoded.validate().is_ok());
     }
 
-    pub fn get_vertex_mut<'r, K: Eq, L, V>(vertex: &'r mut Option<Box<Vertex<K, L, V>>>,
-                                           key: K) -> Option<&'r mut Box<Vertex<K, L, V>>> {
-        match *vertex {
-            Some(ref mut v) => {
-                if v.key == key {
-                    Some(v)
-                } else {
-                    get_vertex_mut(&mut v.next, key)
-                }
-            },
-            None => None
+    #[test]
+    fn round_trip_undirected() {
+        let mut graph = Graph::new_undirected();
+        for v in range(0u, 3) {
+            graph.add_vertex(v);
         }
+        graph.add_edge(0, 1);
+        graph.add_edge(1, 2);
+        let decoded = round_trip(&graph);
+        assert!(decoded == graph);
+        assert!(!decoded.is_directed());
+        assert!(decoded.edge_exist(1, 0));
+        assert!(decoded.validate().is_ok());
     }
 
-    pub fn get_vertex_imm<'r, K: Eq, L, V>(vertex: &'r Option<Box<Vertex<K, L, V>>>,
-                                           key: &K) -> Option<&'r Box<Vertex<K, L, V>>> {
-        match *vertex {
-            Some(ref v) => {
-                if v.key == *key {
-                    vertex.as_ref()
-                } else {
-                    get_vertex_imm(&v.next, key)
-                }
-            },
-            None => None
+    #[test]
+    fn self_loop_degree() {
+        let mut directed: Graph<uint, String, int> = Graph::new();
+        let mut undirected: Graph<uint, String, int> = Graph::new_undirected();
+        for graph in [&mut directed, &mut undirected].mut_iter() {
+            graph.add_vertex(0u);
+            graph.add_vertex(1u);
+            graph.add_edge(0, 0);
+            graph.add_edge(0, 1);
+        }
+        for graph in [&directed, &undirected].iter() {
+            let degrees: Vec<(&uint, uint)> = graph.vertices_by_degree().collect();
+            assert_eq!(degrees, vec![(&1, 1), (&0, 3)]);
         }
     }
 
-    pub fn update_vertex_label<K: Eq, L, V>(vertex: &mut Option<Box<Vertex<K, L, V>>>,
-                                            label: Option<L>,
-                                            key: K) -> () {
-        match *vertex {
-            Some(ref mut v) => {
-                if v.key == key {
-                    v.label = label;
-                } else {
-                    update_vertex_label(&mut v.next, label, key)
-                }
-            },
-            None => {}
+    #[test]
+    fn round_trip_self_loops() {
+        let mut graph = Graph::new();
+        graph.add_vertex(0u);
+        graph.add_vertex(1u);
+        graph.add_edge(0, 0);
+        graph.add_edge(0, 1);
+        let decoded = round_trip(&graph);
+        assert!(decoded == graph);
+        assert!(decoded.edge_exist(0, 0));
+
+        let mut forbidding = Graph::new_with_config(GraphConfig::new().allow_self_loops(false));
+        forbidding.add_vertex(0u);
+        forbidding.add_vertex(1u);
+        forbidding.add_edge(0, 1);
+        let decoded = round_trip(&forbidding);
+        assert!(!decoded.config().allows_self_loops());
+        assert!(decoded == forbidding);
+    }
+
+    #[test]
+    fn round_trip_labels_and_values() {
+        let mut graph = Graph::new();
+        graph.add_vertex_l(0u, "zero".to_string());
+        graph.add_vertex_l(1u, "one".to_string());
+        graph.add_vertex(2u);
+        graph.add_edge_v(0, 1, -4i);
+        graph.add_edge_v(1, 2, 7i);
+        graph.add_edge(2, 0);
+        let decoded = round_trip(&graph);
+        assert!(decoded == graph);
+        assert_eq!(decoded.get_vertex_label(0), Some(&"zero".to_string()));
+        assert_eq!(decoded.get_vertex_label(2), None);
+        assert_eq!(decoded.get_edge_value(0, 1), Some(&-4));
+        assert_eq!(decoded.get_edge_value(2, 0), None);
+    }
+
+    #[test]
+    fn decode_rejects_version() {
+        assert!(decode("{\"version\":99,\"directed\":true,\"vertices\":[],\"edges\":[],\
+                         \"self_loops\":true}").is_err());
+        assert!(decode("{\"version\":0,\"directed\":true,\"vertices\":[],\"edges\":[],\
+                         \"self_loops\":true}").is_err());
+    }
+
+    #[test]
+    fn decode_rejects_inconsistent_edges() {
+        // an Edge to a missing Vertex
+        assert!(decode("{\"version\":2,\"directed\":true,\"vertices\":[[0,null]],\
+                         \"edges\":[[0,1,null]],\"self_loops\":true}").is_err());
+        // an undirected Edge stored in one direction
+        assert!(decode("{\"version\":2,\"directed\":false,\"vertices\":[[0,null],[1,null]],\
+                         \"edges\":[[0,1,null]],\"self_loops\":true}").is_err());
+        // a self loop in a Graph forbidding them
+        assert!(decode("{\"version\":2,\"directed\":true,\"vertices\":[[0,null]],\
+                         \"edges\":[[0,0,null]],\"self_loops\":false}").is_err());
+    }
+
+    // enough Vertices to overflow the stack with a recursive walk
+    static LARGE: uint = 100000;
+
+    #[test]
+    fn large_path() {
+        let mut graph: Graph<uint, String, int> = Graph::new();
+        for v in range(0, LARGE) {
+            assert!(graph.add_vertex(v));
+        }
+        for v in range(1, LARGE) {
+            assert!(graph.add_edge(v - 1, v));
         }
+        for v in range(0, LARGE) {
+            assert!(graph.get_vertex(v).is_some());
+        }
+        let copy = graph.clone();
+        assert!(round_trip(&graph) == graph);
+        drop(graph);
+
+        // each removal walks the whole Graph
+        let mut graph = copy;
+        for v in range(0, LARGE).filter(|v| v % 1000 == 500) {
+            assert!(graph.remove_vertex(v));
+        }
+        assert_eq!(graph.len(), LARGE - LARGE / 1000);
+        assert!(graph.get_vertex(500).is_none());
+        assert!(!graph.edge_exist(499, 500));
+        assert!(graph.edge_exist(501, 502));
     }
 
-    pub fn add_vertex<K: ToString + Ord + Eq + Clone,
-                      L: ToString + Ord + Eq + Clone,
-                      V: ToString + Ord + Eq + Clone>
-                      (vertex: &mut Option<Box<Vertex<K, L, V>>>,
-                      new_vertex: Box<Vertex<K, L, V>>) -> () {
-        match *vertex {
-            Some(ref mut v) => add_vertex(&mut v.next, new_vertex),
-            None            => *vertex = Some(new_vertex)
+    #[test]
+    fn large_star() {
+        let mut graph: Graph<uint, String, int> = Graph::new();
+        for v in range(0, LARGE) {
+            graph.add_vertex(v);
         }
+        for v in range(1, LARGE) {
+            assert!(graph.add_edge_v(0, v, v as int));
+        }
+        assert_eq!(graph.get_vertex(0).unwrap().edges_iter().count(), LARGE - 1);
+        assert_eq!(graph.get_edge_value(0, LARGE - 1), Some(&((LARGE - 1) as int)));
+        let copy = graph.clone();
+        assert!(round_trip(&graph) == graph);
+        drop(graph);
+
+        let mut graph = copy;
+        assert!(graph.remove_vertex(0));
+        assert_eq!(graph.edge_count(), 0);
+        assert!(graph.get_vertex(LARGE - 1).is_some());
     }
 }
-
@@ -0,0 +1,600 @@
+//! Graph addressed by stable integer handles instead of keys.
+//!
+//! The handles are given by IdGraph only, Graph and the algorithms keep
+//! addressing the Vertices by key. An IdGraph is built from a Graph with
+//! from_graph and converted back with to_graph to run an algorithm.
+
+use std::collections::TreeMap;
+use std::slice::Items;
+
+use graph::Graph;
+
+/// Handle of a Vertex of an IdGraph, valid until the Vertex is removed.
+#[deriving(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Show)]
+pub struct VertexId(uint);
+
+/// Handle of an Edge of an IdGraph, valid until the Edge is removed.
+#[deriving(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Show)]
+pub struct EdgeId(uint);
+
+impl VertexId {
+    /// The position of the Vertex in the storage, below the number of
+    /// Vertices ever added.
+    pub fn index(&self) -> uint {
+        let VertexId(i) = *self;
+        i
+    }
+}
+
+impl EdgeId {
+    /// The position of the Edge in the storage, below the number of Edges
+    /// ever added.
+    pub fn index(&self) -> uint {
+        let EdgeId(i) = *self;
+        i
+    }
+}
+
+#[deriving(Clone)]
+struct VertexSlot<K, L> {
+    key:               K,
+    label:             Option<L>,
+    out_edges:         Vec<EdgeId>,
+    in_edges:          Vec<EdgeId>
+}
+
+#[deriving(Clone)]
+struct EdgeSlot<V> {
+    from:              VertexId,
+    to:                VertexId,
+    value:             Option<V>
+}
+
+/**
+* Graph whose Vertices and Edges are addressed by handles.
+*
+* The Vertices and the Edges are stored in vectors and a handle is their
+* position, so reaching a Vertex, its label, its Edges or an Edge from a
+* handle is constant time without cloning a key. The handles are never
+* reused: removing a Vertex or an Edge leaves an empty slot and the other
+* handles stay valid. A map translates the keys to the handles.
+*
+* An IdGraph is directed unless created by new_undirected. An undirected
+* IdGraph stores each Edge in both directions as Graph does, the two halves
+* have their own handles and are added, changed and removed together.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+* * L - The Vertex's Label type
+* * V - The Edge's Value type
+*/
+#[deriving(Clone)]
+pub struct IdGraph<K, L, V> {
+    vertices:          Vec<Option<VertexSlot<K, L>>>,
+    edges:             Vec<Option<EdgeSlot<V>>>,
+    ids:               TreeMap<K, VertexId>,
+    edge_count:        uint,
+    directed:          bool
+}
+
+impl<K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
+     IdGraph<K, L, V> {
+
+    /**
+    * Create a new empty directed IdGraph.
+    *
+    * # Return
+    * A new empty IdGraph.
+    */
+    pub fn new() -> IdGraph<K, L, V> {
+        IdGraph {
+            vertices:   Vec::new(),
+            edges:      Vec::new(),
+            ids:        TreeMap::new(),
+            edge_count: 0,
+            directed:   true
+        }
+    }
+
+    /**
+    * Create a new empty undirected IdGraph.
+    *
+    * # Return
+    * A new empty IdGraph.
+    */
+    pub fn new_undirected() -> IdGraph<K, L, V> {
+        let mut id_graph = IdGraph::new();
+        id_graph.directed = false;
+        id_graph
+    }
+
+    /**
+    * Create a new IdGraph with the Vertices and the Edges of a Graph.
+    *
+    * The Vertices get their handles in ascending key order and the Edges
+    * in the order of edges_iter. The IdGraph is undirected if the Graph is.
+    *
+    * # Arguments
+    * * graph - The graph to copy
+    *
+    * # Return
+    * A new IdGraph with a copy of the labels and the values of the Graph.
+    */
+    pub fn from_graph(graph: &Graph<K, L, V>) -> IdGraph<K, L, V> {
+        let mut id_graph = if graph.is_directed() {
+            IdGraph::new()
+        } else {
+            IdGraph::new_undirected()
+        };
+        for (key, label) in graph.vertices_iter() {
+            id_graph.add_vertex_opt_l(key.clone(), label.map(|l| l.clone()));
+        }
+        for vertex in graph.vertices_ref_iter() {
+            let from = id_graph.vertex_id(vertex.get_key()).unwrap();
+            for (to_key, value) in vertex.edges_iter() {
+                // the opposite half of an undirected Edge is added with it
+                if !graph.is_directed() && *to_key < *vertex.get_key() {
+                    continue
+                }
+                let to = id_graph.vertex_id(to_key).unwrap();
+                id_graph.add_edge_opt_v(from, to, value.map(|v| v.clone()));
+            }
+        }
+        id_graph
+    }
+
+    /**
+    * Create a Graph with the Vertices and the Edges of the IdGraph.
+    *
+    * # Return
+    * A new graph with a copy of the labels and the values, undirected if
+    * the IdGraph is.
+    */
+    pub fn to_graph(&self) -> Graph<K, L, V> {
+        let mut graph = if self.directed { Graph::new() } else { Graph::new_undirected() };
+        for slot in self.vertices.iter().filter_map(|slot| slot.as_ref()) {
+            graph.add_vertex_opt_l(slot.key.clone(), slot.label.clone());
+        }
+        for edge in self.edges.iter().filter_map(|edge| edge.as_ref()) {
+            // the Graph adds the opposite half of an undirected Edge
+            if !self.directed && edge.to < edge.from {
+                continue
+            }
+            graph.add_edge_opt_v(self.vertex(edge.from).key.clone(),
+                                 self.vertex(edge.to).key.clone(),
+                                 edge.value.clone());
+        }
+        graph
+    }
+
+    /**
+    * Get the number of Vertices.
+    */
+    pub fn vertex_count(&self) -> uint {
+        self.ids.len()
+    }
+
+    /**
+    * Get the number of Edges, counted once in an undirected IdGraph.
+    */
+    pub fn edge_count(&self) -> uint {
+        self.edge_count
+    }
+
+    /**
+    * Are the Edges of the IdGraph directed or not.
+    */
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    /**
+    * Add a Vertex with an optional label.
+    *
+    * # Arguments
+    * * key - The key of the Vertex
+    * * label - The optional label of the Vertex
+    *
+    * # Return
+    * Some(id) of the new Vertex, None if a Vertex already has the key.
+    */
+    pub fn add_vertex_opt_l(&mut self,
+                            key: K,
+                            label: Option<L>)
+                            -> Option<VertexId> {
+        if self.ids.contains_key(&key) {
+            return None
+        }
+        let id = VertexId(self.vertices.len());
+        self.ids.insert(key.clone(), id);
+        self.vertices.push(Some(VertexSlot {
+            key:        key,
+            label:      label,
+            out_edges:  Vec::new(),
+            in_edges:   Vec::new()
+        }));
+        Some(id)
+    }
+
+    /**
+    * Add a Vertex with a label.
+    *
+    * # Return
+    * Some(id) of the new Vertex, None if a Vertex already has the key.
+    */
+    pub fn add_vertex_l(&mut self, key: K, label: L) -> Option<VertexId> {
+        self.add_vertex_opt_l(key, Some(label))
+    }
+
+    /**
+    * Add a Vertex.
+    *
+    * # Return
+    * Some(id) of the new Vertex, None if a Vertex already has the key.
+    */
+    pub fn add_vertex(&mut self, key: K) -> Option<VertexId> {
+        self.add_vertex_opt_l(key, None)
+    }
+
+    /**
+    * Remove a Vertex and its Edges.
+    *
+    * # Return
+    * true if the Vertex is removed, false if it does not exist.
+    */
+    pub fn remove_vertex(&mut self, id: VertexId) -> bool {
+        if !self.contains_vertex(id) {
+            return false
+        }
+        let mut edges = self.vertex(id).out_edges.clone();
+        edges.push_all(self.vertex(id).in_edges.as_slice());
+        for &edge in edges.iter() {
+            self.remove_edge(edge);
+        }
+        let slot = self.vertices.get_mut(id.index()).take().unwrap();
+        self.ids.remove(&slot.key);
+        true
+    }
+
+    /**
+    * Get the handle of a Vertex.
+    *
+    * # Arguments
+    * * key - The key of the Vertex
+    *
+    * # Return
+    * Some(id) if the Vertex exist, None otherwise.
+    */
+    pub fn vertex_id(&self, key: &K) -> Option<VertexId> {
+        self.ids.find(key).map(|&id| id)
+    }
+
+    /**
+    * Check if a handle is the one of a Vertex of the IdGraph.
+    */
+    pub fn contains_vertex(&self, id: VertexId) -> bool {
+        id.index() < self.vertices.len() && self.vertices.get(id.index()).is_some()
+    }
+
+    /**
+    * Get the key of a Vertex.
+    *
+    * # Return
+    * Some(key) if the Vertex exist, None otherwise.
+    */
+    pub fn key<'r>(&'r self, id: VertexId) -> Option<&'r K> {
+        self.find_vertex(id).map(|slot| &slot.key)
+    }
+
+    /**
+    * Get the label of a Vertex.
+    *
+    * # Return
+    * Some(label) if the Vertex exist and has a label, None otherwise.
+    */
+    pub fn label<'r>(&'r self, id: VertexId) -> Option<&'r L> {
+        self.find_vertex(id).and_then(|slot| slot.label.as_ref())
+    }
+
+    /**
+    * Set or remove the label of a Vertex.
+    *
+    * # Return
+    * true if the label is set, false if the Vertex does not exist.
+    */
+    pub fn set_label_opt(&mut self, id: VertexId, label: Option<L>) -> bool {
+        if !self.contains_vertex(id) {
+            return false
+        }
+        self.vertices.get_mut(id.index()).get_mut_ref().label = label;
+        true
+    }
+
+    /**
+    * Add an Edge with an optional value.
+    *
+    * # Arguments
+    * * from - The first Vertex of the Edge
+    * * to - The second Vertex of the Edge
+    * * value - The optional value of the Edge
+    *
+    * # Return
+    * Some(id) of the new Edge, None if a Vertex does not exist or the Edge
+    * already exist. In an undirected IdGraph the opposite half gets its own
+    * handle.
+    */
+    pub fn add_edge_opt_v(&mut self,
+                          from: VertexId,
+                          to: VertexId,
+                          value: Option<V>)
+                          -> Option<EdgeId> {
+        if !self.contains_vertex(from) || !self.contains_vertex(to) ||
+           self.find_edge(from, to).is_some() {
+            return None
+        }
+        if !self.directed && from != to {
+            self.attach_edge(to, from, value.clone());
+        }
+        let id = self.attach_edge(from, to, value);
+        self.edge_count += 1;
+        Some(id)
+    }
+
+    /**
+    * Add an Edge with a value.
+    *
+    * # Return
+    * Some(id) of the new Edge, None if a Vertex does not exist or the Edge
+    * already exist.
+    */
+    pub fn add_edge_v(&mut self, from: VertexId, to: VertexId, value: V) -> Option<EdgeId> {
+        self.add_edge_opt_v(from, to, Some(value))
+    }
+
+    /**
+    * Add an Edge.
+    *
+    * # Return
+    * Some(id) of the new Edge, None if a Vertex does not exist or the Edge
+    * already exist.
+    */
+    pub fn add_edge(&mut self, from: VertexId, to: VertexId) -> Option<EdgeId> {
+        self.add_edge_opt_v(from, to, None)
+    }
+
+    /**
+    * Remove an Edge, with its opposite half in an undirected IdGraph.
+    *
+    * # Return
+    * true if the Edge is removed, false if it does not exist.
+    */
+    pub fn remove_edge(&mut self, id: EdgeId) -> bool {
+        let edge = match self.detach_edge(id) {
+            Some(edge) => edge,
+            None       => return false
+        };
+        if !self.directed && edge.from != edge.to {
+            let opposite = self.find_edge(edge.to, edge.from).unwrap();
+            self.detach_edge(opposite);
+        }
+        self.edge_count -= 1;
+        true
+    }
+
+    /**
+    * Find the Edge between two Vertices, in the degree of the first one.
+    *
+    * # Return
+    * Some(id) of the Edge from from to to, None if there is none.
+    */
+    pub fn find_edge(&self, from: VertexId, to: VertexId) -> Option<EdgeId> {
+        match self.find_vertex(from) {
+            Some(slot) => slot.out_edges.iter().find(|&&e| self.edge(e).to == to).map(|&e| e),
+            None       => None
+        }
+    }
+
+    /**
+    * Get the Vertices of an Edge.
+    *
+    * # Return
+    * Some((from, to)) if the Edge exist, None otherwise.
+    */
+    pub fn endpoints(&self, id: EdgeId) -> Option<(VertexId, VertexId)> {
+        self.find_edge_slot(id).map(|edge| (edge.from, edge.to))
+    }
+
+    /**
+    * Get the value of an Edge.
+    *
+    * # Return
+    * Some(value) if the Edge exist and has a value, None otherwise.
+    */
+    pub fn edge_value<'r>(&'r self, id: EdgeId) -> Option<&'r V> {
+        self.find_edge_slot(id).and_then(|edge| edge.value.as_ref())
+    }
+
+    /**
+    * Set or remove the value of an Edge, and of its opposite half in an
+    * undirected IdGraph.
+    *
+    * # Return
+    * true if the value is set, false if the Edge does not exist.
+    */
+    pub fn set_edge_value_opt(&mut self, id: EdgeId, value: Option<V>) -> bool {
+        let (from, to) = match self.endpoints(id) {
+            Some(endpoints) => endpoints,
+            None            => return false
+        };
+        if !self.directed && from != to {
+            let opposite = self.find_edge(to, from).unwrap();
+            self.edges.get_mut(opposite.index()).get_mut_ref().value = value.clone();
+        }
+        self.edges.get_mut(id.index()).get_mut_ref().value = value;
+        true
+    }
+
+    /**
+    * Get the Edges starting from a Vertex, in the order they were added.
+    *
+    * # Return
+    * The handles of the Edges, empty if the Vertex does not exist.
+    */
+    pub fn out_edges<'r>(&'r self, id: VertexId) -> &'r [EdgeId] {
+        match self.find_vertex(id) {
+            Some(slot) => slot.out_edges.as_slice(),
+            None       => &[]
+        }
+    }
+
+    /**
+    * Get the Edges ending at a Vertex, in the order they were added.
+    *
+    * # Return
+    * The handles of the Edges, empty if the Vertex does not exist.
+    */
+    pub fn in_edges<'r>(&'r self, id: VertexId) -> &'r [EdgeId] {
+        match self.find_vertex(id) {
+            Some(slot) => slot.in_edges.as_slice(),
+            None       => &[]
+        }
+    }
+
+    /**
+    * Iterate over the second Vertices of the Edges starting from a Vertex.
+    *
+    * # Return
+    * An iterator over the handles of the neighbors, empty if the Vertex
+    * does not exist.
+    */
+    pub fn neighbors<'r>(&'r self, id: VertexId) -> IdNeighborIterator<'r, V> {
+        IdNeighborIterator {
+            out_edges:  self.out_edges(id).iter(),
+            edges:      &self.edges
+        }
+    }
+
+    /**
+    * Get the handles of the Vertices, in the order they were added.
+    */
+    pub fn vertex_ids(&self) -> Vec<VertexId> {
+        range(0, self.vertices.len()).map(|i| VertexId(i)).filter(|&id| self.contains_vertex(id)).collect()
+    }
+
+    /**
+    * Get the handles of the Edges, in the order they were added.
+    */
+    pub fn edge_ids(&self) -> Vec<EdgeId> {
+        range(0, self.edges.len()).map(|i| EdgeId(i)).filter(|&id| self.find_edge_slot(id).is_some()).collect()
+    }
+
+    fn find_vertex<'r>(&'r self, id: VertexId) -> Option<&'r VertexSlot<K, L>> {
+        if id.index() < self.vertices.len() {
+            self.vertices.get(id.index()).as_ref()
+        } else {
+            None
+        }
+    }
+
+    fn find_edge_slot<'r>(&'r self, id: EdgeId) -> Option<&'r EdgeSlot<V>> {
+        if id.index() < self.edges.len() {
+            self.edges.get(id.index()).as_ref()
+        } else {
+            None
+        }
+    }
+
+    // store an Edge between two valid Vertices
+    fn attach_edge(&mut self, from: VertexId, to: VertexId, value: Option<V>) -> EdgeId {
+        let id = EdgeId(self.edges.len());
+        self.edges.push(Some(EdgeSlot {
+            from:   from,
+            to:     to,
+            value:  value
+        }));
+        self.vertices.get_mut(from.index()).get_mut_ref().out_edges.push(id);
+        self.vertices.get_mut(to.index()).get_mut_ref().in_edges.push(id);
+        id
+    }
+
+    // remove an Edge from the storage and from the lists of its Vertices
+    fn detach_edge(&mut self, id: EdgeId) -> Option<EdgeSlot<V>> {
+        if id.index() >= self.edges.len() {
+            return None
+        }
+        let edge = match self.edges.get_mut(id.index()).take() {
+            Some(edge) => edge,
+            None       => return None
+        };
+        self.vertices.get_mut(edge.from.index()).get_mut_ref().out_edges.retain(|&e| e != id);
+        self.vertices.get_mut(edge.to.index()).get_mut_ref().in_edges.retain(|&e| e != id);
+        Some(edge)
+    }
+
+    // the handle is known to be valid
+    fn vertex<'r>(&'r self, id: VertexId) -> &'r VertexSlot<K, L> {
+        self.vertices.get(id.index()).get_ref()
+    }
+
+    fn edge<'r>(&'r self, id: EdgeId) -> &'r EdgeSlot<V> {
+        self.edges.get(id.index()).get_ref()
+    }
+}
+
+/// Iterator over the handles of the neighbors of a Vertex of an IdGraph.
+pub struct IdNeighborIterator<'s, V> {
+    out_edges: Items<'s, EdgeId>,
+    edges:     &'s Vec<Option<EdgeSlot<V>>>
+}
+
+impl<'s, V> Iterator<VertexId> for IdNeighborIterator<'s, V> {
+    #[inline]
+    fn next(&mut self) -> Option<VertexId> {
+        let edges = self.edges;
+        self.out_edges.next().map(|e| edges.get(e.index()).get_ref().to)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use graph::Graph;
+    use super::IdGraph;
+
+    #[test]
+    fn round_trip_directed() {
+        let graph: Graph<uint, (), int> = Graph::from_edges(vec!((0, 1, Some(1)), (1, 2, None), (2, 2, None)));
+        let id_graph = IdGraph::from_graph(&graph);
+        assert!(id_graph.is_directed());
+        assert_eq!(id_graph.edge_count(), 3);
+        assert!(id_graph.to_graph() == graph);
+    }
+
+    #[test]
+    fn round_trip_undirected() {
+        let mut graph: Graph<uint, (), int> = Graph::new_undirected();
+        for v in range(0u, 3) {
+            graph.add_vertex(v);
+        }
+        graph.add_edge_v(0, 1, 1);
+        graph.add_edge(1, 2);
+        graph.add_edge(2, 2);
+        let mut id_graph = IdGraph::from_graph(&graph);
+        assert!(!id_graph.is_directed());
+        assert_eq!(id_graph.edge_count(), 3);
+        let back = id_graph.to_graph();
+        assert!(!back.is_directed());
+        assert!(back == graph);
+
+        // both halves are changed and removed together
+        let (a, b) = (id_graph.vertex_id(&0).unwrap(), id_graph.vertex_id(&1).unwrap());
+        let edge = id_graph.find_edge(b, a).unwrap();
+        id_graph.set_edge_value_opt(edge, Some(2));
+        assert_eq!(id_graph.edge_value(id_graph.find_edge(a, b).unwrap()), Some(&2));
+        id_graph.remove_edge(edge);
+        assert!(id_graph.find_edge(a, b).is_none());
+        assert_eq!(id_graph.edge_count(), 2);
+        assert!(!id_graph.to_graph().edge_exist(0, 1));
+    }
+}
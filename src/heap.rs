@@ -0,0 +1,54 @@
+//! Binary heap of items by increasing priority, for the algorithms on
+//! weighted Graphs.
+
+pub struct MinHeap<P, T> {
+    items:             Vec<(P, T)>
+}
+
+impl<P: PartialOrd, T> MinHeap<P, T> {
+    pub fn new() -> MinHeap<P, T> {
+        MinHeap { items: Vec::new() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn push(&mut self, priority: P, item: T) {
+        self.items.push((priority, item));
+        let mut i = self.items.len() - 1;
+        while i > 0 && self.less(i, (i - 1) / 2) {
+            self.items.as_mut_slice().swap(i, (i - 1) / 2);
+            i = (i - 1) / 2;
+        }
+    }
+
+    // the item of lowest priority
+    pub fn pop(&mut self) -> Option<(P, T)> {
+        let len = self.items.len();
+        if len == 0 {
+            return None
+        }
+        self.items.as_mut_slice().swap(0, len - 1);
+        let top = self.items.pop();
+        let len = len - 1;
+        let mut i = 0;
+        loop {
+            let mut smallest = i;
+            for child in range(2 * i + 1, 2 * i + 3) {
+                if child < len && self.less(child, smallest) {
+                    smallest = child;
+                }
+            }
+            if smallest == i {
+                return top
+            }
+            self.items.as_mut_slice().swap(i, smallest);
+            i = smallest;
+        }
+    }
+
+    fn less(&self, i: uint, j: uint) -> bool {
+        *self.items.get(i).ref0() < *self.items.get(j).ref0()
+    }
+}
@@ -0,0 +1,286 @@
+//! Dense integer indexing of a Graph, used by the algorithms.
+
+use std::collections::{Deque, RingBuf, TreeMap};
+
+use graph::Graph;
+use view::GraphView;
+use instrument::{Probe, NoProbe};
+
+/**
+* Snapshot of the structure of a Graph where each Vertex is identified by
+* its position in the keys vector.
+*
+* The adjacency lists are sorted by index.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+*/
+pub struct IndexedGraph<K> {
+    pub keys:              Vec<K>,
+    pub index:             TreeMap<K, uint>,
+    pub out_edges:         Vec<Vec<uint>>,
+    pub in_edges:          Vec<Vec<uint>>
+}
+
+impl<K: Ord + Eq + Clone> IndexedGraph<K> {
+
+    /**
+    * Index the Vertices and the Edges of a Graph.
+    *
+    * # Arguments
+    * * graph - The graph to index
+    *
+    * # Return
+    * A new IndexedGraph, the Vertices are numbered in the order of the
+    * Graph's vertices_iter.
+    */
+    pub fn from_graph<L: Eq + Clone,
+                      V: PartialEq + Clone>
+                      (graph: &Graph<K, L, V>) -> IndexedGraph<K> {
+        let mut keys = Vec::with_capacity(graph.len());
+        let mut index = TreeMap::new();
+        for vertex in graph.vertices_ref_iter() {
+            index.insert(vertex.get_key().clone(), keys.len());
+            keys.push(vertex.get_key().clone());
+        }
+
+        let mut out_edges = Vec::from_elem(keys.len(), Vec::new());
+        let mut in_edges = Vec::from_elem(keys.len(), Vec::new());
+        for (from, vertex) in graph.vertices_ref_iter().enumerate() {
+            for (to_key, _) in vertex.edges_iter() {
+                // Edges to unknown Vertices are skipped
+                match index.find(to_key) {
+                    Some(&to) => {
+                        out_edges.get_mut(from).push(to);
+                        in_edges.get_mut(to).push(from);
+                    },
+                    None      => {}
+                }
+            }
+        }
+        for edges in out_edges.mut_iter().chain(in_edges.mut_iter()) {
+            edges.sort();
+        }
+
+        IndexedGraph {
+            keys:       keys,
+            index:      index,
+            out_edges:  out_edges,
+            in_edges:   in_edges
+        }
+    }
+
+    /**
+    * Index the Vertices and the Edges of a view on a Graph.
+    *
+    * Algorithms running on a view index it with this instead of
+    * from_graph, which reads the storage of the Graph directly.
+    *
+    * # Arguments
+    * * view - The view to index
+    *
+    * # Return
+    * A new IndexedGraph, the Vertices are numbered in the order of the
+    * view's vertices.
+    */
+    pub fn from_view<'a, V, G: GraphView<'a, K, V>>(view: &G) -> IndexedGraph<K> {
+        let vertices = view.vertices();
+        let mut keys = Vec::with_capacity(vertices.len());
+        let mut index = TreeMap::new();
+        for key in vertices.iter() {
+            index.insert((*key).clone(), keys.len());
+            keys.push((*key).clone());
+        }
+
+        let mut out_edges = Vec::from_elem(keys.len(), Vec::new());
+        let mut in_edges = Vec::from_elem(keys.len(), Vec::new());
+        for (from, key) in vertices.iter().enumerate() {
+            for &(to_key, _) in view.edges_from(*key).iter() {
+                // Edges to unknown Vertices are skipped
+                match index.find(to_key) {
+                    Some(&to) => {
+                        out_edges.get_mut(from).push(to);
+                        in_edges.get_mut(to).push(from);
+                    },
+                    None      => {}
+                }
+            }
+        }
+        for edges in out_edges.mut_iter().chain(in_edges.mut_iter()) {
+            edges.sort();
+        }
+
+        IndexedGraph {
+            keys:       keys,
+            index:      index,
+            out_edges:  out_edges,
+            in_edges:   in_edges
+        }
+    }
+}
+
+impl<K> IndexedGraph<K> {
+
+    /**
+    * Get the number of Vertices.
+    */
+    pub fn len(&self) -> uint {
+        self.keys.len()
+    }
+
+    /**
+    * Get the distance from a Vertex to every Vertex by breadth first search.
+    *
+    * # Arguments
+    * * source - The index of the first Vertex
+    * * edges - The adjacency to follow, out_edges, in_edges or undirected
+    *
+    * # Return
+    * For each Vertex Some(number of Edges) if it is reachable, None
+    * otherwise.
+    */
+    pub fn distances(&self,
+                     source: uint,
+                     edges: &Vec<Vec<uint>>)
+                     -> Vec<Option<uint>> {
+        self.distances_probed(source, edges, &mut NoProbe)
+    }
+
+    /**
+    * Same as distances, notifying a Probe of the visits, the lookups of
+    * the neighbors and the insertions in the queue.
+    */
+    pub fn distances_probed<P: Probe>(&self,
+                                      source: uint,
+                                      edges: &Vec<Vec<uint>>,
+                                      probe: &mut P)
+                                      -> Vec<Option<uint>> {
+        let mut distances = Vec::from_elem(self.len(), None);
+        let mut queue = RingBuf::new();
+        *distances.get_mut(source) = Some(0u);
+        queue.push_back(source);
+        probe.insertion();
+        while !queue.is_empty() {
+            let v = queue.pop_front().unwrap();
+            probe.visit();
+            let next = distances.get(v).unwrap() + 1;
+            for &u in edges.get(v).iter() {
+                probe.lookup();
+                if distances.get(u).is_none() {
+                    *distances.get_mut(u) = Some(next);
+                    queue.push_back(u);
+                    probe.insertion();
+                }
+            }
+        }
+        distances
+    }
+
+    /**
+    * Get the Vertices reachable from a Vertex by breadth first search.
+    *
+    * # Arguments
+    * * key - The key of the first Vertex
+    * * edges - The adjacency to follow, out_edges, in_edges or undirected
+    *
+    * # Return
+    * The sorted keys of the reachable Vertices without the first one,
+    * empty if it does not exist.
+    */
+    pub fn reachable(&self, key: &K, edges: &Vec<Vec<uint>>) -> Vec<K> {
+        let source = match self.index.find(key) {
+            Some(&source) => source,
+            None          => return Vec::new()
+        };
+        let mut reachable: Vec<K> = self.distances(source, edges).iter().enumerate()
+            .filter(|&(v, d)| v != source && d.is_some())
+            .map(|(v, _)| self.keys.get(v).clone())
+            .collect();
+        reachable.sort();
+        reachable
+    }
+
+    /**
+    * Get the degree of each Vertex.
+    *
+    * The degree counts the Edges starting from the Vertex and the Edges
+    * ending to it, or its Edges once in an undirected Graph. A self loop
+    * adds 2 to the degree of its Vertex in both cases.
+    *
+    * # Arguments
+    * * directed - Are the Edges of the indexed Graph directed or not
+    *
+    * # Return
+    * The degree of each Vertex.
+    */
+    pub fn degrees(&self, directed: bool) -> Vec<uint> {
+        range(0, self.len()).map(|v| {
+            let out_edges = self.out_edges.get(v);
+            if directed {
+                out_edges.len() + self.in_edges.get(v).len()
+            } else if contains_sorted(out_edges.as_slice(), v) {
+                // an undirected Edge is stored in both directions, a self
+                // loop once
+                out_edges.len() + 1
+            } else {
+                out_edges.len()
+            }
+        }).collect()
+    }
+
+    /**
+    * Get the neighbors of each Vertex, ignoring the direction of the Edges.
+    *
+    * # Return
+    * For each Vertex the sorted vector of its neighbors, without duplicates.
+    */
+    pub fn undirected_edges(&self) -> Vec<Vec<uint>> {
+        range(0, self.len()).map(|v| {
+            let mut neighbors = self.out_edges.get(v).clone();
+            neighbors.push_all(self.in_edges.get(v).as_slice());
+            neighbors.sort();
+            neighbors.dedup();
+            neighbors
+        }).collect()
+    }
+}
+
+/**
+* Check if a sorted slice contains an element by binary search.
+*/
+pub fn contains_sorted(a: &[uint], element: uint) -> bool {
+    let mut low = 0;
+    let mut high = a.len();
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if a[mid] < element {
+            low = mid + 1;
+        } else if a[mid] > element {
+            high = mid;
+        } else {
+            return true
+        }
+    }
+    false
+}
+
+/**
+* Count the common elements of two sorted slices by merging them.
+*/
+pub fn intersection_len(a: &[uint], b: &[uint]) -> uint {
+    let mut i = 0;
+    let mut j = 0;
+    let mut common = 0;
+    while i < a.len() && j < b.len() {
+        if a[i] < b[j] {
+            i += 1;
+        } else if a[i] > b[j] {
+            j += 1;
+        } else {
+            common += 1;
+            i += 1;
+            j += 1;
+        }
+    }
+    common
+}
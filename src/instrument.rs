@@ -0,0 +1,140 @@
+//! Counting of the elementary operations done by the algorithms.
+//!
+//! The instrumented algorithms take a Probe notified of each operation.
+//! NoProbe does nothing and is what the plain versions of the algorithms
+//! pass, the calls are inlined away. Counter records every operation and
+//! the elapsed time:
+//!
+//! ```ignore
+//! let mut counter = Counter::new();
+//! let distribution = path_length_distribution_probed(&graph, &mut counter);
+//! println!("{}", counter.report());
+//! ```
+
+use time::precise_time_ns;
+
+/**
+* Receiver of the operations of an algorithm.
+*
+* Every method does nothing by default.
+*/
+pub trait Probe {
+
+    /**
+    * A Vertex or an Edge has been looked up.
+    */
+    #[inline]
+    fn lookup(&mut self) {}
+
+    /**
+    * An element has been inserted in a container of the algorithm.
+    */
+    #[inline]
+    fn insertion(&mut self) {}
+
+    /**
+    * The distance of a Vertex has been improved.
+    */
+    #[inline]
+    fn relaxation(&mut self) {}
+
+    /**
+    * The first element of a priority queue has been removed.
+    */
+    #[inline]
+    fn heap_pop(&mut self) {}
+
+    /**
+    * A Vertex has been visited.
+    */
+    #[inline]
+    fn visit(&mut self) {}
+}
+
+/// Probe ignoring every operation.
+pub struct NoProbe;
+
+impl Probe for NoProbe {}
+
+/**
+* Numbers of operations and elapsed time of the instrumented algorithms.
+*/
+#[deriving(Clone, PartialEq, Show)]
+pub struct Report {
+    pub lookups:       u64,
+    pub insertions:    u64,
+    pub relaxations:   u64,
+    pub heap_pops:     u64,
+    pub visits:        u64,
+    /// The nanoseconds since the creation or the last reset of the Counter
+    pub elapsed_ns:    u64
+}
+
+/**
+* Probe counting every operation.
+*/
+pub struct Counter {
+    report:            Report,
+    start:             u64
+}
+
+impl Counter {
+
+    /**
+    * Create a new Counter, its clock starts now.
+    *
+    * # Return
+    * A new Counter without any operation.
+    */
+    pub fn new() -> Counter {
+        Counter {
+            report:     Report {
+                lookups:        0,
+                insertions:     0,
+                relaxations:    0,
+                heap_pops:      0,
+                visits:         0,
+                elapsed_ns:     0
+            },
+            start:      precise_time_ns()
+        }
+    }
+
+    /**
+    * Forget the counted operations and restart the clock.
+    */
+    pub fn reset(&mut self) {
+        *self = Counter::new();
+    }
+
+    /**
+    * Get the operations counted so far and the elapsed time.
+    */
+    pub fn report(&self) -> Report {
+        let mut report = self.report.clone();
+        report.elapsed_ns = precise_time_ns() - self.start;
+        report
+    }
+}
+
+impl Probe for Counter {
+    fn lookup(&mut self) {
+        self.report.lookups += 1;
+    }
+
+    fn insertion(&mut self) {
+        self.report.insertions += 1;
+    }
+
+    fn relaxation(&mut self) {
+        self.report.relaxations += 1;
+    }
+
+    fn heap_pop(&mut self) {
+        self.report.heap_pops += 1;
+    }
+
+    fn visit(&mut self) {
+        self.report.visits += 1;
+    }
+}
@@ -0,0 +1,514 @@
+//! Compact binary storage of Graphs, much faster to load than the textual
+//! formats.
+//!
+//! The document is laid out as the Graph is stored:
+//!
+//! * the magic `RGRAPHB` and the version of the format on one byte
+//! * a byte of flags, 1 if the Graph is directed and 2 if it allows the
+//!   self loops
+//! * the number of Vertices, then the key and the optional label of each
+//!   Vertex in ascending key order
+//! * for each Vertex, the number of its Edges, then for each Edge the
+//!   difference between the number of its second Vertex and the one of
+//!   the previous Edge, and its optional value
+//!
+//! The counts, the differences and the integers are LEB128 varints, the
+//! signed integers are zigzag encoded first, the floats are their raw
+//! little endian bytes. The keys, the labels and the values are written
+//! through their Encodable implementation with BinaryEncoder, the names of
+//! the fields and of the variants are not written. An undirected Graph
+//! stores each Edge in both directions.
+
+use std::io::{IoError, IoResult, InvalidInput};
+use std::mem;
+use serialize::{Encodable, Decodable, Encoder, Decoder};
+
+use graph::{Graph, GraphConfig};
+
+static MAGIC: &'static [u8] = b"RGRAPHB";
+static VERSION: u8 = 1;
+
+/**
+* Save a Graph in the binary format.
+*
+* # Arguments
+* * graph - The graph to save
+* * writer - The writer receiving the document
+*
+* # Return
+* Ok(()), Err(error) if the writing fails.
+*/
+pub fn save<K: Ord + Eq + Clone + Encodable<BinaryEncoder, IoError>,
+            L: Eq + Clone + Encodable<BinaryEncoder, IoError>,
+            V: PartialEq + Clone + Encodable<BinaryEncoder, IoError>,
+            W: Writer>
+            (graph: &Graph<K, L, V>,
+            writer: &mut W)
+            -> IoResult<()> {
+    let mut e = BinaryEncoder::new();
+    e.bytes.push_all(MAGIC);
+    e.bytes.push(VERSION);
+    let config = graph.config();
+    e.bytes.push(if config.is_directed() { 1 } else { 0 } | if config.allows_self_loops() { 2 } else { 0 });
+
+    let keys: Vec<&K> = graph.vertices_ref_iter().map(|vertex| vertex.get_key()).collect();
+    e.push_varint(keys.len() as u64);
+    for (key, label) in graph.vertices_iter() {
+        try!(key.encode(&mut e));
+        try!(label.encode(&mut e));
+    }
+    for vertex in graph.vertices_ref_iter() {
+        let edges: Vec<(&K, Option<&V>)> = vertex.edges_iter().collect();
+        e.push_varint(edges.len() as u64);
+        // the Edges are sorted, so are the numbers of their Vertices
+        let mut previous = 0u;
+        let mut position = 0u;
+        for &(to_key, value) in edges.iter() {
+            while keys.get(position) != &to_key {
+                position += 1;
+            }
+            e.push_varint((position - previous) as u64);
+            previous = position;
+            try!(value.encode(&mut e));
+        }
+    }
+    writer.write(e.bytes.as_slice())
+}
+
+/**
+* Load a Graph saved in the binary format.
+*
+* # Arguments
+* * reader - The reader of the document
+*
+* # Return
+* Ok(graph), Err(error) if the reading fails or the document is not valid.
+*/
+pub fn load<K: Ord + Eq + Clone + Decodable<BinaryDecoder, IoError>,
+            L: Eq + Clone + Decodable<BinaryDecoder, IoError>,
+            V: PartialEq + Clone + Decodable<BinaryDecoder, IoError>,
+            R: Reader>
+            (reader: &mut R) -> IoResult<Graph<K, L, V>> {
+    let mut d = BinaryDecoder::new(try!(reader.read_to_end()));
+    if d.bytes.len() < MAGIC.len() + 2 || d.bytes.slice_to(MAGIC.len()) != MAGIC {
+        return Err(d.error("not a binary Graph"))
+    }
+    d.position = MAGIC.len();
+    let version = try!(d.next_byte());
+    if version != VERSION {
+        return Err(d.error("unsupported binary Graph version"))
+    }
+    let flags = try!(d.next_byte());
+    let (directed, self_loops) = (flags & 1 != 0, flags & 2 != 0);
+    let mut graph = Graph::new_with_config(GraphConfig::new().directed(directed).allow_self_loops(self_loops));
+
+    let count = try!(d.read_varint()) as uint;
+    // each Vertex takes at least a byte, a larger count is not trusted
+    let remaining = d.bytes.len() - d.position;
+    let mut keys = Vec::with_capacity(if count < remaining { count } else { remaining });
+    for _ in range(0, count) {
+        let key: K = try!(Decodable::decode(&mut d));
+        let label: Option<L> = try!(Decodable::decode(&mut d));
+        if keys.last().map_or(false, |last| *last >= key) {
+            return Err(d.error("Vertices not in ascending key order"))
+        }
+        graph.add_vertex_opt_l(key.clone(), label);
+        keys.push(key);
+    }
+    for key in keys.iter() {
+        let degree = try!(d.read_varint());
+        let mut position = 0u;
+        for i in range(0, degree) {
+            let delta = try!(d.read_varint()) as uint;
+            position += delta;
+            // a zero difference after the first Edge is a duplicate Edge
+            if position >= keys.len() || (i > 0 && delta == 0) {
+                return Err(d.error("Edge to an unknown Vertex"))
+            }
+            let to_key = keys.get(position);
+            if !self_loops && key == to_key {
+                return Err(d.error("self loop in a Graph forbidding them"))
+            }
+            let value: Option<V> = try!(Decodable::decode(&mut d));
+            graph.get_vertex_mut(key.clone()).unwrap().add_edge_opt_v(to_key.clone(), value);
+        }
+    }
+    // an undirected Graph stores each Edge in both directions
+    if !directed {
+        for vertex in graph.vertices_ref_iter() {
+            for (to_key, _) in vertex.edges_iter() {
+                if !graph.edge_exist(to_key.clone(), vertex.get_key().clone()) {
+                    return Err(d.error("Edge without its reverse in an undirected Graph"))
+                }
+            }
+        }
+    }
+    Ok(graph)
+}
+
+/// Encoder of the keys, the labels and the values in the binary format.
+pub struct BinaryEncoder {
+    bytes:             Vec<u8>
+}
+
+impl BinaryEncoder {
+    fn new() -> BinaryEncoder {
+        BinaryEncoder { bytes: Vec::new() }
+    }
+
+    fn push_varint(&mut self, mut n: u64) {
+        loop {
+            let byte = (n & 0x7f) as u8;
+            n >>= 7;
+            if n == 0 {
+                self.bytes.push(byte);
+                return
+            }
+            self.bytes.push(byte | 0x80);
+        }
+    }
+
+    fn push_zigzag(&mut self, n: i64) {
+        self.push_varint(((n << 1) ^ (n >> 63)) as u64)
+    }
+}
+
+/// Decoder of the keys, the labels and the values in the binary format.
+pub struct BinaryDecoder {
+    bytes:             Vec<u8>,
+    position:          uint
+}
+
+impl BinaryDecoder {
+    fn new(bytes: Vec<u8>) -> BinaryDecoder {
+        BinaryDecoder { bytes: bytes, position: 0 }
+    }
+
+    fn next_byte(&mut self) -> IoResult<u8> {
+        if self.position == self.bytes.len() {
+            return Err(self.error("truncated binary Graph"))
+        }
+        self.position += 1;
+        Ok(*self.bytes.get(self.position - 1))
+    }
+
+    fn read_varint(&mut self) -> IoResult<u64> {
+        let mut n = 0u64;
+        let mut shift = 0u;
+        loop {
+            let byte = try!(self.next_byte());
+            if shift > 63 {
+                return Err(self.error("varint overflow"))
+            }
+            n |= (byte & 0x7f) as u64 << shift;
+            if byte & 0x80 == 0 {
+                return Ok(n)
+            }
+            shift += 7;
+        }
+    }
+
+    fn read_zigzag(&mut self) -> IoResult<i64> {
+        let n = try!(self.read_varint());
+        Ok((n >> 1) as i64 ^ -((n & 1) as i64))
+    }
+
+    fn read_raw(&mut self, len: uint) -> IoResult<u64> {
+        let mut n = 0u64;
+        for i in range(0, len) {
+            n |= try!(self.next_byte()) as u64 << (8 * i);
+        }
+        Ok(n)
+    }
+}
+
+impl Encoder<IoError> for BinaryEncoder {
+    fn emit_nil(&mut self) -> IoResult<()> { Ok(()) }
+
+    fn emit_uint(&mut self, v: uint) -> IoResult<()> { self.emit_u64(v as u64) }
+    fn emit_u64(&mut self, v: u64) -> IoResult<()> { self.push_varint(v); Ok(()) }
+    fn emit_u32(&mut self, v: u32) -> IoResult<()> { self.emit_u64(v as u64) }
+    fn emit_u16(&mut self, v: u16) -> IoResult<()> { self.emit_u64(v as u64) }
+    fn emit_u8(&mut self, v: u8) -> IoResult<()> { self.bytes.push(v); Ok(()) }
+
+    fn emit_int(&mut self, v: int) -> IoResult<()> { self.emit_i64(v as i64) }
+    fn emit_i64(&mut self, v: i64) -> IoResult<()> { self.push_zigzag(v); Ok(()) }
+    fn emit_i32(&mut self, v: i32) -> IoResult<()> { self.emit_i64(v as i64) }
+    fn emit_i16(&mut self, v: i16) -> IoResult<()> { self.emit_i64(v as i64) }
+    fn emit_i8(&mut self, v: i8) -> IoResult<()> { self.emit_u8(v as u8) }
+
+    fn emit_bool(&mut self, v: bool) -> IoResult<()> { self.emit_u8(v as u8) }
+
+    fn emit_f64(&mut self, v: f64) -> IoResult<()> {
+        let bits: u64 = unsafe { mem::transmute(v) };
+        for i in range(0u, 8) {
+            self.bytes.push((bits >> (8 * i)) as u8);
+        }
+        Ok(())
+    }
+
+    fn emit_f32(&mut self, v: f32) -> IoResult<()> {
+        let bits: u32 = unsafe { mem::transmute(v) };
+        for i in range(0u, 4) {
+            self.bytes.push((bits >> (8 * i)) as u8);
+        }
+        Ok(())
+    }
+
+    fn emit_char(&mut self, v: char) -> IoResult<()> { self.emit_u64(v as u64) }
+
+    fn emit_str(&mut self, v: &str) -> IoResult<()> {
+        self.push_varint(v.len() as u64);
+        self.bytes.push_all(v.as_bytes());
+        Ok(())
+    }
+
+    fn emit_enum(&mut self, _name: &str, f: |&mut BinaryEncoder| -> IoResult<()>) -> IoResult<()> {
+        f(self)
+    }
+
+    fn emit_enum_variant(&mut self,
+                         _v_name: &str,
+                         v_id: uint,
+                         _len: uint,
+                         f: |&mut BinaryEncoder| -> IoResult<()>)
+                         -> IoResult<()> {
+        try!(self.emit_uint(v_id));
+        f(self)
+    }
+
+    fn emit_enum_variant_arg(&mut self, _a_idx: uint, f: |&mut BinaryEncoder| -> IoResult<()>) -> IoResult<()> {
+        f(self)
+    }
+
+    fn emit_enum_struct_variant(&mut self,
+                                v_name: &str,
+                                v_id: uint,
+                                len: uint,
+                                f: |&mut BinaryEncoder| -> IoResult<()>)
+                                -> IoResult<()> {
+        self.emit_enum_variant(v_name, v_id, len, f)
+    }
+
+    fn emit_enum_struct_variant_field(&mut self,
+                                      _f_name: &str,
+                                      _f_idx: uint,
+                                      f: |&mut BinaryEncoder| -> IoResult<()>)
+                                      -> IoResult<()> {
+        f(self)
+    }
+
+    fn emit_struct(&mut self, _name: &str, _len: uint, f: |&mut BinaryEncoder| -> IoResult<()>) -> IoResult<()> {
+        f(self)
+    }
+
+    fn emit_struct_field(&mut self,
+                         _f_name: &str,
+                         _f_idx: uint,
+                         f: |&mut BinaryEncoder| -> IoResult<()>)
+                         -> IoResult<()> {
+        f(self)
+    }
+
+    fn emit_tuple(&mut self, len: uint, f: |&mut BinaryEncoder| -> IoResult<()>) -> IoResult<()> {
+        try!(self.emit_uint(len));
+        f(self)
+    }
+
+    fn emit_tuple_arg(&mut self, _idx: uint, f: |&mut BinaryEncoder| -> IoResult<()>) -> IoResult<()> {
+        f(self)
+    }
+
+    fn emit_tuple_struct(&mut self, _name: &str, len: uint, f: |&mut BinaryEncoder| -> IoResult<()>) -> IoResult<()> {
+        self.emit_tuple(len, f)
+    }
+
+    fn emit_tuple_struct_arg(&mut self, _f_idx: uint, f: |&mut BinaryEncoder| -> IoResult<()>) -> IoResult<()> {
+        f(self)
+    }
+
+    fn emit_option(&mut self, f: |&mut BinaryEncoder| -> IoResult<()>) -> IoResult<()> {
+        f(self)
+    }
+
+    fn emit_option_none(&mut self) -> IoResult<()> {
+        self.emit_u8(0)
+    }
+
+    fn emit_option_some(&mut self, f: |&mut BinaryEncoder| -> IoResult<()>) -> IoResult<()> {
+        try!(self.emit_u8(1));
+        f(self)
+    }
+
+    fn emit_seq(&mut self, len: uint, f: |&mut BinaryEncoder| -> IoResult<()>) -> IoResult<()> {
+        try!(self.emit_uint(len));
+        f(self)
+    }
+
+    fn emit_seq_elt(&mut self, _idx: uint, f: |&mut BinaryEncoder| -> IoResult<()>) -> IoResult<()> {
+        f(self)
+    }
+
+    fn emit_map(&mut self, len: uint, f: |&mut BinaryEncoder| -> IoResult<()>) -> IoResult<()> {
+        try!(self.emit_uint(len));
+        f(self)
+    }
+
+    fn emit_map_elt_key(&mut self, _idx: uint, f: |&mut BinaryEncoder| -> IoResult<()>) -> IoResult<()> {
+        f(self)
+    }
+
+    fn emit_map_elt_val(&mut self, _idx: uint, f: |&mut BinaryEncoder| -> IoResult<()>) -> IoResult<()> {
+        f(self)
+    }
+}
+
+impl Decoder<IoError> for BinaryDecoder {
+    fn read_nil(&mut self) -> IoResult<()> { Ok(()) }
+
+    fn read_uint(&mut self) -> IoResult<uint> { Ok(try!(self.read_varint()) as uint) }
+    fn read_u64(&mut self) -> IoResult<u64> { self.read_varint() }
+    fn read_u32(&mut self) -> IoResult<u32> { Ok(try!(self.read_varint()) as u32) }
+    fn read_u16(&mut self) -> IoResult<u16> { Ok(try!(self.read_varint()) as u16) }
+    fn read_u8(&mut self) -> IoResult<u8> { self.next_byte() }
+
+    fn read_int(&mut self) -> IoResult<int> { Ok(try!(self.read_zigzag()) as int) }
+    fn read_i64(&mut self) -> IoResult<i64> { self.read_zigzag() }
+    fn read_i32(&mut self) -> IoResult<i32> { Ok(try!(self.read_zigzag()) as i32) }
+    fn read_i16(&mut self) -> IoResult<i16> { Ok(try!(self.read_zigzag()) as i16) }
+    fn read_i8(&mut self) -> IoResult<i8> { Ok(try!(self.next_byte()) as i8) }
+
+    fn read_bool(&mut self) -> IoResult<bool> { Ok(try!(self.next_byte()) != 0) }
+
+    fn read_f64(&mut self) -> IoResult<f64> {
+        let bits = try!(self.read_raw(8));
+        Ok(unsafe { mem::transmute(bits) })
+    }
+
+    fn read_f32(&mut self) -> IoResult<f32> {
+        let bits = try!(self.read_raw(4)) as u32;
+        Ok(unsafe { mem::transmute(bits) })
+    }
+
+    fn read_char(&mut self) -> IoResult<char> {
+        let code = try!(self.read_varint());
+        match ::std::char::from_u32(code as u32) {
+            Some(c) => Ok(c),
+            None    => Err(self.error("invalid char"))
+        }
+    }
+
+    fn read_str(&mut self) -> IoResult<String> {
+        let len = try!(self.read_varint()) as uint;
+        if self.position + len > self.bytes.len() {
+            return Err(self.error("truncated binary Graph"))
+        }
+        let text = match ::std::str::from_utf8(self.bytes.slice(self.position, self.position + len)) {
+            Some(text) => text.to_string(),
+            None       => return Err(self.error("invalid UTF-8 string"))
+        };
+        self.position += len;
+        Ok(text)
+    }
+
+    fn read_enum<T>(&mut self, _name: &str, f: |&mut BinaryDecoder| -> IoResult<T>) -> IoResult<T> {
+        f(self)
+    }
+
+    fn read_enum_variant<T>(&mut self,
+                            names: &[&str],
+                            f: |&mut BinaryDecoder, uint| -> IoResult<T>)
+                            -> IoResult<T> {
+        let id = try!(self.read_uint());
+        if id >= names.len() {
+            return Err(self.error("unknown enum variant"))
+        }
+        f(self, id)
+    }
+
+    fn read_enum_variant_arg<T>(&mut self, _a_idx: uint, f: |&mut BinaryDecoder| -> IoResult<T>) -> IoResult<T> {
+        f(self)
+    }
+
+    fn read_enum_struct_variant<T>(&mut self,
+                                   names: &[&str],
+                                   f: |&mut BinaryDecoder, uint| -> IoResult<T>)
+                                   -> IoResult<T> {
+        self.read_enum_variant(names, f)
+    }
+
+    fn read_enum_struct_variant_field<T>(&mut self,
+                                         _f_name: &str,
+                                         _f_idx: uint,
+                                         f: |&mut BinaryDecoder| -> IoResult<T>)
+                                         -> IoResult<T> {
+        f(self)
+    }
+
+    fn read_struct<T>(&mut self, _s_name: &str, _len: uint, f: |&mut BinaryDecoder| -> IoResult<T>) -> IoResult<T> {
+        f(self)
+    }
+
+    fn read_struct_field<T>(&mut self,
+                            _f_name: &str,
+                            _f_idx: uint,
+                            f: |&mut BinaryDecoder| -> IoResult<T>)
+                            -> IoResult<T> {
+        f(self)
+    }
+
+    fn read_tuple<T>(&mut self, f: |&mut BinaryDecoder, uint| -> IoResult<T>) -> IoResult<T> {
+        let len = try!(self.read_uint());
+        f(self, len)
+    }
+
+    fn read_tuple_arg<T>(&mut self, _a_idx: uint, f: |&mut BinaryDecoder| -> IoResult<T>) -> IoResult<T> {
+        f(self)
+    }
+
+    fn read_tuple_struct<T>(&mut self, _s_name: &str, f: |&mut BinaryDecoder, uint| -> IoResult<T>) -> IoResult<T> {
+        self.read_tuple(f)
+    }
+
+    fn read_tuple_struct_arg<T>(&mut self, _a_idx: uint, f: |&mut BinaryDecoder| -> IoResult<T>) -> IoResult<T> {
+        f(self)
+    }
+
+    fn read_option<T>(&mut self, f: |&mut BinaryDecoder, bool| -> IoResult<T>) -> IoResult<T> {
+        match try!(self.next_byte()) {
+            0 => f(self, false),
+            1 => f(self, true),
+            _ => Err(self.error("invalid option"))
+        }
+    }
+
+    fn read_seq<T>(&mut self, f: |&mut BinaryDecoder, uint| -> IoResult<T>) -> IoResult<T> {
+        let len = try!(self.read_uint());
+        f(self, len)
+    }
+
+    fn read_seq_elt<T>(&mut self, _idx: uint, f: |&mut BinaryDecoder| -> IoResult<T>) -> IoResult<T> {
+        f(self)
+    }
+
+    fn read_map<T>(&mut self, f: |&mut BinaryDecoder, uint| -> IoResult<T>) -> IoResult<T> {
+        let len = try!(self.read_uint());
+        f(self, len)
+    }
+
+    fn read_map_elt_key<T>(&mut self, _idx: uint, f: |&mut BinaryDecoder| -> IoResult<T>) -> IoResult<T> {
+        f(self)
+    }
+
+    fn read_map_elt_val<T>(&mut self, _idx: uint, f: |&mut BinaryDecoder| -> IoResult<T>) -> IoResult<T> {
+        f(self)
+    }
+
+    fn error(&mut self, err: &str) -> IoError {
+        IoError {
+            kind:   InvalidInput,
+            desc:   "invalid binary Graph",
+            detail: Some(format!("{} at byte {}", err, self.position))
+        }
+    }
+}
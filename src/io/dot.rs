@@ -0,0 +1,417 @@
+//! Import of Graphs from the DOT language of Graphviz.
+//!
+//! The keys are the ids of the nodes, the label of a Vertex is the `label`
+//! attribute of its node and the value of an Edge is the `weight` attribute
+//! of its edge, or its `label` without weight. The `node` and `edge`
+//! attribute statements give the default attributes of the following
+//! statements of their subgraph. The subgraphs are flattened: their nodes
+//! and edges belong to the Graph, and a subgraph in an edge statement
+//! stands for all its nodes. The ports, the `graph` attributes and the
+//! other attributes are ignored. A Graph holds one Edge between two
+//! Vertices, the repeated edges keep the value of the first one.
+
+use std::ascii::StrAsciiExt;
+
+use graph::Graph;
+use io::ParseError;
+
+/// Error of the import of a DOT document.
+pub type DotParseError = ParseError;
+
+// the attributes of a statement, the last one of a name wins
+type Attributes = Vec<(String, String)>;
+
+#[deriving(Clone, PartialEq, Show)]
+enum Token {
+    Word(String),
+    Text(String),
+    Open,
+    Close,
+    OpenBracket,
+    CloseBracket,
+    Semicolon,
+    Comma,
+    Equal,
+    Colon,
+    Arrow,
+    Line
+}
+
+// the default attributes of the nodes and the edges of a subgraph
+#[deriving(Clone)]
+struct Defaults {
+    node:              Attributes,
+    edge:              Attributes
+}
+
+/**
+* Build a Graph from a DOT document.
+*
+* # Arguments
+* * input - The document to parse
+*
+* # Return
+* Ok(graph), directed for a digraph, Err(error) at the first syntax error
+* or at an edge operator not matching the kind of the graph.
+*/
+pub fn parse(input: &str) -> Result<Graph<String, String, String>, DotParseError> {
+    let tokens = try!(tokenize(input));
+    let tokens = tokens.as_slice();
+    let mut pos = 0u;
+
+    if is_keyword(tokens, pos, "strict") {
+        pos += 1;
+    }
+    let directed = if is_keyword(tokens, pos, "digraph") {
+        true
+    } else if is_keyword(tokens, pos, "graph") {
+        false
+    } else {
+        return Err(ParseError::new(line_at(tokens, pos), "expected graph or digraph"))
+    };
+    pos += 1;
+    match tokens.get(pos) {
+        Some(&(Word(_), _)) | Some(&(Text(_), _)) => pos += 1,
+        _                                         => {}
+    }
+    try!(expect(tokens, &mut pos, Open, "expected '{'"));
+
+    let mut graph = if directed { Graph::new() } else { Graph::new_undirected() };
+    let mut defaults = Defaults { node: Vec::new(), edge: Vec::new() };
+    try!(parse_statements(tokens, &mut pos, &mut graph, &mut defaults));
+    if pos < tokens.len() {
+        return Err(ParseError::new(line_at(tokens, pos), "unexpected content after the graph"))
+    }
+    Ok(graph)
+}
+
+/**
+* Build a Graph from a DOT document read from a Reader.
+*
+* # Arguments
+* * reader - The reader of the document to parse
+*
+* # Return
+* Ok(graph), Err(error) if the reading fails or as parse.
+*/
+pub fn read<R: Reader>(reader: &mut R) -> Result<Graph<String, String, String>, DotParseError> {
+    match reader.read_to_string() {
+        Ok(input) => parse(input.as_slice()),
+        Err(e)    => Err(ParseError::new(0, e.desc))
+    }
+}
+
+// the statements until the closing brace, pos is moved after it, and the
+// nodes they name
+fn parse_statements(tokens: &[(Token, uint)],
+                    pos: &mut uint,
+                    graph: &mut Graph<String, String, String>,
+                    defaults: &mut Defaults)
+                    -> Result<Vec<String>, ParseError> {
+    let mut nodes = Vec::new();
+    loop {
+        let line = line_at(tokens, *pos);
+        match tokens.get(*pos) {
+            None                  => return Err(ParseError::new(line, "unclosed '{'")),
+            Some(&(Close, _))     => {
+                *pos += 1;
+                return Ok(nodes)
+            },
+            Some(&(Semicolon, _)) => {
+                *pos += 1;
+                continue
+            },
+            _                     => {}
+        }
+
+        // attribute statements
+        let kind = ["node", "edge", "graph"].iter().find(|kind| is_keyword(tokens, *pos, **kind)).map(|k| *k);
+        match kind {
+            Some(kind) if next_is(tokens, *pos + 1, OpenBracket) => {
+                *pos += 1;
+                let attributes = try!(parse_attributes(tokens, pos));
+                match kind {
+                    "node" => defaults.node.push_all(attributes.as_slice()),
+                    "edge" => defaults.edge.push_all(attributes.as_slice()),
+                    _      => {}
+                }
+                continue
+            },
+            _                                                     => {}
+        }
+        // graph attributes as id = id
+        match tokens.get(*pos) {
+            Some(&(Word(_), _)) | Some(&(Text(_), _)) if next_is(tokens, *pos + 1, Equal) => {
+                *pos += 2;
+                try!(parse_id(tokens, pos));
+                continue
+            },
+            _                                                                          => {}
+        }
+
+        let mut operands = vec!(try!(parse_operand(tokens, pos, graph, defaults)));
+        while next_is(tokens, *pos, Arrow) || next_is(tokens, *pos, Line) {
+            let line = line_at(tokens, *pos);
+            if next_is(tokens, *pos, Arrow) != graph.is_directed() {
+                return Err(ParseError::new(line, if graph.is_directed() {
+                    "'--' in a digraph"
+                } else {
+                    "'->' in an undirected graph"
+                }))
+            }
+            *pos += 1;
+            operands.push(try!(parse_operand(tokens, pos, graph, defaults)));
+        }
+        let attributes = if next_is(tokens, *pos, OpenBracket) {
+            try!(parse_attributes(tokens, pos))
+        } else {
+            Vec::new()
+        };
+
+        if operands.len() == 1 {
+            // a node statement, or a lone subgraph
+            if operands.get(0).len() == 1 && !attributes.is_empty() {
+                let key = operands.get(0).get(0).clone();
+                match attribute(&attributes, "label") {
+                    Some(label) => { graph.set_vertex_label(key, label); },
+                    None        => {}
+                }
+            }
+        } else {
+            let mut all = defaults.edge.clone();
+            all.push_all(attributes.as_slice());
+            let value = attribute(&all, "weight").or_else(|| attribute(&all, "label"));
+            for pair in operands.as_slice().windows(2) {
+                for from_key in pair[0].iter() {
+                    for to_key in pair[1].iter() {
+                        graph.add_edge_opt_v(from_key.clone(), to_key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        for operand in operands.move_iter() {
+            nodes.push_all(operand.as_slice());
+        }
+    }
+}
+
+// a node id or a subgraph, pos is moved after it, and its nodes which are
+// added to the Graph with the default attributes
+fn parse_operand(tokens: &[(Token, uint)],
+                 pos: &mut uint,
+                 graph: &mut Graph<String, String, String>,
+                 defaults: &Defaults)
+                 -> Result<Vec<String>, ParseError> {
+    let subgraph = is_keyword(tokens, *pos, "subgraph");
+    if subgraph || next_is(tokens, *pos, Open) {
+        if subgraph {
+            *pos += 1;
+            if !next_is(tokens, *pos, Open) {
+                try!(parse_id(tokens, pos));
+            }
+        }
+        try!(expect(tokens, pos, Open, "expected '{'"));
+        let mut scope = defaults.clone();
+        return parse_statements(tokens, pos, graph, &mut scope)
+    }
+
+    let key = try!(parse_id(tokens, pos));
+    // the port and the compass point
+    while next_is(tokens, *pos, Colon) {
+        *pos += 1;
+        try!(parse_id(tokens, pos));
+    }
+    if !graph.vertex_exist(&key) {
+        graph.add_vertex_opt_l(key.clone(), attribute(&defaults.node, "label"));
+    }
+    Ok(vec!(key))
+}
+
+// the attribute lists, pos is moved after them
+fn parse_attributes(tokens: &[(Token, uint)], pos: &mut uint) -> Result<Attributes, ParseError> {
+    let mut attributes = Vec::new();
+    while next_is(tokens, *pos, OpenBracket) {
+        *pos += 1;
+        loop {
+            match tokens.get(*pos) {
+                Some(&(CloseBracket, _))                  => {
+                    *pos += 1;
+                    break
+                },
+                Some(&(Semicolon, _)) | Some(&(Comma, _)) => *pos += 1,
+                _                                         => {
+                    let name = try!(parse_id(tokens, pos));
+                    try!(expect(tokens, pos, Equal, "expected '=' in an attribute"));
+                    let value = try!(parse_id(tokens, pos));
+                    attributes.push((name, value));
+                }
+            }
+        }
+    }
+    Ok(attributes)
+}
+
+fn parse_id(tokens: &[(Token, uint)], pos: &mut uint) -> Result<String, ParseError> {
+    match tokens.get(*pos) {
+        Some(&(Word(ref id), _)) | Some(&(Text(ref id), _)) => {
+            *pos += 1;
+            Ok(id.clone())
+        },
+        _                                                   => {
+            Err(ParseError::new(line_at(tokens, *pos), "expected an id"))
+        }
+    }
+}
+
+fn expect(tokens: &[(Token, uint)], pos: &mut uint, token: Token, message: &str) -> Result<(), ParseError> {
+    if next_is(tokens, *pos, token) {
+        *pos += 1;
+        Ok(())
+    } else {
+        Err(ParseError::new(line_at(tokens, *pos), message))
+    }
+}
+
+fn next_is(tokens: &[(Token, uint)], pos: uint, token: Token) -> bool {
+    match tokens.get(pos) {
+        Some(&(ref t, _)) => *t == token,
+        None              => false
+    }
+}
+
+// the keywords are case insensitive and never quoted
+fn is_keyword(tokens: &[(Token, uint)], pos: uint, keyword: &str) -> bool {
+    match tokens.get(pos) {
+        Some(&(Word(ref word), _)) => word.as_slice().eq_ignore_ascii_case(keyword),
+        _                          => false
+    }
+}
+
+// the line of the token at pos, or of the last one at the end
+fn line_at(tokens: &[(Token, uint)], pos: uint) -> uint {
+    match tokens.get(pos) {
+        Some(&(_, line)) => line,
+        None             => tokens.last().map_or(0, |&(_, line)| line)
+    }
+}
+
+fn attribute(attributes: &Attributes, name: &str) -> Option<String> {
+    attributes.iter().rev().find(|&&(ref n, _)| n.as_slice() == name).map(|&(_, ref value)| value.clone())
+}
+
+fn tokenize(input: &str) -> Result<Vec<(Token, uint)>, ParseError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut line = 1u;
+    let mut i = 0u;
+
+    while i < chars.len() {
+        let c = *chars.get(i);
+        let next = chars.as_slice().get(i + 1).map(|c| *c);
+        match c {
+            '\n'                   => { line += 1; i += 1; },
+            ' ' | '\t' | '\r'      => i += 1,
+            // the comments and the preprocessor lines
+            '#'                    => {
+                while i < chars.len() && *chars.get(i) != '\n' {
+                    i += 1;
+                }
+            },
+            '/' if next == Some('/') => {
+                while i < chars.len() && *chars.get(i) != '\n' {
+                    i += 1;
+                }
+            },
+            '/' if next == Some('*') => {
+                i += 2;
+                while i < chars.len() && !(*chars.get(i) == '*' && chars.as_slice().get(i + 1) == Some(&'/')) {
+                    if *chars.get(i) == '\n' {
+                        line += 1;
+                    }
+                    i += 1;
+                }
+                if i >= chars.len() {
+                    return Err(ParseError::new(line, "unterminated comment"))
+                }
+                i += 2;
+            },
+            '{'                    => { tokens.push((Open, line)); i += 1; },
+            '}'                    => { tokens.push((Close, line)); i += 1; },
+            '['                    => { tokens.push((OpenBracket, line)); i += 1; },
+            ']'                    => { tokens.push((CloseBracket, line)); i += 1; },
+            ';'                    => { tokens.push((Semicolon, line)); i += 1; },
+            ','                    => { tokens.push((Comma, line)); i += 1; },
+            '='                    => { tokens.push((Equal, line)); i += 1; },
+            ':'                    => { tokens.push((Colon, line)); i += 1; },
+            '-' if next == Some('>') => { tokens.push((Arrow, line)); i += 2; },
+            '-' if next == Some('-') => { tokens.push((Line, line)); i += 2; },
+            '"'                    => {
+                // only the escaped quotes and the line continuations are
+                // unescaped, the other escapes belong to Graphviz
+                let start = line;
+                let mut text = String::new();
+                i += 1;
+                loop {
+                    if i >= chars.len() {
+                        return Err(ParseError::new(start, "unterminated string"))
+                    }
+                    match *chars.get(i) {
+                        '"'  => break,
+                        '\\' if chars.as_slice().get(i + 1) == Some(&'"') => {
+                            text.push_char('"');
+                            i += 1;
+                        },
+                        '\\' if chars.as_slice().get(i + 1) == Some(&'\n') => {
+                            line += 1;
+                            i += 1;
+                        },
+                        '\n' => {
+                            text.push_char('\n');
+                            line += 1;
+                        },
+                        c    => text.push_char(c)
+                    }
+                    i += 1;
+                }
+                i += 1;
+                tokens.push((Text(text), start));
+            },
+            '<'                    => {
+                // an HTML string, kept without its outer brackets
+                let start = line;
+                let mut depth = 1u;
+                let first = i + 1;
+                i += 1;
+                while depth > 0 {
+                    if i >= chars.len() {
+                        return Err(ParseError::new(start, "unterminated HTML string"))
+                    }
+                    match *chars.get(i) {
+                        '<'  => depth += 1,
+                        '>'  => depth -= 1,
+                        '\n' => line += 1,
+                        _    => {}
+                    }
+                    i += 1;
+                }
+                tokens.push((Text(String::from_chars(chars.slice(first, i - 1))), start));
+            },
+            c if is_id_char(c) || c == '-' || c == '.' => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && (is_id_char(*chars.get(i)) || *chars.get(i) == '.') {
+                    i += 1;
+                }
+                tokens.push((Word(String::from_chars(chars.slice(start, i))), line));
+            },
+            c                      => {
+                return Err(ParseError::new(line, format!("unexpected character {}", c).as_slice()))
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn is_id_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c as u32 > 127
+}
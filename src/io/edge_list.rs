@@ -0,0 +1,190 @@
+//! Import and export of Graphs as edge lists, one `from,to,value` line per
+//! Edge.
+//!
+//! The value is empty for an Edge without value and may be left out on
+//! import. The keys and the values are converted with ToString and FromStr,
+//! they are not quoted so they must not contain the delimiter nor a line
+//! break. Labels and Vertices without Edges are not part of the format.
+
+use std::from_str::FromStr;
+use std::io::IoResult;
+
+use graph::Graph;
+use io::ParseError;
+
+/**
+* Layout of an edge list.
+*/
+#[deriving(Clone, PartialEq, Show)]
+pub struct EdgeListFormat {
+    delimiter:         char,
+    header:            bool
+}
+
+impl EdgeListFormat {
+
+    /**
+    * Create a new EdgeListFormat, comma separated without header.
+    */
+    pub fn new() -> EdgeListFormat {
+        EdgeListFormat {
+            delimiter:  ',',
+            header:     false
+        }
+    }
+
+    /**
+    * Separate the fields of a line with another character.
+    */
+    pub fn delimiter(mut self, delimiter: char) -> EdgeListFormat {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /**
+    * Write a `from,to,value` header line, or skip the first line on import.
+    */
+    pub fn header(mut self, header: bool) -> EdgeListFormat {
+        self.header = header;
+        self
+    }
+}
+
+/**
+* Write the Edges of a Graph as an edge list.
+*
+* # Arguments
+* * graph - The graph to write
+* * format - The layout of the list
+* * writer - The writer receiving the list
+*
+* # Return
+* Ok(()), Err(error) if the writing fails.
+*/
+pub fn write_edge_list<K: ToString + Ord + Eq + Clone,
+                       L: ToString + Eq + Clone,
+                       V: ToString + PartialEq + Clone,
+                       W: Writer>
+                       (graph: &Graph<K, L, V>,
+                       format: &EdgeListFormat,
+                       writer: &mut W)
+                       -> IoResult<()> {
+    writer.write_str(to_edge_list(graph, format).as_slice())
+}
+
+/**
+* Convert the Edges of a Graph to an edge list.
+*
+* An undirected Graph lists each Edge once.
+*
+* # Arguments
+* * graph - The graph to convert
+* * format - The layout of the list
+*
+* # Return
+* The edge list, sorted by the keys of the Edges.
+*/
+pub fn to_edge_list<K: ToString + Ord + Eq + Clone,
+                    L: ToString + Eq + Clone,
+                    V: ToString + PartialEq + Clone>
+                    (graph: &Graph<K, L, V>,
+                    format: &EdgeListFormat)
+                    -> String {
+    let mut list = String::new();
+    if format.header {
+        list.push_str(format!("from{}to{}value\n", format.delimiter, format.delimiter).as_slice());
+    }
+    for (from_key, to_key, value) in graph.edges_iter() {
+        list.push_str(from_key.to_string().as_slice());
+        list.push_char(format.delimiter);
+        list.push_str(to_key.to_string().as_slice());
+        list.push_char(format.delimiter);
+        match value {
+            Some(value) => list.push_str(value.to_string().as_slice()),
+            None        => {}
+        }
+        list.push_char('\n');
+    }
+    list
+}
+
+/**
+* Build a Graph from an edge list.
+*
+* The blank lines are ignored, the repeated Edges keep their first value.
+*
+* # Arguments
+* * input - The edge list
+* * format - The layout of the list
+*
+* # Return
+* Ok(graph) of the Edges and their Vertices, Err(error) at the first line
+* without two or three fields or with a key or a value which can't be
+* converted.
+*/
+pub fn parse_edge_list<K: ToString + Ord + Eq + Clone + FromStr,
+                       L: ToString + Eq + Clone,
+                       V: ToString + PartialEq + Clone + FromStr>
+                       (input: &str,
+                       format: &EdgeListFormat)
+                       -> Result<Graph<K, L, V>, ParseError> {
+    let mut edges = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        match try!(parse_edge_line(line, i + 1, format)) {
+            Some(edge) => edges.push(edge),
+            None       => {}
+        }
+    }
+    Ok(Graph::from_edges(edges))
+}
+
+/**
+* Build a Graph from an edge list read from a Reader.
+*
+* # Arguments
+* * reader - The reader of the list
+* * format - The layout of the list
+*
+* # Return
+* Ok(graph), Err(error) if the reading fails or as parse_edge_list.
+*/
+pub fn read_edge_list<K: ToString + Ord + Eq + Clone + FromStr,
+                      L: ToString + Eq + Clone,
+                      V: ToString + PartialEq + Clone + FromStr,
+                      R: Reader>
+                      (reader: &mut R,
+                      format: &EdgeListFormat)
+                      -> Result<Graph<K, L, V>, ParseError> {
+    match reader.read_to_string() {
+        Ok(input) => parse_edge_list(input.as_slice(), format),
+        Err(e)    => Err(ParseError::new(0, e.desc))
+    }
+}
+
+// the Edge of a line, None for the header and the blank lines
+pub fn parse_edge_line<K: FromStr, V: FromStr>(line: &str, number: uint, format: &EdgeListFormat)
+                                               -> Result<Option<(K, K, Option<V>)>, ParseError> {
+    let line = line.trim_right_chars(|c: char| c == '\r' || c == '\n');
+    if (format.header && number == 1) || line.trim().is_empty() {
+        return Ok(None)
+    }
+    let fields: Vec<&str> = line.split(format.delimiter).collect();
+    if fields.len() < 2 || fields.len() > 3 {
+        return Err(ParseError::new(number, "expected from, to and an optional value"))
+    }
+    let from_key: K = try!(convert(*fields.get(0), number));
+    let to_key: K = try!(convert(*fields.get(1), number));
+    let value: Option<V> = match fields.as_slice().get(2) {
+        Some(text) if !text.is_empty() => Some(try!(convert(*text, number))),
+        _                              => None
+    };
+    Ok(Some((from_key, to_key, value)))
+}
+
+// convert a field, reporting its line
+fn convert<T: FromStr>(text: &str, line: uint) -> Result<T, ParseError> {
+    match from_str(text) {
+        Some(converted) => Ok(converted),
+        None            => Err(ParseError::new(line, format!("invalid field {}", text).as_slice()))
+    }
+}
@@ -0,0 +1,276 @@
+//! Import and export of Graphs in the graph6 and sparse6 formats of nauty.
+//!
+//! Both formats pack an undirected Graph without labels nor values in a
+//! line of printable ASCII: graph6 stores the upper triangle of the
+//! adjacency matrix and suits dense Graphs, sparse6 starts with `:` and
+//! stores the list of the Edges, with the self loops. On export the
+//! Vertices are numbered in ascending key order and the direction of the
+//! Edges is ignored, on import the keys are the numbers of the Vertices.
+//! The optional `>>graph6<<` and `>>sparse6<<` headers are accepted.
+
+use graph::Graph;
+use indexed::{IndexedGraph, contains_sorted};
+use io::ParseError;
+
+// the most Vertices of a sparse6 string, whose isolated Vertices take no
+// room in the string
+static SPARSE6_MAX_VERTICES: uint = 1 << 20;
+
+/**
+* Convert a Graph to graph6.
+*
+* # Arguments
+* * graph - The graph to convert
+*
+* # Return
+* The graph6 string, without header nor newline. The self loops are
+* lost.
+*/
+pub fn to_graph6<K: Ord + Eq + Clone,
+                 L: Eq + Clone,
+                 V: PartialEq + Clone>
+                 (graph: &Graph<K, L, V>) -> String {
+    let adjacency = IndexedGraph::from_graph(graph).undirected_edges();
+    let n = adjacency.len();
+    let mut data = Vec::new();
+    push_size(&mut data, n);
+    let mut bits = Vec::new();
+    for j in range(1, n) {
+        for i in range(0, j) {
+            bits.push(contains_sorted(adjacency.get(j).as_slice(), i));
+        }
+    }
+    push_bits(&mut data, bits.as_slice(), false);
+    data.iter().map(|&b| b as char).collect()
+}
+
+/**
+* Convert a Graph to sparse6.
+*
+* # Arguments
+* * graph - The graph to convert
+*
+* # Return
+* The sparse6 string starting with `:`, without header nor newline.
+*/
+pub fn to_sparse6<K: Ord + Eq + Clone,
+                  L: Eq + Clone,
+                  V: PartialEq + Clone>
+                  (graph: &Graph<K, L, V>) -> String {
+    let adjacency = IndexedGraph::from_graph(graph).undirected_edges();
+    let n = adjacency.len();
+    let k = width(n);
+    let mut data = vec!(':' as u8);
+    push_size(&mut data, n);
+
+    // the Edges (u, v) with u <= v, sorted by v and then by u, each one
+    // a bit telling if v changes and the number of u, or of v first when
+    // it increases by more than one
+    let mut bits = Vec::new();
+    let mut current = 0u;
+    for v in range(0, n) {
+        for &u in adjacency.get(v).iter().take_while(|&&u| u <= v) {
+            if v == current {
+                bits.push(false);
+            } else if v == current + 1 {
+                bits.push(true);
+            } else {
+                bits.push(true);
+                push_number(&mut bits, v, k);
+                bits.push(false);
+            }
+            current = v;
+            push_number(&mut bits, u, k);
+        }
+    }
+    // padding with ones could be read as an Edge to n - 1, when there is
+    // room for a number and n is a power of two
+    let padding = (6 - bits.len() % 6) % 6;
+    if k < 6 && n == 1 << k && padding >= k && current < n - 1 {
+        bits.push(false);
+    }
+    push_bits(&mut data, bits.as_slice(), true);
+    data.iter().map(|&b| b as char).collect()
+}
+
+/**
+* Build a Graph from a graph6 string.
+*
+* # Arguments
+* * input - The graph6 string, with an optional header and surrounding
+*   whitespace
+*
+* # Return
+* Ok(graph) with an undirected graph of Vertices numbered from 0,
+* Err(error) if a character is not valid or the string is truncated.
+*/
+pub fn from_graph6(input: &str) -> Result<Graph<uint, (), ()>, ParseError> {
+    let data = try!(decode(strip_header(input, ">>graph6<<")));
+    let (n, start) = try!(read_size(data.as_slice()));
+    let bits = unpack(data.slice_from(start));
+    if n > 0 && bits.len() < n * (n - 1) / 2 {
+        return Err(ParseError::new(0, "truncated graph6"))
+    }
+    let mut graph = numbered(n);
+    let mut pos = 0;
+    for j in range(1, n) {
+        for i in range(0, j) {
+            if *bits.get(pos) {
+                graph.add_edge(i, j);
+            }
+            pos += 1;
+        }
+    }
+    Ok(graph)
+}
+
+/**
+* Build a Graph from a sparse6 string.
+*
+* # Arguments
+* * input - The sparse6 string starting with `:`, with an optional header
+*   and surrounding whitespace
+*
+* # Return
+* Ok(graph) with an undirected graph of Vertices numbered from 0,
+* Err(error) if a character is not valid, the string is truncated or it
+* has more than 2^20 Vertices.
+*/
+pub fn from_sparse6(input: &str) -> Result<Graph<uint, (), ()>, ParseError> {
+    let input = strip_header(input, ">>sparse6<<");
+    if !input.starts_with(":") {
+        return Err(ParseError::new(0, "sparse6 without leading :"))
+    }
+    let data = try!(decode(input.slice_from(1)));
+    let (n, start) = try!(read_size(data.as_slice()));
+    if n > SPARSE6_MAX_VERTICES {
+        return Err(ParseError::new(0, format!("too many vertices {}", n).as_slice()))
+    }
+    let bits = unpack(data.slice_from(start));
+    let k = width(n);
+    let mut graph = numbered(n);
+
+    let mut pos = 0;
+    let mut v = 0u;
+    // a last incomplete group is padding
+    while pos + 1 + k <= bits.len() {
+        if *bits.get(pos) {
+            v += 1;
+        }
+        let x = read_number(bits.slice(pos + 1, pos + 1 + k));
+        pos += 1 + k;
+        if v >= n {
+            break
+        }
+        if x > v {
+            v = x;
+        } else {
+            graph.add_edge(x, v);
+        }
+    }
+    Ok(graph)
+}
+
+// an undirected Graph of n Vertices numbered from 0
+fn numbered(n: uint) -> Graph<uint, (), ()> {
+    let mut graph = Graph::new_undirected();
+    for v in range(0, n) {
+        graph.add_vertex(v);
+    }
+    graph
+}
+
+// the number of bits of the Vertices numbers in sparse6
+fn width(n: uint) -> uint {
+    let mut k = 1;
+    while 1 << k < n {
+        k += 1;
+    }
+    k
+}
+
+fn strip_header<'a>(input: &'a str, header: &str) -> &'a str {
+    let input = input.trim();
+    if input.starts_with(header) {
+        input.slice_from(header.len())
+    } else {
+        input
+    }
+}
+
+// the 6 bits values of the characters
+fn decode(input: &str) -> Result<Vec<u8>, ParseError> {
+    let mut data = Vec::with_capacity(input.len());
+    for b in input.bytes() {
+        if b < 63 || b > 126 {
+            return Err(ParseError::new(0, format!("invalid character {}", b as char).as_slice()))
+        }
+        data.push(b - 63);
+    }
+    Ok(data)
+}
+
+// N(n), the number of Vertices on 1, 4 or 8 characters
+fn push_size(data: &mut Vec<u8>, n: uint) {
+    let groups = if n <= 62 {
+        data.push(n as u8 + 63);
+        return
+    } else if n <= 258047 {
+        data.push(126);
+        3
+    } else {
+        data.push(126);
+        data.push(126);
+        6
+    };
+    for i in range(0, groups).rev() {
+        data.push(((n >> (6 * i)) & 63) as u8 + 63);
+    }
+}
+
+// the number of Vertices and the number of values it takes
+fn read_size(data: &[u8]) -> Result<(uint, uint), ParseError> {
+    let (start, groups) = match data {
+        [63, 63, ..] => (2, 6),
+        [63, ..]     => (1, 3),
+        [n, ..]      => return Ok((n as uint, 1)),
+        []           => return Err(ParseError::new(0, "missing number of vertices"))
+    };
+    if data.len() < start + groups {
+        return Err(ParseError::new(0, "truncated number of vertices"))
+    }
+    let n = data.slice(start, start + groups).iter().fold(0u, |n, &d| (n << 6) | d as uint);
+    Ok((n, start + groups))
+}
+
+// the bits, most significant first, padded to a multiple of 6
+fn push_bits(data: &mut Vec<u8>, bits: &[bool], padding: bool) {
+    for group in bits.chunks(6) {
+        let mut value = 0u8;
+        for i in range(0, 6) {
+            let bit = if i < group.len() { group[i] } else { padding };
+            value = (value << 1) | bit as u8;
+        }
+        data.push(value + 63);
+    }
+}
+
+fn unpack(data: &[u8]) -> Vec<bool> {
+    let mut bits = Vec::with_capacity(6 * data.len());
+    for &d in data.iter() {
+        for i in range(0u, 6).rev() {
+            bits.push((d >> i) & 1 == 1);
+        }
+    }
+    bits
+}
+
+fn push_number(bits: &mut Vec<bool>, x: uint, k: uint) {
+    for i in range(0, k).rev() {
+        bits.push((x >> i) & 1 == 1);
+    }
+}
+
+fn read_number(bits: &[bool]) -> uint {
+    bits.iter().fold(0u, |x, &bit| (x << 1) | bit as uint)
+}
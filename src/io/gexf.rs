@@ -0,0 +1,105 @@
+//! Export of Graphs in GEXF 1.3, the format of Gephi.
+//!
+//! The keys are the ids and the labels of the nodes, the labels of the
+//! Vertices are the `label` attribute of the nodes. A value converted to a
+//! number is the weight of its edge, any other value is the `value`
+//! attribute of its edge. The keys, the labels and the values are
+//! converted with ToString.
+
+use std::io::IoResult;
+
+use graph::Graph;
+use io::escape;
+
+/**
+* Write a Graph as a GEXF document.
+*
+* # Arguments
+* * graph - The graph to write
+* * writer - The writer receiving the document
+*
+* # Return
+* Ok(()), Err(error) if the writing fails.
+*/
+pub fn write<K: ToString + Ord + Eq + Clone,
+             L: ToString + Eq + Clone,
+             V: ToString + PartialEq + Clone,
+             W: Writer>
+             (graph: &Graph<K, L, V>,
+             writer: &mut W)
+             -> IoResult<()> {
+    writer.write_str(to_gexf(graph).as_slice())
+}
+
+/**
+* Convert a Graph to a GEXF document.
+*
+* # Arguments
+* * graph - The graph to convert
+*
+* # Return
+* The GEXF document, with the direction of the Graph as the default edge
+* type.
+*/
+pub fn to_gexf<K: ToString + Ord + Eq + Clone,
+               L: ToString + Eq + Clone,
+               V: ToString + PartialEq + Clone>
+               (graph: &Graph<K, L, V>) -> String {
+    let mut document = String::new();
+    document.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    document.push_str("<gexf xmlns=\"http://gexf.net/1.3\" version=\"1.3\">\n");
+    document.push_str("  <meta>\n    <creator>rgraph</creator>\n  </meta>\n");
+    document.push_str(format!("  <graph mode=\"static\" defaultedgetype=\"{}\">\n",
+                              if graph.is_directed() { "directed" } else { "undirected" }).as_slice());
+    document.push_str("    <attributes class=\"node\">\n");
+    document.push_str("      <attribute id=\"label\" title=\"label\" type=\"string\"/>\n");
+    document.push_str("    </attributes>\n");
+    document.push_str("    <attributes class=\"edge\">\n");
+    document.push_str("      <attribute id=\"value\" title=\"value\" type=\"string\"/>\n");
+    document.push_str("    </attributes>\n");
+
+    document.push_str("    <nodes>\n");
+    for vertex in graph.vertices_ref_iter() {
+        let id = escape(vertex.get_key().to_string().as_slice());
+        match vertex.get_label() {
+            Some(label) => {
+                document.push_str(format!("      <node id=\"{}\" label=\"{}\">\n", id, id).as_slice());
+                document.push_str(format!("        <attvalues><attvalue for=\"label\" value=\"{}\"/></attvalues>\n",
+                                          escape(label.to_string().as_slice())).as_slice());
+                document.push_str("      </node>\n");
+            },
+            None        => {
+                document.push_str(format!("      <node id=\"{}\" label=\"{}\"/>\n", id, id).as_slice());
+            }
+        }
+    }
+    document.push_str("    </nodes>\n");
+
+    document.push_str("    <edges>\n");
+    let mut count = 0u;
+    for (from_key, to_key, value) in graph.edges_iter() {
+        let start = format!("      <edge id=\"{}\" source=\"{}\" target=\"{}\"",
+                            count,
+                            escape(from_key.to_string().as_slice()),
+                            escape(to_key.to_string().as_slice()));
+        count += 1;
+        let value = value.map(|v| v.to_string());
+        match value.as_ref().and_then(|v| from_str::<f64>(v.as_slice())) {
+            Some(weight) => {
+                document.push_str(format!("{} weight=\"{}\"/>\n", start, weight).as_slice());
+            },
+            None         => match value {
+                Some(value) => {
+                    document.push_str(format!("{}>\n", start).as_slice());
+                    document.push_str(format!("        <attvalues><attvalue for=\"value\" value=\"{}\"/></attvalues>\n",
+                                              escape(value.as_slice())).as_slice());
+                    document.push_str("      </edge>\n");
+                },
+                None        => document.push_str(format!("{}/>\n", start).as_slice())
+            }
+        }
+    }
+    document.push_str("    </edges>\n");
+    document.push_str("  </graph>\n</gexf>\n");
+    document
+}
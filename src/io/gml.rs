@@ -0,0 +1,309 @@
+//! Import and export of Graphs in GML, the Graph Modelling Language read
+//! and written by networkx:
+//!
+//! ```ignore
+//! graph [
+//!   directed 1
+//!   node [ id "a" label "x" ]
+//!   node [ id "b" ]
+//!   edge [ source "a" target "b" value 1 ]
+//! ]
+//! ```
+//!
+//! The keys are the ids of the nodes, the labels and the values are the
+//! `label` of the nodes and the `value` of the edges, a missing label or
+//! value is an absent attribute. They are written with ToString, unquoted
+//! when they are numbers, and read back with FromStr. The other attributes
+//! are ignored on import. An undirected Graph lists each Edge once.
+
+use std::from_str::FromStr;
+use std::io::IoResult;
+
+use graph::Graph;
+use io::ParseError;
+
+// a value of the document: a number or a string, or a list of attributes
+// with the line of their key
+#[deriving(Clone, PartialEq, Show)]
+enum Value {
+    Scalar(String),
+    List(Vec<(String, Value, uint)>)
+}
+
+#[deriving(Clone, PartialEq, Show)]
+enum Token {
+    Word(String),
+    Text(String),
+    Open,
+    Close
+}
+
+/**
+* Write a Graph as a GML document.
+*
+* # Arguments
+* * graph - The graph to write
+* * writer - The writer receiving the document
+*
+* # Return
+* Ok(()), Err(error) if the writing fails.
+*/
+pub fn write<K: ToString + Ord + Eq + Clone,
+             L: ToString + Eq + Clone,
+             V: ToString + PartialEq + Clone,
+             W: Writer>
+             (graph: &Graph<K, L, V>,
+             writer: &mut W)
+             -> IoResult<()> {
+    writer.write_str(to_gml(graph).as_slice())
+}
+
+/**
+* Convert a Graph to a GML document.
+*
+* # Arguments
+* * graph - The graph to convert
+*
+* # Return
+* The GML document.
+*/
+pub fn to_gml<K: ToString + Ord + Eq + Clone,
+              L: ToString + Eq + Clone,
+              V: ToString + PartialEq + Clone>
+              (graph: &Graph<K, L, V>) -> String {
+    let mut document = String::new();
+    document.push_str("graph [\n");
+    document.push_str(format!("  directed {}\n", if graph.is_directed() { 1u } else { 0u }).as_slice());
+    for vertex in graph.vertices_ref_iter() {
+        document.push_str("  node [\n");
+        document.push_str(format!("    id {}\n", quote(vertex.get_key().to_string().as_slice())).as_slice());
+        match vertex.get_label() {
+            Some(label) => {
+                document.push_str(format!("    label {}\n", quote(label.to_string().as_slice())).as_slice());
+            },
+            None        => {}
+        }
+        document.push_str("  ]\n");
+    }
+    for (from_key, to_key, value) in graph.edges_iter() {
+        document.push_str("  edge [\n");
+        document.push_str(format!("    source {}\n", quote(from_key.to_string().as_slice())).as_slice());
+        document.push_str(format!("    target {}\n", quote(to_key.to_string().as_slice())).as_slice());
+        match value {
+            Some(value) => {
+                document.push_str(format!("    value {}\n", quote(value.to_string().as_slice())).as_slice());
+            },
+            None        => {}
+        }
+        document.push_str("  ]\n");
+    }
+    document.push_str("]\n");
+    document
+}
+
+/**
+* Build a Graph from a GML document.
+*
+* # Arguments
+* * input - The document to parse
+*
+* # Return
+* Ok(graph), Err(error) at the first syntax error, if there is no graph,
+* an edge has an unknown node or a key, a label or a value can't be
+* converted.
+*/
+pub fn parse<K: ToString + Ord + Eq + Clone + FromStr,
+             L: ToString + Eq + Clone + FromStr,
+             V: ToString + PartialEq + Clone + FromStr>
+             (input: &str) -> Result<Graph<K, L, V>, ParseError> {
+    let tokens = try!(tokenize(input));
+    let mut pos = 0;
+    let document = try!(parse_list(tokens.as_slice(), &mut pos, false));
+    let attributes = match document.iter().find(|&&(ref key, _, _)| key.as_slice() == "graph") {
+        Some(&(_, List(ref attributes), _)) => attributes,
+        Some(&(_, _, line))                 => return Err(ParseError::new(line, "graph is not a list")),
+        None                                => return Err(ParseError::new(0, "no graph"))
+    };
+
+    let directed = match find(attributes, "directed") {
+        Some((&Scalar(ref d), line)) => match from_str::<int>(d.as_slice()) {
+            Some(d) => d != 0,
+            None    => return Err(ParseError::new(line, "directed is not a number"))
+        },
+        Some((_, line))              => return Err(ParseError::new(line, "directed is not a number")),
+        None                         => false
+    };
+    let mut graph = if directed { Graph::new() } else { Graph::new_undirected() };
+
+    for &(ref name, ref element, line) in attributes.iter() {
+        let element = match (name.as_slice(), element) {
+            ("node", &List(ref element)) | ("edge", &List(ref element)) => element,
+            ("node", _) | ("edge", _)                                   => {
+                return Err(ParseError::new(line, format!("{} is not a list", name).as_slice()))
+            },
+            _                                                           => continue
+        };
+        if name.as_slice() == "node" {
+            let key: K = match try!(field(element, "id")) {
+                Some(key) => key,
+                None      => return Err(ParseError::new(line, "node without id"))
+            };
+            let label: Option<L> = try!(field(element, "label"));
+            graph.add_vertex_opt_l(key, label);
+        }
+    }
+    for &(ref name, ref element, line) in attributes.iter() {
+        let element = match (name.as_slice(), element) {
+            ("edge", &List(ref element)) => element,
+            _                            => continue
+        };
+        let (from_key, to_key): (K, K) = match (try!(field(element, "source")),
+                                                try!(field(element, "target"))) {
+            (Some(from_key), Some(to_key)) => (from_key, to_key),
+            _                              => {
+                return Err(ParseError::new(line, "edge without source or target"))
+            }
+        };
+        if !graph.vertex_exist(&from_key) || !graph.vertex_exist(&to_key) {
+            return Err(ParseError::new(line, format!("edge between unknown nodes {} and {}",
+                                                     from_key.to_string(),
+                                                     to_key.to_string()).as_slice()))
+        }
+        let value: Option<V> = try!(field(element, "value"));
+        graph.add_edge_opt_v(from_key, to_key, value);
+    }
+    Ok(graph)
+}
+
+/**
+* Build a Graph from a GML document read from a Reader.
+*
+* # Arguments
+* * reader - The reader of the document to parse
+*
+* # Return
+* Ok(graph), Err(error) if the reading fails or as parse.
+*/
+pub fn read<K: ToString + Ord + Eq + Clone + FromStr,
+            L: ToString + Eq + Clone + FromStr,
+            V: ToString + PartialEq + Clone + FromStr,
+            R: Reader>
+            (reader: &mut R) -> Result<Graph<K, L, V>, ParseError> {
+    match reader.read_to_string() {
+        Ok(input) => parse(input.as_slice()),
+        Err(e)    => Err(ParseError::new(0, e.desc))
+    }
+}
+
+// the first attribute with a name and its line
+fn find<'a>(attributes: &'a Vec<(String, Value, uint)>, name: &str) -> Option<(&'a Value, uint)> {
+    attributes.iter().find(|&&(ref key, _, _)| key.as_slice() == name).map(|&(_, ref value, line)| (value, line))
+}
+
+// the converted scalar attribute, None if it is absent
+fn field<T: FromStr>(attributes: &Vec<(String, Value, uint)>, name: &str) -> Result<Option<T>, ParseError> {
+    match find(attributes, name) {
+        Some((&Scalar(ref text), line)) => match from_str(text.as_slice()) {
+            Some(converted) => Ok(Some(converted)),
+            None            => Err(ParseError::new(line, format!("invalid {} {}", name, text).as_slice()))
+        },
+        Some((_, line))                 => Err(ParseError::new(line, format!("{} is a list", name).as_slice())),
+        None                            => Ok(None)
+    }
+}
+
+// the attributes until the closing bracket of the list, or the end of the
+// document at the top level
+fn parse_list(tokens: &[(Token, uint)], pos: &mut uint, nested: bool)
+              -> Result<Vec<(String, Value, uint)>, ParseError> {
+    let mut attributes = Vec::new();
+    loop {
+        if *pos == tokens.len() {
+            if nested {
+                return Err(ParseError::new(tokens.last().map_or(0, |&(_, line)| line), "unclosed list"))
+            }
+            return Ok(attributes)
+        }
+        let (ref token, line) = tokens[*pos];
+        *pos += 1;
+        let key = match *token {
+            Word(ref key)       => key.clone(),
+            Close if nested     => return Ok(attributes),
+            _                   => return Err(ParseError::new(line, "expected a key"))
+        };
+        if *pos == tokens.len() {
+            return Err(ParseError::new(line, format!("{} without value", key).as_slice()))
+        }
+        let (ref token, value_line) = tokens[*pos];
+        *pos += 1;
+        let value = match *token {
+            Word(ref text) | Text(ref text) => Scalar(text.clone()),
+            Open                            => List(try!(parse_list(tokens, pos, true))),
+            Close                           => {
+                return Err(ParseError::new(value_line, format!("{} without value", key).as_slice()))
+            }
+        };
+        attributes.push((key, value, line));
+    }
+}
+
+// split the document in keys, numbers, strings and brackets, a line
+// starting with # is a comment
+fn tokenize(input: &str) -> Result<Vec<(Token, uint)>, ParseError> {
+    let mut tokens = Vec::new();
+    for (i, text) in input.lines().enumerate() {
+        let line = i + 1;
+        if text.trim_left().starts_with("#") {
+            continue
+        }
+        let mut rest = text;
+        loop {
+            rest = rest.trim_left();
+            if rest.is_empty() {
+                break
+            }
+            if rest.starts_with("[") {
+                tokens.push((Open, line));
+                rest = rest.slice_from(1);
+            } else if rest.starts_with("]") {
+                tokens.push((Close, line));
+                rest = rest.slice_from(1);
+            } else if rest.starts_with("\"") {
+                let end = match rest.slice_from(1).find('"') {
+                    Some(end) => end + 1,
+                    None      => return Err(ParseError::new(line, "unterminated string"))
+                };
+                tokens.push((Text(unquote(rest.slice(1, end))), line));
+                rest = rest.slice_from(end + 1);
+            } else {
+                let end = rest.find(|c: char| c.is_whitespace() || c == '[' || c == ']' || c == '"')
+                              .unwrap_or(rest.len());
+                tokens.push((Word(rest.slice_to(end).to_string()), line));
+                rest = rest.slice_from(end);
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+// the value written bare if it is a number, as a string otherwise
+fn quote(text: &str) -> String {
+    if from_str::<f64>(text).is_some() {
+        return text.to_string()
+    }
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push_char('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("&quot;"),
+            '&' => quoted.push_str("&amp;"),
+            c   => quoted.push_char(c)
+        }
+    }
+    quoted.push_char('"');
+    quoted
+}
+
+fn unquote(text: &str) -> String {
+    text.replace("&quot;", "\"").replace("&amp;", "&")
+}
@@ -0,0 +1,452 @@
+//! Export and import of Graphs in GraphML.
+//!
+//! The labels and the values are written as the `label` data of the nodes
+//! and the `value` data of the edges, declared by `<key>` elements. On
+//! import the node data named `label` is the label and the edge data named
+//! `value` is the value, the other data are ignored. The keys, the labels
+//! and the values are converted with ToString and FromStr.
+//!
+//! Nested graphs, hyperedges and ports are not supported. An undirected
+//! GraphML document is imported as an undirected Graph, the undirected
+//! edges of a document mixing both kinds are imported in both directions.
+
+use std::collections::{TreeMap, TreeSet};
+use std::char::from_u32;
+use std::from_str::FromStr;
+use std::io::IoResult;
+use std::num::from_str_radix;
+
+use graph::Graph;
+use io::{ParseError, escape};
+
+#[deriving(Clone, PartialEq, Show)]
+enum Event {
+    Start(String, TreeMap<String, String>, bool),
+    End(String),
+    Text(String)
+}
+
+/**
+* Write a Graph as a GraphML document.
+*
+* # Arguments
+* * graph - The graph to write
+* * writer - The writer receiving the document
+*
+* # Return
+* Ok(()), Err(error) if the writing fails.
+*/
+pub fn write<K: ToString + Ord + Eq + Clone,
+             L: ToString + Eq + Clone,
+             V: ToString + PartialEq + Clone,
+             W: Writer>
+             (graph: &Graph<K, L, V>,
+             writer: &mut W)
+             -> IoResult<()> {
+    writer.write_str(to_graphml(graph).as_slice())
+}
+
+/**
+* Convert a Graph to a GraphML document.
+*
+* # Arguments
+* * graph - The graph to convert
+*
+* # Return
+* The GraphML document.
+*/
+pub fn to_graphml<K: ToString + Ord + Eq + Clone,
+                  L: ToString + Eq + Clone,
+                  V: ToString + PartialEq + Clone>
+                  (graph: &Graph<K, L, V>) -> String {
+    let mut document = String::new();
+    document.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    document.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+    document.push_str("  <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n");
+    document.push_str("  <key id=\"value\" for=\"edge\" attr.name=\"value\" attr.type=\"string\"/>\n");
+    document.push_str(format!("  <graph id=\"G\" edgedefault=\"{}\">\n",
+                              if graph.is_directed() { "directed" } else { "undirected" }).as_slice());
+    for vertex in graph.vertices_ref_iter() {
+        let id = escape(vertex.get_key().to_string().as_slice());
+        match vertex.get_label() {
+            Some(label) => {
+                document.push_str(format!("    <node id=\"{}\"><data key=\"label\">{}</data></node>\n",
+                                          id, escape(label.to_string().as_slice())).as_slice());
+            },
+            None        => document.push_str(format!("    <node id=\"{}\"/>\n", id).as_slice())
+        }
+    }
+    for vertex in graph.vertices_ref_iter() {
+        for (to_key, value) in vertex.edges_iter() {
+            // an undirected Graph stores each Edge in both directions
+            if !graph.is_directed() && vertex.get_key() > to_key {
+                continue
+            }
+            let source = escape(vertex.get_key().to_string().as_slice());
+            let target = escape(to_key.to_string().as_slice());
+            match value {
+                Some(value) => {
+                    document.push_str(format!("    <edge source=\"{}\" target=\"{}\"><data key=\"value\">{}</data></edge>\n",
+                                              source, target, escape(value.to_string().as_slice())).as_slice());
+                },
+                None        => {
+                    document.push_str(format!("    <edge source=\"{}\" target=\"{}\"/>\n",
+                                              source, target).as_slice());
+                }
+            }
+        }
+    }
+    document.push_str("  </graph>\n</graphml>\n");
+    document
+}
+
+/**
+* Build a Graph from a GraphML document.
+*
+* # Arguments
+* * input - The document to parse
+*
+* # Return
+* Ok(graph), Err(error) at the first syntax error, data inside a data,
+* unknown node or id, label or value which can't be converted.
+*/
+pub fn parse<K: ToString + Ord + Eq + Clone + FromStr,
+             L: ToString + Eq + Clone + FromStr,
+             V: ToString + PartialEq + Clone + FromStr>
+             (input: &str) -> Result<Graph<K, L, V>, ParseError> {
+    let events = try!(tokenize(input));
+
+    // (id, for, attr.name, default) of the keys
+    let mut keys: Vec<(String, String, String, Option<String>)> = Vec::new();
+    // (id, data, line) of the nodes and (source, target, directed, data,
+    // line) of the edges
+    let mut nodes: Vec<(String, TreeMap<String, String>, uint)> = Vec::new();
+    let mut edges: Vec<(String, String, Option<bool>, TreeMap<String, String>, uint)> = Vec::new();
+    let mut directed = true;
+    let mut in_graph = false;
+    let mut stack: Vec<String> = Vec::new();
+    let mut data: Option<(String, String)> = None;
+
+    for &(ref event, line) in events.iter() {
+        match *event {
+            Start(ref name, ref attributes, closed) => {
+                let attribute = |attr: &str| attributes.find(&attr.to_string()).map(|v| v.clone());
+                match name.as_slice() {
+                    "graph" => {
+                        if in_graph {
+                            return Err(ParseError::new(line, "nested graphs are not supported"))
+                        }
+                        in_graph = !closed;
+                        directed = attribute("edgedefault").map_or(true, |d| d.as_slice() != "undirected");
+                    },
+                    "key"   => {
+                        let id = match attribute("id") {
+                            Some(id) => id,
+                            None     => return Err(ParseError::new(line, "key without id"))
+                        };
+                        keys.push((id,
+                                   attribute("for").unwrap_or("all".to_string()),
+                                   attribute("attr.name").unwrap_or(String::new()),
+                                   None));
+                    },
+                    "node"  => {
+                        let id = match attribute("id") {
+                            Some(id) => id,
+                            None     => return Err(ParseError::new(line, "node without id"))
+                        };
+                        nodes.push((id, TreeMap::new(), line));
+                    },
+                    "edge"  => {
+                        let (source, target) = match (attribute("source"), attribute("target")) {
+                            (Some(source), Some(target)) => (source, target),
+                            _                            => {
+                                return Err(ParseError::new(line, "edge without source or target"))
+                            }
+                        };
+                        let edge_directed = attribute("directed").map(|d| d.as_slice() == "true");
+                        edges.push((source, target, edge_directed, TreeMap::new(), line));
+                    },
+                    "data" | "default" if data.is_some() => {
+                        return Err(ParseError::new(line, format!("<{}> inside a data", name).as_slice()))
+                    },
+                    "data"  => {
+                        match attribute("key") {
+                            Some(key) => data = Some((key, String::new())),
+                            None      => return Err(ParseError::new(line, "data without key"))
+                        }
+                    },
+                    "default" => data = Some((String::new(), String::new())),
+                    "hyperedge" | "port" => {
+                        return Err(ParseError::new(line, format!("{} is not supported", name).as_slice()))
+                    },
+                    _       => {}
+                }
+                if !closed {
+                    stack.push(name.clone());
+                } else {
+                    close(name.as_slice(), &mut data, &mut keys, &mut nodes, &mut edges, &stack);
+                }
+            },
+            End(ref name) => {
+                match stack.pop() {
+                    Some(ref open) if open == name => {},
+                    _                              => {
+                        return Err(ParseError::new(line, format!("unexpected </{}>", name).as_slice()))
+                    }
+                }
+                if name.as_slice() == "graph" {
+                    in_graph = false;
+                }
+                close(name.as_slice(), &mut data, &mut keys, &mut nodes, &mut edges, &stack);
+            },
+            Text(ref text) => {
+                match data {
+                    Some((_, ref mut content)) => content.push_str(text.as_slice()),
+                    None                       => {}
+                }
+            }
+        }
+    }
+    match stack.last() {
+        Some(name) => return Err(ParseError::new(0, format!("<{}> is not closed", name).as_slice())),
+        None       => {}
+    }
+
+    // the key of the labels and of the values with their defaults, the
+    // other data are ignored
+    let choose = |target: &str, attr_name: &str| -> Option<(String, Option<String>)> {
+        keys.iter().find(|k| {
+            (k.ref1().as_slice() == target || k.ref1().as_slice() == "all") &&
+            k.ref2().as_slice() == attr_name
+        }).map(|&(ref id, _, _, ref default)| (id.clone(), default.clone()))
+    };
+    let label_key = choose("node", "label");
+    let value_key = choose("edge", "value");
+
+    let mut graph = if !directed && edges.iter().all(|e| *e.ref2() != Some(true)) {
+        Graph::new_undirected()
+    } else {
+        Graph::new()
+    };
+    let mut ids = TreeSet::new();
+    for &(ref id, ref node_data, line) in nodes.iter() {
+        let key: K = match from_str(id.as_slice()) {
+            Some(key) => key,
+            None      => return Err(ParseError::new(line, format!("invalid node id {}", id).as_slice()))
+        };
+        let label: Option<L> = match find_data(node_data, &label_key) {
+            Some(text) => match from_str(text.as_slice()) {
+                Some(label) => Some(label),
+                None        => return Err(ParseError::new(line, format!("invalid label {}", text).as_slice()))
+            },
+            None       => None
+        };
+        ids.insert(id.clone());
+        graph.add_vertex_opt_l(key, label);
+    }
+    for &(ref source, ref target, edge_directed, ref edge_data, line) in edges.iter() {
+        if !ids.contains(source) || !ids.contains(target) {
+            return Err(ParseError::new(line, "edge between unknown nodes"))
+        }
+        let value: Option<V> = match find_data(edge_data, &value_key) {
+            Some(text) => match from_str(text.as_slice()) {
+                Some(value) => Some(value),
+                None        => return Err(ParseError::new(line, format!("invalid value {}", text).as_slice()))
+            },
+            None       => None
+        };
+        let from_key: K = from_str(source.as_slice()).unwrap();
+        let to_key: K = from_str(target.as_slice()).unwrap();
+        if graph.is_directed() && !edge_directed.unwrap_or(directed) {
+            graph.add_edge_opt_v(to_key.clone(), from_key.clone(), value.clone());
+        }
+        graph.add_edge_opt_v(from_key, to_key, value);
+    }
+    Ok(graph)
+}
+
+/**
+* Build a Graph from a GraphML document read from a Reader.
+*
+* # Arguments
+* * reader - The reader of the document to parse
+*
+* # Return
+* Ok(graph), Err(error) if the reading fails or as parse.
+*/
+pub fn read<K: ToString + Ord + Eq + Clone + FromStr,
+            L: ToString + Eq + Clone + FromStr,
+            V: ToString + PartialEq + Clone + FromStr,
+            R: Reader>
+            (reader: &mut R) -> Result<Graph<K, L, V>, ParseError> {
+    match reader.read_to_string() {
+        Ok(input) => parse(input.as_slice()),
+        Err(e)    => Err(ParseError::new(0, e.desc))
+    }
+}
+
+// store the data of an element when it ends
+fn close(name: &str,
+         data: &mut Option<(String, String)>,
+         keys: &mut Vec<(String, String, String, Option<String>)>,
+         nodes: &mut Vec<(String, TreeMap<String, String>, uint)>,
+         edges: &mut Vec<(String, String, Option<bool>, TreeMap<String, String>, uint)>,
+         stack: &Vec<String>) {
+    let parent = stack.last().map(|p| p.as_slice());
+    match name {
+        "data"    => {
+            let (key, text) = data.take_unwrap();
+            match parent {
+                Some("node") => { nodes.mut_last().unwrap().mut1().insert(key, text); },
+                Some("edge") => { edges.mut_last().unwrap().mut3().insert(key, text); },
+                _            => {}
+            }
+        },
+        "default" => {
+            let (_, text) = data.take_unwrap();
+            if parent == Some("key") {
+                *keys.mut_last().unwrap().mut3() = Some(text);
+            }
+        },
+        _         => {}
+    }
+}
+
+// the data of a key or its default
+fn find_data(data: &TreeMap<String, String>, key: &Option<(String, Option<String>)>) -> Option<String> {
+    match *key {
+        Some((ref id, ref default)) => data.find(id).map(|d| d.clone()).or(default.clone()),
+        None                        => None
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<(Event, uint)>, ParseError> {
+    let mut events = Vec::new();
+    let mut pos = 0u;
+    let mut line = 1u;
+    while pos < input.len() {
+        let rest = input.slice_from(pos);
+        let (length, event) = if rest.starts_with("<!--") {
+            (try!(skip_to(rest, "-->", line)), None)
+        } else if rest.starts_with("<![CDATA[") {
+            let end = try!(skip_to(rest, "]]>", line));
+            (end, Some(Text(rest.slice(9, end - 3).to_string())))
+        } else if rest.starts_with("<?") {
+            (try!(skip_to(rest, "?>", line)), None)
+        } else if rest.starts_with("<!") {
+            (try!(skip_to(rest, ">", line)), None)
+        } else if rest.starts_with("</") {
+            let end = try!(skip_to(rest, ">", line));
+            (end, Some(End(rest.slice(2, end - 1).trim().to_string())))
+        } else if rest.starts_with("<") {
+            let end = try!(tag_end(rest, line));
+            let (name, attributes, closed) = try!(parse_tag(rest.slice(1, end - 1), line));
+            (end, Some(Start(name, attributes, closed)))
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            (end, Some(Text(try!(unescape(rest.slice_to(end), line)))))
+        };
+        match event {
+            Some(event) => events.push((event, line)),
+            None        => {}
+        }
+        line += rest.slice_to(length).chars().filter(|&c| c == '\n').count();
+        pos += length;
+    }
+    Ok(events)
+}
+
+// the length up to the end of a delimiter
+fn skip_to(rest: &str, delimiter: &str, line: uint) -> Result<uint, ParseError> {
+    match rest.find_str(delimiter) {
+        Some(i) => Ok(i + delimiter.len()),
+        None    => Err(ParseError::new(line, format!("expected {}", delimiter).as_slice()))
+    }
+}
+
+// the length of a tag, its attributes values may contain a '>'
+fn tag_end(rest: &str, line: uint) -> Result<uint, ParseError> {
+    let mut quote: Option<char> = None;
+    for (i, c) in rest.char_indices() {
+        match (quote, c) {
+            (None, '>')                   => return Ok(i + 1),
+            (None, '"') | (None, '\'')    => quote = Some(c),
+            (Some(q), c) if q == c        => quote = None,
+            _                             => {}
+        }
+    }
+    Err(ParseError::new(line, "unterminated tag"))
+}
+
+// the name, the attributes and the self closing of the content of a tag
+fn parse_tag(content: &str, line: uint)
+             -> Result<(String, TreeMap<String, String>, bool), ParseError> {
+    let content = content.trim();
+    let closed = content.ends_with("/");
+    let content = if closed { content.slice_to(content.len() - 1) } else { content };
+    let name_end = content.find(|c: char| c.is_whitespace()).unwrap_or(content.len());
+    let name = content.slice_to(name_end).to_string();
+    let mut attributes = TreeMap::new();
+    let mut rest = content.slice_from(name_end).trim_left();
+    while !rest.is_empty() {
+        let equal = match rest.find('=') {
+            Some(equal) => equal,
+            None        => return Err(ParseError::new(line, "expected '=' in an attribute"))
+        };
+        let attr_name = rest.slice_to(equal).trim().to_string();
+        let value_part = rest.slice_from(equal + 1).trim_left();
+        let quote = match value_part.chars().next() {
+            Some(q) if q == '"' || q == '\'' => q,
+            _                                => {
+                return Err(ParseError::new(line, "expected a quoted attribute value"))
+            }
+        };
+        let end = match value_part.slice_from(1).find(quote) {
+            Some(end) => end + 1,
+            None      => return Err(ParseError::new(line, "unterminated attribute value"))
+        };
+        attributes.insert(attr_name, try!(unescape(value_part.slice(1, end), line)));
+        rest = value_part.slice_from(end + 1).trim_left();
+    }
+    Ok((name, attributes, closed))
+}
+
+fn unescape(text: &str, line: uint) -> Result<String, ParseError> {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+    loop {
+        let start = match rest.find('&') {
+            Some(start) => start,
+            None        => break
+        };
+        unescaped.push_str(rest.slice_to(start));
+        let end = match rest.slice_from(start).find(';') {
+            Some(end) => start + end,
+            None      => return Err(ParseError::new(line, "unterminated entity"))
+        };
+        let entity = rest.slice(start + 1, end);
+        let c = match entity {
+            "amp"  => Some('&'),
+            "lt"   => Some('<'),
+            "gt"   => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ if entity.starts_with("#x") => {
+                from_str_radix::<u32>(entity.slice_from(2), 16).and_then(from_u32)
+            },
+            _ if entity.starts_with("#")  => {
+                from_str::<u32>(entity.slice_from(1)).and_then(from_u32)
+            },
+            _      => None
+        };
+        match c {
+            Some(c) => unescaped.push_char(c),
+            None    => {
+                return Err(ParseError::new(line, format!("unknown entity &{};", entity).as_slice()))
+            }
+        }
+        rest = rest.slice_from(end + 1);
+    }
+    unescaped.push_str(rest);
+    Ok(unescaped)
+}
@@ -0,0 +1,226 @@
+//! Import and export of Graphs as node-link JSON documents, the format read
+//! by the d3.js force layouts:
+//!
+//! ```ignore
+//! {
+//!   "directed": true,
+//!   "nodes": [{"id": "a", "label": "x"}, {"id": "b"}],
+//!   "edges": [{"source": "a", "target": "b", "value": "1"}]
+//! }
+//! ```
+//!
+//! The keys, the labels and the values are written as JSON strings with
+//! ToString and read back with FromStr, numbers are also accepted on import.
+//! A missing label or value is an absent member. An undirected Graph lists
+//! each Edge once.
+
+use std::from_str::FromStr;
+use std::io::IoResult;
+use serialize::json;
+use serialize::json::Json;
+
+use graph::Graph;
+use io::ParseError;
+
+/**
+* Write a Graph as a JSON document.
+*
+* # Arguments
+* * graph - The graph to write
+* * writer - The writer receiving the document
+*
+* # Return
+* Ok(()), Err(error) if the writing fails.
+*/
+pub fn write<K: ToString + Ord + Eq + Clone,
+             L: ToString + Eq + Clone,
+             V: ToString + PartialEq + Clone,
+             W: Writer>
+             (graph: &Graph<K, L, V>,
+             writer: &mut W)
+             -> IoResult<()> {
+    writer.write_str(to_json(graph).as_slice())
+}
+
+/**
+* Convert a Graph to a JSON document.
+*
+* # Arguments
+* * graph - The graph to convert
+*
+* # Return
+* The JSON document.
+*/
+pub fn to_json<K: ToString + Ord + Eq + Clone,
+               L: ToString + Eq + Clone,
+               V: ToString + PartialEq + Clone>
+               (graph: &Graph<K, L, V>) -> String {
+    let nodes: Vec<String> = graph.vertices_ref_iter().map(|vertex| {
+        let id = quote(vertex.get_key().to_string().as_slice());
+        match vertex.get_label() {
+            Some(label) => format!("{{\"id\": {}, \"label\": {}}}",
+                                   id, quote(label.to_string().as_slice())),
+            None        => format!("{{\"id\": {}}}", id)
+        }
+    }).collect();
+    let edges: Vec<String> = graph.edges_iter().map(|(from_key, to_key, value)| {
+        let source = quote(from_key.to_string().as_slice());
+        let target = quote(to_key.to_string().as_slice());
+        match value {
+            Some(value) => format!("{{\"source\": {}, \"target\": {}, \"value\": {}}}",
+                                   source, target, quote(value.to_string().as_slice())),
+            None        => format!("{{\"source\": {}, \"target\": {}}}", source, target)
+        }
+    }).collect();
+
+    let mut document = String::new();
+    document.push_str("{\n");
+    document.push_str(format!("  \"directed\": {},\n", graph.is_directed()).as_slice());
+    document.push_str("  \"nodes\": [");
+    push_list(&mut document, nodes.as_slice());
+    document.push_str("],\n");
+    document.push_str("  \"edges\": [");
+    push_list(&mut document, edges.as_slice());
+    document.push_str("]\n");
+    document.push_str("}\n");
+    document
+}
+
+/**
+* Build a Graph from a JSON document.
+*
+* The Edges to an unknown node are an error.
+*
+* # Arguments
+* * input - The JSON document
+*
+* # Return
+* Ok(graph), Err(error) if the document is not valid JSON, does not follow
+* the format or has a key, a label or a value which can't be converted.
+*/
+pub fn from_json<K: ToString + Ord + Eq + Clone + FromStr,
+                 L: ToString + Eq + Clone + FromStr,
+                 V: ToString + PartialEq + Clone + FromStr>
+                 (input: &str) -> Result<Graph<K, L, V>, ParseError> {
+    let document = match json::from_str(input) {
+        Ok(document) => document,
+        Err(e)       => return Err(ParseError::new(0, format!("{}", e).as_slice()))
+    };
+    if document.as_object().is_none() {
+        return Err(ParseError::new(0, "the document is not an object"))
+    }
+    let directed = match document.find(&"directed".to_string()) {
+        Some(directed) => match directed.as_boolean() {
+            Some(directed) => directed,
+            None           => return Err(ParseError::new(0, "directed is not a boolean"))
+        },
+        None           => true
+    };
+    let mut graph = if directed { Graph::new() } else { Graph::new_undirected() };
+
+    for node in try!(members(&document, "nodes")).iter() {
+        let key: K = match try!(field(node, "id")) {
+            Some(key) => key,
+            None      => return Err(ParseError::new(0, "node without id"))
+        };
+        let label: Option<L> = try!(field(node, "label"));
+        graph.add_vertex_opt_l(key, label);
+    }
+    for edge in try!(members(&document, "edges")).iter() {
+        let (from_key, to_key): (K, K) = match (try!(field(edge, "source")),
+                                                try!(field(edge, "target"))) {
+            (Some(from_key), Some(to_key)) => (from_key, to_key),
+            _                              => {
+                return Err(ParseError::new(0, "edge without source or target"))
+            }
+        };
+        if !graph.vertex_exist(&from_key) || !graph.vertex_exist(&to_key) {
+            return Err(ParseError::new(0, format!("edge between unknown nodes {} and {}",
+                                                  from_key.to_string(),
+                                                  to_key.to_string()).as_slice()))
+        }
+        let value: Option<V> = try!(field(edge, "value"));
+        graph.add_edge_opt_v(from_key, to_key, value);
+    }
+    Ok(graph)
+}
+
+/**
+* Build a Graph from a JSON document read from a Reader.
+*
+* # Arguments
+* * reader - The reader of the document to parse
+*
+* # Return
+* Ok(graph), Err(error) if the reading fails or as from_json.
+*/
+pub fn read<K: ToString + Ord + Eq + Clone + FromStr,
+            L: ToString + Eq + Clone + FromStr,
+            V: ToString + PartialEq + Clone + FromStr,
+            R: Reader>
+            (reader: &mut R) -> Result<Graph<K, L, V>, ParseError> {
+    match reader.read_to_string() {
+        Ok(input) => from_json(input.as_slice()),
+        Err(e)    => Err(ParseError::new(0, e.desc))
+    }
+}
+
+// the elements of the list member of the document, none if it is absent
+fn members<'a>(document: &'a Json, name: &str) -> Result<Vec<&'a Json>, ParseError> {
+    match document.find(&name.to_string()) {
+        Some(list) => match list.as_list() {
+            Some(list) => Ok(list.iter().collect()),
+            None       => Err(ParseError::new(0, format!("{} is not a list", name).as_slice()))
+        },
+        None       => Ok(Vec::new())
+    }
+}
+
+// the converted string or number member of an object, None if it is absent
+fn field<T: FromStr>(object: &Json, name: &str) -> Result<Option<T>, ParseError> {
+    if object.as_object().is_none() {
+        return Err(ParseError::new(0, "a node or an edge is not an object"))
+    }
+    let text = match object.find(&name.to_string()) {
+        Some(member) if member.is_string() => member.as_string().unwrap().to_string(),
+        Some(member) if member.is_number() => member.to_string(),
+        Some(_)                            => {
+            return Err(ParseError::new(0, format!("{} is not a string", name).as_slice()))
+        },
+        None                               => return Ok(None)
+    };
+    match from_str(text.as_slice()) {
+        Some(converted) => Ok(Some(converted)),
+        None            => Err(ParseError::new(0, format!("invalid {} {}", name, text).as_slice()))
+    }
+}
+
+// append the elements, one per line
+fn push_list(document: &mut String, elements: &[String]) {
+    for (i, element) in elements.iter().enumerate() {
+        document.push_str(if i == 0 { "\n    " } else { ",\n    " });
+        document.push_str(element.as_slice());
+    }
+    if !elements.is_empty() {
+        document.push_str("\n  ");
+    }
+}
+
+// the JSON string of a text
+fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push_char('"');
+    for c in text.chars() {
+        match c {
+            '"'                => quoted.push_str("\\\""),
+            '\\'               => quoted.push_str("\\\\"),
+            '\n'               => quoted.push_str("\\n"),
+            '\r'               => quoted.push_str("\\r"),
+            '\t'               => quoted.push_str("\\t"),
+            c if c < '\x20'    => quoted.push_str(format!("\\u{:04x}", c as u32).as_slice()),
+            c                  => quoted.push_char(c)
+        }
+    }
+    quoted.push_char('"');
+    quoted
+}
@@ -0,0 +1,240 @@
+//! Import of Graphs from Matrix Market files, the format of the
+//! SuiteSparse collection.
+//!
+//! The matrix is the adjacency matrix of the Graph: each row and column is
+//! a Vertex, numbered from 0, and each nonzero entry (i, j) is an Edge from
+//! i to j valued by the entry. A rectangular matrix has a Vertex for each
+//! row or column of the longest side. A pattern matrix gives the value 1.0
+//! to its Edges. A symmetric matrix is an undirected Graph, a
+//! skew-symmetric one a directed Graph with the opposite Edges. Complex
+//! matrices are not supported.
+
+use std::ascii::StrAsciiExt;
+use std::from_str::FromStr;
+
+use graph::Graph;
+use io::ParseError;
+
+/**
+* Build a Graph from a Matrix Market document.
+*
+* # Arguments
+* * input - The document to parse
+*
+* # Return
+* Ok(graph), Err(error) if the header is missing or not supported, or an
+* entry is not valid.
+*/
+pub fn parse(input: &str) -> Result<Graph<uint, (), f64>, ParseError> {
+    let mut lines = input.lines().enumerate().map(|(i, line)| (i + 1, line.trim()));
+
+    let header: Vec<String> = match lines.next() {
+        Some((_, line)) => line.split(|c: char| c.is_whitespace())
+                               .filter(|word| !word.is_empty())
+                               .map(|word| word.to_ascii_lower())
+                               .collect(),
+        None            => return Err(ParseError::new(0, "empty document"))
+    };
+    if header.len() != 5 || header.get(0).as_slice() != "%%matrixmarket" || header.get(1).as_slice() != "matrix" {
+        return Err(ParseError::new(1, "missing %%MatrixMarket matrix header"))
+    }
+    let coordinate = match header.get(2).as_slice() {
+        "coordinate" => true,
+        "array"      => false,
+        format       => return Err(ParseError::new(1, format!("unknown format {}", format).as_slice()))
+    };
+    let pattern = match header.get(3).as_slice() {
+        "real" | "integer" => false,
+        "pattern"          => true,
+        field              => return Err(ParseError::new(1, format!("unsupported field {}", field).as_slice()))
+    };
+    let (symmetric, skew) = match header.get(4).as_slice() {
+        "general"                 => (false, false),
+        "symmetric" | "hermitian" => (true, false),
+        "skew-symmetric"          => (false, true),
+        symmetry                  => {
+            return Err(ParseError::new(1, format!("unknown symmetry {}", symmetry).as_slice()))
+        }
+    };
+    if pattern && !coordinate {
+        return Err(ParseError::new(1, "pattern array matrix"))
+    }
+
+    // the comments and the blank lines are skipped
+    let mut entries = lines.filter(|&(_, line)| !line.is_empty() && !line.starts_with("%"));
+    let (rows, columns) = match entries.next() {
+        Some((line, text)) => {
+            let size: Vec<uint> = try!(numbers(text, line));
+            match (coordinate, size.as_slice()) {
+                (true, [rows, columns, _]) | (false, [rows, columns]) => (rows, columns),
+                _                                                     => {
+                    return Err(ParseError::new(line, "invalid size line"))
+                }
+            }
+        },
+        None               => return Err(ParseError::new(0, "missing size line"))
+    };
+
+    let mut graph = if symmetric { Graph::new_undirected() } else { Graph::new() };
+    for v in range(0, if rows > columns { rows } else { columns }) {
+        graph.add_vertex(v);
+    }
+    if coordinate {
+        for (line, text) in entries {
+            let words: Vec<&str> = text.split(|c: char| c.is_whitespace()).filter(|w| !w.is_empty()).collect();
+            if words.len() != if pattern { 2 } else { 3 } {
+                return Err(ParseError::new(line, "invalid entry"))
+            }
+            let i: uint = try!(convert(*words.get(0), line));
+            let j: uint = try!(convert(*words.get(1), line));
+            if i == 0 || j == 0 || i > rows || j > columns {
+                return Err(ParseError::new(line, "entry out of the matrix"))
+            }
+            let value = if pattern { 1.0 } else { try!(convert(*words.get(2), line)) };
+            add_entry(&mut graph, skew, i - 1, j - 1, value);
+        }
+    } else {
+        // the entries column by column, only the lower triangle of a
+        // symmetric matrix and below the diagonal of a skew one
+        let mut cells = Vec::new();
+        for j in range(0, columns) {
+            let first = if skew { j + 1 } else if symmetric { j } else { 0 };
+            for i in range(first, rows) {
+                cells.push((i, j));
+            }
+        }
+        let mut count = 0;
+        for (line, text) in entries {
+            if count == cells.len() {
+                return Err(ParseError::new(line, "too many entries"))
+            }
+            let (i, j) = *cells.get(count);
+            add_entry(&mut graph, skew, i, j, try!(convert(text, line)));
+            count += 1;
+        }
+        if count < cells.len() {
+            return Err(ParseError::new(0, "missing entries"))
+        }
+    }
+    Ok(graph)
+}
+
+/**
+* Build a Graph from a Matrix Market document read from a Reader.
+*
+* # Arguments
+* * reader - The reader of the document to parse
+*
+* # Return
+* Ok(graph), Err(error) if the reading fails or as parse.
+*/
+pub fn read<R: Reader>(reader: &mut R) -> Result<Graph<uint, (), f64>, ParseError> {
+    match reader.read_to_string() {
+        Ok(input) => parse(input.as_slice()),
+        Err(e)    => Err(ParseError::new(0, e.desc))
+    }
+}
+
+// the Edge of a nonzero entry, and its opposite one in a skew-symmetric
+// matrix
+fn add_entry(graph: &mut Graph<uint, (), f64>, skew: bool, i: uint, j: uint, value: f64) {
+    if value != 0.0 {
+        graph.add_edge_v(i, j, value);
+        if skew && i != j {
+            graph.add_edge_v(j, i, -value);
+        }
+    }
+}
+
+fn numbers(text: &str, line: uint) -> Result<Vec<uint>, ParseError> {
+    let mut numbers = Vec::new();
+    for word in text.split(|c: char| c.is_whitespace()).filter(|w| !w.is_empty()) {
+        numbers.push(try!(convert(word, line)));
+    }
+    Ok(numbers)
+}
+
+fn convert<T: FromStr>(text: &str, line: uint) -> Result<T, ParseError> {
+    match from_str(text) {
+        Some(converted) => Ok(converted),
+        None            => Err(ParseError::new(line, format!("invalid number {}", text).as_slice()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse;
+
+    #[test]
+    fn parse_real_general() {
+        let graph = parse("%%MatrixMarket matrix coordinate real general\n\
+                           % a comment\n\
+                           3 3 3\n\
+                           1 2 1.5\n\
+                           2 3 -2\n\
+                           3 3 4e1\n").unwrap();
+        assert!(graph.is_directed());
+        assert_eq!(graph.vertices_iter().count(), 3);
+        assert_eq!(graph.edge_count(), 3);
+        assert_eq!(graph.get_edge_value(0, 1), Some(&1.5));
+        assert_eq!(graph.get_edge_value(1, 2), Some(&-2.0));
+        assert_eq!(graph.get_edge_value(2, 2), Some(&40.0));
+        assert!(!graph.edge_exist(1, 0));
+    }
+
+    #[test]
+    fn parse_pattern() {
+        let graph = parse("%%MatrixMarket matrix coordinate pattern general\n\
+                           2 4 2\n\
+                           1 4\n\
+                           2 1\n").unwrap();
+        assert_eq!(graph.vertices_iter().count(), 4);
+        assert_eq!(graph.get_edge_value(0, 3), Some(&1.0));
+        assert_eq!(graph.get_edge_value(1, 0), Some(&1.0));
+        assert_eq!(graph.edge_count(), 2);
+    }
+
+    #[test]
+    fn parse_symmetric() {
+        let graph = parse("%%MatrixMarket matrix coordinate real symmetric\n\
+                           3 3 2\n\
+                           2 1 0.5\n\
+                           3 3 1\n").unwrap();
+        assert!(!graph.is_directed());
+        assert_eq!(graph.get_edge_value(0, 1), Some(&0.5));
+        assert_eq!(graph.get_edge_value(1, 0), Some(&0.5));
+        assert_eq!(graph.get_edge_value(2, 2), Some(&1.0));
+        assert_eq!(graph.edge_count(), 2);
+    }
+
+    #[test]
+    fn parse_symmetric_array() {
+        let graph = parse("%%MatrixMarket matrix array real symmetric\n\
+                           2 2\n\
+                           1\n\
+                           3\n\
+                           0\n").unwrap();
+        assert_eq!(graph.get_edge_value(0, 0), Some(&1.0));
+        assert_eq!(graph.get_edge_value(0, 1), Some(&3.0));
+        assert_eq!(graph.get_edge_value(1, 0), Some(&3.0));
+        assert!(!graph.edge_exist(1, 1));
+    }
+
+    #[test]
+    fn parse_skew_symmetric() {
+        let graph = parse("%%MatrixMarket matrix coordinate integer skew-symmetric\n\
+                           2 2 1\n\
+                           2 1 3\n").unwrap();
+        assert!(graph.is_directed());
+        assert_eq!(graph.get_edge_value(1, 0), Some(&3.0));
+        assert_eq!(graph.get_edge_value(0, 1), Some(&-3.0));
+    }
+
+    #[test]
+    fn reject_invalid() {
+        assert!(parse("").is_err());
+        assert!(parse("%%MatrixMarket matrix coordinate complex general\n1 1 0\n").is_err());
+        assert!(parse("%%MatrixMarket matrix coordinate real general\n2 2 1\n3 1 1.0\n").is_err());
+        assert_eq!(parse("%%MatrixMarket matrix coordinate real general\n2 2 1\n1 x 1.0\n").err().unwrap().line, 3);
+    }
+}
@@ -0,0 +1,59 @@
+//! Import and export of Graphs in external formats.
+
+use std::fmt;
+
+pub use self::edge_list::{EdgeListFormat, write_edge_list, to_edge_list, parse_edge_list,
+                          read_edge_list};
+
+pub mod ntriples;
+pub mod graphml;
+pub mod json;
+pub mod gexf;
+pub mod gml;
+pub mod g6;
+pub mod matrix_market;
+pub mod binary;
+pub mod stream;
+pub mod dot;
+mod edge_list;
+
+/**
+* Error of an import, with the line where it happened.
+*/
+#[deriving(Clone, PartialEq)]
+pub struct ParseError {
+    /// The line of the input, starting at 1, 0 if unknown
+    pub line:          uint,
+    pub message:       String
+}
+
+impl ParseError {
+    pub fn new(line: uint, message: &str) -> ParseError {
+        ParseError {
+            line:       line,
+            message:    message.to_string()
+        }
+    }
+}
+
+impl fmt::Show for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+// escape the text of an XML attribute or element
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&'  => escaped.push_str("&amp;"),
+            '<'  => escaped.push_str("&lt;"),
+            '>'  => escaped.push_str("&gt;"),
+            '"'  => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c    => escaped.push_char(c)
+        }
+    }
+    escaped
+}
@@ -0,0 +1,282 @@
+//! Import of RDF data in N-Triples or in a subset of Turtle.
+//!
+//! Each subject and object is a Vertex and each triple an Edge valued with
+//! its predicate. The keys and the values are the terms in N-Triples
+//! syntax: `<iri>`, `_:blank` or `"literal"` with its `@lang` or
+//! `^^<datatype>` suffix, so the same term gets the same key in both
+//! formats.
+//!
+//! The Turtle subset has `@prefix` directives, prefixed names, `a` for
+//! rdf:type, and the `;` and `,` lists. Bases, collections, nested blank
+//! nodes, long strings and bare numbers or booleans are not supported.
+
+use std::collections::{HashMap, TreeSet};
+
+use graph::Graph;
+use io::ParseError;
+
+static RDF_TYPE: &'static str = "<http://www.w3.org/1999/02/22-rdf-syntax-ns#type>";
+
+/// The triples whose subject and object are already linked by another
+/// predicate, a Graph holds one Edge between two Vertices.
+pub type ParallelTriples = Vec<(String, String, String)>;
+
+#[deriving(Clone, PartialEq)]
+enum Token {
+    Iri(String),
+    Name(String),
+    Literal(String),
+    Datatype,
+    Dot,
+    Semicolon,
+    Comma
+}
+
+/**
+* Build a Graph from N-Triples or Turtle.
+*
+* # Arguments
+* * input - The text to parse
+*
+* # Return
+* Ok((graph, parallel)) with the Graph of the triples and the triples not
+* added because their subject and object are already linked, Err(error) at
+* the first syntax error.
+*/
+pub fn parse(input: &str)
+             -> Result<(Graph<String, String, String>, ParallelTriples), ParseError> {
+    let tokens = try!(tokenize(input));
+    let mut prefixes: HashMap<String, String> = HashMap::new();
+    let mut edges = Vec::new();
+    let mut linked = TreeSet::new();
+    let mut parallel = Vec::new();
+    let mut pos = 0u;
+
+    while pos < tokens.len() {
+        let (ref token, line) = *tokens.get(pos);
+        match *token {
+            Name(ref name) if name.as_slice() == "@prefix" => {
+                let prefix = match tokens.as_slice().get(pos + 1) {
+                    Some(&(Name(ref prefix), _)) if prefix.as_slice().ends_with(":") => {
+                        prefix.as_slice().slice_to(prefix.len() - 1).to_string()
+                    },
+                    _                                                                 => {
+                        return Err(ParseError::new(line, "expected a prefix"))
+                    }
+                };
+                let iri = match tokens.as_slice().get(pos + 2) {
+                    Some(&(Iri(ref iri), _)) => iri.clone(),
+                    _                        => return Err(ParseError::new(line, "expected an IRI"))
+                };
+                try!(expect_dot(&tokens, pos + 3, line));
+                prefixes.insert(prefix, iri);
+                pos += 4;
+                continue
+            },
+            _                                              => {}
+        }
+
+        let subject = try!(resolve(&tokens, &mut pos, &prefixes, false));
+        loop {
+            let predicate = try!(resolve(&tokens, &mut pos, &prefixes, true));
+            loop {
+                let object = try!(resolve(&tokens, &mut pos, &prefixes, false));
+                let pair = (subject.clone(), object.clone());
+                if linked.contains(&pair) {
+                    parallel.push((subject.clone(), predicate.clone(), object));
+                } else {
+                    linked.insert(pair);
+                    edges.push((subject.clone(), object, Some(predicate.clone())));
+                }
+                if !next_is(&tokens, pos, Comma) {
+                    break
+                }
+                pos += 1;
+            }
+            if !next_is(&tokens, pos, Semicolon) {
+                break
+            }
+            pos += 1;
+            // a trailing ; is allowed before the .
+            if next_is(&tokens, pos, Dot) {
+                break
+            }
+        }
+        try!(expect_dot(&tokens, pos, last_line(&tokens, pos)));
+        pos += 1;
+    }
+
+    Ok((Graph::from_edges(edges), parallel))
+}
+
+/**
+* Build a Graph from N-Triples or Turtle read from a Reader.
+*
+* # Arguments
+* * reader - The reader of the text to parse
+*
+* # Return
+* Ok((graph, parallel)) as parse, Err(error) if the reading fails or at the
+* first syntax error.
+*/
+pub fn read<R: Reader>(reader: &mut R)
+                       -> Result<(Graph<String, String, String>, ParallelTriples), ParseError> {
+    match reader.read_to_string() {
+        Ok(input) => parse(input.as_slice()),
+        Err(e)    => Err(ParseError::new(0, e.desc))
+    }
+}
+
+fn next_is(tokens: &Vec<(Token, uint)>, pos: uint, token: Token) -> bool {
+    pos < tokens.len() && tokens.get(pos).ref0() == &token
+}
+
+fn last_line(tokens: &Vec<(Token, uint)>, pos: uint) -> uint {
+    match tokens.get(if pos < tokens.len() { pos } else { pos - 1 }) {
+        &(_, line) => line
+    }
+}
+
+fn expect_dot(tokens: &Vec<(Token, uint)>,
+              pos: uint,
+              line: uint)
+              -> Result<(), ParseError> {
+    if next_is(tokens, pos, Dot) {
+        Ok(())
+    } else {
+        Err(ParseError::new(line, "expected a '.'"))
+    }
+}
+
+// the term at pos in N-Triples syntax, pos is moved after it
+fn resolve(tokens: &Vec<(Token, uint)>,
+           pos: &mut uint,
+           prefixes: &HashMap<String, String>,
+           predicate: bool)
+           -> Result<String, ParseError> {
+    if *pos >= tokens.len() {
+        return Err(ParseError::new(last_line(tokens, *pos), "unexpected end of input"))
+    }
+    let (ref token, line) = *tokens.get(*pos);
+    *pos += 1;
+    match *token {
+        Iri(ref iri)                                       => Ok(format!("<{}>", iri)),
+        Name(ref name) if predicate && name.as_slice() == "a" => Ok(RDF_TYPE.to_string()),
+        Name(ref name) if name.as_slice().starts_with("_:") => {
+            if predicate {
+                Err(ParseError::new(line, "a blank node can't be a predicate"))
+            } else {
+                Ok(name.clone())
+            }
+        },
+        Name(ref name)                                     => {
+            match name.as_slice().find(':') {
+                Some(i) => {
+                    match prefixes.find(&name.as_slice().slice_to(i).to_string()) {
+                        Some(iri) => Ok(format!("<{}{}>", iri, name.as_slice().slice_from(i + 1))),
+                        None      => Err(ParseError::new(line, "unknown prefix"))
+                    }
+                },
+                None    => Err(ParseError::new(line, "unsupported term"))
+            }
+        },
+        Literal(ref literal) if !predicate                 => {
+            if next_is(tokens, *pos, Datatype) {
+                *pos += 1;
+                let datatype = try!(resolve(tokens, pos, prefixes, true));
+                Ok(format!("{}^^{}", literal, datatype))
+            } else {
+                Ok(literal.clone())
+            }
+        },
+        _                                                  => {
+            Err(ParseError::new(line, "expected a term"))
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<(Token, uint)>, ParseError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut line = 1u;
+    let mut i = 0u;
+
+    while i < chars.len() {
+        let c = *chars.get(i);
+        match c {
+            '\n'                   => { line += 1; i += 1; },
+            ' ' | '\t' | '\r'      => i += 1,
+            '#'                    => {
+                while i < chars.len() && *chars.get(i) != '\n' {
+                    i += 1;
+                }
+            },
+            '.'                    => { tokens.push((Dot, line)); i += 1; },
+            ';'                    => { tokens.push((Semicolon, line)); i += 1; },
+            ','                    => { tokens.push((Comma, line)); i += 1; },
+            '^'                    => {
+                if i + 1 < chars.len() && *chars.get(i + 1) == '^' {
+                    tokens.push((Datatype, line));
+                    i += 2;
+                } else {
+                    return Err(ParseError::new(line, "expected '^^'"))
+                }
+            },
+            '<'                    => {
+                let start = i + 1;
+                while i < chars.len() && *chars.get(i) != '>' {
+                    if *chars.get(i) == '\n' {
+                        return Err(ParseError::new(line, "unterminated IRI"))
+                    }
+                    i += 1;
+                }
+                if i == chars.len() {
+                    return Err(ParseError::new(line, "unterminated IRI"))
+                }
+                tokens.push((Iri(String::from_chars(chars.slice(start, i))), line));
+                i += 1;
+            },
+            '"'                    => {
+                // the literal is kept with its quotes, escapes and suffix
+                let start = i;
+                i += 1;
+                while i < chars.len() && *chars.get(i) != '"' {
+                    match *chars.get(i) {
+                        '\\' => i += 2,
+                        '\n' => return Err(ParseError::new(line, "unterminated literal")),
+                        _    => i += 1
+                    }
+                }
+                if i >= chars.len() {
+                    return Err(ParseError::new(line, "unterminated literal"))
+                }
+                i += 1;
+                if i < chars.len() && *chars.get(i) == '@' {
+                    i += 1;
+                    while i < chars.len() && (chars.get(i).is_alphanumeric() || *chars.get(i) == '-') {
+                        i += 1;
+                    }
+                }
+                tokens.push((Literal(String::from_chars(chars.slice(start, i))), line));
+            },
+            _                      => {
+                let start = i;
+                while i < chars.len() && !is_delimiter(*chars.get(i)) {
+                    i += 1;
+                }
+                // a name can't end with a '.', it ends the statement
+                let mut end = i;
+                while end > start && *chars.get(end - 1) == '.' {
+                    end -= 1;
+                }
+                i = end;
+                tokens.push((Name(String::from_chars(chars.slice(start, end))), line));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn is_delimiter(c: char) -> bool {
+    c.is_whitespace() || c == '<' || c == '"' || c == '#' || c == ',' || c == ';' || c == '^'
+}
@@ -0,0 +1,137 @@
+//! Incremental import of edge lists too large to be read in a string.
+//!
+//! The lines are read one at a time from a Buffer, so only the current line
+//! and the Graph being built are kept in memory. The layout of the lines is
+//! the one of `parse_edge_list`.
+
+use std::from_str::FromStr;
+use std::io::EndOfFile;
+
+use graph::Graph;
+use io::{EdgeListFormat, ParseError};
+use io::edge_list::parse_edge_line;
+
+/**
+* Reader of the Edges of an edge list, one line at a time.
+*
+* It iterates over the Edges of the list in their order, and stops after
+* the first error.
+*
+* # Types parameters
+* * R - The type of the Buffer of the list
+* * K - The Vertex's Key type
+* * V - The Edge's Value type
+*/
+pub struct EdgeStream<R, K, V> {
+    reader:            R,
+    format:            EdgeListFormat,
+    line:              uint,
+    done:              bool
+}
+
+impl<R: Buffer, K: FromStr, V: FromStr> EdgeStream<R, K, V> {
+
+    /**
+    * Create a new EdgeStream.
+    *
+    * # Arguments
+    * * reader - The buffer of the list, a BufferedReader around a File for
+    *   example
+    * * format - The layout of the list
+    *
+    * # Return
+    * A new EdgeStream, at the start of the list.
+    */
+    pub fn new(reader: R, format: EdgeListFormat) -> EdgeStream<R, K, V> {
+        EdgeStream {
+            reader: reader,
+            format: format,
+            line:   0,
+            done:   false
+        }
+    }
+
+    /**
+    * Get the number of the last line read.
+    *
+    * # Return
+    * The number of the line, starting at 1, 0 if nothing was read.
+    */
+    pub fn line(&self) -> uint {
+        self.line
+    }
+
+    /**
+    * Give every remaining Edge of the list to a callback.
+    *
+    * # Arguments
+    * * f - The callback receiving the keys and the value of each Edge
+    *
+    * # Return
+    * Ok(count) with the number of Edges read, Err(error) if the reading
+    * fails or at the first line which is not valid.
+    */
+    pub fn each_edge(&mut self, f: |K, K, Option<V>|) -> Result<uint, ParseError> {
+        let mut count = 0;
+        loop {
+            match self.next() {
+                Some(edge) => {
+                    let (from_key, to_key, value) = try!(edge);
+                    f(from_key, to_key, value);
+                    count += 1;
+                },
+                None       => return Ok(count)
+            }
+        }
+    }
+}
+
+impl<R: Buffer, K: Ord + Eq + Clone + FromStr, V: PartialEq + Clone + FromStr> EdgeStream<R, K, V> {
+
+    /**
+    * Build a Graph from the remaining Edges of the list.
+    *
+    * The Edges are added as they are read, the repeated Edges keep their
+    * first value.
+    *
+    * # Return
+    * Ok(graph) of the Edges and their Vertices, Err(error) as each_edge.
+    */
+    pub fn to_graph<L: Eq + Clone>(&mut self) -> Result<Graph<K, L, V>, ParseError> {
+        let mut graph = Graph::new();
+        try!(self.each_edge(|from_key, to_key, value| {
+            graph.add_vertex(from_key.clone());
+            graph.add_vertex(to_key.clone());
+            graph.add_edge_opt_v(from_key, to_key, value);
+        }));
+        Ok(graph)
+    }
+}
+
+impl<R: Buffer, K: FromStr, V: FromStr> Iterator<Result<(K, K, Option<V>), ParseError>> for EdgeStream<R, K, V> {
+    fn next(&mut self) -> Option<Result<(K, K, Option<V>), ParseError>> {
+        while !self.done {
+            let text = match self.reader.read_line() {
+                Ok(text)                          => text,
+                Err(ref e) if e.kind == EndOfFile => {
+                    self.done = true;
+                    return None
+                },
+                Err(e)                            => {
+                    self.done = true;
+                    return Some(Err(ParseError::new(self.line, e.desc)))
+                }
+            };
+            self.line += 1;
+            match parse_edge_line(text.as_slice(), self.line, &self.format) {
+                Ok(Some(edge)) => return Some(Ok(edge)),
+                Ok(None)       => continue,
+                Err(e)         => {
+                    self.done = true;
+                    return Some(Err(e))
+                }
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,154 @@
+//! Circular and radial layouts.
+
+use std::collections::{Deque, RingBuf};
+use std::f64::consts::PI;
+
+use graph::Graph;
+use indexed::IndexedGraph;
+use property_map::PropertyMap;
+
+/**
+* Place the Vertices of a Graph evenly on a circle centered on the origin.
+*
+* Without ordering the Vertices are sorted by key. With ordering they are
+* taken in depth first order ignoring the direction of the Edges, each
+* connected component is contiguous and most neighbors are close, which
+* usually reduces the crossings.
+*
+* # Arguments
+* * graph - The graph to draw
+* * radius - The radius of the circle
+* * ordered - Order the Vertices to reduce the crossings or not
+*
+* # Return
+* The position of each Vertex.
+*/
+pub fn circular_layout<K: Ord + Eq + Clone,
+                       L: Eq + Clone,
+                       V: PartialEq + Clone>
+                       (graph: &Graph<K, L, V>,
+                       radius: f64,
+                       ordered: bool)
+                       -> PropertyMap<K, (f64, f64)> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let n = indexed.len();
+    let order: Vec<uint> = if ordered {
+        depth_first_order(&indexed.undirected_edges())
+    } else {
+        let mut order: Vec<uint> = range(0, n).collect();
+        order.sort_by(|&a, &b| indexed.keys.get(a).cmp(indexed.keys.get(b)));
+        order
+    };
+    order.iter().enumerate().map(|(i, &v)| {
+        let angle = 2.0 * PI * i as f64 / n as f64;
+        (indexed.keys.get(v).clone(), (radius * angle.cos(), radius * angle.sin()))
+    }).collect()
+}
+
+/**
+* Place the Vertices of a rooted tree on circles centered on the root, one
+* circle per depth.
+*
+* Each subtree gets an angular sector proportional to its number of leaves.
+* The Edges are followed in their direction in a directed Graph, a Graph
+* which is not a tree is drawn along its breadth first search tree and the
+* Vertices not reachable from the root are not placed.
+*
+* # Arguments
+* * graph - The graph to draw
+* * root - The key of the root, placed on the origin
+* * layer_spacing - The distance between two circles
+*
+* # Return
+* Some(positions) with the position of each reachable Vertex, None if the
+* root does not exist.
+*/
+pub fn radial_tree_layout<K: Ord + Eq + Clone,
+                          L: Eq + Clone,
+                          V: PartialEq + Clone>
+                          (graph: &Graph<K, L, V>,
+                          root: &K,
+                          layer_spacing: f64)
+                          -> Option<PropertyMap<K, (f64, f64)>> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let root = match indexed.index.find(root) {
+        Some(&root) => root,
+        None        => return None
+    };
+    let neighbors = if graph.is_directed() {
+        indexed.out_edges.clone()
+    } else {
+        indexed.undirected_edges()
+    };
+
+    // breadth first search tree
+    let n = indexed.len();
+    let mut depth: Vec<Option<uint>> = Vec::from_elem(n, None);
+    let mut children: Vec<Vec<uint>> = Vec::from_elem(n, Vec::new());
+    let mut order = Vec::new();
+    let mut queue = RingBuf::new();
+    *depth.get_mut(root) = Some(0u);
+    queue.push_back(root);
+    while !queue.is_empty() {
+        let v = queue.pop_front().unwrap();
+        order.push(v);
+        let next = depth.get(v).unwrap() + 1;
+        for &u in neighbors.get(v).iter() {
+            if depth.get(u).is_none() {
+                *depth.get_mut(u) = Some(next);
+                children.get_mut(v).push(u);
+                queue.push_back(u);
+            }
+        }
+    }
+
+    let mut leaves = Vec::from_elem(n, 0u);
+    for &v in order.iter().rev() {
+        let sum = children.get(v).iter().fold(0, |s, &c| s + *leaves.get(c));
+        *leaves.get_mut(v) = if sum == 0 { 1 } else { sum };
+    }
+
+    // the sector of each Vertex, split between its children
+    let mut sectors = Vec::from_elem(n, (0.0f64, 2.0 * PI));
+    let mut positions = PropertyMap::new();
+    for &v in order.iter() {
+        let (start, end) = *sectors.get(v);
+        let radius = depth.get(v).unwrap() as f64 * layer_spacing;
+        let angle = (start + end) / 2.0;
+        positions.insert(indexed.keys.get(v).clone(), (radius * angle.cos(), radius * angle.sin()));
+        let mut child_start = start;
+        for &c in children.get(v).iter() {
+            let width = (end - start) * *leaves.get(c) as f64 / *leaves.get(v) as f64;
+            *sectors.get_mut(c) = (child_start, child_start + width);
+            child_start += width;
+        }
+    }
+    Some(positions)
+}
+
+// the Vertices in depth first order, component after component
+fn depth_first_order(neighbors: &Vec<Vec<uint>>) -> Vec<uint> {
+    let n = neighbors.len();
+    let mut visited = Vec::from_elem(n, false);
+    let mut order = Vec::with_capacity(n);
+    for start in range(0, n) {
+        if *visited.get(start) {
+            continue
+        }
+        let mut stack = vec!(start);
+        while !stack.is_empty() {
+            let v = stack.pop().unwrap();
+            if *visited.get(v) {
+                continue
+            }
+            *visited.get_mut(v) = true;
+            order.push(v);
+            for &u in neighbors.get(v).iter().rev() {
+                if !*visited.get(u) {
+                    stack.push(u);
+                }
+            }
+        }
+    }
+    order
+}
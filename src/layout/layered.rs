@@ -0,0 +1,251 @@
+//! Layered layout of directed acyclic Graphs (Sugiyama).
+
+use std::cmp::{max, Less, Equal, Greater};
+
+use graph::Graph;
+use indexed::IndexedGraph;
+use property_map::{PropertyMap, EdgePropertyMap};
+
+/**
+* Layered drawing of a directed acyclic Graph.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+*/
+#[deriving(Clone, PartialEq, Show)]
+pub struct LayeredLayout<K> {
+    /// The position of each Vertex
+    pub positions:     PropertyMap<K, (f64, f64)>,
+    /// The bend points of the Edges crossing several layers, from the
+    /// first Vertex to the second one
+    pub bends:         EdgePropertyMap<K, Vec<(f64, f64)>>,
+    /// The number of layers
+    pub layers:        uint
+}
+
+/**
+* Draw a directed acyclic Graph top-down in layers, every Edge going down.
+*
+* The layer of each Vertex is the length of the longest path reaching it,
+* the Edges crossing several layers go through a dummy Vertex on each
+* layer. The order of the Vertices in each layer is improved by sweeps
+* placing each Vertex at the median position of its neighbors in the
+* previous layer, the order with the least crossings is kept. Each Vertex
+* is then moved toward the mean position of its neighbors, keeping the
+* spacing between the Vertices of a layer.
+*
+* # Arguments
+* * graph - The graph to draw
+* * layer_spacing - The vertical distance between two layers
+* * vertex_spacing - The minimum horizontal distance between two Vertices
+* * sweeps - The number of crossing reduction sweeps, alternately down and
+*   up
+*
+* # Return
+* Some(layout) if the Graph is acyclic, None otherwise.
+*/
+pub fn layered_layout<K: Ord + Eq + Clone,
+                      L: Eq + Clone,
+                      V: PartialEq + Clone>
+                      (graph: &Graph<K, L, V>,
+                      layer_spacing: f64,
+                      vertex_spacing: f64,
+                      sweeps: uint)
+                      -> Option<LayeredLayout<K>> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let n = indexed.len();
+    let order = match topological_order(&indexed) {
+        Some(order) => order,
+        None        => return None
+    };
+
+    // longest path layering
+    let mut layer_of = Vec::from_elem(n, 0u);
+    for &v in order.iter() {
+        for &u in indexed.in_edges.get(v).iter() {
+            let l = max(*layer_of.get(v), *layer_of.get(u) + 1);
+            *layer_of.get_mut(v) = l;
+        }
+    }
+    let layer_count = layer_of.iter().map(|&l| l + 1).max().unwrap_or(0);
+
+    // the Edges are split into Edges between consecutive layers
+    let mut down: Vec<Vec<uint>> = Vec::from_elem(n, Vec::new());
+    let mut up: Vec<Vec<uint>> = Vec::from_elem(n, Vec::new());
+    let mut chains = Vec::new();
+    for from in range(0, n) {
+        for &to in indexed.out_edges.get(from).iter() {
+            let mut last = from;
+            let mut dummies = Vec::new();
+            for l in range(*layer_of.get(from) + 1, *layer_of.get(to)) {
+                let dummy = layer_of.len();
+                layer_of.push(l);
+                down.push(Vec::new());
+                up.push(Vec::new());
+                link(&mut down, &mut up, last, dummy);
+                dummies.push(dummy);
+                last = dummy;
+            }
+            link(&mut down, &mut up, last, to);
+            if !dummies.is_empty() {
+                chains.push((from, to, dummies));
+            }
+        }
+    }
+
+    let mut layers: Vec<Vec<uint>> = Vec::from_elem(layer_count, Vec::new());
+    for (node, &l) in layer_of.iter().enumerate() {
+        layers.get_mut(l).push(node);
+    }
+    let mut position = positions_in_layers(&layers, layer_of.len());
+
+    // crossing reduction
+    let mut best = layers.clone();
+    let mut best_crossings = crossings(&layers, &down, &position);
+    for sweep in range(0, sweeps) {
+        if best_crossings == 0 {
+            break
+        }
+        if sweep % 2 == 0 {
+            for l in range(1, layer_count) {
+                reorder(layers.get_mut(l), &up, &mut position);
+            }
+        } else {
+            for l in range(0, layer_count).rev().skip(1) {
+                reorder(layers.get_mut(l), &down, &mut position);
+            }
+        }
+        let c = crossings(&layers, &down, &position);
+        if c < best_crossings {
+            best_crossings = c;
+            best = layers.clone();
+        }
+    }
+    let layers = best;
+
+    // coordinate assignment
+    let mut x = Vec::from_elem(layer_of.len(), 0.0f64);
+    for layer in layers.iter() {
+        for (i, &node) in layer.iter().enumerate() {
+            *x.get_mut(node) = i as f64 * vertex_spacing;
+        }
+    }
+    for l in range(1, layer_count) {
+        place(layers.get(l), &up, &mut x, vertex_spacing);
+    }
+    for l in range(0, layer_count).rev().skip(1) {
+        place(layers.get(l), &down, &mut x, vertex_spacing);
+    }
+    let min_x = x.iter().fold(0.0f64, |m, &v| if v < m { v } else { m });
+    let point = |node: uint| (*x.get(node) - min_x, *layer_of.get(node) as f64 * layer_spacing);
+
+    let mut bends = EdgePropertyMap::new();
+    for &(from, to, ref dummies) in chains.iter() {
+        bends.insert((indexed.keys.get(from).clone(), indexed.keys.get(to).clone()),
+                     dummies.iter().map(|&d| point(d)).collect());
+    }
+    Some(LayeredLayout {
+        positions:  range(0, n).map(|v| (indexed.keys.get(v).clone(), point(v))).collect(),
+        bends:      bends,
+        layers:     layer_count
+    })
+}
+
+fn link(down: &mut Vec<Vec<uint>>, up: &mut Vec<Vec<uint>>, from: uint, to: uint) {
+    down.get_mut(from).push(to);
+    up.get_mut(to).push(from);
+}
+
+// Kahn's algorithm, None if there is a cycle
+fn topological_order<K>(indexed: &IndexedGraph<K>) -> Option<Vec<uint>> {
+    let n = indexed.len();
+    let mut in_degree: Vec<uint> = indexed.in_edges.iter().map(|e| e.len()).collect();
+    let mut ready: Vec<uint> = range(0, n).filter(|&v| *in_degree.get(v) == 0).collect();
+    let mut order = Vec::with_capacity(n);
+    while !ready.is_empty() {
+        let v = ready.pop().unwrap();
+        order.push(v);
+        for &u in indexed.out_edges.get(v).iter() {
+            *in_degree.get_mut(u) -= 1;
+            if *in_degree.get(u) == 0 {
+                ready.push(u);
+            }
+        }
+    }
+    if order.len() == n { Some(order) } else { None }
+}
+
+fn positions_in_layers(layers: &Vec<Vec<uint>>, len: uint) -> Vec<uint> {
+    let mut position = Vec::from_elem(len, 0u);
+    for layer in layers.iter() {
+        for (i, &node) in layer.iter().enumerate() {
+            *position.get_mut(node) = i;
+        }
+    }
+    position
+}
+
+// sort a layer by the median position of the neighbors in the adjacent
+// layer, the nodes without neighbors keep their position
+fn reorder(layer: &mut Vec<uint>, neighbors: &Vec<Vec<uint>>, position: &mut Vec<uint>) {
+    let mut keyed: Vec<(f64, uint)> = layer.iter().map(|&node| {
+        let mut p: Vec<uint> = neighbors.get(node).iter().map(|&u| *position.get(u)).collect();
+        p.sort();
+        let key = match p.len() {
+            0                   => *position.get(node) as f64,
+            len if len % 2 == 1 => *p.get(len / 2) as f64,
+            len                 => (*p.get(len / 2 - 1) + *p.get(len / 2)) as f64 / 2.0
+        };
+        (key, node)
+    }).collect();
+    keyed.sort_by(|&(a, _), &(b, _)| {
+        if a < b { Less } else if a > b { Greater } else { Equal }
+    });
+    *layer = keyed.move_iter().map(|(_, node)| node).collect();
+    for (i, &node) in layer.iter().enumerate() {
+        *position.get_mut(node) = i;
+    }
+}
+
+// the crossings of the Edges between each pair of consecutive layers
+fn crossings(layers: &Vec<Vec<uint>>, down: &Vec<Vec<uint>>, position: &Vec<uint>) -> uint {
+    let mut count = 0;
+    for layer in layers.iter() {
+        let mut edges = Vec::new();
+        for &node in layer.iter() {
+            for &to in down.get(node).iter() {
+                edges.push((*position.get(node), *position.get(to)));
+            }
+        }
+        for i in range(0, edges.len()) {
+            for j in range(i + 1, edges.len()) {
+                let (a1, b1) = *edges.get(i);
+                let (a2, b2) = *edges.get(j);
+                if (a1 < a2 && b1 > b2) || (a1 > a2 && b1 < b2) {
+                    count += 1;
+                }
+            }
+        }
+    }
+    count
+}
+
+// move each node of a layer toward the mean position of its neighbors,
+// keeping the order and the spacing
+fn place(layer: &Vec<uint>, neighbors: &Vec<Vec<uint>>, x: &mut Vec<f64>, spacing: f64) {
+    let mut last: Option<f64> = None;
+    for &node in layer.iter() {
+        let around = neighbors.get(node);
+        let mut wanted = if around.is_empty() {
+            *x.get(node)
+        } else {
+            around.iter().map(|&u| *x.get(u)).fold(0.0, |a, b| a + b) / around.len() as f64
+        };
+        match last {
+            Some(l) if wanted < l + spacing => wanted = l + spacing,
+            _                               => {}
+        }
+        *x.get_mut(node) = wanted;
+        last = Some(wanted);
+    }
+}
@@ -0,0 +1,12 @@
+//! Positions of the Vertices in the plane, to draw a Graph.
+//!
+//! The layouts return the (x, y) position of each Vertex, the y axis going
+//! down like in the drawing formats.
+
+pub use self::layered::{LayeredLayout, layered_layout};
+pub use self::circular::{circular_layout, radial_tree_layout};
+pub use self::planar::{PlanarEmbedding, is_planar, planar_embedding, planar_layout};
+
+mod layered;
+mod circular;
+mod planar;
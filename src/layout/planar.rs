@@ -0,0 +1,604 @@
+//! Planarity test, planar embedding and straight-line planar drawing.
+
+use std::cmp::min;
+use std::collections::{Deque, RingBuf, TreeMap, TreeSet};
+
+use graph::Graph;
+use indexed::IndexedGraph;
+use property_map::PropertyMap;
+use union_find::UnionFind;
+
+/**
+* Combinatorial embedding of a planar Graph in the plane.
+*
+* The direction of the Edges, the self loops and the duplicated Edges are
+* ignored.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+*/
+#[deriving(Clone, PartialEq, Show)]
+pub struct PlanarEmbedding<K> {
+    /// The neighbors of each Vertex in their cyclic order around it
+    pub rotation:      PropertyMap<K, Vec<K>>,
+    /// The boundary walk of each face, each face follows the neighbor of
+    /// the previous Vertex in the rotation of the next one
+    pub faces:         Vec<Vec<K>>
+}
+
+/**
+* Check if a Graph can be drawn in the plane without crossings.
+*
+* # Arguments
+* * graph - The graph to test
+*
+* # Return
+* true if the Graph is planar, false otherwise.
+*/
+pub fn is_planar<K: Ord + Eq + Clone,
+                 L: Eq + Clone,
+                 V: PartialEq + Clone>
+                 (graph: &Graph<K, L, V>) -> bool {
+    let indexed = IndexedGraph::from_graph(graph);
+    embed(&simple_neighbors(&indexed)).is_some()
+}
+
+/**
+* Compute a planar embedding of a Graph.
+*
+* Each biconnected component is embedded by the algorithm of Demoucron,
+* Malgrange and Pertuiset, adding at each step a path of a fragment which
+* fits in the fewest faces, and the rotations of the components are
+* concatenated at the cut Vertices. The complexity is O(n^3) in the worst
+* case.
+*
+* # Arguments
+* * graph - The graph to embed
+*
+* # Return
+* Some(embedding) if the Graph is planar, None otherwise.
+*/
+pub fn planar_embedding<K: Ord + Eq + Clone,
+                        L: Eq + Clone,
+                        V: PartialEq + Clone>
+                        (graph: &Graph<K, L, V>) -> Option<PlanarEmbedding<K>> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let rotation = match embed(&simple_neighbors(&indexed)) {
+        Some(rotation) => rotation,
+        None           => return None
+    };
+    let keys = |vertices: &Vec<uint>| -> Vec<K> {
+        vertices.iter().map(|&v| indexed.keys.get(v).clone()).collect()
+    };
+    Some(PlanarEmbedding {
+        faces:      trace_faces(&rotation).iter().map(|face| keys(face)).collect(),
+        rotation:   rotation.iter().enumerate().map(|(v, around)| {
+            (indexed.keys.get(v).clone(), keys(around))
+        }).collect()
+    })
+}
+
+/**
+* Draw a planar Graph with straight Edges and without crossings.
+*
+* The embedding is made connected and biconnected by adding Edges, then a
+* Vertex is added in each face with more than 3 sides to get a
+* triangulation. One triangle is fixed as the outer face and every other
+* Vertex is placed at the barycenter of its neighbors (Tutte), solved by
+* Gauss-Seidel iterations. The added Edges and Vertices are dropped from
+* the result.
+*
+* # Arguments
+* * graph - The graph to draw
+* * size - The side of the outer triangle
+*
+* # Return
+* Some(positions) if the Graph is planar, None otherwise.
+*/
+pub fn planar_layout<K: Ord + Eq + Clone,
+                     L: Eq + Clone,
+                     V: PartialEq + Clone>
+                     (graph: &Graph<K, L, V>,
+                     size: f64)
+                     -> Option<PropertyMap<K, (f64, f64)>> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let n = indexed.len();
+    let mut rotation = match embed(&simple_neighbors(&indexed)) {
+        Some(rotation) => rotation,
+        None           => return None
+    };
+    if n < 3 {
+        return Some(range(0, n).map(|v| {
+            (indexed.keys.get(v).clone(), (v as f64 * size, 0.0))
+        }).collect())
+    }
+
+    connect(&mut rotation);
+    biconnect(&mut rotation);
+    let outer = triangulate(&mut rotation);
+
+    // Tutte's barycentric drawing with the outer triangle fixed
+    let total = rotation.len();
+    let corners = [(0.0, 0.0), (size, 0.0), (size / 2.0, size * 3.0f64.sqrt() / 2.0)];
+    let center = (size / 2.0, size * 3.0f64.sqrt() / 6.0);
+    let mut fixed = Vec::from_elem(total, false);
+    let mut x = Vec::from_elem(total, center);
+    for (i, &v) in outer.iter().enumerate() {
+        *fixed.get_mut(v) = true;
+        *x.get_mut(v) = corners[i];
+    }
+    let tolerance = size * 1e-9;
+    for _ in range(0, 50 * total + 100) {
+        let mut moved = 0.0f64;
+        for v in range(0, total) {
+            if *fixed.get(v) {
+                continue
+            }
+            let around = rotation.get(v);
+            let (sx, sy) = around.iter().fold((0.0, 0.0), |(sx, sy), &u| {
+                let (ux, uy) = *x.get(u);
+                (sx + ux, sy + uy)
+            });
+            let new = (sx / around.len() as f64, sy / around.len() as f64);
+            let (ox, oy) = *x.get(v);
+            let (nx, ny) = new;
+            let d = (nx - ox).abs() + (ny - oy).abs();
+            if d > moved {
+                moved = d;
+            }
+            *x.get_mut(v) = new;
+        }
+        if moved < tolerance {
+            break
+        }
+    }
+    Some(range(0, n).map(|v| (indexed.keys.get(v).clone(), *x.get(v))).collect())
+}
+
+// the undirected neighbors of each Vertex, without self loops
+fn simple_neighbors<K>(indexed: &IndexedGraph<K>) -> Vec<Vec<uint>> {
+    indexed.undirected_edges().move_iter().enumerate().map(|(v, neighbors)| {
+        neighbors.move_iter().filter(|&u| u != v).collect()
+    }).collect()
+}
+
+// the rotation system of a simple Graph, None if it is not planar
+fn embed(neighbors: &Vec<Vec<uint>>) -> Option<Vec<Vec<uint>>> {
+    let mut rotation = Vec::from_elem(neighbors.len(), Vec::new());
+    for block in biconnected_components(neighbors).iter() {
+        if block.len() == 1 {
+            let (u, v) = *block.get(0);
+            rotation.get_mut(u).push(v);
+            rotation.get_mut(v).push(u);
+            continue
+        }
+        // local numbering of the Vertices of the block
+        let mut local = TreeMap::new();
+        let mut global = Vec::new();
+        for &(u, v) in block.iter() {
+            for &w in [u, v].iter() {
+                if !local.contains_key(&w) {
+                    local.insert(w, global.len());
+                    global.push(w);
+                }
+            }
+        }
+        let mut adjacency = Vec::from_elem(global.len(), Vec::new());
+        for &(u, v) in block.iter() {
+            let lu = *local.find(&u).unwrap();
+            let lv = *local.find(&v).unwrap();
+            adjacency.get_mut(lu).push(lv);
+            adjacency.get_mut(lv).push(lu);
+        }
+        match embed_biconnected(&adjacency) {
+            Some(local_rotation) => {
+                for (lv, around) in local_rotation.iter().enumerate() {
+                    let v = *global.get(lv);
+                    for &lu in around.iter() {
+                        rotation.get_mut(v).push(*global.get(lu));
+                    }
+                }
+            },
+            None                 => return None
+        }
+    }
+    Some(rotation)
+}
+
+// the Edges of each biconnected component (Tarjan), a bridge is alone
+fn biconnected_components(neighbors: &Vec<Vec<uint>>) -> Vec<Vec<(uint, uint)>> {
+    let n = neighbors.len();
+    let mut discovery: Vec<Option<uint>> = Vec::from_elem(n, None);
+    let mut low = Vec::from_elem(n, 0u);
+    let mut time = 0u;
+    let mut edges = Vec::new();
+    let mut blocks = Vec::new();
+
+    for root in range(0, n) {
+        if discovery.get(root).is_some() {
+            continue
+        }
+        *discovery.get_mut(root) = Some(time);
+        *low.get_mut(root) = time;
+        time += 1;
+        // (vertex, parent, next neighbor)
+        let mut stack = vec!((root, n, 0u));
+        while !stack.is_empty() {
+            let (v, parent, next) = *stack.last().unwrap();
+            if next < neighbors.get(v).len() {
+                *stack.mut_last().unwrap() = (v, parent, next + 1);
+                let u = *neighbors.get(v).get(next);
+                if u == parent {
+                    continue
+                }
+                match *discovery.get(u) {
+                    None                                               => {
+                        edges.push((v, u));
+                        *discovery.get_mut(u) = Some(time);
+                        *low.get_mut(u) = time;
+                        time += 1;
+                        stack.push((u, v, 0));
+                    },
+                    Some(d) if d < discovery.get(v).unwrap()           => {
+                        edges.push((v, u));
+                        *low.get_mut(v) = min(*low.get(v), d);
+                    },
+                    _                                                  => {}
+                }
+            } else {
+                stack.pop();
+                if parent == n {
+                    continue
+                }
+                *low.get_mut(parent) = min(*low.get(parent), *low.get(v));
+                if *low.get(v) >= discovery.get(parent).unwrap() {
+                    let mut block = Vec::new();
+                    loop {
+                        let edge = edges.pop().unwrap();
+                        block.push(edge);
+                        if edge == (parent, v) {
+                            break
+                        }
+                    }
+                    blocks.push(block);
+                }
+            }
+        }
+    }
+    blocks
+}
+
+// Demoucron, Malgrange and Pertuiset on a biconnected Graph with a cycle
+fn embed_biconnected(adjacency: &Vec<Vec<uint>>) -> Option<Vec<Vec<uint>>> {
+    let n = adjacency.len();
+    let edge_count = adjacency.iter().fold(0, |s, a| s + a.len()) / 2;
+    let cycle = find_cycle(adjacency);
+    let mut embedded = Vec::from_elem(n, false);
+    let mut embedded_edges = TreeSet::new();
+    for (i, &v) in cycle.iter().enumerate() {
+        *embedded.get_mut(v) = true;
+        embedded_edges.insert(edge_key(v, *cycle.get((i + 1) % cycle.len())));
+    }
+    let mut reversed = cycle.clone();
+    reversed.reverse();
+    let mut faces = vec!(cycle, reversed);
+
+    while embedded_edges.len() < edge_count {
+        let fragments = fragments(adjacency, &embedded, &embedded_edges);
+        let mut chosen = None;
+        for (i, &(ref attachments, _)) in fragments.iter().enumerate() {
+            let admissible: Vec<uint> = range(0, faces.len()).filter(|&f| {
+                attachments.iter().all(|a| faces.get(f).contains(a))
+            }).collect();
+            match admissible.len() {
+                0 => return None,
+                1 => {
+                    chosen = Some((i, *admissible.get(0)));
+                    break
+                },
+                _ => if chosen.is_none() {
+                    chosen = Some((i, *admissible.get(0)));
+                }
+            }
+        }
+        let (fragment, face) = chosen.unwrap();
+        let path = fragment_path(adjacency, &embedded, fragments.get(fragment));
+        for (i, &v) in path.iter().enumerate() {
+            *embedded.get_mut(v) = true;
+            if i > 0 {
+                embedded_edges.insert(edge_key(*path.get(i - 1), v));
+            }
+        }
+        let (first, second) = split_face(faces.get(face), &path);
+        *faces.get_mut(face) = first;
+        faces.push(second);
+    }
+
+    // each angle (a, b, c) of a face gives the successor of a around b
+    let mut successor: Vec<TreeMap<uint, uint>> = Vec::from_elem(n, TreeMap::new());
+    for face in faces.iter() {
+        let len = face.len();
+        for i in range(0, len) {
+            let a = *face.get((i + len - 1) % len);
+            let b = *face.get(i);
+            let c = *face.get((i + 1) % len);
+            successor.get_mut(b).insert(a, c);
+        }
+    }
+    Some(range(0, n).map(|v| {
+        let start = *adjacency.get(v).get(0);
+        let mut around = vec!(start);
+        let mut next = *successor.get(v).find(&start).unwrap();
+        while next != start {
+            around.push(next);
+            next = *successor.get(v).find(&next).unwrap();
+        }
+        around
+    }).collect())
+}
+
+fn edge_key(u: uint, v: uint) -> (uint, uint) {
+    if u < v { (u, v) } else { (v, u) }
+}
+
+// a cycle found by depth first search from the Vertex 0
+fn find_cycle(adjacency: &Vec<Vec<uint>>) -> Vec<uint> {
+    let n = adjacency.len();
+    let mut parent: Vec<Option<uint>> = Vec::from_elem(n, None);
+    let mut visited = Vec::from_elem(n, false);
+    let mut stack = vec!((0u, 0u));
+    *visited.get_mut(0) = true;
+    while !stack.is_empty() {
+        let (v, next) = *stack.last().unwrap();
+        if next == adjacency.get(v).len() {
+            stack.pop();
+            continue
+        }
+        *stack.mut_last().unwrap() = (v, next + 1);
+        let u = *adjacency.get(v).get(next);
+        if Some(u) == *parent.get(v) {
+            continue
+        }
+        if *visited.get(u) {
+            // the first back Edge goes to an ancestor
+            let mut cycle = vec!(v);
+            let mut w = v;
+            while w != u {
+                w = parent.get(w).unwrap();
+                cycle.push(w);
+            }
+            return cycle
+        }
+        *visited.get_mut(u) = true;
+        *parent.get_mut(u) = Some(v);
+        stack.push((u, 0));
+    }
+    unreachable!()
+}
+
+// the fragments of the Graph relative to the embedded part, as (sorted
+// attachments, Vertices not embedded)
+fn fragments(adjacency: &Vec<Vec<uint>>,
+             embedded: &Vec<bool>,
+             embedded_edges: &TreeSet<(uint, uint)>)
+             -> Vec<(Vec<uint>, Vec<uint>)> {
+    let n = adjacency.len();
+    let mut fragments = Vec::new();
+    for v in range(0, n) {
+        for &u in adjacency.get(v).iter() {
+            if v < u && *embedded.get(v) && *embedded.get(u) &&
+               !embedded_edges.contains(&(v, u)) {
+                fragments.push((vec!(v, u), Vec::new()));
+            }
+        }
+    }
+    let mut seen = Vec::from_elem(n, false);
+    for start in range(0, n) {
+        if *embedded.get(start) || *seen.get(start) {
+            continue
+        }
+        let mut attachments = TreeSet::new();
+        let mut component = vec!(start);
+        let mut queue = RingBuf::new();
+        *seen.get_mut(start) = true;
+        queue.push_back(start);
+        while !queue.is_empty() {
+            let v = queue.pop_front().unwrap();
+            for &u in adjacency.get(v).iter() {
+                if *embedded.get(u) {
+                    attachments.insert(u);
+                } else if !*seen.get(u) {
+                    *seen.get_mut(u) = true;
+                    component.push(u);
+                    queue.push_back(u);
+                }
+            }
+        }
+        fragments.push((attachments.move_iter().collect(), component));
+    }
+    fragments
+}
+
+// a path through a fragment between two of its attachments
+fn fragment_path(adjacency: &Vec<Vec<uint>>,
+                 embedded: &Vec<bool>,
+                 fragment: &(Vec<uint>, Vec<uint>))
+                 -> Vec<uint> {
+    let &(ref attachments, ref component) = fragment;
+    if component.is_empty() {
+        return attachments.clone()
+    }
+    let start = *attachments.get(0);
+    let first = *adjacency.get(start).iter().find(|&&u| {
+        !*embedded.get(u) && component.contains(&u)
+    }).unwrap();
+    let mut parent = TreeMap::new();
+    let mut queue = RingBuf::new();
+    parent.insert(first, start);
+    queue.push_back(first);
+    while !queue.is_empty() {
+        let v = queue.pop_front().unwrap();
+        for &u in adjacency.get(v).iter() {
+            if *embedded.get(u) {
+                if u != start {
+                    let mut path = vec!(u, v);
+                    let mut w = v;
+                    while w != start {
+                        w = *parent.find(&w).unwrap();
+                        path.push(w);
+                    }
+                    path.reverse();
+                    return path
+                }
+            } else if !parent.contains_key(&u) {
+                parent.insert(u, v);
+                queue.push_back(u);
+            }
+        }
+    }
+    unreachable!()
+}
+
+// the two faces made by a path between two Vertices of a face
+fn split_face(face: &Vec<uint>, path: &Vec<uint>) -> (Vec<uint>, Vec<uint>) {
+    let len = face.len();
+    let i = face.iter().position(|&v| v == *path.get(0)).unwrap();
+    let j = face.iter().position(|&v| v == *path.last().unwrap()).unwrap();
+    let inner = path.slice(1, path.len() - 1);
+
+    let mut first = Vec::new();
+    let mut k = i;
+    loop {
+        first.push(*face.get(k));
+        if k == j {
+            break
+        }
+        k = (k + 1) % len;
+    }
+    first.extend(inner.iter().rev().map(|&v| v));
+
+    let mut second = Vec::new();
+    let mut k = j;
+    loop {
+        second.push(*face.get(k));
+        if k == i {
+            break
+        }
+        k = (k + 1) % len;
+    }
+    second.push_all(inner);
+    (first, second)
+}
+
+// the boundary walks of the faces of a rotation system
+fn trace_faces(rotation: &Vec<Vec<uint>>) -> Vec<Vec<uint>> {
+    let mut visited = TreeSet::new();
+    let mut faces = Vec::new();
+    for a in range(0, rotation.len()) {
+        for &b in rotation.get(a).iter() {
+            if visited.contains(&(a, b)) {
+                continue
+            }
+            let mut face = Vec::new();
+            let (mut from, mut to) = (a, b);
+            while visited.insert((from, to)) {
+                face.push(from);
+                let around = rotation.get(to);
+                let i = around.iter().position(|&v| v == from).unwrap();
+                let next = *around.get((i + 1) % around.len());
+                from = to;
+                to = next;
+            }
+            faces.push(face);
+        }
+    }
+    faces
+}
+
+// link the first Vertex of each connected component to the Vertex 0
+fn connect(rotation: &mut Vec<Vec<uint>>) {
+    let n = rotation.len();
+    let mut seen = Vec::from_elem(n, false);
+    for start in range(0, n) {
+        if *seen.get(start) {
+            continue
+        }
+        if start > 0 {
+            rotation.get_mut(0).push(start);
+            rotation.get_mut(start).push(0);
+        }
+        let mut stack = vec!(start);
+        *seen.get_mut(start) = true;
+        while !stack.is_empty() {
+            let v = stack.pop().unwrap();
+            for &u in rotation.get(v).iter() {
+                if !*seen.get(u) {
+                    *seen.get_mut(u) = true;
+                    stack.push(u);
+                }
+            }
+        }
+    }
+}
+
+// add an Edge between the consecutive neighbors of a cut Vertex which are
+// in different biconnected components, inside their common face
+fn biconnect(rotation: &mut Vec<Vec<uint>>) {
+    let blocks = biconnected_components(rotation);
+    let mut block_of = TreeMap::new();
+    for (b, block) in blocks.iter().enumerate() {
+        for &(u, v) in block.iter() {
+            block_of.insert(edge_key(u, v), b);
+        }
+    }
+    let mut sets = UnionFind::new(blocks.len());
+    for v in range(0, rotation.len()) {
+        let degree = rotation.get(v).len();
+        if degree < 2 {
+            continue
+        }
+        for k in range(0, degree) {
+            let u = *rotation.get(v).get(k);
+            let w = *rotation.get(v).get((k + 1) % degree);
+            let bu = *block_of.find(&edge_key(v, u)).unwrap();
+            let bw = *block_of.find(&edge_key(v, w)).unwrap();
+            if sets.find(bu) == sets.find(bw) {
+                continue
+            }
+            let i = rotation.get(u).iter().position(|&x| x == v).unwrap();
+            rotation.get_mut(u).insert(i, w);
+            let i = rotation.get(w).iter().position(|&x| x == v).unwrap();
+            rotation.get_mut(w).insert(i + 1, u);
+            sets.union(bu, bw);
+            block_of.insert(edge_key(u, w), bu);
+        }
+    }
+}
+
+// add a Vertex in each face with more than 3 sides, return the outer
+// triangle
+fn triangulate(rotation: &mut Vec<Vec<uint>>) -> Vec<uint> {
+    let faces = trace_faces(rotation);
+    let outer = faces.iter().enumerate().max_by(|&(_, face)| face.len()).unwrap().val0();
+    let mut triangle = faces.get(outer).clone();
+    for (f, face) in faces.iter().enumerate() {
+        let len = face.len();
+        if len <= 3 {
+            continue
+        }
+        let center = rotation.len();
+        let mut around: Vec<uint> = face.clone();
+        around.reverse();
+        rotation.push(around);
+        for i in range(0, len) {
+            let previous = *face.get((i + len - 1) % len);
+            let v = *face.get(i);
+            let k = rotation.get(v).iter().position(|&x| x == previous).unwrap();
+            rotation.get_mut(v).insert(k + 1, center);
+        }
+        if f == outer {
+            triangle = vec!(center, *face.get(0), *face.get(1));
+        }
+    }
+    triangle
+}
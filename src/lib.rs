@@ -7,19 +7,71 @@
 #![license = "MIT"]
 #![crate_type = "lib"]
 
+#![feature(macro_rules)]
+
 #![deny(non_camel_case_types)]
 #![deny(non_uppercase_statics)]
 #![deny(unnecessary_qualification)]
 // #[warn(missing_doc)];
 
 extern crate serialize;
+extern crate time;
 
 // public reexports
-pub use graph::Graph;
-pub use graph::{Vertex, VertexIterator};
-pub use graph::{Edge, EdgeIterator};
+pub use graph::{Graph, GraphConfig, StructureViolation, Cycle, ExtraEdge, Disconnected, EmptyGraph};
+pub use graph::{IntegrityViolation, DanglingEdge, DuplicateEdge, UnsortedEdges,
+                MissingReverseEdge};
+pub use graph::{GraphError, VertexNotFound, VertexAlreadyExists, EdgeNotFound, EdgeAlreadyExists,
+                SelfLoopForbidden};
+pub use graph::{Vertex, VertexIterator, VertexMutIterator, VertexRefIterator, NeighborIterator};
+pub use graph::{Edge, EdgeIterator, EdgeMutIterator, GraphEdgeIterator, SelfLoopIterator};
+pub use property_map::{PropertyMap, EdgePropertyMap};
+pub use weight::{Weight, WeightProvider, EdgeValues, UnitWeights};
+pub use view::{GraphView, FilteredView, ReversedView, UndirectedView};
+pub use observer::{GraphObserver, ObserverId};
+pub use diff::{GraphDiff, DiffConflict, VertexExists, MissingVertex, LabelMismatch,
+               EdgeExists, MissingEdge, ValueMismatch, UnexpectedEdge};
+pub use versioned::VersionedGraph;
+pub use traversal::{Traversal, Bfs, Dfs};
+pub use temporal::{TemporalGraph, TemporalEdge};
+pub use attributes::{Attributes, AttributeSchema, AttributeDef, AttributeType, AttributeValue};
+pub use attributes::{BoolType, IntType, FloatType, StringType};
+pub use attributes::{AttrBool, AttrInt, AttrFloat, AttrString};
+pub use spatial::SpatialGraph;
+pub use handle::{IdGraph, VertexId, EdgeId, IdNeighborIterator};
+pub use csr::CsrGraph;
 
 // mods
+#[macro_escape]
+mod macros;
 mod graph;
+mod indexed;
+mod union_find;
+mod heap;
+mod flow_network;
+mod property_map;
+mod weight;
+mod view;
+mod observer;
+mod diff;
+mod versioned;
+mod temporal;
+mod attributes;
+mod spatial;
+mod handle;
+mod csr;
+pub mod parallel;
+pub mod dynamic;
+pub mod stream;
+pub mod sample;
+pub mod community;
+pub mod matrix;
+pub mod analysis;
+pub mod algo;
+pub mod traversal;
+pub mod query;
+pub mod rewrite;
+pub mod io;
+pub mod layout;
+pub mod instrument;
 // pub mod graphviz;
-// pub mod graphml;
\ No newline at end of file
@@ -0,0 +1,30 @@
+//! Macros building Graphs.
+
+/**
+* Build a Graph from a list of Edges.
+*
+* Each Edge is `from -> to` optionally followed by its value between
+* brackets, the Vertices are created from the keys without label. The keys
+* are literals or variables, the crate must be linked as `rgraph`.
+*
+* ```ignore
+* let graph: Graph<&str, int, int> = graph!{ "a" -> "b" [3], "b" -> "c" };
+* ```
+*/
+#[macro_export]
+macro_rules! graph(
+    ($($from:tt -> $to:tt $([$value:expr])*),*) => ({
+        let mut graph = ::rgraph::Graph::new();
+        $({
+            let from = $from;
+            let to = $to;
+            graph.add_vertex(from.clone());
+            graph.add_vertex(to.clone());
+            graph.add_edge_opt_v(from, to, None $(.or(Some($value)))*);
+        })*
+        graph
+    });
+    ($($from:tt -> $to:tt $([$value:expr])*),+,) => (
+        graph!($($from -> $to $([$value])*),+)
+    )
+)
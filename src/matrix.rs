@@ -0,0 +1,368 @@
+//! Graph stored as an adjacency matrix.
+
+use std::collections::TreeMap;
+
+use graph::Graph;
+use view::GraphView;
+
+/**
+* Graph of dense Vertices stored as an adjacency matrix.
+*
+* The Vertices are numbered from 0 in the order they are added and each
+* pair of Vertices has a cell, so checking an Edge or reading its value is
+* constant time. The matrix takes the square of the number of Vertices
+* cells, it suits small and dense Graphs. The Vertices can't be removed.
+*
+* A borrowed MatrixGraph is a GraphView, so the algorithms on views run on
+* it directly: bfs_view, dfs_view, shortest_path_view and
+* strongly_connected_components_view.
+*
+* # Types parameters
+* * L - The Vertex's Label type
+* * V - The Edge's Value type
+*/
+#[deriving(Clone, PartialEq)]
+pub struct MatrixGraph<L, V> {
+    // the numbers of the Vertices, the keys borrowed by GraphView
+    keys:              Vec<uint>,
+    labels:            Vec<Option<L>>,
+    // None without Edge, Some(value) with an Edge and its optional value
+    cells:             Vec<Vec<Option<Option<V>>>>,
+    directed:          bool,
+    edge_count:        uint
+}
+
+impl<L: Eq + Clone, V: PartialEq + Clone> MatrixGraph<L, V> {
+
+    /**
+    * Create a new empty MatrixGraph.
+    *
+    * # Return
+    * A new empty directed MatrixGraph.
+    */
+    pub fn new() -> MatrixGraph<L, V> {
+        MatrixGraph {
+            keys:       Vec::new(),
+            labels:     Vec::new(),
+            cells:      Vec::new(),
+            directed:   true,
+            edge_count: 0
+        }
+    }
+
+    /**
+    * Create a new empty undirected MatrixGraph, each Edge is stored in the
+    * two cells of its Vertices.
+    *
+    * # Return
+    * A new empty undirected MatrixGraph.
+    */
+    pub fn new_undirected() -> MatrixGraph<L, V> {
+        let mut graph = MatrixGraph::new();
+        graph.directed = false;
+        graph
+    }
+
+    /**
+    * Create a new MatrixGraph with the Vertices and the Edges of a Graph.
+    *
+    * # Arguments
+    * * graph - The graph to copy
+    *
+    * # Return
+    * The keys of the Graph in the order of the numbers of the Vertices, and
+    * a new MatrixGraph directed as the Graph.
+    */
+    pub fn from_graph<K: Ord + Eq + Clone>(graph: &Graph<K, L, V>) -> (Vec<K>, MatrixGraph<L, V>) {
+        let mut matrix = if graph.is_directed() { MatrixGraph::new() } else { MatrixGraph::new_undirected() };
+        let mut index = TreeMap::new();
+        for (key, label) in graph.vertices_iter() {
+            index.insert(key, matrix.add_vertex_opt_l(label.map(|l| l.clone())));
+        }
+        for (from_key, to_key, value) in graph.edges_iter() {
+            matrix.add_edge_opt_v(*index.find(&from_key).unwrap(),
+                                  *index.find(&to_key).unwrap(),
+                                  value.map(|v| v.clone()));
+        }
+        (graph.vertices_iter().map(|(key, _)| key.clone()).collect(), matrix)
+    }
+
+    /**
+    * Create a Graph with the Vertices and the Edges of the MatrixGraph.
+    *
+    * # Return
+    * A new graph directed as the MatrixGraph, keyed by the numbers of the
+    * Vertices.
+    */
+    pub fn to_graph(&self) -> Graph<uint, L, V> {
+        let mut graph = if self.directed { Graph::new() } else { Graph::new_undirected() };
+        for (v, label) in self.labels.iter().enumerate() {
+            graph.add_vertex_opt_l(v, label.clone());
+        }
+        for (from, row) in self.cells.iter().enumerate() {
+            for (to, cell) in row.iter().enumerate() {
+                match *cell {
+                    Some(ref value) => { graph.add_edge_opt_v(from, to, value.clone()); },
+                    None            => {}
+                }
+            }
+        }
+        graph
+    }
+
+    /**
+    * Get the number of Vertices.
+    */
+    pub fn len(&self) -> uint {
+        self.labels.len()
+    }
+
+    /**
+    * Get the number of Edges, counted once in an undirected MatrixGraph.
+    */
+    pub fn edge_count(&self) -> uint {
+        self.edge_count
+    }
+
+    /**
+    * Is the MatrixGraph directed or not.
+    */
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    /**
+    * Add a Vertex with an optional label, in linear time.
+    *
+    * # Return
+    * The number of the new Vertex.
+    */
+    pub fn add_vertex_opt_l(&mut self, label: Option<L>) -> uint {
+        let v = self.labels.len();
+        for row in self.cells.mut_iter() {
+            row.push(None);
+        }
+        self.cells.push(Vec::from_fn(v + 1, |_| None));
+        self.labels.push(label);
+        self.keys.push(v);
+        v
+    }
+
+    /**
+    * Add a Vertex with a label.
+    *
+    * # Return
+    * The number of the new Vertex.
+    */
+    pub fn add_vertex_l(&mut self, label: L) -> uint {
+        self.add_vertex_opt_l(Some(label))
+    }
+
+    /**
+    * Add a Vertex.
+    *
+    * # Return
+    * The number of the new Vertex.
+    */
+    pub fn add_vertex(&mut self) -> uint {
+        self.add_vertex_opt_l(None)
+    }
+
+    /**
+    * Get the label of a Vertex.
+    *
+    * # Return
+    * Some(label) if the Vertex exist and has a label, None otherwise.
+    */
+    pub fn get_vertex_label<'r>(&'r self, v: uint) -> Option<&'r L> {
+        if v < self.len() {
+            self.labels.get(v).as_ref()
+        } else {
+            None
+        }
+    }
+
+    /**
+    * Set or remove the label of a Vertex.
+    *
+    * # Return
+    * true if the label is set, false if the Vertex does not exist.
+    */
+    pub fn set_vertex_label_opt(&mut self, v: uint, label: Option<L>) -> bool {
+        if v >= self.len() {
+            return false
+        }
+        *self.labels.get_mut(v) = label;
+        true
+    }
+
+    /**
+    * Check if there is an Edge between two Vertices, in constant time.
+    *
+    * # Return
+    * true if the Edge exist, false otherwise.
+    */
+    pub fn adjacent(&self, from: uint, to: uint) -> bool {
+        from < self.len() && to < self.len() && self.cells.get(from).get(to).is_some()
+    }
+
+    /**
+    * Get the value of an Edge, in constant time.
+    *
+    * # Return
+    * Some(value) if the Edge exist and has a value, None otherwise.
+    */
+    pub fn get_edge_value<'r>(&'r self, from: uint, to: uint) -> Option<&'r V> {
+        if !self.adjacent(from, to) {
+            return None
+        }
+        self.cells.get(from).get(to).get_ref().as_ref()
+    }
+
+    /**
+    * Add an Edge with an optional value.
+    *
+    * # Return
+    * true if the Edge is added, false if a Vertex does not exist or the
+    * Edge already exist.
+    */
+    pub fn add_edge_opt_v(&mut self, from: uint, to: uint, value: Option<V>) -> bool {
+        if from >= self.len() || to >= self.len() || self.adjacent(from, to) {
+            return false
+        }
+        if !self.directed {
+            *self.cells.get_mut(to).get_mut(from) = Some(value.clone());
+        }
+        *self.cells.get_mut(from).get_mut(to) = Some(value);
+        self.edge_count += 1;
+        true
+    }
+
+    /**
+    * Add an Edge with a value.
+    *
+    * # Return
+    * true if the Edge is added, false otherwise.
+    */
+    pub fn add_edge_v(&mut self, from: uint, to: uint, value: V) -> bool {
+        self.add_edge_opt_v(from, to, Some(value))
+    }
+
+    /**
+    * Add an Edge.
+    *
+    * # Return
+    * true if the Edge is added, false otherwise.
+    */
+    pub fn add_edge(&mut self, from: uint, to: uint) -> bool {
+        self.add_edge_opt_v(from, to, None)
+    }
+
+    /**
+    * Set or remove the value of an Edge.
+    *
+    * # Return
+    * true if the value is set, false if the Edge does not exist.
+    */
+    pub fn set_edge_value_opt(&mut self, from: uint, to: uint, value: Option<V>) -> bool {
+        if !self.adjacent(from, to) {
+            return false
+        }
+        if !self.directed {
+            *self.cells.get_mut(to).get_mut(from) = Some(value.clone());
+        }
+        *self.cells.get_mut(from).get_mut(to) = Some(value);
+        true
+    }
+
+    /**
+    * Remove an Edge.
+    *
+    * # Return
+    * true if the Edge is removed, false if it does not exist.
+    */
+    pub fn remove_edge(&mut self, from: uint, to: uint) -> bool {
+        if !self.adjacent(from, to) {
+            return false
+        }
+        if !self.directed {
+            *self.cells.get_mut(to).get_mut(from) = None;
+        }
+        *self.cells.get_mut(from).get_mut(to) = None;
+        self.edge_count -= 1;
+        true
+    }
+
+    /**
+    * Get the neighbors of a Vertex, in linear time.
+    *
+    * # Return
+    * The ascending numbers of the second Vertices of the Edges starting
+    * from the Vertex, empty if it does not exist.
+    */
+    pub fn neighbors(&self, v: uint) -> Vec<uint> {
+        if v >= self.len() {
+            return Vec::new()
+        }
+        self.cells.get(v).iter().enumerate().filter(|&(_, cell)| cell.is_some()).map(|(to, _)| to).collect()
+    }
+}
+
+impl<'a,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
+     GraphView<'a, uint, V> for &'a MatrixGraph<L, V> {
+    fn vertices(&self) -> Vec<&'a uint> {
+        let graph: &'a MatrixGraph<L, V> = *self;
+        graph.keys.iter().collect()
+    }
+
+    fn contains_vertex(&self, key: &uint) -> bool {
+        *key < self.len()
+    }
+
+    fn edges_from(&self, key: &uint) -> Vec<(&'a uint, Option<&'a V>)> {
+        let graph: &'a MatrixGraph<L, V> = *self;
+        if *key >= graph.len() {
+            return Vec::new()
+        }
+        graph.cells.get(*key).iter().zip(graph.keys.iter()).filter_map(|(cell, to)| {
+            cell.as_ref().map(|value| (to, value.as_ref()))
+        }).collect()
+    }
+
+    fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    fn contains_edge(&self, from_key: &uint, to_key: &uint) -> bool {
+        self.adjacent(*from_key, *to_key)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use weight::UnitWeights;
+    use traversal::{bfs_view, dfs_view};
+    use algo::{shortest_path_view, strongly_connected_components_view};
+    use super::MatrixGraph;
+
+    #[test]
+    fn view_algorithms() {
+        // 0 and 1 in a cycle, then 2 and 3
+        let mut graph: MatrixGraph<(), ()> = MatrixGraph::new();
+        for _ in range(0u, 4) {
+            graph.add_vertex();
+        }
+        graph.add_edge(0, 1);
+        graph.add_edge(1, 0);
+        graph.add_edge(1, 2);
+        graph.add_edge(0, 3);
+        graph.add_edge(3, 2);
+        assert_eq!(bfs_view(&&graph, &0), vec!(0, 1, 3, 2));
+        assert_eq!(dfs_view(&&graph, &0), vec!(0, 1, 2, 3));
+        assert_eq!(shortest_path_view(&&graph, &UnitWeights, &1, &3), Some((2.0, vec!(1, 0, 3))));
+        assert_eq!(strongly_connected_components_view(&&graph),
+                   vec!(vec!(0, 1), vec!(3), vec!(2)));
+    }
+}
@@ -0,0 +1,181 @@
+//! Notification of the mutations of a Graph.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use attributes::Attributes;
+use property_map::PropertyMap;
+
+/**
+* Receiver of the mutations of a Graph, registered with add_observer.
+*
+* Every method does nothing by default. The mutations done through the
+* references returned by get_vertex_mut are not notified.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+* * L - The Vertex's Label type
+* * V - The Edge's Value type
+*/
+pub trait GraphObserver<K, L, V> {
+
+    /**
+    * The observer has been registered on a Graph, directed or not.
+    */
+    fn on_registered(&mut self, _directed: bool) {}
+
+    /**
+    * A Vertex has been added.
+    */
+    fn on_vertex_added(&mut self, _key: &K, _label: Option<&L>) {}
+
+    /**
+    * A Vertex has been removed, after the removal of its Edges.
+    */
+    fn on_vertex_removed(&mut self, _key: &K) {}
+
+    /**
+    * The label of a Vertex has been set or removed.
+    */
+    fn on_vertex_label_changed(&mut self, _key: &K, _label: Option<&L>) {}
+
+    /**
+    * An Edge has been added.
+    */
+    fn on_edge_added(&mut self, _from_key: &K, _to_key: &K, _value: Option<&V>) {}
+
+    /**
+    * An Edge has been removed.
+    */
+    fn on_edge_removed(&mut self, _from_key: &K, _to_key: &K) {}
+
+    /**
+    * The value of an Edge has been set or removed.
+    */
+    fn on_edge_value_changed(&mut self, _from_key: &K, _to_key: &K, _value: Option<&V>) {}
+
+    /**
+    * Every Vertex and Edge has been removed.
+    */
+    fn on_clear(&mut self) {}
+}
+
+/// Identifier of an observer registered on a Graph.
+#[deriving(Clone, PartialEq, Eq, Show)]
+pub struct ObserverId(uint);
+
+/// The observers registered on a Graph, they are not cloned with it.
+pub struct ObserverList<K, L, V> {
+    ids:               Vec<ObserverId>,
+    observers:         Vec<Box<GraphObserver<K, L, V>>>,
+    next_id:           uint
+}
+
+impl<K, L, V> ObserverList<K, L, V> {
+    pub fn new() -> ObserverList<K, L, V> {
+        ObserverList {
+            ids:        Vec::new(),
+            observers:  Vec::new(),
+            next_id:    0
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.observers.is_empty()
+    }
+
+    pub fn add(&mut self, observer: Box<GraphObserver<K, L, V>>) -> ObserverId {
+        let id = ObserverId(self.next_id);
+        self.next_id += 1;
+        self.ids.push(id.clone());
+        self.observers.push(observer);
+        id
+    }
+
+    pub fn remove(&mut self, id: &ObserverId) -> Option<Box<GraphObserver<K, L, V>>> {
+        match self.ids.iter().position(|i| i == id) {
+            Some(pos) => {
+                self.ids.remove(pos);
+                self.observers.remove(pos)
+            },
+            None      => None
+        }
+    }
+
+    pub fn notify(&mut self, f: |&mut GraphObserver<K, L, V>|) {
+        for observer in self.observers.mut_iter() {
+            f(&mut **observer);
+        }
+    }
+}
+
+impl<K, L, V> Clone for ObserverList<K, L, V> {
+    fn clone(&self) -> ObserverList<K, L, V> {
+        ObserverList::new()
+    }
+}
+
+impl<K, L, V> PartialEq for ObserverList<K, L, V> {
+    fn eq(&self, _: &ObserverList<K, L, V>) -> bool {
+        true
+    }
+}
+
+// a shared observer stays readable by its owner while registered
+impl<K, L, V, T: GraphObserver<K, L, V>> GraphObserver<K, L, V> for Rc<RefCell<T>> {
+    fn on_registered(&mut self, directed: bool) {
+        self.borrow_mut().on_registered(directed)
+    }
+
+    fn on_vertex_added(&mut self, key: &K, label: Option<&L>) {
+        self.borrow_mut().on_vertex_added(key, label)
+    }
+
+    fn on_vertex_removed(&mut self, key: &K) {
+        self.borrow_mut().on_vertex_removed(key)
+    }
+
+    fn on_vertex_label_changed(&mut self, key: &K, label: Option<&L>) {
+        self.borrow_mut().on_vertex_label_changed(key, label)
+    }
+
+    fn on_edge_added(&mut self, from_key: &K, to_key: &K, value: Option<&V>) {
+        self.borrow_mut().on_edge_added(from_key, to_key, value)
+    }
+
+    fn on_edge_removed(&mut self, from_key: &K, to_key: &K) {
+        self.borrow_mut().on_edge_removed(from_key, to_key)
+    }
+
+    fn on_edge_value_changed(&mut self, from_key: &K, to_key: &K, value: Option<&V>) {
+        self.borrow_mut().on_edge_value_changed(from_key, to_key, value)
+    }
+
+    fn on_clear(&mut self) {
+        self.borrow_mut().on_clear()
+    }
+}
+
+impl<K: Ord, L, V, T> GraphObserver<K, L, V> for PropertyMap<K, T> {
+    fn on_vertex_removed(&mut self, key: &K) {
+        self.pop(key);
+    }
+
+    fn on_clear(&mut self) {
+        self.clear()
+    }
+}
+
+impl<K: Ord + Clone, L, V> GraphObserver<K, L, V> for Attributes<K> {
+    fn on_vertex_removed(&mut self, key: &K) {
+        self.remove_vertex(key)
+    }
+
+    fn on_edge_removed(&mut self, from_key: &K, to_key: &K) {
+        self.remove_edge(from_key, to_key)
+    }
+
+    fn on_clear(&mut self) {
+        self.clear()
+    }
+}
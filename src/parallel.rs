@@ -0,0 +1,253 @@
+//! Multi-task implementations of the iterative analytics.
+//!
+//! Each iteration splits the Vertices between several tasks, every task
+//! computes the new scores of its Vertices from the shared scores of the
+//! previous iteration, then the scores are gathered before the next one.
+
+use std::cmp::{min, max};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use graph::Graph;
+use property_map::PropertyMap;
+use indexed::IndexedGraph;
+
+/**
+* Compute the PageRank of each Vertex of a Graph using several tasks.
+*
+* # Arguments
+* * graph - The graph to rank
+* * damping - The probability to follow an Edge instead of teleporting
+* * iterations - The number of iterations to run
+* * tasks - The number of tasks to use
+*
+* # Return
+* The rank of each Vertex, the ranks sum to 1.
+*/
+pub fn pagerank<K: Ord + Eq + Clone,
+                L: Eq + Clone,
+                V: PartialEq + Clone>
+                (graph: &Graph<K, L, V>,
+                damping: f64,
+                iterations: uint,
+                tasks: uint)
+                -> PropertyMap<K, f64> {
+    let IndexedGraph { keys, out_edges, in_edges, .. } = IndexedGraph::from_graph(graph);
+    let n = keys.len();
+    if n == 0 {
+        return PropertyMap::new()
+    }
+    let out_degree: Arc<Vec<uint>> = Arc::new(out_edges.iter().map(|e| e.len()).collect());
+    let in_edges = Arc::new(in_edges);
+
+    let mut scores = Vec::from_elem(n, 1.0 / n as f64);
+    for _ in range(0, iterations) {
+        // the rank of the dangling Vertices is spread over the whole Graph
+        let mut dangling = 0.0;
+        for (v, score) in scores.iter().enumerate() {
+            if *out_degree.get(v) == 0 {
+                dangling += *score;
+            }
+        }
+        let step = Arc::new(PageRankStep {
+            in_edges:   in_edges.clone(),
+            out_degree: out_degree.clone(),
+            scores:     scores,
+            teleport:   (1.0 - damping) / n as f64 + damping * dangling / n as f64,
+            damping:    damping
+        });
+        scores = split_compute(n, tasks, step, pagerank_score);
+    }
+    to_map(keys, scores)
+}
+
+/**
+* Compute the hub and authority scores of each Vertex of a Graph using
+* several tasks (HITS algorithm).
+*
+* # Arguments
+* * graph - The graph to score
+* * iterations - The number of iterations to run
+* * tasks - The number of tasks to use
+*
+* # Return
+* A tuple (hubs, authorities) of the scores of each Vertex, each vector of
+* scores is normalized to 1.
+*/
+pub fn hits<K: Ord + Eq + Clone,
+            L: Eq + Clone,
+            V: PartialEq + Clone>
+            (graph: &Graph<K, L, V>,
+            iterations: uint,
+            tasks: uint)
+            -> (PropertyMap<K, f64>, PropertyMap<K, f64>) {
+    let IndexedGraph { keys, out_edges, in_edges, .. } = IndexedGraph::from_graph(graph);
+    let n = keys.len();
+    let out_edges = Arc::new(out_edges);
+    let in_edges = Arc::new(in_edges);
+
+    let mut hubs = Vec::from_elem(n, 1.0f64);
+    let mut authorities = Vec::from_elem(n, 1.0f64);
+    for _ in range(0, iterations) {
+        let step = Arc::new(SumStep {
+            edges:  in_edges.clone(),
+            scores: hubs
+        });
+        authorities = normalize(split_compute(n, tasks, step, sum_score));
+        let step = Arc::new(SumStep {
+            edges:  out_edges.clone(),
+            scores: authorities.clone()
+        });
+        hubs = normalize(split_compute(n, tasks, step, sum_score));
+    }
+    (to_map(keys.clone(), hubs), to_map(keys, authorities))
+}
+
+/**
+* Detect the communities of a Graph by label propagation using several
+* tasks.
+*
+* Every Vertex starts in its own community, then at each iteration takes
+* the community the most represented among its neighbors, the direction of
+* the Edges is ignored. The propagation stops when no Vertex changes.
+*
+* # Arguments
+* * graph - The graph to partition
+* * max_iterations - The maximum number of iterations to run
+* * tasks - The number of tasks to use
+*
+* # Return
+* The community of each Vertex, the communities are numbered from 0.
+*/
+pub fn label_propagation<K: Ord + Eq + Clone,
+                         L: Eq + Clone,
+                         V: PartialEq + Clone>
+                         (graph: &Graph<K, L, V>,
+                         max_iterations: uint,
+                         tasks: uint)
+                         -> PropertyMap<K, uint> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let n = indexed.len();
+    let neighbors = Arc::new(indexed.undirected_edges());
+
+    let mut labels: Vec<uint> = range(0, n).collect();
+    for _ in range(0, max_iterations) {
+        let step = Arc::new(LabelStep {
+            neighbors:  neighbors.clone(),
+            labels:     labels.clone()
+        });
+        let new_labels = split_compute(n, tasks, step, majority_label);
+        let stable = new_labels == labels;
+        labels = new_labels;
+        if stable {
+            break
+        }
+    }
+
+    // renumber the communities in order of appearance
+    let mut ids: HashMap<uint, uint> = HashMap::new();
+    let mut communities = PropertyMap::new();
+    for (key, label) in indexed.keys.move_iter().zip(labels.move_iter()) {
+        let next = ids.len();
+        let id = *ids.find_or_insert(label, next);
+        communities.insert(key, id);
+    }
+    communities
+}
+
+struct PageRankStep {
+    in_edges:   Arc<Vec<Vec<uint>>>,
+    out_degree: Arc<Vec<uint>>,
+    scores:     Vec<f64>,
+    teleport:   f64,
+    damping:    f64
+}
+
+fn pagerank_score(step: &PageRankStep, v: uint) -> f64 {
+    let mut sum = 0.0;
+    for &u in step.in_edges.get(v).iter() {
+        sum += *step.scores.get(u) / *step.out_degree.get(u) as f64;
+    }
+    step.teleport + step.damping * sum
+}
+
+struct SumStep {
+    edges:  Arc<Vec<Vec<uint>>>,
+    scores: Vec<f64>
+}
+
+fn sum_score(step: &SumStep, v: uint) -> f64 {
+    step.edges.get(v).iter().fold(0.0, |sum, &u| sum + *step.scores.get(u))
+}
+
+struct LabelStep {
+    neighbors:  Arc<Vec<Vec<uint>>>,
+    labels:     Vec<uint>
+}
+
+fn majority_label(step: &LabelStep, v: uint) -> uint {
+    let current = *step.labels.get(v);
+    let mut counts: HashMap<uint, uint> = HashMap::new();
+    for &u in step.neighbors.get(v).iter() {
+        *counts.find_or_insert(*step.labels.get(u), 0) += 1;
+    }
+
+    // keep the current label on ties, otherwise take the smallest one
+    let mut best = current;
+    let mut best_count = match counts.find(&current) {
+        Some(c) => *c,
+        None    => 0
+    };
+    for (&label, &count) in counts.iter() {
+        if count > best_count || (count == best_count && label < best && best != current) {
+            best = label;
+            best_count = count;
+        }
+    }
+    best
+}
+
+/// Compute f(step, v) for every Vertex v, splitting the Vertices between tasks.
+fn split_compute<S: Send + Share, T: Send>(n: uint,
+                                           tasks: uint,
+                                           step: Arc<S>,
+                                           f: fn(&S, uint) -> T)
+                                           -> Vec<T> {
+    let (tx, rx) = channel();
+    let tasks = max(tasks, 1);
+    let chunk = (n + tasks - 1) / tasks;
+    let mut spawned = 0u;
+    let mut start = 0u;
+    while start < n {
+        let end = min(start + chunk, n);
+        let tx = tx.clone();
+        let step = step.clone();
+        spawn(proc() {
+            let values: Vec<T> = range(start, end).map(|v| f(&*step, v)).collect();
+            tx.send((start, values));
+        });
+        spawned += 1;
+        start = end;
+    }
+
+    let mut chunks: Vec<(uint, Vec<T>)> = range(0, spawned).map(|_| rx.recv()).collect();
+    chunks.sort_by(|&(a, _), &(b, _)| a.cmp(&b));
+    let mut result = Vec::with_capacity(n);
+    for (_, values) in chunks.move_iter() {
+        result.push_all_move(values);
+    }
+    result
+}
+
+fn normalize(scores: Vec<f64>) -> Vec<f64> {
+    let norm = scores.iter().fold(0.0, |sum, s| sum + *s * *s).sqrt();
+    if norm == 0.0 {
+        scores
+    } else {
+        scores.move_iter().map(|s| s / norm).collect()
+    }
+}
+
+fn to_map<K: Ord, T>(keys: Vec<K>, values: Vec<T>) -> PropertyMap<K, T> {
+    keys.move_iter().zip(values.move_iter()).collect()
+}
@@ -0,0 +1,305 @@
+//! Data attached to the Vertices and the Edges outside of a Graph.
+
+use std::collections::TreeMap;
+use std::collections::treemap::Entries;
+use std::iter::FromIterator;
+
+use graph::Graph;
+use observer::GraphObserver;
+
+/**
+* Map from the Vertices of a Graph to arbitrary data.
+*
+* The map is not owned by the Graph. Registered as an observer with
+* add_observer, behind an Rc<RefCell<..>> to keep reading it, it drops the
+* entries of the removed Vertices automatically. An unregistered map has
+* to call sync after removing Vertices to drop their entries.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+* * T - The type of the data
+*/
+#[deriving(Clone, PartialEq, Show)]
+pub struct PropertyMap<K, T> {
+    map:               TreeMap<K, T>
+}
+
+impl<K: Ord, T> PropertyMap<K, T> {
+
+    /**
+    * Create a new empty PropertyMap.
+    *
+    * # Return
+    * A new empty PropertyMap.
+    */
+    pub fn new() -> PropertyMap<K, T> {
+        PropertyMap {
+            map:    TreeMap::new()
+        }
+    }
+
+    /**
+    * Get an iterator over the entries, sorted by key.
+    *
+    * # Return
+    * An iterator of (key, data).
+    */
+    pub fn iter<'r>(&'r self) -> Entries<'r, K, T> {
+        self.map.iter()
+    }
+}
+
+impl<K: Ord + Eq + Clone, T: Clone> PropertyMap<K, T> {
+
+    /**
+    * Create a new PropertyMap with the same data for every Vertex of a
+    * Graph.
+    *
+    * # Arguments
+    * * graph - The graph of the Vertices
+    * * value - The data of each Vertex
+    *
+    * # Return
+    * A new PropertyMap.
+    */
+    pub fn from_graph<L: Eq + Clone,
+                      V: PartialEq + Clone>
+                      (graph: &Graph<K, L, V>,
+                      value: T)
+                      -> PropertyMap<K, T> {
+        graph.vertices_ref_iter().map(|vertex| {
+            (vertex.get_key().clone(), value.clone())
+        }).collect()
+    }
+}
+
+impl<K: Ord + Eq + Clone, T> PropertyMap<K, T> {
+
+    /**
+    * Drop the entries of the Vertices which are not in a Graph anymore.
+    *
+    * # Arguments
+    * * graph - The graph of the Vertices
+    *
+    * # Return
+    * The number of entries dropped.
+    */
+    pub fn sync<L: Eq + Clone,
+                V: PartialEq + Clone>
+                (&mut self,
+                graph: &Graph<K, L, V>)
+                -> uint {
+        let removed: Vec<K> = self.map.iter()
+                                      .map(|(key, _)| key)
+                                      .filter(|key| !graph.vertex_exist(*key))
+                                      .map(|key| key.clone())
+                                      .collect();
+        for key in removed.iter() {
+            self.map.remove(key);
+        }
+        removed.len()
+    }
+}
+
+impl<K, T> Collection for PropertyMap<K, T> {
+    fn len(&self) -> uint {
+        self.map.len()
+    }
+}
+
+impl<K: Ord, T> Mutable for PropertyMap<K, T> {
+    fn clear(&mut self) {
+        self.map.clear()
+    }
+}
+
+impl<K: Ord, T> Map<K, T> for PropertyMap<K, T> {
+    fn find<'r>(&'r self, key: &K) -> Option<&'r T> {
+        self.map.find(key)
+    }
+}
+
+impl<K: Ord, T> MutableMap<K, T> for PropertyMap<K, T> {
+    fn swap(&mut self, key: K, value: T) -> Option<T> {
+        self.map.swap(key, value)
+    }
+
+    fn pop(&mut self, key: &K) -> Option<T> {
+        self.map.pop(key)
+    }
+
+    fn find_mut<'r>(&'r mut self, key: &K) -> Option<&'r mut T> {
+        self.map.find_mut(key)
+    }
+}
+
+impl<K: Ord, T> FromIterator<(K, T)> for PropertyMap<K, T> {
+    fn from_iter<I: Iterator<(K, T)>>(iter: I) -> PropertyMap<K, T> {
+        PropertyMap {
+            map:    iter.collect()
+        }
+    }
+}
+
+/**
+* Map from the Edges of a Graph to arbitrary data, the Edges are identified
+* by the pair (from_key, to_key).
+*
+* The map is not owned by the Graph, call sync after removing Vertices or
+* Edges to drop their entries, or register it as an observer of the Graph.
+* Registered on an undirected Graph, it drops both orientations of a
+* removed Edge.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+* * T - The type of the data
+*/
+#[deriving(Clone, PartialEq, Show)]
+pub struct EdgePropertyMap<K, T> {
+    map:               TreeMap<(K, K), T>,
+    undirected:        bool
+}
+
+impl<K: Ord, T> EdgePropertyMap<K, T> {
+
+    /**
+    * Create a new empty EdgePropertyMap.
+    *
+    * # Return
+    * A new empty EdgePropertyMap.
+    */
+    pub fn new() -> EdgePropertyMap<K, T> {
+        EdgePropertyMap {
+            map:        TreeMap::new(),
+            undirected: false
+        }
+    }
+
+    /**
+    * Get an iterator over the entries, sorted by Edge.
+    *
+    * # Return
+    * An iterator of ((from_key, to_key), data).
+    */
+    pub fn iter<'r>(&'r self) -> Entries<'r, (K, K), T> {
+        self.map.iter()
+    }
+}
+
+impl<K: Ord + Eq + Clone, T: Clone> EdgePropertyMap<K, T> {
+
+    /**
+    * Create a new EdgePropertyMap with the same data for every Edge of a
+    * Graph.
+    *
+    * # Arguments
+    * * graph - The graph of the Edges
+    * * value - The data of each Edge
+    *
+    * # Return
+    * A new EdgePropertyMap.
+    */
+    pub fn from_graph<L: Eq + Clone,
+                      V: PartialEq + Clone>
+                      (graph: &Graph<K, L, V>,
+                      value: T)
+                      -> EdgePropertyMap<K, T> {
+        let mut map = EdgePropertyMap::new();
+        for vertex in graph.vertices_ref_iter() {
+            for (to_key, _) in vertex.edges_iter() {
+                map.insert((vertex.get_key().clone(), to_key.clone()), value.clone());
+            }
+        }
+        map
+    }
+}
+
+impl<K: Ord + Eq + Clone, T> EdgePropertyMap<K, T> {
+
+    /**
+    * Drop the entries of the Edges which are not in a Graph anymore.
+    *
+    * # Arguments
+    * * graph - The graph of the Edges
+    *
+    * # Return
+    * The number of entries dropped.
+    */
+    pub fn sync<L: Eq + Clone,
+                V: PartialEq + Clone>
+                (&mut self,
+                graph: &Graph<K, L, V>)
+                -> uint {
+        let removed: Vec<(K, K)> = self.map.iter()
+                                           .map(|(edge, _)| edge)
+                                           .filter(|&&(ref from, ref to)| {
+                                               !graph.edge_exist(from.clone(), to.clone())
+                                           })
+                                           .map(|edge| edge.clone())
+                                           .collect();
+        for edge in removed.iter() {
+            self.map.remove(edge);
+        }
+        removed.len()
+    }
+}
+
+// here rather than in observer, the direction of the Graph is private to the map
+impl<K: Ord + Clone, L, V, T> GraphObserver<K, L, V> for EdgePropertyMap<K, T> {
+    fn on_registered(&mut self, directed: bool) {
+        self.undirected = !directed;
+    }
+
+    // an undirected Edge is notified once, in either orientation
+    fn on_edge_removed(&mut self, from_key: &K, to_key: &K) {
+        self.pop(&(from_key.clone(), to_key.clone()));
+        if self.undirected {
+            self.pop(&(to_key.clone(), from_key.clone()));
+        }
+    }
+
+    fn on_clear(&mut self) {
+        self.clear()
+    }
+}
+
+impl<K, T> Collection for EdgePropertyMap<K, T> {
+    fn len(&self) -> uint {
+        self.map.len()
+    }
+}
+
+impl<K: Ord, T> Mutable for EdgePropertyMap<K, T> {
+    fn clear(&mut self) {
+        self.map.clear()
+    }
+}
+
+impl<K: Ord, T> Map<(K, K), T> for EdgePropertyMap<K, T> {
+    fn find<'r>(&'r self, edge: &(K, K)) -> Option<&'r T> {
+        self.map.find(edge)
+    }
+}
+
+impl<K: Ord, T> MutableMap<(K, K), T> for EdgePropertyMap<K, T> {
+    fn swap(&mut self, edge: (K, K), value: T) -> Option<T> {
+        self.map.swap(edge, value)
+    }
+
+    fn pop(&mut self, edge: &(K, K)) -> Option<T> {
+        self.map.pop(edge)
+    }
+
+    fn find_mut<'r>(&'r mut self, edge: &(K, K)) -> Option<&'r mut T> {
+        self.map.find_mut(edge)
+    }
+}
+
+impl<K: Ord, T> FromIterator<((K, K), T)> for EdgePropertyMap<K, T> {
+    fn from_iter<I: Iterator<((K, K), T)>>(iter: I) -> EdgePropertyMap<K, T> {
+        EdgePropertyMap {
+            map:        iter.collect(),
+            undirected: false
+        }
+    }
+}
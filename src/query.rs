@@ -0,0 +1,340 @@
+//! Declarative pattern matching on a Graph.
+//!
+//! A Query describes a small pattern of named Vertices and Edges with
+//! optional predicates on the labels and the values, every occurrence of
+//! the pattern in a Graph is found by backtracking:
+//!
+//! ```ignore
+//! let query = Query::new().vertex_where("a", |_, label| label == Some(&x))
+//!                         .vertex_where("b", |_, label| label == Some(&y))
+//!                         .edge_where("a", "b", |value| value.map_or(false, |v| *v > 3));
+//! for bindings in query.matches(&graph) {
+//!     println!("{} -> {}", bindings.find(&"a".to_string()), bindings.find(&"b".to_string()));
+//! }
+//! ```
+
+use std::collections::TreeMap;
+
+use graph::{Graph, Vertex};
+use indexed::IndexedGraph;
+
+struct PatternVertex<'q, K, L> {
+    name:              String,
+    predicate:         Option<|&K, Option<&L>|: 'q -> bool>
+}
+
+struct PatternEdge<'q, V> {
+    from:              uint,
+    to:                uint,
+    predicate:         Option<|Option<&V>|: 'q -> bool>
+}
+
+/**
+* Pattern of Vertices and Edges to find in a Graph.
+*
+* A match binds each Vertex of the pattern to a distinct Vertex of the
+* Graph such that every Edge of the pattern is an Edge of the Graph and
+* every predicate holds. The Graph may have more Edges between the bound
+* Vertices than the pattern.
+*
+* # Types parameters
+* * 'q - The lifetime of the predicates
+* * K - The Vertex's Key type
+* * L - The Vertex's Label type
+* * V - The Edge's Value type
+*/
+pub struct Query<'q, K, L, V> {
+    vertices:          Vec<PatternVertex<'q, K, L>>,
+    edges:             Vec<PatternEdge<'q, V>>
+}
+
+impl<'q,
+     K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
+     Query<'q, K, L, V> {
+
+    /**
+    * Create a new empty Query.
+    *
+    * # Return
+    * A new Query without any Vertex.
+    */
+    pub fn new() -> Query<'q, K, L, V> {
+        Query {
+            vertices:   Vec::new(),
+            edges:      Vec::new()
+        }
+    }
+
+    /**
+    * Add a Vertex matching any Vertex of the Graph.
+    *
+    * # Arguments
+    * * name - The name of the Vertex in the bindings
+    *
+    * # Return
+    * The Query with the Vertex.
+    */
+    pub fn vertex(self, name: &str) -> Query<'q, K, L, V> {
+        let mut query = self;
+        query.variable(name);
+        query
+    }
+
+    /**
+    * Add a Vertex matching the Vertices accepted by a predicate.
+    *
+    * # Arguments
+    * * name - The name of the Vertex in the bindings
+    * * predicate - Accept a Vertex from its key and its label
+    *
+    * # Return
+    * The Query with the Vertex.
+    */
+    pub fn vertex_where(self,
+                        name: &str,
+                        predicate: |&K, Option<&L>|: 'q -> bool)
+                        -> Query<'q, K, L, V> {
+        let mut query = self;
+        let i = query.variable(name);
+        query.vertices.get_mut(i).predicate = Some(predicate);
+        query
+    }
+
+    /**
+    * Add an Edge matching any Edge of the Graph.
+    *
+    * The Vertices not declared yet are added without predicate.
+    *
+    * # Arguments
+    * * from - The name of the first Vertex of the Edge
+    * * to - The name of the second Vertex of the Edge
+    *
+    * # Return
+    * The Query with the Edge.
+    */
+    pub fn edge(self, from: &str, to: &str) -> Query<'q, K, L, V> {
+        let mut query = self;
+        let from = query.variable(from);
+        let to = query.variable(to);
+        query.edges.push(PatternEdge { from: from, to: to, predicate: None });
+        query
+    }
+
+    /**
+    * Add an Edge matching the Edges accepted by a predicate.
+    *
+    * The Vertices not declared yet are added without predicate.
+    *
+    * # Arguments
+    * * from - The name of the first Vertex of the Edge
+    * * to - The name of the second Vertex of the Edge
+    * * predicate - Accept an Edge from its value
+    *
+    * # Return
+    * The Query with the Edge.
+    */
+    pub fn edge_where(self,
+                      from: &str,
+                      to: &str,
+                      predicate: |Option<&V>|: 'q -> bool)
+                      -> Query<'q, K, L, V> {
+        let mut query = self;
+        let from = query.variable(from);
+        let to = query.variable(to);
+        query.edges.push(PatternEdge { from: from, to: to, predicate: Some(predicate) });
+        query
+    }
+
+    /**
+    * Find the matches of the Query in a Graph.
+    *
+    * # Arguments
+    * * graph - The graph to search
+    *
+    * # Return
+    * A lazy iterator over the bindings of each match, from the name of each
+    * Vertex of the Query to the key of its Vertex in the Graph.
+    */
+    pub fn matches<'a>(&'a self, graph: &'a Graph<K, L, V>) -> Matches<'a, 'q, K, L, V> {
+        let indexed = IndexedGraph::from_graph(graph);
+        let n = indexed.len();
+        let order = self.search_order();
+        let mut matches = Matches {
+            query:          self,
+            vertices:       graph.vertices_ref_iter().collect(),
+            indexed:        indexed,
+            order:          order,
+            candidates:     Vec::new(),
+            positions:      Vec::new(),
+            assignment:     Vec::from_elem(self.vertices.len(), None),
+            used:           Vec::from_elem(n, false)
+        };
+        if !self.vertices.is_empty() {
+            let first = matches.candidates_at(0);
+            matches.candidates.push(first);
+            matches.positions.push(0);
+        }
+        matches
+    }
+
+    // the index of a Vertex, added if needed
+    fn variable(&mut self, name: &str) -> uint {
+        match self.vertices.iter().position(|v| v.name.as_slice() == name) {
+            Some(i) => i,
+            None    => {
+                self.vertices.push(PatternVertex {
+                    name:       name.to_string(),
+                    predicate:  None
+                });
+                self.vertices.len() - 1
+            }
+        }
+    }
+
+    // each following Vertex has the most Edges to the previous ones, so
+    // its candidates are the neighbors of an already bound Vertex
+    fn search_order(&self) -> Vec<uint> {
+        let n = self.vertices.len();
+        let mut order = Vec::with_capacity(n);
+        let mut ordered = Vec::from_elem(n, false);
+        while order.len() < n {
+            let next = range(0, n).filter(|&p| !*ordered.get(p)).max_by(|&p| {
+                self.edges.iter().filter(|e| {
+                    (e.from == p && *ordered.get(e.to)) || (e.to == p && *ordered.get(e.from))
+                }).count()
+            }).unwrap();
+            *ordered.get_mut(next) = true;
+            order.push(next);
+        }
+        order
+    }
+}
+
+/**
+* Iterator over the matches of a Query in a Graph.
+*/
+pub struct Matches<'a, 'q, K, L, V> {
+    query:             &'a Query<'q, K, L, V>,
+    vertices:          Vec<&'a Vertex<K, L, V>>,
+    indexed:           IndexedGraph<K>,
+    order:             Vec<uint>,
+    // the candidates of each depth of the search and the next one to try
+    candidates:        Vec<Vec<uint>>,
+    positions:         Vec<uint>,
+    assignment:        Vec<Option<uint>>,
+    used:              Vec<bool>
+}
+
+impl<'a,
+     'q,
+     K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
+     Matches<'a, 'q, K, L, V> {
+
+    // the neighbors of a bound Vertex linked to the Vertex of this depth,
+    // every Vertex otherwise
+    fn candidates_at(&self, depth: uint) -> Vec<uint> {
+        let p = *self.order.get(depth);
+        for edge in self.query.edges.iter() {
+            if edge.to == p {
+                match *self.assignment.get(edge.from) {
+                    Some(v) => return self.indexed.out_edges.get(v).clone(),
+                    None    => {}
+                }
+            }
+            if edge.from == p {
+                match *self.assignment.get(edge.to) {
+                    Some(v) => return self.indexed.in_edges.get(v).clone(),
+                    None    => {}
+                }
+            }
+        }
+        range(0, self.indexed.len()).collect()
+    }
+
+    // can the Vertex of this depth be bound to v
+    fn feasible(&self, depth: uint, v: uint) -> bool {
+        let p = *self.order.get(depth);
+        if *self.used.get(v) {
+            return false
+        }
+        let vertex = *self.vertices.get(v);
+        let accepted = match self.query.vertices.get(p).predicate {
+            Some(ref predicate) => (*predicate)(vertex.get_key(), vertex.get_label()),
+            None                => true
+        };
+        accepted && self.query.edges.iter().all(|edge| {
+            let ends = if edge.from == p && edge.to == p {
+                Some((v, v))
+            } else if edge.from == p {
+                (*self.assignment.get(edge.to)).map(|to| (v, to))
+            } else if edge.to == p {
+                (*self.assignment.get(edge.from)).map(|from| (from, v))
+            } else {
+                None
+            };
+            match ends {
+                Some((from, to)) => {
+                    let from = *self.vertices.get(from);
+                    let to_key = self.indexed.keys.get(to);
+                    from.edge_exist(to_key) && match edge.predicate {
+                        Some(ref predicate) => (*predicate)(from.get_edge_value(to_key)),
+                        None                => true
+                    }
+                },
+                None             => true
+            }
+        })
+    }
+}
+
+impl<'a,
+     'q,
+     K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
+     Iterator<TreeMap<String, &'a K>> for Matches<'a, 'q, K, L, V> {
+    fn next(&mut self) -> Option<TreeMap<String, &'a K>> {
+        let n = self.order.len();
+        while !self.candidates.is_empty() {
+            let depth = self.candidates.len() - 1;
+            let p = *self.order.get(depth);
+            // release the Vertex bound at this depth by the previous step
+            match *self.assignment.get(p) {
+                Some(v) => {
+                    *self.used.get_mut(v) = false;
+                    *self.assignment.get_mut(p) = None;
+                },
+                None    => {}
+            }
+            let position = *self.positions.get(depth);
+            if position == self.candidates.get(depth).len() {
+                self.candidates.pop();
+                self.positions.pop();
+                continue
+            }
+            *self.positions.get_mut(depth) += 1;
+            let v = *self.candidates.get(depth).get(position);
+            if !self.feasible(depth, v) {
+                continue
+            }
+            *self.assignment.get_mut(p) = Some(v);
+            *self.used.get_mut(v) = true;
+            if depth + 1 == n {
+                let mut bindings = TreeMap::new();
+                for (vertex, bound) in self.query.vertices.iter().zip(self.assignment.iter()) {
+                    let key = self.vertices.get((*bound).unwrap()).get_key();
+                    bindings.insert(vertex.name.clone(), key);
+                }
+                return Some(bindings)
+            }
+            let next = self.candidates_at(depth + 1);
+            self.candidates.push(next);
+            self.positions.push(0);
+        }
+        None
+    }
+}
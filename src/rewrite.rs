@@ -0,0 +1,225 @@
+//! Rewriting of a Graph by rules replacing a pattern.
+//!
+//! A Rule is a pattern Graph, a replacement Graph and an interface pairing
+//! Vertices of the pattern with Vertices of the replacement. The Vertices
+//! of both Graphs are named by their keys. Each match of the pattern is
+//! rewritten: the Edges of the pattern are removed, the Vertices of the
+//! pattern outside the interface are removed with all their Edges, the
+//! Vertices of the interface are kept and take the label of their
+//! replacement Vertex, the other Vertices of the replacement are created
+//! and the Edges of the replacement are added.
+//!
+//! ```ignore
+//! // a -> b becomes a -> c -> b
+//! let rule = Rule::new(pattern, replacement, vec!(("a".to_string(), "a".to_string()),
+//!                                                 ("b".to_string(), "b".to_string()))).unwrap();
+//! let mut next = 1000u;
+//! apply_rules(&mut graph, &[rule], Deterministic, 100, |_| { next += 1; next });
+//! ```
+
+use std::collections::{TreeMap, TreeSet};
+
+use graph::Graph;
+use query::Query;
+
+/**
+* Order in which the matches of the rules are rewritten.
+*/
+#[deriving(Clone, PartialEq, Show)]
+pub enum RewriteStrategy {
+    /// Rewrite the first match of the first rule which matches, then start
+    /// again from the first rule
+    Deterministic,
+    /// Rewrite every match of a rule which does not share a Vertex with a
+    /// match already rewritten, for each rule in turn, then start again
+    Exhaustive
+}
+
+/**
+* Rule replacing a pattern by another Graph.
+*
+* A pattern Vertex with a label only matches Vertices with the same label
+* and a pattern Edge with a value only matches Edges with the same value,
+* the other ones match anything.
+*
+* # Types parameters
+* * L - The Vertex's Label type
+* * V - The Edge's Value type
+*/
+#[deriving(Clone)]
+pub struct Rule<L, V> {
+    pattern:           Graph<String, L, V>,
+    replacement:       Graph<String, L, V>,
+    // from the pattern Vertices to the replacement Vertices
+    interface:         TreeMap<String, String>
+}
+
+impl<L: Eq + Clone,
+     V: PartialEq + Clone>
+     Rule<L, V> {
+
+    /**
+    * Create a new Rule.
+    *
+    * # Arguments
+    * * pattern - The graph to find
+    * * replacement - The graph replacing the pattern
+    * * interface - The (pattern_key, replacement_key) of the kept Vertices
+    *
+    * # Return
+    * Some(rule), None if a Vertex of the interface does not exist or
+    * appears twice.
+    */
+    pub fn new(pattern: Graph<String, L, V>,
+               replacement: Graph<String, L, V>,
+               interface: Vec<(String, String)>)
+               -> Option<Rule<L, V>> {
+        let mut map = TreeMap::new();
+        let mut images = TreeSet::new();
+        for (from, to) in interface.move_iter() {
+            if !pattern.vertex_exist(&from) || !replacement.vertex_exist(&to) ||
+                !images.insert(to.clone()) || !map.insert(from, to) {
+                return None
+            }
+        }
+        Some(Rule {
+            pattern:        pattern,
+            replacement:    replacement,
+            interface:      map
+        })
+    }
+
+    // the matches of the pattern, all of them or only the first one
+    fn find_matches<K: Ord + Eq + Clone>
+                   (&self,
+                   graph: &Graph<K, L, V>,
+                   first_only: bool)
+                   -> Vec<TreeMap<String, K>> {
+        let mut query = Query::new();
+        for vertex in self.pattern.vertices_ref_iter() {
+            query = query.vertex(vertex.get_key().as_slice());
+            for (to_key, _) in vertex.edges_iter() {
+                query = query.edge(vertex.get_key().as_slice(), to_key.as_slice());
+            }
+        }
+
+        let mut found = Vec::new();
+        for bindings in query.matches(graph) {
+            let accepted = self.pattern.vertices_ref_iter().all(|vertex| {
+                let host = graph.get_vertex((*bindings.find(vertex.get_key()).unwrap()).clone()).unwrap();
+                let label_ok = match vertex.get_label() {
+                    Some(label) => host.get_label() == Some(label),
+                    None        => true
+                };
+                label_ok && vertex.edges_iter().all(|(to_key, value)| {
+                    match value {
+                        Some(value) => host.get_edge_value(*bindings.find(to_key).unwrap()) == Some(value),
+                        None        => true
+                    }
+                })
+            });
+            if accepted {
+                found.push(bindings.iter().map(|(name, &key)| (name.clone(), key.clone())).collect());
+                if first_only {
+                    break
+                }
+            }
+        }
+        found
+    }
+
+    // replace a match by the replacement
+    fn rewrite<K: Ord + Eq + Clone>
+              (&self,
+              graph: &mut Graph<K, L, V>,
+              bindings: &TreeMap<String, K>,
+              new_key: &mut |&String| -> K) {
+        for vertex in self.pattern.vertices_ref_iter() {
+            for (to_key, _) in vertex.edges_iter() {
+                graph.remove_edge(bindings.find(vertex.get_key()).unwrap().clone(),
+                                  bindings.find(to_key).unwrap().clone());
+            }
+        }
+        let mut host_keys: TreeMap<&String, K> = TreeMap::new();
+        for (name, key) in bindings.iter() {
+            match self.interface.find(name) {
+                Some(image) => { host_keys.insert(image, key.clone()); },
+                None        => { graph.remove_vertex(key.clone()); }
+            }
+        }
+        for vertex in self.replacement.vertices_ref_iter() {
+            let label = vertex.get_label().map(|l| l.clone());
+            if host_keys.contains_key(&vertex.get_key()) {
+                graph.set_vertex_label_opt(host_keys.find(&vertex.get_key()).unwrap().clone(), label);
+            } else {
+                let key = (*new_key)(vertex.get_key());
+                graph.add_vertex_opt_l(key.clone(), label);
+                host_keys.insert(vertex.get_key(), key);
+            }
+        }
+        for vertex in self.replacement.vertices_ref_iter() {
+            for (to_key, value) in vertex.edges_iter() {
+                graph.add_edge_opt_v(host_keys.find(&vertex.get_key()).unwrap().clone(),
+                                     host_keys.find(&to_key).unwrap().clone(),
+                                     value.map(|v| v.clone()));
+            }
+        }
+    }
+}
+
+/**
+* Rewrite a Graph with rules until none of them matches or a number of
+* rewrites is reached.
+*
+* # Arguments
+* * graph - The graph to rewrite
+* * rules - The rules, tried in their order
+* * strategy - The order of the rewrites
+* * max_rewrites - The maximum number of matches rewritten, the rules may
+* never stop matching
+* * new_key - Give a new unused key for a created Vertex from the name of
+* its replacement Vertex
+*
+* # Return
+* The number of matches rewritten.
+*/
+pub fn apply_rules<K: Ord + Eq + Clone,
+                   L: Eq + Clone,
+                   V: PartialEq + Clone>
+                   (graph: &mut Graph<K, L, V>,
+                   rules: &[Rule<L, V>],
+                   strategy: RewriteStrategy,
+                   max_rewrites: uint,
+                   new_key: |&String| -> K)
+                   -> uint {
+    let mut new_key = new_key;
+    let mut rewrites = 0;
+    loop {
+        let mut rewritten = false;
+        for rule in rules.iter() {
+            if rewrites >= max_rewrites {
+                return rewrites
+            }
+            let matches = rule.find_matches(graph, strategy == Deterministic);
+            let mut touched = TreeSet::new();
+            for bindings in matches.iter() {
+                if rewrites >= max_rewrites {
+                    return rewrites
+                }
+                if bindings.iter().any(|(_, key)| touched.contains(key)) {
+                    continue
+                }
+                touched.extend(bindings.iter().map(|(_, key)| key.clone()));
+                rule.rewrite(graph, bindings, &mut new_key);
+                rewrites += 1;
+                rewritten = true;
+            }
+            if rewritten && strategy == Deterministic {
+                break
+            }
+        }
+        if !rewritten {
+            return rewrites
+        }
+    }
+}
@@ -0,0 +1,245 @@
+//! Sampling of subgraphs, to prototype analyses on Graphs too big to be
+//! processed whole.
+//!
+//! Each sampler returns a subgraph with at most the requested number of
+//! Vertices, fewer only when the Graph is smaller. Labels and values are
+//! kept. Random node sampling keeps the degree distribution scaled down,
+//! random edge sampling favors the high degree Vertices, snowball and
+//! forest fire sampling keep the local structure (clustering, communities)
+//! and forest fire also keeps the heavy tail of the degrees.
+
+use std::collections::{Deque, RingBuf};
+use std::rand::Rng;
+
+use graph::Graph;
+use indexed::IndexedGraph;
+
+/**
+* Sample Vertices uniformly at random.
+*
+* # Arguments
+* * graph - The graph to sample
+* * size - The number of Vertices to sample
+* * rng - The random number generator used to choose the Vertices
+*
+* # Return
+* The subgraph induced by the sampled Vertices.
+*/
+pub fn random_node_sample<K: Ord + Eq + Clone,
+                          L: Eq + Clone,
+                          V: PartialEq + Clone,
+                          R: Rng>
+                          (graph: &Graph<K, L, V>,
+                          size: uint,
+                          rng: &mut R)
+                          -> Graph<K, L, V> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let mut order: Vec<uint> = range(0, indexed.len()).collect();
+    rng.shuffle(order.as_mut_slice());
+    order.truncate(size);
+    induced_subgraph(graph, &indexed, order.as_slice())
+}
+
+/**
+* Sample Edges uniformly at random until their Vertices reach the requested
+* size.
+*
+* # Arguments
+* * graph - The graph to sample
+* * size - The number of Vertices to sample
+* * rng - The random number generator used to choose the Edges
+*
+* # Return
+* The subgraph of the sampled Edges and their Vertices, it has fewer
+* Vertices than size when the Edges don't cover enough Vertices.
+*/
+pub fn random_edge_sample<K: Ord + Eq + Clone,
+                          L: Eq + Clone,
+                          V: PartialEq + Clone,
+                          R: Rng>
+                          (graph: &Graph<K, L, V>,
+                          size: uint,
+                          rng: &mut R)
+                          -> Graph<K, L, V> {
+    let mut edges: Vec<(&K, &K, Option<&V>)> = Vec::new();
+    for vertex in graph.vertices_ref_iter() {
+        for (to_key, value) in vertex.edges_iter() {
+            edges.push((vertex.get_key(), to_key, value));
+        }
+    }
+    rng.shuffle(edges.as_mut_slice());
+
+    let mut sample = Graph::new();
+    for &(from_key, to_key, value) in edges.iter() {
+        let new_vertices = !sample.vertex_exist(from_key) as uint +
+                           (from_key != to_key && !sample.vertex_exist(to_key)) as uint;
+        if sample.len() + new_vertices > size {
+            if sample.len() == size {
+                break
+            }
+            continue
+        }
+        for &key in [from_key, to_key].iter() {
+            if !sample.vertex_exist(key) {
+                let label = graph.get_vertex_label(key.clone()).map(|l| l.clone());
+                sample.add_vertex_opt_l(key.clone(), label);
+            }
+        }
+        sample.add_edge_opt_v(from_key.clone(), to_key.clone(), value.map(|v| v.clone()));
+    }
+    sample
+}
+
+/**
+* Sample Vertices by breadth first search from a random Vertex, following
+* a bounded number of random neighbors of each Vertex.
+*
+* The direction of the Edges is ignored. When the search is exhausted
+* before reaching the requested size it restarts from another random
+* Vertex.
+*
+* # Arguments
+* * graph - The graph to sample
+* * size - The number of Vertices to sample
+* * neighbors - The maximum number of neighbors followed from each Vertex
+* * rng - The random number generator used to choose the Vertices
+*
+* # Return
+* The subgraph induced by the sampled Vertices.
+*/
+pub fn snowball_sample<K: Ord + Eq + Clone,
+                       L: Eq + Clone,
+                       V: PartialEq + Clone,
+                       R: Rng>
+                       (graph: &Graph<K, L, V>,
+                       size: uint,
+                       neighbors: uint,
+                       rng: &mut R)
+                       -> Graph<K, L, V> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let sampled = spread(&indexed, size, rng, |candidates, _| {
+        if neighbors < candidates { neighbors } else { candidates }
+    });
+    induced_subgraph(graph, &indexed, sampled.as_slice())
+}
+
+/**
+* Sample Vertices by forest fire: a fire starts at a random Vertex and
+* each burning Vertex burns a geometrically distributed number of its
+* unburnt neighbors.
+*
+* The direction of the Edges is ignored. Each Vertex burns on average
+* p / (1 - p) neighbors, when the fire dies before reaching the requested
+* size a new one starts at another random Vertex. Values of p around 0.7
+* keep the degree distribution and the diameter of the Graph.
+*
+* # Arguments
+* * graph - The graph to sample
+* * size - The number of Vertices to sample
+* * p - The forward burning probability, in [0, 1)
+* * rng - The random number generator used to spread the fire
+*
+* # Return
+* The subgraph induced by the burnt Vertices.
+*/
+pub fn forest_fire_sample<K: Ord + Eq + Clone,
+                          L: Eq + Clone,
+                          V: PartialEq + Clone,
+                          R: Rng>
+                          (graph: &Graph<K, L, V>,
+                          size: uint,
+                          p: f64,
+                          rng: &mut R)
+                          -> Graph<K, L, V> {
+    let indexed = IndexedGraph::from_graph(graph);
+    let sampled = spread(&indexed, size, rng, |candidates, rng| {
+        let mut burnt = 0;
+        while burnt < candidates && rng.gen::<f64>() < p {
+            burnt += 1;
+        }
+        burnt
+    });
+    induced_subgraph(graph, &indexed, sampled.as_slice())
+}
+
+// breadth first spreading from random seeds, count gives the number of
+// unvisited neighbors taken from a Vertex among the candidates ones
+fn spread<K: Ord + Eq + Clone,
+          R: Rng>
+          (indexed: &IndexedGraph<K>,
+          size: uint,
+          rng: &mut R,
+          count: |uint, &mut R| -> uint)
+          -> Vec<uint> {
+    let neighbors = indexed.undirected_edges();
+    let mut seeds: Vec<uint> = range(0, indexed.len()).collect();
+    rng.shuffle(seeds.as_mut_slice());
+    let mut visited = Vec::from_elem(indexed.len(), false);
+    let mut sampled = Vec::new();
+    let mut queue = RingBuf::new();
+
+    for &seed in seeds.iter() {
+        if sampled.len() >= size {
+            break
+        }
+        if *visited.get(seed) {
+            continue
+        }
+        *visited.get_mut(seed) = true;
+        sampled.push(seed);
+        queue.push_back(seed);
+        while !queue.is_empty() && sampled.len() < size {
+            let v = queue.pop_front().unwrap();
+            let mut candidates: Vec<uint> = neighbors.get(v).iter()
+                                                      .map(|&u| u)
+                                                      .filter(|&u| !*visited.get(u))
+                                                      .collect();
+            rng.shuffle(candidates.as_mut_slice());
+            let taken = count(candidates.len(), rng);
+            for &u in candidates.iter().take(taken) {
+                if sampled.len() >= size {
+                    break
+                }
+                *visited.get_mut(u) = true;
+                sampled.push(u);
+                queue.push_back(u);
+            }
+        }
+        queue.clear();
+    }
+    sampled
+}
+
+// the Vertices and the Edges between them, with their labels and values
+fn induced_subgraph<K: Ord + Eq + Clone,
+                    L: Eq + Clone,
+                    V: PartialEq + Clone>
+                    (graph: &Graph<K, L, V>,
+                    indexed: &IndexedGraph<K>,
+                    vertices: &[uint])
+                    -> Graph<K, L, V> {
+    let mut sampled = Vec::from_elem(indexed.len(), false);
+    for &v in vertices.iter() {
+        *sampled.get_mut(v) = true;
+    }
+    let mut subgraph = Graph::new();
+    for &v in vertices.iter() {
+        let key = indexed.keys.get(v);
+        let label = graph.get_vertex_label(key.clone()).map(|l| l.clone());
+        subgraph.add_vertex_opt_l(key.clone(), label);
+    }
+    for &v in vertices.iter() {
+        let vertex = graph.get_vertex(indexed.keys.get(v).clone()).unwrap();
+        for (to_key, value) in vertex.edges_iter() {
+            match indexed.index.find(to_key) {
+                Some(&to) if *sampled.get(to) => {
+                    subgraph.add_edge_opt_v(vertex.get_key().clone(),
+                                            to_key.clone(),
+                                            value.map(|v| v.clone()));
+                },
+                _                             => {}
+            }
+        }
+    }
+    subgraph
+}
@@ -0,0 +1,383 @@
+//! Graph with a position in the plane for each Vertex.
+
+use std::collections::TreeMap;
+
+use graph::Graph;
+use property_map::EdgePropertyMap;
+use weight::WeightProvider;
+
+/**
+* Graph whose Vertices have 2D coordinates, indexed by a uniform grid for
+* the geometric queries.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+* * L - The Vertex's Label type
+* * V - The Edge's Value type
+*/
+#[deriving(Clone)]
+pub struct SpatialGraph<K, L, V> {
+    graph:             Graph<K, L, V>,
+    positions:         TreeMap<K, (f64, f64)>,
+    cell_size:         f64,
+    grid:              TreeMap<(i64, i64), Vec<K>>
+}
+
+impl<K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
+     SpatialGraph<K, L, V> {
+
+    /**
+    * Create a new empty SpatialGraph.
+    *
+    * # Arguments
+    * * cell_size - The side of the cells of the grid, about the distance
+    *   between close Vertices is a good choice
+    *
+    * # Return
+    * A new empty SpatialGraph.
+    */
+    pub fn new(cell_size: f64) -> SpatialGraph<K, L, V> {
+        SpatialGraph {
+            graph:      Graph::new(),
+            positions:  TreeMap::new(),
+            cell_size:  if cell_size > 0.0 { cell_size } else { 1.0 },
+            grid:       TreeMap::new()
+        }
+    }
+
+    /**
+    * Get the underlying Graph.
+    */
+    pub fn graph<'r>(&'r self) -> &'r Graph<K, L, V> {
+        &self.graph
+    }
+
+    /**
+    * Add a Vertex at a position with an optional label.
+    *
+    * # Arguments
+    * * key - The key of the Vertex
+    * * label - The optional label of the Vertex
+    * * x - The abscissa of the Vertex
+    * * y - The ordinate of the Vertex
+    *
+    * # Return
+    * true if the Vertex is added, false otherwise.
+    */
+    pub fn add_vertex_opt_l(&mut self,
+                            key: K,
+                            label: Option<L>,
+                            x: f64,
+                            y: f64)
+                            -> bool {
+        if !self.graph.add_vertex_opt_l(key.clone(), label) {
+            return false
+        }
+        self.insert_position(key, (x, y));
+        true
+    }
+
+    /**
+    * Add a Vertex at a position.
+    *
+    * # Return
+    * true if the Vertex is added, false otherwise.
+    */
+    pub fn add_vertex(&mut self,
+                      key: K,
+                      x: f64,
+                      y: f64)
+                      -> bool {
+        self.add_vertex_opt_l(key, None, x, y)
+    }
+
+    /**
+    * Remove a Vertex and its Edges.
+    *
+    * # Return
+    * true if the Vertex is removed, false otherwise.
+    */
+    pub fn remove_vertex(&mut self, key: K) -> bool {
+        if !self.graph.remove_vertex(key.clone()) {
+            return false
+        }
+        self.remove_position(&key);
+        true
+    }
+
+    /**
+    * Get the position of a Vertex.
+    *
+    * # Return
+    * Some((x, y)) if the Vertex exist, None otherwise.
+    */
+    pub fn get_position(&self, key: &K) -> Option<(f64, f64)> {
+        self.positions.find(key).map(|&p| p)
+    }
+
+    /**
+    * Move a Vertex.
+    *
+    * # Return
+    * true if the Vertex is moved, false if it does not exist.
+    */
+    pub fn set_position(&mut self,
+                        key: K,
+                        x: f64,
+                        y: f64)
+                        -> bool {
+        if !self.positions.contains_key(&key) {
+            return false
+        }
+        self.remove_position(&key);
+        self.insert_position(key, (x, y));
+        true
+    }
+
+    /**
+    * Add an Edge with an optional value.
+    *
+    * # Return
+    * true if the Edge is added, false otherwise.
+    */
+    pub fn add_edge_opt_v(&mut self,
+                          from_key: K,
+                          to_key: K,
+                          value: Option<V>)
+                          -> bool {
+        self.graph.add_edge_opt_v(from_key, to_key, value)
+    }
+
+    /**
+    * Remove an Edge.
+    *
+    * # Return
+    * true if the Edge is removed, false otherwise.
+    */
+    pub fn remove_edge(&mut self,
+                       from_key: K,
+                       to_key: K)
+                       -> bool {
+        self.graph.remove_edge(from_key, to_key)
+    }
+
+    /**
+    * Get the Euclidean distance between two Vertices.
+    *
+    * # Return
+    * Some(distance) if both Vertices exist, None otherwise.
+    */
+    pub fn distance(&self, from_key: &K, to_key: &K) -> Option<f64> {
+        match (self.positions.find(from_key), self.positions.find(to_key)) {
+            (Some(&(x1, y1)), Some(&(x2, y2))) => {
+                Some(((x2 - x1) * (x2 - x1) + (y2 - y1) * (y2 - y1)).sqrt())
+            },
+            _                                  => None
+        }
+    }
+
+    /**
+    * Get the length of every Edge, for the algorithms taking weights.
+    *
+    * # Return
+    * The Euclidean length of each Edge.
+    */
+    pub fn euclidean_weights(&self) -> EdgePropertyMap<K, f64> {
+        let mut weights = EdgePropertyMap::new();
+        for vertex in self.graph.vertices_ref_iter() {
+            for (to_key, _) in vertex.edges_iter() {
+                match self.distance(vertex.get_key(), to_key) {
+                    Some(d) => { weights.insert((vertex.get_key().clone(), to_key.clone()), d); },
+                    None    => {}
+                }
+            }
+        }
+        weights
+    }
+
+    /**
+    * Find the Vertex closest to a point.
+    *
+    * The rings of cells around the point are searched until no farther
+    * cell can hold a closer Vertex.
+    *
+    * # Return
+    * Some(key) of the closest Vertex, None if the SpatialGraph is empty.
+    */
+    pub fn nearest<'r>(&'r self, x: f64, y: f64) -> Option<&'r K> {
+        let (cx, cy) = self.cell(x, y);
+        let max_ring = self.grid.iter().map(|(&(gx, gy), _)| {
+            let dx = (gx - cx).abs();
+            let dy = (gy - cy).abs();
+            if dx > dy { dx } else { dy }
+        }).max();
+        let max_ring = match max_ring {
+            Some(ring) => ring,
+            None       => return None
+        };
+
+        let mut best: Option<(f64, &'r K)> = None;
+        let mut ring = 0i64;
+        while ring <= max_ring {
+            for gx in range(cx - ring, cx + ring + 1) {
+                for gy in range(cy - ring, cy + ring + 1) {
+                    if (gx - cx).abs() != ring && (gy - cy).abs() != ring {
+                        continue
+                    }
+                    for key in self.grid.find(&(gx, gy)).iter().flat_map(|keys| keys.iter()) {
+                        let &(px, py) = self.positions.find(key).unwrap();
+                        let d = ((px - x) * (px - x) + (py - y) * (py - y)).sqrt();
+                        let closer = match best {
+                            Some((best_d, _)) => d < best_d,
+                            None              => true
+                        };
+                        if closer {
+                            best = Some((d, key));
+                        }
+                    }
+                }
+            }
+            // the cells out of the ring are farther than this
+            match best {
+                Some((d, _)) if d <= ring as f64 * self.cell_size => break,
+                _                                                 => {}
+            }
+            ring += 1;
+        }
+        best.map(|(_, key)| key)
+    }
+
+    /**
+    * Find the Vertices at most at a distance from a point.
+    *
+    * # Return
+    * The keys of the Vertices, sorted.
+    */
+    pub fn within_radius<'r>(&'r self, x: f64, y: f64, radius: f64) -> Vec<&'r K> {
+        let mut keys: Vec<&'r K> = self.in_box(x - radius, y - radius, x + radius, y + radius)
+                                       .move_iter().filter(|key| {
+            let &(px, py) = self.positions.find(*key).unwrap();
+            (px - x) * (px - x) + (py - y) * (py - y) <= radius * radius
+        }).collect();
+        keys.sort();
+        keys
+    }
+
+    /**
+    * Find the Vertices in a bounding box, borders included.
+    *
+    * # Return
+    * The keys of the Vertices, sorted.
+    */
+    pub fn within_box<'r>(&'r self,
+                          min_x: f64,
+                          min_y: f64,
+                          max_x: f64,
+                          max_y: f64)
+                          -> Vec<&'r K> {
+        let mut keys = self.in_box(min_x, min_y, max_x, max_y);
+        keys.sort();
+        keys
+    }
+
+    /**
+    * Extract the Vertices in a bounding box and the Edges between them.
+    *
+    * # Return
+    * A new SpatialGraph with the same grid.
+    */
+    pub fn subgraph_in_box(&self,
+                           min_x: f64,
+                           min_y: f64,
+                           max_x: f64,
+                           max_y: f64)
+                           -> SpatialGraph<K, L, V> {
+        let mut subgraph = SpatialGraph::new(self.cell_size);
+        let keys = self.within_box(min_x, min_y, max_x, max_y);
+        for &key in keys.iter() {
+            let &(x, y) = self.positions.find(key).unwrap();
+            let label = self.graph.get_vertex_label(key.clone()).map(|l| l.clone());
+            subgraph.add_vertex_opt_l(key.clone(), label, x, y);
+        }
+        for &key in keys.iter() {
+            let vertex = self.graph.get_vertex(key.clone()).unwrap();
+            for (to_key, value) in vertex.edges_iter() {
+                if subgraph.positions.contains_key(to_key) {
+                    subgraph.add_edge_opt_v(key.clone(), to_key.clone(), value.map(|v| v.clone()));
+                }
+            }
+        }
+        subgraph
+    }
+
+    fn in_box<'r>(&'r self,
+                  min_x: f64,
+                  min_y: f64,
+                  max_x: f64,
+                  max_y: f64)
+                  -> Vec<&'r K> {
+        let (min_cx, min_cy) = self.cell(min_x, min_y);
+        let (max_cx, max_cy) = self.cell(max_x, max_y);
+        let mut keys = Vec::new();
+        for (&(gx, gy), cell) in self.grid.iter() {
+            if gx < min_cx || gx > max_cx || gy < min_cy || gy > max_cy {
+                continue
+            }
+            for key in cell.iter() {
+                let &(px, py) = self.positions.find(key).unwrap();
+                if min_x <= px && px <= max_x && min_y <= py && py <= max_y {
+                    keys.push(key);
+                }
+            }
+        }
+        keys
+    }
+
+    fn cell(&self, x: f64, y: f64) -> (i64, i64) {
+        ((x / self.cell_size).floor() as i64, (y / self.cell_size).floor() as i64)
+    }
+
+    fn insert_position(&mut self, key: K, position: (f64, f64)) {
+        let (x, y) = position;
+        let cell = self.cell(x, y);
+        if !self.grid.contains_key(&cell) {
+            self.grid.insert(cell, Vec::new());
+        }
+        self.grid.find_mut(&cell).unwrap().push(key.clone());
+        self.positions.insert(key, position);
+    }
+
+    fn remove_position(&mut self, key: &K) {
+        let (x, y) = match self.positions.pop(key) {
+            Some(position) => position,
+            None           => return
+        };
+        let cell = self.cell(x, y);
+        let empty = match self.grid.find_mut(&cell) {
+            Some(keys) => {
+                keys.retain(|k| k != key);
+                keys.is_empty()
+            },
+            None       => false
+        };
+        if empty {
+            self.grid.pop(&cell);
+        }
+    }
+}
+
+// the Euclidean length of the existing Edges
+impl<K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
+     WeightProvider<K, f64> for SpatialGraph<K, L, V> {
+    fn weight(&self, from_key: &K, to_key: &K) -> Option<f64> {
+        if self.graph.edge_exist(from_key.clone(), to_key.clone()) {
+            self.distance(from_key, to_key)
+        } else {
+            None
+        }
+    }
+}
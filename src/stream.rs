@@ -0,0 +1,257 @@
+//! Algorithms working on a stream of Edges.
+//!
+//! The Edges are seen once and the full Graph is never stored, so these
+//! work on inputs which don't fit in memory. The direction of the Edges is
+//! ignored.
+
+use std::collections::{TreeMap, TreeSet};
+use std::hash::Hash;
+use std::hash::sip::hash_with_keys;
+use std::rand::Rng;
+
+use dynamic::DynamicConnectivity;
+
+/**
+* Estimate the number of triangles of a stream of Edges.
+*
+* A fixed number of Edges is kept by reservoir sampling, each new Edge
+* counts the triangles it closes in the sample, weighted by the inverse
+* probability for these triangles to be sampled (TRIEST-IMPR estimator).
+*
+* # Types parameters
+* * K - The Vertex's Key type
+* * R - The random number generator type
+*/
+pub struct TriangleCounter<K, R> {
+    capacity:          uint,
+    seen:              uint,
+    sample:            Vec<(K, K)>,
+    neighbors:         TreeMap<K, TreeSet<K>>,
+    estimate:          f64,
+    rng:               R
+}
+
+impl<K: Ord + Eq + Clone, R: Rng> TriangleCounter<K, R> {
+
+    /**
+    * Create a new TriangleCounter.
+    *
+    * # Arguments
+    * * capacity - The number of Edges to keep in memory, at least 2
+    * * rng - The random number generator used for the sampling
+    *
+    * # Return
+    * A new TriangleCounter.
+    */
+    pub fn new(capacity: uint, rng: R) -> TriangleCounter<K, R> {
+        TriangleCounter {
+            capacity:   if capacity < 2 { 2 } else { capacity },
+            seen:       0,
+            sample:     Vec::with_capacity(capacity),
+            neighbors:  TreeMap::new(),
+            estimate:   0.0,
+            rng:        rng
+        }
+    }
+
+    /**
+    * Process the next Edge of the stream.
+    *
+    * Self loops are ignored, each Edge should appear only once in the
+    * stream.
+    *
+    * # Arguments
+    * * from_key - The key of the first Vertex of the Edge
+    * * to_key - The key of the second Vertex of the Edge
+    */
+    pub fn add_edge(&mut self, from_key: K, to_key: K) {
+        if from_key == to_key {
+            return
+        }
+        self.seen += 1;
+
+        let t = self.seen as f64;
+        let m = self.capacity as f64;
+        let weight = ((t - 1.0) * (t - 2.0) / (m * (m - 1.0))).max(1.0);
+        let closed = match (self.neighbors.find(&from_key), self.neighbors.find(&to_key)) {
+            (Some(a), Some(b)) => a.intersection(b).count(),
+            _                  => 0
+        };
+        self.estimate += weight * closed as f64;
+
+        if self.sample.len() < self.capacity {
+            self.insert(from_key, to_key);
+        } else if self.rng.gen_range(0, self.seen) < self.capacity {
+            let victim = self.rng.gen_range(0, self.capacity);
+            let (old_from, old_to) = self.sample.swap_remove(victim).unwrap();
+            self.unlink(&old_from, &old_to);
+            self.unlink(&old_to, &old_from);
+            self.insert(from_key, to_key);
+        }
+    }
+
+    /**
+    * Get the estimated number of triangles of the Edges seen so far.
+    */
+    pub fn estimate(&self) -> f64 {
+        self.estimate
+    }
+
+    /**
+    * Get the number of Edges seen so far.
+    */
+    pub fn edges_seen(&self) -> uint {
+        self.seen
+    }
+
+    fn insert(&mut self, from_key: K, to_key: K) {
+        self.link(from_key.clone(), to_key.clone());
+        self.link(to_key.clone(), from_key.clone());
+        self.sample.push((from_key, to_key));
+    }
+
+    fn link(&mut self, from_key: K, to_key: K) {
+        if !self.neighbors.contains_key(&from_key) {
+            self.neighbors.insert(from_key.clone(), TreeSet::new());
+        }
+        self.neighbors.find_mut(&from_key).unwrap().insert(to_key);
+    }
+
+    fn unlink(&mut self, from_key: &K, to_key: &K) {
+        let empty = match self.neighbors.find_mut(from_key) {
+            Some(set) => {
+                set.remove(to_key);
+                set.is_empty()
+            },
+            None      => false
+        };
+        if empty {
+            self.neighbors.remove(from_key);
+        }
+    }
+}
+
+/**
+* Compute the connected components of a stream of Edges.
+*
+* Only one integer per Vertex is kept in memory.
+*
+* # Arguments
+* * edges - The stream of (from_key, to_key) Edges
+*
+* # Return
+* A DynamicConnectivity which can still be updated with new Edges.
+*/
+pub fn connected_components<K: Ord + Eq + Clone,
+                            I: Iterator<(K, K)>>
+                            (mut edges: I) -> DynamicConnectivity<K> {
+    let mut connectivity = DynamicConnectivity::new();
+    for (from_key, to_key) in edges {
+        connectivity.add_edge(from_key, to_key);
+    }
+    connectivity
+}
+
+/**
+* Estimate the degrees of the Vertices of a stream of Edges with count-min
+* sketches.
+*
+* The memory used only depends on the width and the depth of the sketches,
+* the estimations are never lower than the exact degrees and the excess is
+* at most 2 * edges / width with probability 1 - (1 / 2) ^ depth.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+*/
+#[deriving(Clone)]
+pub struct DegreeSketch<K> {
+    out_counts:        CountMin,
+    in_counts:         CountMin
+}
+
+impl<K: Hash> DegreeSketch<K> {
+
+    /**
+    * Create a new DegreeSketch.
+    *
+    * # Arguments
+    * * width - The number of counters per row
+    * * depth - The number of rows
+    *
+    * # Return
+    * A new DegreeSketch.
+    */
+    pub fn new(width: uint, depth: uint) -> DegreeSketch<K> {
+        DegreeSketch {
+            out_counts: CountMin::new(width, depth),
+            in_counts:  CountMin::new(width, depth)
+        }
+    }
+
+    /**
+    * Process the next Edge of the stream.
+    *
+    * # Arguments
+    * * from_key - The key of the first Vertex of the Edge
+    * * to_key - The key of the second Vertex of the Edge
+    */
+    pub fn add_edge(&mut self, from_key: &K, to_key: &K) {
+        self.out_counts.add(from_key);
+        self.in_counts.add(to_key);
+    }
+
+    /**
+    * Get the estimated number of Edges starting from a Vertex.
+    */
+    pub fn out_degree(&self, key: &K) -> uint {
+        self.out_counts.estimate(key)
+    }
+
+    /**
+    * Get the estimated number of Edges ending to a Vertex.
+    */
+    pub fn in_degree(&self, key: &K) -> uint {
+        self.in_counts.estimate(key)
+    }
+
+    /**
+    * Get the estimated number of Edges starting from or ending to a Vertex.
+    */
+    pub fn degree(&self, key: &K) -> uint {
+        self.out_degree(key) + self.in_degree(key)
+    }
+}
+
+#[deriving(Clone)]
+struct CountMin {
+    width:             uint,
+    rows:              Vec<Vec<uint>>
+}
+
+impl CountMin {
+    fn new(width: uint, depth: uint) -> CountMin {
+        let width = if width == 0 { 1 } else { width };
+        let depth = if depth == 0 { 1 } else { depth };
+        CountMin {
+            width:  width,
+            rows:   Vec::from_elem(depth, Vec::from_elem(width, 0u))
+        }
+    }
+
+    fn column<T: Hash>(&self, row: uint, value: &T) -> uint {
+        (hash_with_keys(row as u64, 0, value) % self.width as u64) as uint
+    }
+
+    fn add<T: Hash>(&mut self, value: &T) {
+        for row in range(0, self.rows.len()) {
+            let column = self.column(row, value);
+            *self.rows.get_mut(row).get_mut(column) += 1;
+        }
+    }
+
+    fn estimate<T: Hash>(&self, value: &T) -> uint {
+        range(0, self.rows.len()).map(|row| {
+            *self.rows.get(row).get(self.column(row, value))
+        }).min().unwrap_or(0)
+    }
+}
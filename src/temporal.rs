@@ -0,0 +1,368 @@
+//! Graph whose Edges exist during time intervals.
+
+use std::collections::{TreeMap, TreeSet};
+use std::slice::Items;
+
+use graph::Graph;
+use property_map::PropertyMap;
+use instrument::{Probe, NoProbe};
+
+/**
+* Edge existing from its start time to its end time, both included.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+* * V - The Edge's Value type
+* * T - The time type
+*/
+#[deriving(Clone, PartialEq, Show)]
+pub struct TemporalEdge<K, V, T> {
+    pub from_key:      K,
+    pub to_key:        K,
+    pub start:         T,
+    pub end:           T,
+    pub value:         Option<V>
+}
+
+impl<K, V, T: Ord> TemporalEdge<K, V, T> {
+
+    /**
+    * Check if the Edge exists at a time.
+    */
+    pub fn is_active_at(&self, time: &T) -> bool {
+        self.start <= *time && *time <= self.end
+    }
+
+    /**
+    * Check if the Edge exists at some time of an interval, both bounds
+    * included.
+    */
+    pub fn is_active_between(&self, start: &T, end: &T) -> bool {
+        self.start <= *end && *start <= self.end
+    }
+}
+
+/**
+* Directed Graph of time stamped Edges, each Edge exists during an
+* interval and two Vertices can be linked by several Edges at different
+* times.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+* * L - The Vertex's Label type
+* * V - The Edge's Value type
+* * T - The time type
+*/
+#[deriving(Clone)]
+pub struct TemporalGraph<K, L, V, T> {
+    vertices:          TreeMap<K, Option<L>>,
+    edges:             Vec<TemporalEdge<K, V, T>>,
+    // the positions of the Edges starting from each Vertex
+    out_edges:         TreeMap<K, Vec<uint>>
+}
+
+impl<K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone,
+     T: Ord + Clone>
+     TemporalGraph<K, L, V, T> {
+
+    /**
+    * Create a new empty TemporalGraph.
+    *
+    * # Return
+    * A new empty TemporalGraph.
+    */
+    pub fn new() -> TemporalGraph<K, L, V, T> {
+        TemporalGraph {
+            vertices:   TreeMap::new(),
+            edges:      Vec::new(),
+            out_edges:  TreeMap::new()
+        }
+    }
+
+    /**
+    * Get the number of Vertices.
+    */
+    pub fn len(&self) -> uint {
+        self.vertices.len()
+    }
+
+    /**
+    * Get the number of Edges.
+    */
+    pub fn edges_len(&self) -> uint {
+        self.edges.len()
+    }
+
+    /**
+    * Add a Vertex with an optional label.
+    *
+    * # Arguments
+    * * key - The key of the Vertex
+    * * label - The optional label of the Vertex
+    *
+    * # Return
+    * true if the Vertex is added, false if it already exists.
+    */
+    pub fn add_vertex_opt_l(&mut self,
+                            key: K,
+                            label: Option<L>)
+                            -> bool {
+        if self.vertices.contains_key(&key) {
+            return false
+        }
+        self.out_edges.insert(key.clone(), Vec::new());
+        self.vertices.insert(key, label)
+    }
+
+    /**
+    * Add a Vertex without label.
+    *
+    * # Return
+    * true if the Vertex is added, false if it already exists.
+    */
+    pub fn add_vertex(&mut self, key: K) -> bool {
+        self.add_vertex_opt_l(key, None)
+    }
+
+    /**
+    * Add an Edge existing during an interval.
+    *
+    * # Arguments
+    * * from_key - The key of the first Vertex of the Edge
+    * * to_key - The key of the second Vertex of the Edge
+    * * start - The first time of the Edge
+    * * end - The last time of the Edge
+    * * value - The optional value of the Edge
+    *
+    * # Return
+    * true if the Edge is added, false if a Vertex does not exist or if
+    * start is after end.
+    */
+    pub fn add_edge_interval(&mut self,
+                             from_key: K,
+                             to_key: K,
+                             start: T,
+                             end: T,
+                             value: Option<V>)
+                             -> bool {
+        if start > end || !self.vertices.contains_key(&to_key) {
+            return false
+        }
+        match self.out_edges.find_mut(&from_key) {
+            Some(edges) => edges.push(self.edges.len()),
+            None        => return false
+        }
+        self.edges.push(TemporalEdge {
+            from_key:   from_key,
+            to_key:     to_key,
+            start:      start,
+            end:        end,
+            value:      value
+        });
+        true
+    }
+
+    /**
+    * Add an Edge existing at a single time.
+    *
+    * # Return
+    * true if the Edge is added, false if a Vertex does not exist.
+    */
+    pub fn add_edge_at(&mut self,
+                       from_key: K,
+                       to_key: K,
+                       time: T,
+                       value: Option<V>)
+                       -> bool {
+        self.add_edge_interval(from_key, to_key, time.clone(), time, value)
+    }
+
+    /**
+    * Get an iterator over the Edges, in their order of addition.
+    */
+    pub fn edges_iter<'r>(&'r self) -> Items<'r, TemporalEdge<K, V, T>> {
+        self.edges.iter()
+    }
+
+    /**
+    * Build the static Graph of the Edges existing at a time.
+    *
+    * Every Vertex is kept, of several Edges between the same Vertices the
+    * first one added is kept.
+    *
+    * # Arguments
+    * * time - The time of the snapshot
+    *
+    * # Return
+    * A new Graph.
+    */
+    pub fn snapshot_at(&self, time: &T) -> Graph<K, L, V> {
+        self.snapshot(|edge| edge.is_active_at(time))
+    }
+
+    /**
+    * Build the static Graph of the Edges existing at some time of an
+    * interval, both bounds included.
+    *
+    * Every Vertex is kept, of several Edges between the same Vertices the
+    * first one added is kept.
+    *
+    * # Arguments
+    * * start - The first time of the interval
+    * * end - The last time of the interval
+    *
+    * # Return
+    * A new Graph.
+    */
+    pub fn snapshot_between(&self, start: &T, end: &T) -> Graph<K, L, V> {
+        self.snapshot(|edge| edge.is_active_between(start, end))
+    }
+
+    /**
+    * Compute the earliest arrival time at each Vertex reachable from a
+    * source by a time respecting path.
+    *
+    * A time respecting path takes its Edges at non decreasing times, each
+    * Edge is taken at a time where it exists and crossing it takes no time.
+    *
+    * # Arguments
+    * * source - The key of the first Vertex
+    * * start - The time of the departure from the source
+    *
+    * # Return
+    * The earliest arrival time of each reachable Vertex, start for the
+    * source, empty if the source does not exist.
+    */
+    pub fn earliest_arrivals(&self, source: &K, start: &T) -> PropertyMap<K, T> {
+        self.earliest_arrivals_probed(source, start, &mut NoProbe)
+    }
+
+    /**
+    * Same as earliest_arrivals, notifying a Probe of the operations of the
+    * search.
+    */
+    pub fn earliest_arrivals_probed<P: Probe>(&self,
+                                              source: &K,
+                                              start: &T,
+                                              probe: &mut P)
+                                              -> PropertyMap<K, T> {
+        self.search(source, start, probe).iter().map(|(key, &(ref time, _))| {
+            (key.clone(), time.clone())
+        }).collect()
+    }
+
+    /**
+    * Check if a Vertex is reachable from another one by a time respecting
+    * path.
+    *
+    * # Arguments
+    * * source - The key of the first Vertex
+    * * target - The key of the last Vertex
+    * * start - The time of the departure from the source
+    *
+    * # Return
+    * true if the target is reachable, false otherwise.
+    */
+    pub fn is_reachable(&self, source: &K, target: &K, start: &T) -> bool {
+        self.search(source, start, &mut NoProbe).contains_key(target)
+    }
+
+    /**
+    * Find a time respecting path arriving as early as possible.
+    *
+    * # Arguments
+    * * source - The key of the first Vertex
+    * * target - The key of the last Vertex
+    * * start - The time of the departure from the source
+    *
+    * # Return
+    * Some(path) with the (from_key, to_key, time) of the Edges taken, None
+    * if the target is not reachable.
+    */
+    pub fn earliest_arrival_path(&self,
+                                 source: &K,
+                                 target: &K,
+                                 start: &T)
+                                 -> Option<Vec<(K, K, T)>> {
+        let arrivals = self.search(source, start, &mut NoProbe);
+        if !arrivals.contains_key(target) {
+            return None
+        }
+        let mut path = Vec::new();
+        let mut key = target;
+        loop {
+            match *arrivals.find(key).unwrap() {
+                (ref time, Some(i)) => {
+                    let edge = self.edges.get(i);
+                    path.push((edge.from_key.clone(), edge.to_key.clone(), time.clone()));
+                    key = &edge.from_key;
+                },
+                (_, None)           => break
+            }
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    fn snapshot(&self, active: |&TemporalEdge<K, V, T>| -> bool) -> Graph<K, L, V> {
+        let mut graph = Graph::new();
+        for (key, label) in self.vertices.iter() {
+            graph.add_vertex_opt_l(key.clone(), label.clone());
+        }
+        for edge in self.edges.iter() {
+            if active(edge) {
+                graph.add_edge_opt_v(edge.from_key.clone(), edge.to_key.clone(), edge.value.clone());
+            }
+        }
+        graph
+    }
+
+    // Dijkstra on the arrival times, the (time, Edge) of the arrival at
+    // each reachable Vertex
+    fn search<P: Probe>(&self,
+                        source: &K,
+                        start: &T,
+                        probe: &mut P)
+                        -> PropertyMap<K, (T, Option<uint>)> {
+        let mut arrivals = PropertyMap::new();
+        if !self.vertices.contains_key(source) {
+            return arrivals
+        }
+        arrivals.insert(source.clone(), (start.clone(), None));
+        let mut queue = TreeSet::new();
+        queue.insert((start.clone(), source.clone()));
+
+        loop {
+            let (time, key) = match queue.iter().next() {
+                Some(first) => first.clone(),
+                None        => break
+            };
+            queue.remove(&(time.clone(), key.clone()));
+            probe.heap_pop();
+            probe.visit();
+            for &i in self.out_edges.find(&key).unwrap().iter() {
+                let edge = self.edges.get(i);
+                probe.lookup();
+                let departure = if edge.start > time { edge.start.clone() } else { time.clone() };
+                if departure > edge.end {
+                    continue
+                }
+                let old = arrivals.find(&edge.to_key).map(|&(ref t, _)| t.clone());
+                match old {
+                    Some(ref t) if *t <= departure => continue,
+                    Some(t)                        => {
+                        queue.remove(&(t, edge.to_key.clone()));
+                    },
+                    None                           => {}
+                }
+                queue.insert((departure.clone(), edge.to_key.clone()));
+                arrivals.insert(edge.to_key.clone(), (departure, Some(i)));
+                probe.relaxation();
+                probe.insertion();
+            }
+        }
+        arrivals
+    }
+}
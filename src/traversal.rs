@@ -0,0 +1,570 @@
+//! Chainable traversals of a Graph.
+//!
+//! A Traversal holds the Vertices reached so far, each step moves or
+//! filters them:
+//!
+//! ```ignore
+//! let keys = graph.traverse(start).out().filter(|label| label == Some(&x)).out().keys();
+//! ```
+//!
+//! The breadth first and depth first searches iterate over the keys of the
+//! Vertices reachable from a start Vertex, or call visitors on the
+//! discovered Vertices and Edges:
+//!
+//! ```ignore
+//! for key in bfs(&graph, start) { ... }
+//! dfs_visit(&graph, start, |key| { ... }, |from_key, to_key, value| { ... });
+//! ```
+//!
+//! bfs_view and dfs_view search a filtered, reversed or undirected view of
+//! a Graph instead.
+
+use std::collections::{Deque, RingBuf, TreeMap, TreeSet};
+
+use graph::{Graph, Vertex, EdgeIterator};
+use indexed::IndexedGraph;
+use view::GraphView;
+
+/**
+* The Vertices reached by a traversal of a Graph.
+*
+* A Vertex reached by several paths is kept once per path, use dedup to
+* keep it once.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+* * L - The Vertex's Label type
+* * V - The Edge's Value type
+*/
+pub struct Traversal<'a, K, L, V> {
+    graph:             &'a Graph<K, L, V>,
+    // the predecessors of each Vertex, indexed by the first in_ step
+    predecessors:      Option<TreeMap<&'a K, Vec<(&'a K, Option<&'a V>)>>>,
+    current:           Vec<&'a Vertex<K, L, V>>
+}
+
+impl<K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
+     Graph<K, L, V> {
+
+    /**
+    * Start a traversal of the Graph.
+    *
+    * # Arguments
+    * * start - The key of the first Vertex
+    *
+    * # Return
+    * A Traversal on the start Vertex, empty if it does not exist.
+    */
+    pub fn traverse<'a>(&'a self, start: K) -> Traversal<'a, K, L, V> {
+        Traversal {
+            graph:          self,
+            predecessors:   None,
+            current:        self.get_vertex(start).map(|vertex| &**vertex).move_iter().collect()
+        }
+    }
+
+    /**
+    * Start a traversal of the Graph from every Vertex.
+    *
+    * # Return
+    * A Traversal on every Vertex.
+    */
+    pub fn traverse_all<'a>(&'a self) -> Traversal<'a, K, L, V> {
+        Traversal {
+            graph:          self,
+            predecessors:   None,
+            current:        self.vertices_ref_iter().collect()
+        }
+    }
+}
+
+impl<'a,
+     K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
+     Traversal<'a, K, L, V> {
+
+    /**
+    * Move to the successors of the Vertices.
+    */
+    pub fn out(self) -> Traversal<'a, K, L, V> {
+        self.out_where(|_| true)
+    }
+
+    /**
+    * Move to the successors of the Vertices through the Edges accepted by a
+    * predicate.
+    *
+    * # Arguments
+    * * predicate - Accept an Edge from its value
+    */
+    pub fn out_where(self, predicate: |Option<&V>| -> bool) -> Traversal<'a, K, L, V> {
+        let mut traversal = self;
+        traversal.current = traversal.successors(predicate);
+        traversal
+    }
+
+    /**
+    * Move to the predecessors of the Vertices.
+    */
+    pub fn in_(self) -> Traversal<'a, K, L, V> {
+        self.in_where(|_| true)
+    }
+
+    /**
+    * Move to the predecessors of the Vertices through the Edges accepted by
+    * a predicate.
+    *
+    * The Edges entering each Vertex are indexed by the first of these
+    * steps.
+    *
+    * # Arguments
+    * * predicate - Accept an Edge from its value
+    */
+    pub fn in_where(self, predicate: |Option<&V>| -> bool) -> Traversal<'a, K, L, V> {
+        let mut traversal = self;
+        traversal.current = traversal.predecessors(predicate);
+        traversal
+    }
+
+    /**
+    * Move to the successors and the predecessors of the Vertices.
+    */
+    pub fn both(self) -> Traversal<'a, K, L, V> {
+        let mut traversal = self;
+        let mut next = traversal.successors(|_| true);
+        next.push_all(traversal.predecessors(|_| true).as_slice());
+        traversal.current = next;
+        traversal
+    }
+
+    /**
+    * Keep the Vertices whose label is accepted by a predicate.
+    *
+    * # Arguments
+    * * predicate - Accept a Vertex from its label
+    */
+    pub fn filter(self, predicate: |Option<&L>| -> bool) -> Traversal<'a, K, L, V> {
+        let mut traversal = self;
+        traversal.current.retain(|vertex| predicate(vertex.get_label()));
+        traversal
+    }
+
+    /**
+    * Keep the Vertices whose key is accepted by a predicate.
+    *
+    * # Arguments
+    * * predicate - Accept a Vertex from its key
+    */
+    pub fn filter_key(self, predicate: |&K| -> bool) -> Traversal<'a, K, L, V> {
+        let mut traversal = self;
+        traversal.current.retain(|vertex| predicate(vertex.get_key()));
+        traversal
+    }
+
+    /**
+    * Keep the first occurrence of each Vertex.
+    */
+    pub fn dedup(self) -> Traversal<'a, K, L, V> {
+        let mut traversal = self;
+        let mut seen = TreeSet::new();
+        traversal.current.retain(|vertex| seen.insert(vertex.get_key()));
+        traversal
+    }
+
+    /**
+    * Keep the first Vertices.
+    *
+    * # Arguments
+    * * n - The number of Vertices to keep
+    */
+    pub fn limit(self, n: uint) -> Traversal<'a, K, L, V> {
+        let mut traversal = self;
+        traversal.current.truncate(n);
+        traversal
+    }
+
+    /**
+    * Get the number of Vertices reached.
+    */
+    pub fn count(&self) -> uint {
+        self.current.len()
+    }
+
+    /**
+    * Get the Vertices reached.
+    */
+    pub fn vertices(self) -> Vec<&'a Vertex<K, L, V>> {
+        self.current
+    }
+
+    /**
+    * Get the keys of the Vertices reached.
+    */
+    pub fn keys(self) -> Vec<&'a K> {
+        self.current.iter().map(|vertex| vertex.get_key()).collect()
+    }
+
+    /**
+    * Get the labels of the Vertices reached.
+    */
+    pub fn labels(self) -> Vec<Option<&'a L>> {
+        self.current.iter().map(|vertex| vertex.get_label()).collect()
+    }
+
+    fn successors(&self, predicate: |Option<&V>| -> bool) -> Vec<&'a Vertex<K, L, V>> {
+        let mut next = Vec::new();
+        for vertex in self.current.iter() {
+            for (to_key, value) in vertex.edges_iter() {
+                if predicate(value) {
+                    match self.graph.get_vertex(to_key.clone()) {
+                        Some(to) => next.push(&**to),
+                        None     => {}
+                    }
+                }
+            }
+        }
+        next
+    }
+
+    fn predecessors(&mut self, predicate: |Option<&V>| -> bool) -> Vec<&'a Vertex<K, L, V>> {
+        if self.predecessors.is_none() {
+            let mut predecessors: TreeMap<&'a K, Vec<(&'a K, Option<&'a V>)>> = TreeMap::new();
+            for vertex in self.graph.vertices_ref_iter() {
+                let from_key = vertex.get_key();
+                for (to_key, value) in vertex.edges_iter() {
+                    if !predecessors.contains_key(&to_key) {
+                        predecessors.insert(to_key, Vec::new());
+                    }
+                    predecessors.find_mut(&to_key).unwrap().push((from_key, value));
+                }
+            }
+            self.predecessors = Some(predecessors);
+        }
+        let predecessors = self.predecessors.get_ref();
+        let mut next = Vec::new();
+        for vertex in self.current.iter() {
+            match predecessors.find(&vertex.get_key()) {
+                Some(edges) => {
+                    for &(from_key, value) in edges.iter() {
+                        if predicate(value) {
+                            next.push(&**self.graph.get_vertex(from_key.clone()).unwrap());
+                        }
+                    }
+                },
+                None        => {}
+            }
+        }
+        next
+    }
+}
+
+/**
+* Iterator over the keys of the Vertices reached by a breadth first search.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+* * L - The Vertex's Label type
+* * V - The Edge's Value type
+*/
+pub struct Bfs<'a, K, L, V> {
+    graph:             &'a Graph<K, L, V>,
+    queue:             RingBuf<&'a K>,
+    discovered:        TreeSet<&'a K>
+}
+
+impl<'a,
+     K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
+     Iterator<&'a K> for Bfs<'a, K, L, V> {
+    fn next(&mut self) -> Option<&'a K> {
+        let key = match self.queue.pop_front() {
+            Some(key) => key,
+            None      => return None
+        };
+        for (to_key, _) in self.graph.get_vertex(key.clone()).unwrap().edges_iter() {
+            if self.discovered.insert(to_key) {
+                self.queue.push_back(to_key);
+            }
+        }
+        Some(key)
+    }
+}
+
+/**
+* Iterator over the keys of the Vertices reached by a depth first search,
+* in preorder.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+* * L - The Vertex's Label type
+* * V - The Edge's Value type
+*/
+pub struct Dfs<'a, K, L, V> {
+    graph:             &'a Graph<K, L, V>,
+    stack:             Vec<&'a K>,
+    visited:           TreeSet<&'a K>
+}
+
+impl<'a,
+     K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
+     Iterator<&'a K> for Dfs<'a, K, L, V> {
+    fn next(&mut self) -> Option<&'a K> {
+        loop {
+            let key = match self.stack.pop() {
+                Some(key) => key,
+                None      => return None
+            };
+            if !self.visited.insert(key) {
+                continue
+            }
+            // pushed in reverse so the successors are visited in key order
+            let successors: Vec<&'a K> = self.graph.get_vertex(key.clone()).unwrap()
+                                                   .edges_iter().map(|(to_key, _)| to_key).collect();
+            for &to_key in successors.iter().rev() {
+                if !self.visited.contains(&to_key) {
+                    self.stack.push(to_key);
+                }
+            }
+            return Some(key)
+        }
+    }
+}
+
+/**
+* Search a Graph breadth first.
+*
+* The successors of a Vertex are discovered in the order of their keys.
+*
+* # Arguments
+* * graph - The graph to search
+* * start - The key of the first Vertex
+*
+* # Return
+* An iterator over the keys of the Vertices reachable from start, in the
+* order they are visited, empty if start does not exist.
+*/
+pub fn bfs<'a,
+           K: Ord + Eq + Clone,
+           L: Eq + Clone,
+           V: PartialEq + Clone>
+           (graph: &'a Graph<K, L, V>,
+           start: K)
+           -> Bfs<'a, K, L, V> {
+    let mut queue = RingBuf::new();
+    let mut discovered = TreeSet::new();
+    match graph.get_vertex(start) {
+        Some(vertex) => {
+            queue.push_back(vertex.get_key());
+            discovered.insert(vertex.get_key());
+        },
+        None         => {}
+    }
+    Bfs {
+        graph:          graph,
+        queue:          queue,
+        discovered:     discovered
+    }
+}
+
+/**
+* Search a Graph depth first.
+*
+* The successors of a Vertex are explored in the order of their keys.
+*
+* # Arguments
+* * graph - The graph to search
+* * start - The key of the first Vertex
+*
+* # Return
+* An iterator over the keys of the Vertices reachable from start, in
+* preorder, empty if start does not exist.
+*/
+pub fn dfs<'a,
+           K: Ord + Eq + Clone,
+           L: Eq + Clone,
+           V: PartialEq + Clone>
+           (graph: &'a Graph<K, L, V>,
+           start: K)
+           -> Dfs<'a, K, L, V> {
+    Dfs {
+        graph:          graph,
+        stack:          graph.get_vertex(start).map(|vertex| vertex.get_key()).move_iter().collect(),
+        visited:        TreeSet::new()
+    }
+}
+
+/**
+* Search a Graph breadth first, calling a visitor on each discovered Vertex
+* and each Edge leaving a visited Vertex.
+*
+* # Arguments
+* * graph - The graph to search
+* * start - The key of the first Vertex
+* * on_vertex - Called with the key of each Vertex when it is discovered
+* * on_edge - Called with the (from_key, to_key, value) of each Edge
+* leaving a visited Vertex, before its second Vertex is discovered
+*/
+pub fn bfs_visit<K: Ord + Eq + Clone,
+                 L: Eq + Clone,
+                 V: PartialEq + Clone>
+                 (graph: &Graph<K, L, V>,
+                 start: K,
+                 on_vertex: |&K|,
+                 on_edge: |&K, &K, Option<&V>|) {
+    let mut queue = RingBuf::new();
+    let mut discovered = TreeSet::new();
+    match graph.get_vertex(start) {
+        Some(vertex) => {
+            on_vertex(vertex.get_key());
+            queue.push_back(&**vertex);
+            discovered.insert(vertex.get_key());
+        },
+        None         => return
+    }
+    while !queue.is_empty() {
+        let vertex = queue.pop_front().unwrap();
+        for (to_key, value) in vertex.edges_iter() {
+            on_edge(vertex.get_key(), to_key, value);
+            if discovered.insert(to_key) {
+                on_vertex(to_key);
+                queue.push_back(&**graph.get_vertex(to_key.clone()).unwrap());
+            }
+        }
+    }
+}
+
+/**
+* Search a Graph depth first, calling a visitor on each discovered Vertex
+* and each Edge leaving a visited Vertex.
+*
+* # Arguments
+* * graph - The graph to search
+* * start - The key of the first Vertex
+* * on_vertex - Called with the key of each Vertex when it is discovered,
+* in preorder
+* * on_edge - Called with the (from_key, to_key, value) of each Edge
+* leaving a visited Vertex, before its second Vertex is explored
+*/
+pub fn dfs_visit<K: Ord + Eq + Clone,
+                 L: Eq + Clone,
+                 V: PartialEq + Clone>
+                 (graph: &Graph<K, L, V>,
+                 start: K,
+                 on_vertex: |&K|,
+                 on_edge: |&K, &K, Option<&V>|) {
+    let start = match graph.get_vertex(start) {
+        Some(vertex) => vertex,
+        None         => return
+    };
+    on_vertex(start.get_key());
+    let mut discovered = TreeSet::new();
+    discovered.insert(start.get_key());
+    // the explored Vertices with their remaining Edges
+    let mut stack: Vec<(&K, EdgeIterator<K, V>)> = vec!((start.get_key(), start.edges_iter()));
+    while !stack.is_empty() {
+        let from_key = *stack.last().unwrap().ref0();
+        let next = stack.mut_last().unwrap().mut1().next();
+        match next {
+            Some((to_key, value)) => {
+                on_edge(from_key, to_key, value);
+                if discovered.insert(to_key) {
+                    on_vertex(to_key);
+                    let vertex = graph.get_vertex(to_key.clone()).unwrap();
+                    stack.push((to_key, vertex.edges_iter()));
+                }
+            },
+            None                  => { stack.pop(); }
+        }
+    }
+}
+
+/**
+* Search a view on a Graph breadth first.
+*
+* The view is indexed first, the successors of a Vertex are discovered in
+* the order of the view's vertices.
+*
+* # Arguments
+* * view - The view to search
+* * start - The key of the first Vertex
+*
+* # Return
+* The keys of the Vertices reachable from start in the view, in the order
+* they are visited, empty if start is not in the view.
+*/
+pub fn bfs_view<'a,
+                K: Ord + Eq + Clone,
+                V,
+                G: GraphView<'a, K, V>>
+                (view: &G,
+                start: &K)
+                -> Vec<K> {
+    let indexed = IndexedGraph::from_view(view);
+    let source = match indexed.index.find(start) {
+        Some(&source) => source,
+        None          => return Vec::new()
+    };
+    let mut order = vec!(source);
+    let mut discovered = Vec::from_elem(indexed.len(), false);
+    *discovered.get_mut(source) = true;
+    let mut i = 0;
+    while i < order.len() {
+        let v = *order.get(i);
+        for &u in indexed.out_edges.get(v).iter() {
+            if !*discovered.get(u) {
+                *discovered.get_mut(u) = true;
+                order.push(u);
+            }
+        }
+        i += 1;
+    }
+    order.iter().map(|&v| indexed.keys.get(v).clone()).collect()
+}
+
+/**
+* Search a view on a Graph depth first.
+*
+* The view is indexed first, the successors of a Vertex are explored in
+* the order of the view's vertices.
+*
+* # Arguments
+* * view - The view to search
+* * start - The key of the first Vertex
+*
+* # Return
+* The keys of the Vertices reachable from start in the view, in preorder,
+* empty if start is not in the view.
+*/
+pub fn dfs_view<'a,
+                K: Ord + Eq + Clone,
+                V,
+                G: GraphView<'a, K, V>>
+                (view: &G,
+                start: &K)
+                -> Vec<K> {
+    let indexed = IndexedGraph::from_view(view);
+    let mut stack: Vec<uint> = indexed.index.find(start).map(|&v| v).move_iter().collect();
+    let mut visited = Vec::from_elem(indexed.len(), false);
+    let mut order = Vec::new();
+    while !stack.is_empty() {
+        let v = stack.pop().unwrap();
+        if *visited.get(v) {
+            continue
+        }
+        *visited.get_mut(v) = true;
+        order.push(indexed.keys.get(v).clone());
+        // pushed in reverse so the successors are visited in order
+        for &u in indexed.out_edges.get(v).iter().rev() {
+            if !*visited.get(u) {
+                stack.push(u);
+            }
+        }
+    }
+    order
+}
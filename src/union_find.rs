@@ -0,0 +1,111 @@
+//! Disjoint sets of integers, used by the incremental algorithms.
+
+/**
+* Union-find structure with union by rank and path compression.
+*/
+#[deriving(Clone)]
+pub struct UnionFind {
+    parent:            Vec<uint>,
+    rank:              Vec<uint>,
+    sets:              uint
+}
+
+impl UnionFind {
+
+    /**
+    * Create a new UnionFind with n singleton sets.
+    *
+    * # Arguments
+    * * n - The number of elements
+    *
+    * # Return
+    * A new UnionFind.
+    */
+    pub fn new(n: uint) -> UnionFind {
+        UnionFind {
+            parent: range(0, n).collect(),
+            rank:   Vec::from_elem(n, 0u),
+            sets:   n
+        }
+    }
+
+    /**
+    * Add a new singleton set.
+    *
+    * # Return
+    * The new element.
+    */
+    pub fn add(&mut self) -> uint {
+        let element = self.parent.len();
+        self.parent.push(element);
+        self.rank.push(0);
+        self.sets += 1;
+        element
+    }
+
+    /**
+    * Get the number of elements.
+    */
+    pub fn len(&self) -> uint {
+        self.parent.len()
+    }
+
+    /**
+    * Get the number of disjoint sets.
+    */
+    pub fn sets(&self) -> uint {
+        self.sets
+    }
+
+    /**
+    * Get the representative of the set of an element, compressing the path.
+    */
+    pub fn find(&mut self, element: uint) -> uint {
+        let mut current = element;
+        while *self.parent.get(current) != current {
+            let grand_parent = *self.parent.get(*self.parent.get(current));
+            *self.parent.get_mut(current) = grand_parent;
+            current = grand_parent;
+        }
+        current
+    }
+
+    /**
+    * Get the representative of the set of an element, without modifying the
+    * structure.
+    */
+    pub fn find_imm(&self, element: uint) -> uint {
+        let mut current = element;
+        while *self.parent.get(current) != current {
+            current = *self.parent.get(current);
+        }
+        current
+    }
+
+    /**
+    * Merge the sets of two elements.
+    *
+    * # Return
+    * true if the sets are merged, false if the elements were already in the
+    * same set.
+    */
+    pub fn union(&mut self, a: uint, b: uint) -> bool {
+        let a = self.find(a);
+        let b = self.find(b);
+        if a == b {
+            return false
+        }
+        let rank_a = *self.rank.get(a);
+        let rank_b = *self.rank.get(b);
+        if rank_a < rank_b {
+            *self.parent.get_mut(a) = b;
+        } else {
+            *self.parent.get_mut(b) = a;
+            if rank_a == rank_b {
+                *self.rank.get_mut(a) += 1;
+            }
+        }
+        self.sets -= 1;
+        true
+    }
+}
@@ -0,0 +1,330 @@
+//! Graph recording its mutations to go back to any earlier revision.
+
+use std::rc::Rc;
+
+use graph::Graph;
+
+/// A recorded mutation with what is needed to undo it.
+#[deriving(Clone)]
+enum Change<K, L, V> {
+    AddVertex(K, Option<L>),
+    // the removed Edges are needed to restore the Vertex
+    RemoveVertex(K, Option<L>, Vec<(K, K, Option<V>)>),
+    SetLabel(K, Option<L>, Option<L>),
+    AddEdge(K, K, Option<V>),
+    RemoveEdge(K, K, Option<V>),
+    SetValue(K, K, Option<V>, Option<V>)
+}
+
+/**
+* Graph recording its mutations in a log, to materialize or to roll back
+* to any earlier revision.
+*
+* The revision is the number of mutations since the creation. A snapshot
+* of the Graph is kept every snapshot_interval revisions, an earlier
+* revision is rebuilt by replaying the log from the closest snapshot or by
+* undoing it from the current Graph. The snapshots are shared between the
+* clones of the VersionedGraph.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+* * L - The Vertex's Label type
+* * V - The Edge's Value type
+*/
+#[deriving(Clone)]
+pub struct VersionedGraph<K, L, V> {
+    graph:             Graph<K, L, V>,
+    log:               Vec<Change<K, L, V>>,
+    snapshots:         Vec<(uint, Rc<Graph<K, L, V>>)>,
+    snapshot_interval: uint
+}
+
+impl<K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
+     VersionedGraph<K, L, V> {
+
+    /**
+    * Create a new empty VersionedGraph.
+    *
+    * # Arguments
+    * * snapshot_interval - The number of revisions between two snapshots,
+    *   0 to keep only the initial Graph
+    *
+    * # Return
+    * A new VersionedGraph at revision 0.
+    */
+    pub fn new(snapshot_interval: uint) -> VersionedGraph<K, L, V> {
+        VersionedGraph::from_graph(Graph::new(), snapshot_interval)
+    }
+
+    /**
+    * Create a new VersionedGraph starting from a Graph.
+    *
+    * # Arguments
+    * * graph - The graph of the revision 0
+    * * snapshot_interval - The number of revisions between two snapshots,
+    *   0 to keep only the initial Graph
+    *
+    * # Return
+    * A new VersionedGraph at revision 0.
+    */
+    pub fn from_graph(graph: Graph<K, L, V>,
+                      snapshot_interval: uint)
+                      -> VersionedGraph<K, L, V> {
+        VersionedGraph {
+            snapshots:          vec!((0, Rc::new(graph.clone()))),
+            graph:              graph,
+            log:                Vec::new(),
+            snapshot_interval:  snapshot_interval
+        }
+    }
+
+    /**
+    * Get the Graph at the current revision.
+    */
+    pub fn graph<'r>(&'r self) -> &'r Graph<K, L, V> {
+        &self.graph
+    }
+
+    /**
+    * Get the current revision.
+    */
+    pub fn revision(&self) -> uint {
+        self.log.len()
+    }
+
+    /**
+    * Add a Vertex with an optional label.
+    *
+    * # Return
+    * true if the Vertex is added and a revision recorded, false otherwise.
+    */
+    pub fn add_vertex_opt_l(&mut self,
+                            key: K,
+                            label: Option<L>)
+                            -> bool {
+        self.record(AddVertex(key, label))
+    }
+
+    /**
+    * Remove a Vertex and its Edges.
+    *
+    * # Return
+    * true if the Vertex is removed and a revision recorded, false otherwise.
+    */
+    pub fn remove_vertex(&mut self,
+                         key: K)
+                         -> bool {
+        let label = match self.graph.get_vertex(key.clone()) {
+            Some(vertex) => vertex.get_label().map(|l| l.clone()),
+            None         => return false
+        };
+        let mut edges = Vec::new();
+        for vertex in self.graph.vertices_ref_iter() {
+            for (to_key, value) in vertex.edges_iter() {
+                if *vertex.get_key() == key || *to_key == key {
+                    edges.push((vertex.get_key().clone(),
+                                to_key.clone(),
+                                value.map(|v| v.clone())));
+                }
+            }
+        }
+        self.record(RemoveVertex(key, label, edges))
+    }
+
+    /**
+    * Set or remove the label of a Vertex.
+    *
+    * # Return
+    * true if the label is set and a revision recorded, false otherwise.
+    */
+    pub fn set_vertex_label_opt(&mut self,
+                                key: K,
+                                label: Option<L>)
+                                -> bool {
+        let old = match self.graph.get_vertex(key.clone()) {
+            Some(vertex) => vertex.get_label().map(|l| l.clone()),
+            None         => return false
+        };
+        self.record(SetLabel(key, old, label))
+    }
+
+    /**
+    * Add an Edge with an optional value.
+    *
+    * # Return
+    * true if the Edge is added and a revision recorded, false otherwise.
+    */
+    pub fn add_edge_opt_v(&mut self,
+                          from_key: K,
+                          to_key: K,
+                          value: Option<V>)
+                          -> bool {
+        self.record(AddEdge(from_key, to_key, value))
+    }
+
+    /**
+    * Remove an Edge.
+    *
+    * # Return
+    * true if the Edge is removed and a revision recorded, false otherwise.
+    */
+    pub fn remove_edge(&mut self,
+                       from_key: K,
+                       to_key: K)
+                       -> bool {
+        match self.edge_value(&from_key, &to_key) {
+            Some(value) => self.record(RemoveEdge(from_key, to_key, value)),
+            None        => false
+        }
+    }
+
+    /**
+    * Set or remove the value of an Edge.
+    *
+    * # Return
+    * true if the value is set and a revision recorded, false otherwise.
+    */
+    pub fn set_edge_value_opt(&mut self,
+                              from_key: K,
+                              to_key: K,
+                              value: Option<V>)
+                              -> bool {
+        match self.edge_value(&from_key, &to_key) {
+            Some(old) => self.record(SetValue(from_key, to_key, old, value)),
+            None      => false
+        }
+    }
+
+    /**
+    * Build the Graph of an earlier revision, the current one is unchanged.
+    *
+    * # Arguments
+    * * revision - The revision to build
+    *
+    * # Return
+    * Some(graph) if the revision exist, None otherwise.
+    */
+    pub fn materialize(&self, revision: uint) -> Option<Graph<K, L, V>> {
+        if revision > self.revision() {
+            return None
+        }
+        let &(base, ref snapshot) = self.snapshots.iter()
+                                                  .take_while(|&&(r, _)| r <= revision)
+                                                  .last()
+                                                  .unwrap();
+        if revision - base <= self.revision() - revision {
+            let mut graph = (**snapshot).clone();
+            for change in self.log.slice(base, revision).iter() {
+                apply(&mut graph, change);
+            }
+            Some(graph)
+        } else {
+            let mut graph = self.graph.clone();
+            for change in self.log.slice_from(revision).iter().rev() {
+                undo(&mut graph, change);
+            }
+            Some(graph)
+        }
+    }
+
+    /**
+    * Go back to an earlier revision, the following revisions are dropped.
+    *
+    * # Arguments
+    * * revision - The revision to go back to
+    *
+    * # Return
+    * true if the revision exist, false otherwise.
+    */
+    pub fn rollback(&mut self, revision: uint) -> bool {
+        if revision > self.revision() {
+            return false
+        }
+        while self.log.len() > revision {
+            let change = self.log.pop().unwrap();
+            undo(&mut self.graph, &change);
+        }
+        self.snapshots.retain(|&(r, _)| r <= revision);
+        true
+    }
+
+    // Some(value) if the Edge exist, None otherwise
+    fn edge_value(&self, from_key: &K, to_key: &K) -> Option<Option<V>> {
+        match self.graph.get_vertex(from_key.clone()) {
+            Some(vertex) if vertex.edge_exist(to_key) => {
+                Some(vertex.get_edge_value(to_key).map(|v| v.clone()))
+            },
+            _                                         => None
+        }
+    }
+
+    fn record(&mut self, change: Change<K, L, V>) -> bool {
+        if !apply(&mut self.graph, &change) {
+            return false
+        }
+        self.log.push(change);
+        let revision = self.log.len();
+        if self.snapshot_interval > 0 && revision % self.snapshot_interval == 0 {
+            self.snapshots.push((revision, Rc::new(self.graph.clone())));
+        }
+        true
+    }
+}
+
+fn apply<K: Ord + Eq + Clone,
+         L: Eq + Clone,
+         V: PartialEq + Clone>
+         (graph: &mut Graph<K, L, V>,
+         change: &Change<K, L, V>)
+         -> bool {
+    match *change {
+        AddVertex(ref key, ref label)                 => {
+            graph.add_vertex_opt_l(key.clone(), label.clone())
+        },
+        RemoveVertex(ref key, _, _)                   => graph.remove_vertex(key.clone()),
+        SetLabel(ref key, _, ref label)               => {
+            graph.set_vertex_label_opt(key.clone(), label.clone())
+        },
+        AddEdge(ref from_key, ref to_key, ref value)  => {
+            graph.add_edge_opt_v(from_key.clone(), to_key.clone(), value.clone())
+        },
+        RemoveEdge(ref from_key, ref to_key, _)       => {
+            graph.remove_edge(from_key.clone(), to_key.clone())
+        },
+        SetValue(ref from_key, ref to_key, _, ref value) => {
+            graph.set_edge_value_opt(from_key.clone(), to_key.clone(), value.clone())
+        }
+    }
+}
+
+fn undo<K: Ord + Eq + Clone,
+        L: Eq + Clone,
+        V: PartialEq + Clone>
+        (graph: &mut Graph<K, L, V>,
+        change: &Change<K, L, V>) {
+    match *change {
+        AddVertex(ref key, _)                         => {
+            graph.remove_vertex(key.clone());
+        },
+        RemoveVertex(ref key, ref label, ref edges)   => {
+            graph.add_vertex_opt_l(key.clone(), label.clone());
+            for &(ref from_key, ref to_key, ref value) in edges.iter() {
+                graph.add_edge_opt_v(from_key.clone(), to_key.clone(), value.clone());
+            }
+        },
+        SetLabel(ref key, ref label, _)               => {
+            graph.set_vertex_label_opt(key.clone(), label.clone());
+        },
+        AddEdge(ref from_key, ref to_key, _)          => {
+            graph.remove_edge(from_key.clone(), to_key.clone());
+        },
+        RemoveEdge(ref from_key, ref to_key, ref value) => {
+            graph.add_edge_opt_v(from_key.clone(), to_key.clone(), value.clone());
+        },
+        SetValue(ref from_key, ref to_key, ref value, _) => {
+            graph.set_edge_value_opt(from_key.clone(), to_key.clone(), value.clone());
+        }
+    }
+}
@@ -0,0 +1,420 @@
+//! Borrowed perspectives on a Graph.
+//!
+//! A view borrows a Graph and exposes its Vertices and Edges through the
+//! GraphView trait, filtered, reversed or without direction, without
+//! copying the keys, the labels or the values.
+
+use std::cell::RefCell;
+use std::cmp::{Less, Equal, Greater};
+use std::collections::TreeMap;
+
+use graph::Graph;
+
+/**
+* Read access to the structure of a Graph or of a view on a Graph.
+*
+* # Types parameters
+* * 'a - The lifetime of the borrowed Graph
+* * K - The Vertex's Key type
+* * V - The Edge's Value type
+*/
+pub trait GraphView<'a, K: Eq, V> {
+
+    /**
+    * Get the keys of the Vertices of the view.
+    */
+    fn vertices(&self) -> Vec<&'a K>;
+
+    /**
+    * Check if a Vertex is in the view.
+    */
+    fn contains_vertex(&self, key: &K) -> bool;
+
+    /**
+    * Get the Edges starting from a Vertex.
+    *
+    * # Arguments
+    * * key - The key of the Vertex
+    *
+    * # Return
+    * The (to_key, value) of the Edges, empty if the Vertex is not in the
+    * view.
+    */
+    fn edges_from(&self, key: &K) -> Vec<(&'a K, Option<&'a V>)>;
+
+    /**
+    * Are the Edges of the view directed or not.
+    */
+    fn is_directed(&self) -> bool;
+
+    /**
+    * Check if an Edge is in the view.
+    */
+    fn contains_edge(&self, from_key: &K, to_key: &K) -> bool {
+        self.edges_from(from_key).iter().any(|&(to, _)| to == to_key)
+    }
+}
+
+impl<'a,
+     K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
+     GraphView<'a, K, V> for &'a Graph<K, L, V> {
+    fn vertices(&self) -> Vec<&'a K> {
+        self.vertices_ref_iter().map(|vertex| vertex.get_key()).collect()
+    }
+
+    fn contains_vertex(&self, key: &K) -> bool {
+        self.vertex_exist(key)
+    }
+
+    fn edges_from(&self, key: &K) -> Vec<(&'a K, Option<&'a V>)> {
+        let graph: &'a Graph<K, L, V> = *self;
+        match graph.get_vertex(key.clone()) {
+            Some(vertex) => vertex.edges_iter().collect(),
+            None         => Vec::new()
+        }
+    }
+
+    fn is_directed(&self) -> bool {
+        (*self).is_directed()
+    }
+}
+
+/**
+* View of the Vertices and the Edges of a Graph accepted by predicates.
+*
+* An Edge is in the view if both its Vertices and the Edge are accepted.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+* * L - The Vertex's Label type
+* * V - The Edge's Value type
+*/
+pub struct FilteredView<'a, K, L, V> {
+    graph:             &'a Graph<K, L, V>,
+    vertex_filter:     |&K|: 'a -> bool,
+    edge_filter:       |&K, &K, Option<&V>|: 'a -> bool
+}
+
+impl<'a,
+     K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
+     FilteredView<'a, K, L, V> {
+
+    /**
+    * Create a new FilteredView on a Graph.
+    *
+    * # Arguments
+    * * graph - The graph to view
+    * * vertex_filter - Accept a Vertex from its key
+    * * edge_filter - Accept an Edge from its keys and its value
+    *
+    * # Return
+    * A new FilteredView borrowing the Graph.
+    */
+    pub fn new(graph: &'a Graph<K, L, V>,
+               vertex_filter: |&K|: 'a -> bool,
+               edge_filter: |&K, &K, Option<&V>|: 'a -> bool)
+               -> FilteredView<'a, K, L, V> {
+        FilteredView {
+            graph:          graph,
+            vertex_filter:  vertex_filter,
+            edge_filter:    edge_filter
+        }
+    }
+}
+
+impl<'a,
+     K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
+     GraphView<'a, K, V> for FilteredView<'a, K, L, V> {
+    fn vertices(&self) -> Vec<&'a K> {
+        self.graph.vertices().move_iter().filter(|key| (self.vertex_filter)(*key)).collect()
+    }
+
+    fn contains_vertex(&self, key: &K) -> bool {
+        self.graph.vertex_exist(key) && (self.vertex_filter)(key)
+    }
+
+    fn edges_from(&self, key: &K) -> Vec<(&'a K, Option<&'a V>)> {
+        if !(self.vertex_filter)(key) {
+            return Vec::new()
+        }
+        self.graph.edges_from(key).move_iter().filter(|&(to, value)| {
+            (self.vertex_filter)(to) && (self.edge_filter)(key, to, value)
+        }).collect()
+    }
+
+    fn is_directed(&self) -> bool {
+        self.graph.is_directed()
+    }
+}
+
+/**
+* View of a Graph where every Edge is reversed.
+*
+* The Edges entering each Vertex are indexed by the first lookup of the
+* Edges starting from a Vertex of the view, with references into the Graph
+* so the keys and the values are not copied.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+* * L - The Vertex's Label type
+* * V - The Edge's Value type
+*/
+pub struct ReversedView<'a, K, L, V> {
+    graph:             &'a Graph<K, L, V>,
+    predecessors:      RefCell<Option<Predecessors<'a, K, V>>>
+}
+
+impl<'a,
+     K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
+     ReversedView<'a, K, L, V> {
+
+    /**
+    * Create a new ReversedView on a Graph.
+    *
+    * # Arguments
+    * * graph - The graph to view
+    *
+    * # Return
+    * A new ReversedView borrowing the Graph.
+    */
+    pub fn new(graph: &'a Graph<K, L, V>) -> ReversedView<'a, K, L, V> {
+        ReversedView {
+            graph:          graph,
+            predecessors:   RefCell::new(None)
+        }
+    }
+}
+
+impl<'a,
+     K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
+     GraphView<'a, K, V> for ReversedView<'a, K, L, V> {
+    fn vertices(&self) -> Vec<&'a K> {
+        self.graph.vertices()
+    }
+
+    fn contains_vertex(&self, key: &K) -> bool {
+        self.graph.vertex_exist(key)
+    }
+
+    fn edges_from(&self, key: &K) -> Vec<(&'a K, Option<&'a V>)> {
+        if self.graph.is_directed() {
+            predecessors(self.graph, &self.predecessors, key)
+        } else {
+            self.graph.edges_from(key)
+        }
+    }
+
+    fn is_directed(&self) -> bool {
+        self.graph.is_directed()
+    }
+
+    fn contains_edge(&self, from_key: &K, to_key: &K) -> bool {
+        self.graph.edge_exist(to_key.clone(), from_key.clone())
+    }
+}
+
+/**
+* View of a Graph ignoring the direction of the Edges.
+*
+* Each Vertex is linked to its successors and its predecessors, two
+* opposite Edges are seen as one with the value of the Edge leaving the
+* Vertex. The predecessors of each Vertex are indexed by the first lookup
+* of the Edges starting from a Vertex of the view, with references into the
+* Graph so the keys and the values are not copied.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+* * L - The Vertex's Label type
+* * V - The Edge's Value type
+*/
+pub struct UndirectedView<'a, K, L, V> {
+    graph:             &'a Graph<K, L, V>,
+    predecessors:      RefCell<Option<Predecessors<'a, K, V>>>
+}
+
+impl<'a,
+     K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
+     UndirectedView<'a, K, L, V> {
+
+    /**
+    * Create a new UndirectedView on a Graph.
+    *
+    * # Arguments
+    * * graph - The graph to view
+    *
+    * # Return
+    * A new UndirectedView borrowing the Graph.
+    */
+    pub fn new(graph: &'a Graph<K, L, V>) -> UndirectedView<'a, K, L, V> {
+        UndirectedView {
+            graph:          graph,
+            predecessors:   RefCell::new(None)
+        }
+    }
+}
+
+impl<'a,
+     K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
+     GraphView<'a, K, V> for UndirectedView<'a, K, L, V> {
+    fn vertices(&self) -> Vec<&'a K> {
+        self.graph.vertices()
+    }
+
+    fn contains_vertex(&self, key: &K) -> bool {
+        self.graph.vertex_exist(key)
+    }
+
+    fn edges_from(&self, key: &K) -> Vec<(&'a K, Option<&'a V>)> {
+        let successors = self.graph.edges_from(key);
+        if !self.graph.is_directed() {
+            return successors
+        }
+        // merge the two lists sorted by key, the successor wins a tie
+        let predecessors = predecessors(self.graph, &self.predecessors, key);
+        let mut edges = Vec::with_capacity(successors.len() + predecessors.len());
+        let mut successors = successors.move_iter().peekable();
+        let mut predecessors = predecessors.move_iter().peekable();
+        loop {
+            let order = match (successors.peek(), predecessors.peek()) {
+                (Some(&(a, _)), Some(&(b, _))) => a.cmp(b),
+                (Some(_), None)                => Less,
+                (None, Some(_))                => Greater,
+                (None, None)                   => return edges
+            };
+            match order {
+                Less    => edges.push(successors.next().unwrap()),
+                Greater => edges.push(predecessors.next().unwrap()),
+                Equal   => {
+                    edges.push(successors.next().unwrap());
+                    predecessors.next();
+                }
+            }
+        }
+    }
+
+    fn is_directed(&self) -> bool {
+        false
+    }
+
+    fn contains_edge(&self, from_key: &K, to_key: &K) -> bool {
+        self.graph.edge_exist(from_key.clone(), to_key.clone()) ||
+        self.graph.edge_exist(to_key.clone(), from_key.clone())
+    }
+}
+
+// the Edges entering each Vertex of a Graph
+type Predecessors<'a, K, V> = TreeMap<&'a K, Vec<(&'a K, Option<&'a V>)>>;
+
+// the (from_key, value) of the Edges entering a Vertex, sorted by key, the
+// Edges entering every Vertex are indexed by the first call
+fn predecessors<'a,
+                K: Ord + Eq + Clone,
+                L: Eq + Clone,
+                V: PartialEq + Clone>
+                (graph: &'a Graph<K, L, V>,
+                index: &RefCell<Option<Predecessors<'a, K, V>>>,
+                key: &K)
+                -> Vec<(&'a K, Option<&'a V>)> {
+    let key = match graph.get_vertex(key.clone()) {
+        Some(vertex) => vertex.get_key(),
+        None         => return Vec::new()
+    };
+    let mut index = index.borrow_mut();
+    if index.is_none() {
+        let mut predecessors: Predecessors<'a, K, V> = TreeMap::new();
+        for vertex in graph.vertices_ref_iter() {
+            for (to_key, value) in vertex.edges_iter() {
+                if !predecessors.contains_key(&to_key) {
+                    predecessors.insert(to_key, Vec::new());
+                }
+                predecessors.find_mut(&to_key).unwrap().push((vertex.get_key(), value));
+            }
+        }
+        *index = Some(predecessors);
+    }
+    match index.get_ref().find(&key) {
+        Some(edges) => edges.clone(),
+        None        => Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use graph::Graph;
+    use weight::UnitWeights;
+    use traversal::{bfs_view, dfs_view};
+    use algo::{shortest_path_view, strongly_connected_components_view};
+    use super::{FilteredView, ReversedView};
+
+    // two paths from 0 to 3, through 1 and 2 or through 4
+    fn paths() -> Graph<uint, (), ()> {
+        Graph::from_edges(vec!((0, 1, None), (1, 2, None), (2, 3, None), (0, 4, None), (4, 3, None)))
+    }
+
+    // 0 and 1 in a cycle, then 2
+    fn cycle() -> Graph<uint, (), ()> {
+        Graph::from_edges(vec!((0, 1, None), (1, 0, None), (1, 2, None)))
+    }
+
+    #[test]
+    fn traversal_filtered() {
+        let graph = paths();
+        let view = FilteredView::new(&graph, |key| *key != 1, |_, _, _| true);
+        assert_eq!(bfs_view(&view, &0), vec!(0, 4, 3));
+        assert_eq!(dfs_view(&view, &0), vec!(0, 4, 3));
+        assert_eq!(bfs_view(&view, &1), vec!());
+    }
+
+    #[test]
+    fn traversal_reversed() {
+        let graph = paths();
+        let view = ReversedView::new(&graph);
+        assert_eq!(bfs_view(&view, &3), vec!(3, 2, 4, 1, 0));
+        assert_eq!(dfs_view(&view, &3), vec!(3, 2, 1, 0, 4));
+        assert_eq!(bfs_view(&view, &0), vec!(0));
+    }
+
+    #[test]
+    fn shortest_path_filtered() {
+        let graph = paths();
+        let view = FilteredView::new(&graph, |_| true, |from, to, _| !(*from == 0 && *to == 4));
+        assert_eq!(shortest_path_view(&view, &UnitWeights, &0, &3), Some((3.0, vec!(0, 1, 2, 3))));
+        assert_eq!(shortest_path_view(&view, &UnitWeights, &4, &0), None);
+    }
+
+    #[test]
+    fn shortest_path_reversed() {
+        let graph = paths();
+        let view = ReversedView::new(&graph);
+        assert_eq!(shortest_path_view(&view, &UnitWeights, &3, &0), Some((2.0, vec!(3, 4, 0))));
+        assert_eq!(shortest_path_view(&view, &UnitWeights, &0, &3), None);
+    }
+
+    #[test]
+    fn components_filtered() {
+        let graph = cycle();
+        let view = FilteredView::new(&graph, |_| true, |from, to, _| !(*from == 1 && *to == 0));
+        assert_eq!(strongly_connected_components_view(&view), vec!(vec!(0), vec!(1), vec!(2)));
+    }
+
+    #[test]
+    fn components_reversed() {
+        let graph = cycle();
+        let view = ReversedView::new(&graph);
+        assert_eq!(strongly_connected_components_view(&view), vec!(vec!(2), vec!(0, 1)));
+    }
+}
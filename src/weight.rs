@@ -0,0 +1,160 @@
+//! Weights of the Edges given to the algorithms.
+
+use std::collections::TreeMap;
+use std::num::One;
+
+use graph::{Graph, Vertex};
+use property_map::EdgePropertyMap;
+
+/**
+* Weight of a path, for the algorithms adding the weights of the Edges.
+*
+* Only the algorithms need to add and compare the weights, the Graph stores
+* its values without these bounds. The comparison is the one of PartialOrd,
+* the weights must not be NaN.
+*/
+pub trait Weight: Clone + PartialOrd {
+
+    /**
+    * Get the weight of an empty path.
+    *
+    * # Return
+    * The neutral element of add_weight.
+    */
+    fn zero() -> Self;
+
+    /**
+    * Add two weights.
+    *
+    * # Arguments
+    * * other - The weight to add
+    *
+    * # Return
+    * The weight of a path made of the two paths.
+    */
+    fn add_weight(&self, other: &Self) -> Self;
+}
+
+macro_rules! weight_impl(
+    ($t:ty, $zero:expr) => (
+        impl Weight for $t {
+            #[inline]
+            fn zero() -> $t { $zero }
+            #[inline]
+            fn add_weight(&self, other: &$t) -> $t { *self + *other }
+        }
+    )
+)
+
+weight_impl!(int, 0)
+weight_impl!(i8, 0)
+weight_impl!(i16, 0)
+weight_impl!(i32, 0)
+weight_impl!(i64, 0)
+weight_impl!(uint, 0)
+weight_impl!(u8, 0)
+weight_impl!(u16, 0)
+weight_impl!(u32, 0)
+weight_impl!(u64, 0)
+weight_impl!(f32, 0.0)
+weight_impl!(f64, 0.0)
+
+/**
+* Source of the weight of the Edges used by an algorithm, instead of the
+* value stored in the Graph.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+* * W - The weight type
+*/
+pub trait WeightProvider<K, W> {
+
+    /**
+    * Get the weight of an Edge.
+    *
+    * # Arguments
+    * * from_key - The first Vertex of the Edge
+    * * to_key - The second Vertex of the Edge
+    *
+    * # Return
+    * Some(weight) if the Edge has a weight, None otherwise.
+    */
+    fn weight(&self, from_key: &K, to_key: &K) -> Option<W>;
+}
+
+impl<'a, K, W> WeightProvider<K, W> for |&K, &K|: 'a -> Option<W> {
+    fn weight(&self, from_key: &K, to_key: &K) -> Option<W> {
+        (*self)(from_key, to_key)
+    }
+}
+
+impl<K, W> WeightProvider<K, W> for fn(&K, &K) -> Option<W> {
+    fn weight(&self, from_key: &K, to_key: &K) -> Option<W> {
+        (*self)(from_key, to_key)
+    }
+}
+
+impl<K: Ord + Clone, W: Clone> WeightProvider<K, W> for EdgePropertyMap<K, W> {
+    fn weight(&self, from_key: &K, to_key: &K) -> Option<W> {
+        self.find(&(from_key.clone(), to_key.clone())).map(|w| w.clone())
+    }
+}
+
+/**
+* The values stored in the Edges of a Graph used as weights.
+*
+* # Types parameters
+* * K - The Vertex's Key type
+* * L - The Vertex's Label type
+* * V - The Edge's Value type
+*/
+pub struct EdgeValues<'a, K, L, V> {
+    vertices:          TreeMap<&'a K, &'a Vertex<K, L, V>>
+}
+
+impl<'a,
+     K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
+     EdgeValues<'a, K, L, V> {
+
+    /**
+    * Create a new EdgeValues reading the values of a Graph.
+    *
+    * # Arguments
+    * * graph - The graph of the Edges
+    *
+    * # Return
+    * A new EdgeValues borrowing the Graph.
+    */
+    pub fn new(graph: &'a Graph<K, L, V>) -> EdgeValues<'a, K, L, V> {
+        EdgeValues {
+            vertices:   graph.vertices_ref_iter().map(|v| (v.get_key(), v)).collect()
+        }
+    }
+}
+
+impl<'a,
+     K: Ord + Eq + Clone,
+     L: Eq + Clone,
+     V: PartialEq + Clone>
+     WeightProvider<K, V> for EdgeValues<'a, K, L, V> {
+    fn weight(&self, from_key: &K, to_key: &K) -> Option<V> {
+        match self.vertices.find(&from_key) {
+            Some(vertex) => vertex.get_edge_value(to_key).map(|v| v.clone()),
+            None         => None
+        }
+    }
+}
+
+/**
+* The same weight of one for every Edge, the algorithms then count the
+* Edges.
+*/
+pub struct UnitWeights;
+
+impl<K, W: One> WeightProvider<K, W> for UnitWeights {
+    fn weight(&self, _: &K, _: &K) -> Option<W> {
+        Some(One::one())
+    }
+}